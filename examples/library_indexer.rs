@@ -0,0 +1,210 @@
+//! Media library indexer: probe every media file of a folder and keep a JSON index of
+//! the results, then print a quality control summary.
+//!
+//! ```text
+//! cargo run --example library_indexer -- /media/movies library.json
+//! ```
+//!
+//! Files whose size and modification time match the index aren't probed again, so
+//! re-running on a large library only probes new and changed files. The index is saved
+//! every [`BATCH_SIZE`] probes, an interrupted run keeps what it has probed so far.
+//!
+//! The index is a JSON file to keep the example free of extra dependencies. For a real
+//! application swap [`Index::load`] and [`Index::save`] for a database, eg. SQLite
+//! through `rusqlite`, with one row per [`Entry`].
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use ffprobe::{Config, FfProbe, Scanner, StreamKinds};
+use serde::{Deserialize, Serialize};
+
+/// Extensions probed by the indexer.
+pub const EXTENSIONS: [&str; 7] = ["mkv", "mp4", "m4v", "mov", "webm", "avi", "ts"];
+
+/// Probes between saving the index.
+pub const BATCH_SIZE: usize = 50;
+
+/// Indexed file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub size: u64,
+    /// Modification time in seconds since the epoch.
+    pub modified: u64,
+    pub container: String,
+    pub duration_secs: Option<f64>,
+    /// eg. `h264 1920x1080`
+    pub video: Vec<String>,
+    /// eg. `aac 2ch`
+    pub audio: Vec<String>,
+    pub subtitles: usize,
+    pub chapters: usize,
+    /// Quality control findings, empty if the file looks fine.
+    pub issues: Vec<String>,
+}
+
+/// Probe results of a library, by path.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    pub entries: BTreeMap<PathBuf, Entry>,
+    /// Files ffprobe couldn't read, with the error.
+    pub failed: BTreeMap<PathBuf, String>,
+}
+
+impl Index {
+    /// Read the index at `path`, empty if there is none yet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read(path) {
+            Ok(json) => Ok(serde_json::from_slice(&json)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Write the index through a temporary file, so a crash never leaves a partial index.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(temp, path)?;
+        Ok(())
+    }
+
+    /// Whether the entry for `path` still matches the file on disk.
+    fn is_current(&self, path: &Path) -> bool {
+        let Some(entry) = self.entries.get(path) else {
+            return false;
+        };
+        file_stamp(path).is_some_and(|stamp| stamp == (entry.size, entry.modified))
+    }
+}
+
+/// What [`index`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub probed: usize,
+    /// Files skipped as their index entry is current.
+    pub cached: usize,
+    pub failed: usize,
+    /// Entries of files that no longer exist.
+    pub removed: usize,
+    /// Issue counts over the whole index, eg. `no audio stream` => 3.
+    pub issues: BTreeMap<String, usize>,
+}
+
+/// Update the index at `index_path` with the media files in `dir` and its subdirectories.
+pub fn index(
+    config: Config,
+    dir: &Path,
+    index_path: &Path,
+) -> Result<Report, Box<dyn std::error::Error>> {
+    let mut index = Index::load(index_path)?;
+    let mut report = Report::default();
+
+    let before = index.entries.len() + index.failed.len();
+    index.entries.retain(|path, _| path.is_file());
+    index.failed.retain(|path, _| path.is_file());
+    report.removed = before - index.entries.len() - index.failed.len();
+
+    let cached = index.clone();
+    let scan = Scanner::new(config)
+        .extensions(EXTENSIONS)
+        .recursive(true)
+        .filter(move |path| !cached.is_current(path))
+        .scan(dir);
+
+    for (path, probe) in scan {
+        match probe {
+            Ok(probe) => {
+                if let Some(entry) = entry(&path, &probe) {
+                    index.failed.remove(&path);
+                    index.entries.insert(path, entry);
+                }
+                report.probed += 1;
+            }
+            Err(err) => {
+                index.entries.remove(&path);
+                index.failed.insert(path, err.inner().to_string());
+                report.failed += 1;
+            }
+        }
+        if (report.probed + report.failed) % BATCH_SIZE == 0 {
+            index.save(index_path)?;
+        }
+    }
+    index.save(index_path)?;
+
+    report.cached = index.entries.len().saturating_sub(report.probed);
+    for issue in index.entries.values().flat_map(|e| &e.issues) {
+        *report.issues.entry(issue.clone()).or_default() += 1;
+    }
+    Ok(report)
+}
+
+fn entry(path: &Path, probe: &FfProbe) -> Option<Entry> {
+    let (size, modified) = file_stamp(path)?;
+    let mut entry = Entry {
+        size,
+        modified,
+        container: probe.format.format_name.clone(),
+        duration_secs: probe.format.duration.map(|d| d.as_secs_f64()),
+        video: Vec::new(),
+        audio: Vec::new(),
+        subtitles: 0,
+        chapters: probe.chapters.len(),
+        issues: probe
+            .chapter_warnings()
+            .iter()
+            .map(|w| w.to_string())
+            .collect(),
+    };
+    for stream in &probe.streams {
+        match &stream.stream {
+            StreamKinds::Video(video) if stream.disposition.attached_pic == 0 => entry.video.push(
+                format!("{} {}x{}", video.codec_name, video.width, video.height),
+            ),
+            StreamKinds::Audio(audio) => entry
+                .audio
+                .push(format!("{} {}ch", audio.codec_name, audio.channels)),
+            StreamKinds::Subtitle(_) => entry.subtitles += 1,
+            _ => {}
+        }
+    }
+    if entry.video.is_empty() {
+        entry.issues.push("no video stream".into());
+    }
+    if entry.audio.is_empty() {
+        entry.issues.push("no audio stream".into());
+    }
+    if entry.duration_secs.is_none() {
+        entry.issues.push("unknown duration".into());
+    }
+    Some(entry)
+}
+
+/// Size and modification time, used to detect changed files.
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), modified.as_secs()))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args_os().skip(1);
+    let (Some(dir), Some(index_path)) = (args.next(), args.next()) else {
+        eprintln!("usage: library_indexer <media dir> <index.json>");
+        std::process::exit(2);
+    };
+
+    let report = index(Config::new(), Path::new(&dir), Path::new(&index_path))?;
+    println!(
+        "probed {}, cached {}, failed {}, removed {}",
+        report.probed, report.cached, report.failed, report.removed
+    );
+    for (issue, count) in &report.issues {
+        println!("  {count:>5}  {issue}");
+    }
+    Ok(())
+}
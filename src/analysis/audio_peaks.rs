@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use crate::{
+    error::{invalid_data, FfProbeError},
+    Config, StreamIndex,
+};
+
+use super::{parse_number, run_ffmpeg};
+
+/// Peak level in dBFS from which a channel counts as clipping. Lossy codecs decode
+/// full scale masters slightly below 0 dBFS.
+pub const CLIPPING_LEVEL_DB: f64 = -0.1;
+
+/// Levels of an audio stream, see [`audio_peaks`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct AudioPeaks {
+    /// Level of the loudest sample in dBFS, 0 at full scale (`max_volume` of
+    /// `volumedetect`).
+    pub max_volume: f64,
+    /// Mean level in dBFS (`mean_volume` of `volumedetect`).
+    pub mean_volume: f64,
+    /// Samples at the peak level of the channels reaching [`CLIPPING_LEVEL_DB`], ie.
+    /// samples cut off at full scale.
+    pub clipped_samples: u64,
+    /// Peak level in dBFS of each channel.
+    pub channel_peaks: Vec<f64>,
+}
+
+impl AudioPeaks {
+    pub fn is_clipped(&self) -> bool {
+        self.clipped_samples > 0
+    }
+}
+
+/// Measure the peak and mean level of the audio stream with index `stream` with ffmpeg's
+/// `volumedetect` and `astats` filters, eg. to reject clipped masters on upload.
+///
+/// Decodes the whole audio stream.
+pub fn audio_peaks(
+    path: impl AsRef<Path>,
+    stream: impl Into<StreamIndex>,
+    config: &Config,
+) -> Result<AudioPeaks, FfProbeError> {
+    let stream = stream.into();
+    let log = run_ffmpeg(
+        config,
+        path.as_ref(),
+        &[],
+        &[
+            "-map",
+            &format!("0:{stream}"),
+            "-filter:a",
+            "volumedetect,astats",
+        ],
+    )?;
+    parse_peaks(&log).ok_or_else(|| {
+        invalid_data("could not find the volumedetect and astats summary in the ffmpeg output")
+    })
+}
+
+/// Parse the summaries printed by the volumedetect and astats filters when they are
+/// closed:
+///
+/// ```text
+/// [Parsed_volumedetect_0 @ 0x5581] mean_volume: -21.3 dB
+/// [Parsed_volumedetect_0 @ 0x5581] max_volume: 0.0 dB
+/// [Parsed_astats_1 @ 0x5582] Channel: 1
+/// [Parsed_astats_1 @ 0x5582] Peak level dB: 0.000000
+/// [Parsed_astats_1 @ 0x5582] Peak count: 12
+/// [Parsed_astats_1 @ 0x5582] Overall
+/// [Parsed_astats_1 @ 0x5582] Peak level dB: 0.000000
+/// ```
+fn parse_peaks(log: &str) -> Option<AudioPeaks> {
+    let mut mean_volume = None;
+    let mut max_volume = None;
+    // `(peak level, peak count)` of the channels.
+    let mut channels: Vec<(Option<f64>, u64)> = Vec::new();
+    let mut overall = false;
+    for line in log.lines() {
+        let Some((_, message)) = line.split_once("] ") else {
+            continue;
+        };
+        let (key, value) = message.split_once(':').unwrap_or((message, ""));
+        let value = value.trim();
+        match key.trim() {
+            "mean_volume" => mean_volume = parse_number(value),
+            "max_volume" => max_volume = parse_number(value),
+            "Channel" => {
+                overall = false;
+                channels.push((None, 0));
+            }
+            "Overall" => overall = true,
+            "Peak level dB" if !overall => {
+                if let Some(channel) = channels.last_mut() {
+                    channel.0 = parse_number(value);
+                }
+            }
+            "Peak count" if !overall => {
+                if let Some(channel) = channels.last_mut() {
+                    channel.1 = parse_number(value).map_or(0, |c| c as u64);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(AudioPeaks {
+        max_volume: max_volume?,
+        mean_volume: mean_volume?,
+        clipped_samples: channels
+            .iter()
+            .filter(|(peak, _)| peak.is_some_and(|p| p >= CLIPPING_LEVEL_DB))
+            .map(|(_, count)| count)
+            .sum(),
+        channel_peaks: channels.iter().filter_map(|(peak, _)| *peak).collect(),
+    })
+}
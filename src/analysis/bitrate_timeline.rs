@@ -0,0 +1,82 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+    error::{invalid_data, FfProbeError},
+    Bitrate, Config, StreamIndex,
+};
+
+use super::probe_packets;
+
+/// Bit rate of a time bucket, see [`bitrate_timeline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BitrateSample {
+    /// Start of the bucket.
+    pub timestamp: Duration,
+    /// Size of the packets in the bucket per second of the bucket length.
+    pub bitrate: Bitrate,
+    /// Size of the packets in the bucket.
+    pub bytes: u64,
+}
+
+/// Bit rate of the stream with index `stream` over time, in buckets of `bucket` length.
+///
+/// Packets are assigned to buckets by their presentation timestamp, falling back to
+/// the decode timestamp. Buckets without packets are included with a zero bit rate, so
+/// the samples are evenly spaced. The last bucket is usually cut short by the end of
+/// the stream and shows a lower bit rate.
+///
+/// Only demuxes the input, restrict long inputs with [`Config::read_intervals`].
+pub fn bitrate_timeline(
+    path: impl AsRef<Path>,
+    stream: impl Into<StreamIndex>,
+    bucket: Duration,
+    config: &Config,
+) -> Result<Vec<BitrateSample>, FfProbeError> {
+    if bucket.is_zero() {
+        return Err(invalid_data("bitrate timeline bucket must not be zero"));
+    }
+    let packets: Vec<(f64, u64)> = probe_packets(
+        path.as_ref(),
+        stream.into(),
+        "pts_time,dts_time,size",
+        config,
+    )?
+    .iter()
+    .filter_map(|packet| {
+        let field = |key| packet.get(key)?.as_str();
+        let time: f64 = field("pts_time")
+            .or_else(|| field("dts_time"))?
+            .parse()
+            .ok()?;
+        Some((time.max(0.), field("size")?.parse().ok()?))
+    })
+    .collect();
+    Ok(timeline(&packets, bucket))
+}
+
+/// Aggregate `(timestamp, size)` packets into buckets.
+fn timeline(packets: &[(f64, u64)], bucket: Duration) -> Vec<BitrateSample> {
+    let length = bucket.as_secs_f64();
+    let index = |time: f64| (time / length) as u64;
+    let (Some(first), Some(last)) = (
+        packets.iter().map(|&(t, _)| index(t)).min(),
+        packets.iter().map(|&(t, _)| index(t)).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut bytes = vec![0; (last - first + 1) as usize];
+    for &(time, size) in packets {
+        bytes[(index(time) - first) as usize] += size;
+    }
+    bytes
+        .into_iter()
+        .zip(first..)
+        .map(|(bytes, i)| BitrateSample {
+            timestamp: bucket.saturating_mul(u32::try_from(i).unwrap_or(u32::MAX)),
+            bitrate: Bitrate((bytes as f64 * 8. / length).round() as u64),
+            bytes,
+        })
+        .collect()
+}
@@ -0,0 +1,227 @@
+use std::{fmt::Debug, path::Path, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{entries, error::FfProbeError, Config, Entries, EntriesSpec, ProbeCommand};
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+use crate::runtime;
+
+use super::run_ffmpeg;
+
+/// Section of the ffprobe output an [`Analysis`] needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProbeSection {
+    Format,
+    Streams,
+    Chapters,
+    /// Every packet of the input, requires demuxing the whole file.
+    Packets,
+    /// Every frame of the input, requires decoding the whole file.
+    Frames,
+}
+
+impl ProbeSection {
+    /// Section name as used by `-show_entries`, eg. `stream`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProbeSection::Format => "format",
+            ProbeSection::Streams => "stream",
+            ProbeSection::Chapters => "chapter",
+            ProbeSection::Packets => "packet",
+            ProbeSection::Frames => "frame",
+        }
+    }
+}
+
+/// Custom analysis, registered with [`Config::analysis`] and executed by [`run_analyses`].
+///
+/// ```rust
+/// use ffprobe::analysis::{Analysis, ProbeContext, ProbeSection};
+///
+/// struct KeyframeCount;
+///
+/// impl Analysis for KeyframeCount {
+///     fn name(&self) -> &str {
+///         "keyframe_count"
+///     }
+///
+///     fn sections(&self) -> &[ProbeSection] {
+///         &[ProbeSection::Packets]
+///     }
+///
+///     fn run(&self, ctx: &ProbeContext) -> Result<serde_json::Value, ffprobe::error::FfProbeError> {
+///         let count = ctx
+///             .entries()
+///             .packets
+///             .iter()
+///             .filter(|p| p.get("flags").and_then(|f| f.as_str()).is_some_and(|f| f.starts_with('K')))
+///             .count();
+///         Ok(count.into())
+///     }
+/// }
+///
+/// let config = ffprobe::Config::new().analysis(KeyframeCount);
+/// ```
+pub trait Analysis: Send + Sync {
+    /// Unique name, used to report the result.
+    fn name(&self) -> &str;
+
+    /// Sections of the shared ffprobe run the analysis reads from [`ProbeContext::entries`].
+    fn sections(&self) -> &[ProbeSection] {
+        &[]
+    }
+
+    fn run(&self, ctx: &ProbeContext) -> Result<Value, FfProbeError>;
+}
+
+/// Analyses registered on a [`Config`].
+#[derive(Clone, Default)]
+pub(crate) struct Analyses(pub(crate) Vec<Arc<dyn Analysis>>);
+
+impl Debug for Analyses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|a| a.name()))
+            .finish()
+    }
+}
+
+/// Input and shared probe output passed to [`Analysis::run`].
+#[derive(Debug)]
+pub struct ProbeContext<'a> {
+    path: &'a Path,
+    config: &'a Config,
+    entries: Entries,
+}
+
+impl ProbeContext<'_> {
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    pub fn config(&self) -> &Config {
+        self.config
+    }
+
+    /// Output of the ffprobe run shared by all analyses, containing the union of their
+    /// [`Analysis::sections`].
+    pub fn entries(&self) -> &Entries {
+        &self.entries
+    }
+
+    /// Run ffmpeg on the input with the given options, discarding the output,
+    /// and return its log. Useful for lavfi based analyses, eg.
+    /// `ctx.ffmpeg(&[], &["-filter:v", "idet"])`.
+    pub fn ffmpeg(
+        &self,
+        input_args: &[&str],
+        output_args: &[&str],
+    ) -> Result<String, FfProbeError> {
+        run_ffmpeg(self.config, self.path, input_args, output_args)
+    }
+
+    /// ffprobe command for the input with the settings of the config, for analyses that
+    /// need options beyond the shared run.
+    pub fn probe_command(&self) -> ProbeCommand {
+        ProbeCommand::new(self.config.clone()).input(self.path)
+    }
+}
+
+/// Result of a single [`Analysis`].
+#[derive(Debug)]
+pub struct AnalysisOutcome {
+    pub name: String,
+    pub result: Result<Value, FfProbeError>,
+}
+
+/// Run all analyses registered with [`Config::analysis`] on `path`.
+///
+/// ffprobe runs once with the sections required by any of the analyses. A failing
+/// analysis doesn't stop the others; the error is an error of the shared ffprobe run.
+pub fn run_analyses(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<AnalysisOutcome>, FfProbeError> {
+    let path = path.as_ref();
+    let spec = shared_spec(&config.analyses.0);
+    let entries = if spec.is_empty() {
+        Entries::default()
+    } else {
+        entries::run(ProbeCommand::new(config.clone()).show_entries(spec), path)?
+    };
+    Ok(run_each(&ProbeContext {
+        path,
+        config,
+        entries,
+    }))
+}
+
+/// [`run_analyses`] on the default runtime, see [`run_analyses_async_with`].
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub async fn run_analyses_async(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<AnalysisOutcome>, FfProbeError> {
+    run_analyses_async_with::<runtime::DefaultSpawner>(path, config).await
+}
+
+/// [`run_analyses`] on the runtime of `S`, eg.
+/// `run_analyses_async_with::<runtime::AsyncStd>` with both runtime features enabled.
+///
+/// The shared ffprobe run is spawned on the runtime. [`Analysis::run`] is blocking, the
+/// analyses run off the runtime through [`Spawner::unblock`](runtime::Spawner::unblock).
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub async fn run_analyses_async_with<S: runtime::Spawner>(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<AnalysisOutcome>, FfProbeError> {
+    let path = path.as_ref();
+    let spec = shared_spec(&config.analyses.0);
+    let entries = if spec.is_empty() {
+        Entries::default()
+    } else {
+        let cmd = ProbeCommand::new(config.clone())
+            .show_entries(spec)
+            .input(path);
+        let out = runtime::output::<S>(config, &cmd)
+            .await
+            .map_err(FfProbeError::from_io);
+        out.and_then(entries::parse)
+            .map_err(|e| e.context(path, &cmd))?
+    };
+    let (path, config) = (path.to_path_buf(), config.clone());
+    Ok(S::unblock(move || {
+        run_each(&ProbeContext {
+            path: &path,
+            config: &config,
+            entries,
+        })
+    })
+    .await)
+}
+
+/// Sections required by any of `analyses`, once each.
+fn shared_spec(analyses: &[Arc<dyn Analysis>]) -> EntriesSpec {
+    let mut spec = EntriesSpec::new();
+    let mut seen = Vec::new();
+    for section in analyses.iter().flat_map(|a| a.sections()) {
+        if !seen.contains(section) {
+            seen.push(*section);
+            spec = spec.section(section.as_str(), std::iter::empty::<String>());
+        }
+    }
+    spec
+}
+
+fn run_each(ctx: &ProbeContext<'_>) -> Vec<AnalysisOutcome> {
+    ctx.config
+        .analyses
+        .0
+        .iter()
+        .map(|analysis| AnalysisOutcome {
+            name: analysis.name().to_string(),
+            result: analysis.run(ctx),
+        })
+        .collect()
+}
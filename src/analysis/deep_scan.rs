@@ -0,0 +1,140 @@
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use crate::{command::ffmpeg_input, error::FfProbeError, Config, StreamIndex};
+
+/// Number of errors [`DeepScanReport::errors`] keeps, corrupt files can log one for
+/// every frame. [`DeepScanReport::per_stream`] counts all of them.
+pub const DEEP_SCAN_MAX_ERRORS: usize = 1000;
+
+/// Error logged by ffmpeg while decoding, see [`deep_scan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodeError {
+    /// Output position when the error was logged. ffmpeg reports its progress every
+    /// 0.5 s, so this is approximate and `None` for errors before the first report.
+    pub timestamp: Option<Duration>,
+    /// Index of the stream, `None` if ffmpeg didn't name it. Older ffmpeg versions only
+    /// prefix decoder errors with the codec, eg. `[h264 @ 0x55d1c8]`.
+    pub stream: Option<StreamIndex>,
+    /// eg. `error while decoding MB 12 34, bytestream -5`
+    pub message: String,
+}
+
+/// Result of [`deep_scan`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeepScanReport {
+    /// The first [`DEEP_SCAN_MAX_ERRORS`] errors in log order.
+    pub errors: Vec<DecodeError>,
+    /// Number of errors by stream index, including the ones not kept in
+    /// [`DeepScanReport::errors`].
+    pub per_stream: BTreeMap<Option<StreamIndex>, usize>,
+    /// Whether ffmpeg decoded the whole input. It gives up on inputs exceeding its
+    /// maximum decode error rate.
+    pub completed: bool,
+}
+
+impl DeepScanReport {
+    /// Total number of errors.
+    pub fn error_count(&self) -> usize {
+        self.per_stream.values().sum()
+    }
+
+    /// Whether the whole input decoded without errors.
+    pub fn is_clean(&self) -> bool {
+        self.completed && self.per_stream.is_empty()
+    }
+}
+
+/// Decode every video and audio stream with ffmpeg (`ffmpeg -v error -i file -f null -`)
+/// and collect the errors it logs, the standard check for a corrupt file.
+///
+/// Runs with `-err_detect crccheck+bitstream+buffer`, so decoders also verify checksums
+/// and report bitstream violations they would otherwise conceal. Decodes the whole file,
+/// which takes about as long as transcoding it.
+pub fn deep_scan(path: impl AsRef<Path>, config: &Config) -> Result<DeepScanReport, FfProbeError> {
+    let mut cmd = config.command(&config.ffmpeg_bin);
+    cmd.args(["-hide_banner", "-nostats", "-nostdin", "-v", "error"]);
+    cmd.args(["-progress", "pipe:2"]);
+    cmd.args(["-err_detect", "crccheck+bitstream+buffer"]);
+    cmd.args(ffmpeg_input(config, path.as_ref()).map_err(FfProbeError::from_io)?);
+    cmd.args(["-map", "0:v?", "-map", "0:a?", "-f", "null", "-"]);
+
+    let out = config
+        .output(&mut cmd)
+        .map_err(|e| FfProbeError::from_io(e).command_context(Some(path.as_ref()), &cmd))?;
+    let log = String::from_utf8_lossy(&out.stderr);
+    let (mut report, started) = parse_log(&log);
+    // Without any progress ffmpeg failed to open the input.
+    if !out.status.success() && !started {
+        return Err(FfProbeError::Status(out).command_context(Some(path.as_ref()), &cmd));
+    }
+    report.completed = out.status.success();
+    Ok(report)
+}
+
+/// Parse the error log with `-progress` reports interleaved, returning whether there
+/// was a progress report.
+fn parse_log(log: &str) -> (DeepScanReport, bool) {
+    let mut report = DeepScanReport::default();
+    let mut timestamp = None;
+    let mut started = false;
+    for line in log.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        // Progress lines are `key=value` pairs without whitespace, eg. `out_time_us=5000000`.
+        if !line.contains(char::is_whitespace) {
+            if let Some((key, value)) = line.split_once('=') {
+                started = true;
+                if key == "out_time_us" {
+                    if let Ok(us) = value.parse::<u64>() {
+                        timestamp = Some(Duration::from_micros(us));
+                    }
+                }
+                continue;
+            }
+        }
+        let error = parse_error(line, timestamp);
+        *report.per_stream.entry(error.stream).or_default() += 1;
+        if report.errors.len() < DEEP_SCAN_MAX_ERRORS {
+            report.errors.push(error);
+        }
+    }
+    (report, started)
+}
+
+/// Split the `[...]` context prefixes from an error line:
+///
+/// ```text
+/// [vist#0:0/h264 @ 0x55d1c8] [dec:h264 @ 0x55d1d0] error while decoding MB 12 34, bytestream -5
+/// Error while decoding stream #0:1: Invalid data found when processing input
+/// ```
+fn parse_error(line: &str, timestamp: Option<Duration>) -> DecodeError {
+    let mut stream = None;
+    let mut message = line;
+    while let Some(rest) = message.strip_prefix('[') {
+        let Some((context, after)) = rest.split_once(']') else {
+            break;
+        };
+        // Input stream of ffmpeg 6.1 and later, eg. `vist#0:0/h264` or `aist#0:1/aac`.
+        if let Some((_, id)) = context.split_once("ist#") {
+            stream = stream.or_else(|| stream_index(id));
+        }
+        message = after.trim_start();
+    }
+    if let Some((_, id)) = message.split_once("stream #") {
+        stream = stream.or_else(|| stream_index(id));
+    }
+    DecodeError {
+        timestamp,
+        stream,
+        message: message.to_string(),
+    }
+}
+
+/// Stream index of a stream specifier with the input file, eg. `0:1/aac` => 1.
+fn stream_index(id: &str) -> Option<StreamIndex> {
+    let (_, index) = id.split_once(':')?;
+    let end = index
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(index.len());
+    index[..end].parse().ok().map(StreamIndex)
+}
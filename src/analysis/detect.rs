@@ -0,0 +1,215 @@
+//! Black frame, silence and scene change detection.
+
+use std::{path::Path, time::Duration};
+
+use crate::{error::FfProbeError, Config};
+
+use super::{run_ffmpeg, value_after};
+
+/// Black section of the first video stream, see [`black_intervals`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlackInterval {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+/// Silent section of the first audio stream, see [`silence_intervals`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SilenceInterval {
+    pub start: Duration,
+    /// `None` if the silence lasts until the end of the input and ffmpeg didn't report an end.
+    pub end: Option<Duration>,
+}
+
+/// Section of the first video stream without changes between frames, see [`freeze_intervals`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreezeInterval {
+    pub start: Duration,
+    /// `None` if the freeze lasts until the end of the input and ffmpeg didn't report an end.
+    pub end: Option<Duration>,
+}
+
+/// Frame of the first video stream that differs strongly from its predecessor,
+/// see [`scene_changes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneChange {
+    /// Presentation time of the frame.
+    pub time: Duration,
+    /// Scene change score from 0 (identical) to 1 (completely different).
+    pub score: f64,
+}
+
+impl BlackInterval {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+impl SilenceInterval {
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.end?.saturating_sub(self.start))
+    }
+}
+
+impl FreezeInterval {
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.end?.saturating_sub(self.start))
+    }
+}
+
+/// Detect black sections in the first video stream with the `blackdetect` filter.
+/// Thresholds are taken from [`super::Heuristics::black`].
+pub fn black_intervals(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<BlackInterval>, FfProbeError> {
+    let options = &config.heuristics.black;
+    let filter = format!(
+        "blackdetect=d={}:pic_th={}:pix_th={}",
+        options.min_duration.as_secs_f64(),
+        options.picture_threshold,
+        options.pixel_threshold
+    );
+    let log = run_ffmpeg(
+        config,
+        path.as_ref(),
+        &[],
+        &["-map", "0:v:0", "-filter:v", &filter],
+    )?;
+    Ok(parse_black(&log))
+}
+
+/// Detect silent sections in the first audio stream with the `silencedetect` filter.
+/// Thresholds are taken from [`super::Heuristics::silence`].
+pub fn silence_intervals(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<SilenceInterval>, FfProbeError> {
+    let options = &config.heuristics.silence;
+    let filter = format!(
+        "silencedetect=n={}dB:d={}",
+        options.noise_db,
+        options.min_duration.as_secs_f64()
+    );
+    let log = run_ffmpeg(
+        config,
+        path.as_ref(),
+        &[],
+        &["-map", "0:a:0", "-filter:a", &filter],
+    )?;
+    Ok(parse_silence(&log))
+}
+
+/// Detect frozen sections in the first video stream with the `freezedetect` filter.
+/// Thresholds are taken from [`super::Heuristics::freeze`].
+pub fn freeze_intervals(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<FreezeInterval>, FfProbeError> {
+    let options = &config.heuristics.freeze;
+    let filter = format!(
+        "freezedetect=n={}dB:d={}",
+        options.noise_db,
+        options.min_duration.as_secs_f64()
+    );
+    let log = run_ffmpeg(
+        config,
+        path.as_ref(),
+        &[],
+        &["-map", "0:v:0", "-filter:v", &filter],
+    )?;
+    Ok(parse_freeze(&log))
+}
+
+/// Detect scene changes in the first video stream, reporting frames with a
+/// scene score above [`super::Heuristics::scene_threshold`].
+pub fn scene_changes(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<SceneChange>, FfProbeError> {
+    let filter = format!(
+        "select='gt(scene,{})',metadata=print",
+        config.heuristics.scene_threshold
+    );
+    let log = run_ffmpeg(
+        config,
+        path.as_ref(),
+        &[],
+        &["-map", "0:v:0", "-filter:v", &filter],
+    )?;
+    Ok(parse_scenes(&log))
+}
+
+/// `[blackdetect @ 0x55] black_start:0 black_end:2.04 black_duration:2.04`
+fn parse_black(log: &str) -> Vec<BlackInterval> {
+    log.lines()
+        .filter(|line| line.contains("black_start"))
+        .filter_map(|line| {
+            Some(BlackInterval {
+                start: seconds(value_after(line, "black_start")?)?,
+                end: seconds(value_after(line, "black_end")?)?,
+            })
+        })
+        .collect()
+}
+
+/// ```text
+/// [silencedetect @ 0x55] silence_start: 1.234
+/// [silencedetect @ 0x55] silence_end: 3.456 | silence_duration: 2.222
+/// ```
+fn parse_silence(log: &str) -> Vec<SilenceInterval> {
+    let mut out: Vec<SilenceInterval> = Vec::new();
+    for line in log.lines() {
+        if let Some(start) = value_after(line, "silence_start").and_then(seconds) {
+            out.push(SilenceInterval { start, end: None });
+        } else if let Some(end) = value_after(line, "silence_end").and_then(seconds) {
+            if let Some(last) = out.last_mut().filter(|last| last.end.is_none()) {
+                last.end = Some(end);
+            }
+        }
+    }
+    out
+}
+
+/// ```text
+/// [freezedetect @ 0x55] lavfi.freezedetect.freeze_start: 4.2
+/// [freezedetect @ 0x55] lavfi.freezedetect.freeze_duration: 2.5
+/// [freezedetect @ 0x55] lavfi.freezedetect.freeze_end: 6.7
+/// ```
+fn parse_freeze(log: &str) -> Vec<FreezeInterval> {
+    let mut out: Vec<FreezeInterval> = Vec::new();
+    for line in log.lines() {
+        if let Some(start) = value_after(line, "freeze_start").and_then(seconds) {
+            out.push(FreezeInterval { start, end: None });
+        } else if let Some(end) = value_after(line, "freeze_end").and_then(seconds) {
+            if let Some(last) = out.last_mut().filter(|last| last.end.is_none()) {
+                last.end = Some(end);
+            }
+        }
+    }
+    out
+}
+
+/// ```text
+/// [Parsed_metadata_1 @ 0x55] frame:0    pts:300     pts_time:12.5
+/// [Parsed_metadata_1 @ 0x55] lavfi.scene_score=0.578823
+/// ```
+fn parse_scenes(log: &str) -> Vec<SceneChange> {
+    let mut out = Vec::new();
+    let mut time = None;
+    for line in log.lines() {
+        if let Some(pts_time) = value_after(line, "pts_time") {
+            time = seconds(pts_time);
+        } else if let Some((_, score)) = line.split_once("lavfi.scene_score=") {
+            if let (Some(time), Ok(score)) = (time.take(), score.trim().parse()) {
+                out.push(SceneChange { time, score });
+            }
+        }
+    }
+    out
+}
+
+/// Time of a log value, clamped to 0. `None` if out of range, eg. `inf`.
+fn seconds(value: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(value.max(0.)).ok()
+}
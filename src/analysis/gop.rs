@@ -0,0 +1,126 @@
+use std::{path::Path, time::Duration};
+
+use serde_json::Value;
+
+use crate::{error::FfProbeError, Config, StreamIndex};
+
+use super::probe_packets;
+
+/// GOP (group of pictures) structure of a video stream, see [`gop_structure`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct GopStructure {
+    /// Presentation timestamps of the keyframes, where a GOP starts.
+    pub keyframes: Vec<Duration>,
+    /// Number of packets, ie. frames, of the stream.
+    pub frames: u64,
+    /// Average number of frames per GOP. The last GOP may be cut short by the end of
+    /// the stream or of [`Config::read_intervals`].
+    pub average_gop_length: Option<f64>,
+    pub max_gop_length: Option<u64>,
+    /// GOPs with frames displayed before their keyframe, which reference the previous GOP.
+    /// Decoding can't start cleanly at such a keyframe, segmenters need closed GOPs.
+    pub open_gops: u64,
+    pub closed_gops: u64,
+    /// Frames displayed before a frame decoded earlier, ie. B-frames.
+    pub b_frames: u64,
+    /// Longest run of consecutive B-frames.
+    pub max_consecutive_b_frames: u64,
+}
+
+impl GopStructure {
+    /// Whether every GOP is closed, so the stream can be cut at any keyframe.
+    pub fn is_closed(&self) -> bool {
+        self.open_gops == 0
+    }
+
+    /// Share of B-frames among all frames.
+    pub fn b_frame_ratio(&self) -> Option<f64> {
+        (self.frames > 0).then(|| self.b_frames as f64 / self.frames as f64)
+    }
+
+    /// Largest distance between two consecutive keyframes.
+    pub fn max_keyframe_interval(&self) -> Option<Duration> {
+        self.keyframes
+            .windows(2)
+            .map(|w| w[1].saturating_sub(w[0]))
+            .max()
+    }
+}
+
+/// Inspect the GOP structure of the stream with index `stream`, which must be a video
+/// stream.
+///
+/// Only demuxes the input (`-show_entries packet`), the frame types are derived from the
+/// decode and presentation order of the packets. Restrict long inputs with
+/// [`Config::read_intervals`].
+pub fn gop_structure(
+    path: impl AsRef<Path>,
+    stream: impl Into<StreamIndex>,
+    config: &Config,
+) -> Result<GopStructure, FfProbeError> {
+    let packets: Vec<(Option<f64>, bool)> =
+        probe_packets(path.as_ref(), stream.into(), "pts_time,flags", config)?
+            .iter()
+            .filter_map(|packet| {
+                let flags = packet.get("flags").and_then(Value::as_str).unwrap_or("");
+                // Discarded packets, eg. leading B-frames without their references after a cut.
+                if flags.contains('D') {
+                    return None;
+                }
+                let pts = packet
+                    .get("pts_time")
+                    .and_then(Value::as_str)
+                    .and_then(|t| t.parse().ok());
+                Some((pts, flags.starts_with('K')))
+            })
+            .collect();
+    Ok(structure(&packets))
+}
+
+/// Structure of `(pts, keyframe)` packets in decode order.
+fn structure(packets: &[(Option<f64>, bool)]) -> GopStructure {
+    // `(frames, keyframe pts, open)` of the GOPs.
+    let mut gops: Vec<(u64, Option<f64>, bool)> = Vec::new();
+    let mut gop = GopStructure {
+        frames: packets.len() as u64,
+        ..GopStructure::default()
+    };
+    let mut max_pts = f64::NEG_INFINITY;
+    let mut b_run = 0;
+    for &(pts, keyframe) in packets {
+        if keyframe {
+            gops.push((0, pts, false));
+        }
+        if let Some((frames, keyframe_pts, open)) = gops.last_mut() {
+            *frames += 1;
+            if let (Some(pts), Some(keyframe_pts)) = (pts, keyframe_pts) {
+                *open |= pts < *keyframe_pts;
+            }
+        }
+        let Some(pts) = pts else {
+            continue;
+        };
+        if pts < max_pts {
+            gop.b_frames += 1;
+            b_run += 1;
+            gop.max_consecutive_b_frames = gop.max_consecutive_b_frames.max(b_run);
+        } else {
+            b_run = 0;
+            max_pts = pts;
+        }
+    }
+
+    gop.keyframes = gops
+        .iter()
+        .filter_map(|&(_, pts, _)| Some(Duration::from_secs_f64(pts?.max(0.))))
+        .collect();
+    gop.keyframes.sort();
+    gop.open_gops = gops.iter().filter(|(_, _, open)| *open).count() as u64;
+    gop.closed_gops = gops.len() as u64 - gop.open_gops;
+    let lengths = gops.iter().map(|(frames, _, _)| *frames);
+    gop.max_gop_length = lengths.clone().max();
+    gop.average_gop_length =
+        (!gops.is_empty()).then(|| lengths.sum::<u64>() as f64 / gops.len() as f64);
+    gop
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::frame_rate::FrameRate;
+
+use super::SamplingStrategy;
+
+/// Thresholds used by the detectors of this crate.
+///
+/// Set with [`crate::Config::heuristics`]. Supports serde so the values can be
+/// kept in a central configuration file; missing fields use the defaults and
+/// durations are given in seconds:
+///
+/// ```rust
+/// let heuristics: ffprobe::analysis::Heuristics =
+///     serde_json::from_str(r#"{ "black": { "min_duration": 0.5 } }"#).unwrap();
+/// assert_eq!(heuristics.black.min_duration.as_millis(), 500);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct Heuristics {
+    /// Relative difference between average and real frame rate above which a stream
+    /// is considered variable frame rate. See [`Heuristics::is_variable_frame_rate`].
+    pub vfr_tolerance: f64,
+    /// Minimum scene score (0-1) for [`super::detect::scene_changes`].
+    pub scene_threshold: f64,
+    pub black: BlackDetectOptions,
+    pub silence: SilenceDetectOptions,
+    pub freeze: FreezeDetectOptions,
+    pub upscale: UpscaleDetectOptions,
+    /// Frames inspected by [`super::interlace_detect`] and [`super::detect_upscale`].
+    pub sampling: SamplingStrategy,
+}
+
+impl Default for Heuristics {
+    fn default() -> Self {
+        Heuristics {
+            vfr_tolerance: FrameRate::VARIABLE_TOLERANCE,
+            scene_threshold: 0.4,
+            black: BlackDetectOptions::default(),
+            silence: SilenceDetectOptions::default(),
+            freeze: FreezeDetectOptions::default(),
+            upscale: UpscaleDetectOptions::default(),
+            sampling: SamplingStrategy::default(),
+        }
+    }
+}
+
+impl Heuristics {
+    /// Whether `rate` is variable frame rate within the [`Heuristics::vfr_tolerance`],
+    /// see [`FrameRate::is_variable_within`].
+    ///
+    /// ```rust
+    /// use ffprobe::{analysis::Heuristics, FrameRate, Ratio};
+    ///
+    /// let rate = FrameRate {
+    ///     r_frame_rate: Ratio::new(30, 1),
+    ///     avg_frame_rate: Ratio::new(2991, 100),
+    ///     field_order: None,
+    ///     measured_frame_rate: None,
+    /// };
+    /// let mut heuristics = Heuristics::default();
+    /// assert!(heuristics.is_variable_frame_rate(&rate));
+    /// heuristics.vfr_tolerance = 0.01;
+    /// assert!(!heuristics.is_variable_frame_rate(&rate));
+    /// ```
+    pub fn is_variable_frame_rate(&self, rate: &FrameRate) -> bool {
+        rate.is_variable_within(self.vfr_tolerance)
+    }
+}
+
+/// Options of the `blackdetect` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlackDetectOptions {
+    /// Minimum duration of a black section.
+    #[serde(with = "seconds")]
+    pub min_duration: Duration,
+    /// Ratio of black pixels for a frame to count as black (`pic_th`).
+    pub picture_threshold: f64,
+    /// Luminance below which a pixel counts as black, from 0 to 1 (`pix_th`).
+    pub pixel_threshold: f64,
+}
+
+impl Default for BlackDetectOptions {
+    fn default() -> Self {
+        BlackDetectOptions {
+            min_duration: Duration::from_secs(2),
+            picture_threshold: 0.98,
+            pixel_threshold: 0.10,
+        }
+    }
+}
+
+/// Options of the `silencedetect` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SilenceDetectOptions {
+    /// Minimum duration of a silent section.
+    #[serde(with = "seconds")]
+    pub min_duration: Duration,
+    /// Level below which audio counts as silence, in dB.
+    pub noise_db: f64,
+}
+
+impl Default for SilenceDetectOptions {
+    fn default() -> Self {
+        SilenceDetectOptions {
+            min_duration: Duration::from_secs(2),
+            noise_db: -60.,
+        }
+    }
+}
+
+/// Options of the `freezedetect` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreezeDetectOptions {
+    /// Minimum duration of a frozen section.
+    #[serde(with = "seconds")]
+    pub min_duration: Duration,
+    /// Difference between frames below which they count as identical, in dB.
+    pub noise_db: f64,
+}
+
+impl Default for FreezeDetectOptions {
+    fn default() -> Self {
+        FreezeDetectOptions {
+            min_duration: Duration::from_secs(2),
+            noise_db: -60.,
+        }
+    }
+}
+
+/// Options of [`super::detect_upscale`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpscaleDetectOptions {
+    /// PSNR of the scaling round trip above which frames count as carrying no detail
+    /// beyond the lower resolution, in dB.
+    pub psnr_threshold: f64,
+}
+
+impl Default for UpscaleDetectOptions {
+    fn default() -> Self {
+        UpscaleDetectOptions {
+            psnr_threshold: 45.,
+        }
+    }
+}
+
+/// (De)serialize a [`Duration`] as floating point seconds.
+pub(crate) mod seconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use crate::{
+    error::{invalid_data, FfProbeError},
+    Config,
+};
+
+use super::{
+    sampling::{input_duration, run_sampled},
+    value_after,
+};
+
+/// Share of frames with a repeated field above which a stream is considered telecined.
+/// 3:2 pulldown repeats a field in 2 of 5 frames.
+const TELECINE_THRESHOLD: f64 = 25.;
+
+/// Frame counts of an `idet` detection pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IdetCounts {
+    /// Interlaced, top field first.
+    pub tff: u64,
+    /// Interlaced, bottom field first.
+    pub bff: u64,
+    pub progressive: u64,
+    pub undetermined: u64,
+}
+
+impl IdetCounts {
+    pub fn total(&self) -> u64 {
+        self.tff + self.bff + self.progressive + self.undetermined
+    }
+
+    fn add(&mut self, other: &IdetCounts) {
+        self.tff += other.tff;
+        self.bff += other.bff;
+        self.progressive += other.progressive;
+        self.undetermined += other.undetermined;
+    }
+}
+
+/// Scan type of a video stream, see [`InterlaceReport::scan_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ScanType {
+    Progressive,
+    TopFieldFirst,
+    BottomFieldFirst,
+    /// Progressive content with repeated fields, eg. 3:2 pulldown of 24 fps film to
+    /// 29.97 fps. Needs inverse telecine (`fieldmatch,decimate`) rather than deinterlacing.
+    Telecined,
+    /// Too few frames determined either way, eg. for static content.
+    Undetermined,
+}
+
+/// Result of [`interlace_detect`]. Percentages are of the frames the multi frame
+/// detection of `idet` looked at, which is more reliable than the single frame one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InterlaceReport {
+    pub single_frame: IdetCounts,
+    pub multi_frame: IdetCounts,
+    /// Frames without a repeated field.
+    pub repeated_neither: u64,
+    /// Frames repeating their top field.
+    pub repeated_top: u64,
+    /// Frames repeating their bottom field.
+    pub repeated_bottom: u64,
+}
+
+impl InterlaceReport {
+    pub fn tff_percent(&self) -> f64 {
+        percent(self.multi_frame.tff, self.multi_frame.total())
+    }
+
+    pub fn bff_percent(&self) -> f64 {
+        percent(self.multi_frame.bff, self.multi_frame.total())
+    }
+
+    pub fn progressive_percent(&self) -> f64 {
+        percent(self.multi_frame.progressive, self.multi_frame.total())
+    }
+
+    /// Share of frames with a repeated field.
+    pub fn telecined_percent(&self) -> f64 {
+        let repeated = self.repeated_top + self.repeated_bottom;
+        percent(repeated, repeated + self.repeated_neither)
+    }
+
+    /// Most likely scan type: telecined above a quarter of frames with repeated fields,
+    /// otherwise the most frequent multi frame detection result.
+    pub fn scan_type(&self) -> ScanType {
+        if self.telecined_percent() > TELECINE_THRESHOLD {
+            return ScanType::Telecined;
+        }
+        let counts = self.multi_frame;
+        [
+            (counts.progressive, ScanType::Progressive),
+            (counts.tff, ScanType::TopFieldFirst),
+            (counts.bff, ScanType::BottomFieldFirst),
+        ]
+        .into_iter()
+        .filter(|&(frames, _)| frames > counts.undetermined)
+        .max_by_key(|&(frames, _)| frames)
+        .map_or(ScanType::Undetermined, |(_, scan_type)| scan_type)
+    }
+
+    /// Whether the stream needs deinterlacing, ie. is interlaced but not telecined.
+    pub fn is_interlaced(&self) -> bool {
+        matches!(
+            self.scan_type(),
+            ScanType::TopFieldFirst | ScanType::BottomFieldFirst
+        )
+    }
+
+    fn add(&mut self, other: &InterlaceReport) {
+        self.single_frame.add(&other.single_frame);
+        self.multi_frame.add(&other.multi_frame);
+        self.repeated_neither += other.repeated_neither;
+        self.repeated_top += other.repeated_top;
+        self.repeated_bottom += other.repeated_bottom;
+    }
+}
+
+fn percent(frames: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.
+    } else {
+        frames as f64 * 100. / total as f64
+    }
+}
+
+/// Detect interlacing and telecine in the first video stream with ffmpeg's `idet`
+/// filter, summing the counts of the samples of [`Heuristics::sampling`]. The
+/// `field_order` of the stream is set by the encoder and often wrong, eg. progressive
+/// for interlaced broadcast captures.
+///
+/// [`Heuristics::sampling`]: super::Heuristics::sampling
+pub fn interlace_detect(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<InterlaceReport, FfProbeError> {
+    let path = path.as_ref();
+    let strategy = &config.heuristics.sampling;
+    let duration = input_duration(config, path, strategy)?;
+    let mut report = InterlaceReport::default();
+    for (_, log) in run_sampled(config, path, strategy, duration, "idet")? {
+        let sample = parse_idet(&log)
+            .ok_or_else(|| invalid_data("could not find the idet summary in the ffmpeg output"))?;
+        report.add(&sample);
+    }
+    Ok(report)
+}
+
+/// Parse the summary printed by the idet filter when it is closed:
+///
+/// ```text
+/// [Parsed_idet_0 @ 0x5581] Repeated Fields: Neither:   998 Top:     1 Bottom:     1
+/// [Parsed_idet_0 @ 0x5581] Single frame detection: TFF:   688 BFF:     0 Progressive:   285 Undetermined:    27
+/// [Parsed_idet_0 @ 0x5581] Multi frame detection: TFF:   996 BFF:     0 Progressive:     4 Undetermined:     0
+/// ```
+fn parse_idet(log: &str) -> Option<InterlaceReport> {
+    let count = |line: &str, key| value_after(line, key).map_or(0, |v| v as u64);
+    let counts = |line: &str| IdetCounts {
+        tff: count(line, "TFF"),
+        bff: count(line, "BFF"),
+        progressive: count(line, "Progressive"),
+        undetermined: count(line, "Undetermined"),
+    };
+    let last = |marker| log.lines().rev().find(|l| l.contains(marker));
+
+    let multi_frame = last("Multi frame detection:")?;
+    let mut report = InterlaceReport {
+        multi_frame: counts(multi_frame),
+        ..InterlaceReport::default()
+    };
+    if let Some(line) = last("Single frame detection:") {
+        report.single_frame = counts(line);
+    }
+    if let Some(line) = last("Repeated Fields:") {
+        report.repeated_neither = count(line, "Neither");
+        report.repeated_top = count(line, "Top");
+        report.repeated_bottom = count(line, "Bottom");
+    }
+    Some(report)
+}
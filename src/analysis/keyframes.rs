@@ -0,0 +1,70 @@
+use std::{path::Path, time::Duration};
+
+use crate::{error::FfProbeError, Config};
+
+use super::probe_packets;
+
+/// Keyframe of a video stream, see [`keyframe_index`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Keyframe {
+    /// Presentation timestamp, the decode timestamp if the packet has none.
+    pub timestamp: Duration,
+    /// Byte offset of the packet in the file, `None` if the demuxer doesn't know it, eg.
+    /// for some network inputs.
+    pub pos: Option<u64>,
+}
+
+/// Keyframes of the first video stream in presentation order, eg. to build a seek index
+/// or pick the frames of a thumbnail sprite, which decode fastest at keyframes.
+///
+/// With `every`, only keyframes at least `every` after the previously kept one are kept,
+/// starting with the first keyframe.
+///
+/// Only demuxes the input, restrict long inputs with [`Config::read_intervals`].
+pub fn keyframe_index(
+    path: impl AsRef<Path>,
+    every: Option<Duration>,
+    config: &Config,
+) -> Result<Vec<Keyframe>, FfProbeError> {
+    let mut keyframes: Vec<Keyframe> =
+        probe_packets(path.as_ref(), "v:0", "pts_time,dts_time,pos,flags", config)?
+            .iter()
+            .filter_map(|packet| {
+                let field = |key| packet.get(key)?.as_str();
+                let flags = field("flags").unwrap_or("");
+                // Discarded packets, eg. leading frames without their references after a cut.
+                if !flags.starts_with('K') || flags.contains('D') {
+                    return None;
+                }
+                let time: f64 = field("pts_time")
+                    .or_else(|| field("dts_time"))?
+                    .parse()
+                    .ok()?;
+                Some(Keyframe {
+                    timestamp: Duration::from_secs_f64(time.max(0.)),
+                    pos: field("pos").and_then(|p| p.parse().ok()),
+                })
+            })
+            .collect();
+    keyframes.sort_by_key(|k| k.timestamp);
+    keyframes.dedup_by_key(|k| k.timestamp);
+    Ok(match every {
+        Some(every) => downsample(keyframes, every),
+        None => keyframes,
+    })
+}
+
+fn downsample(keyframes: Vec<Keyframe>, every: Duration) -> Vec<Keyframe> {
+    let mut next = Duration::ZERO;
+    keyframes
+        .into_iter()
+        .filter(|k| {
+            let keep = k.timestamp >= next;
+            if keep {
+                next = k.timestamp + every;
+            }
+            keep
+        })
+        .collect()
+}
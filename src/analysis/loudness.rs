@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use crate::{error::FfProbeError, Config};
+
+use super::{parse_number, run_ffmpeg};
+
+/// Loudness measurement according to EBU R128 / ITU-R BS.1770, see [`loudness`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoudnessReport {
+    /// Integrated loudness in LUFS.
+    pub integrated: f64,
+    /// Relative gating threshold of the integrated loudness in LUFS.
+    pub integrated_threshold: f64,
+    /// Loudness range (LRA) in LU.
+    pub loudness_range: f64,
+    /// Lower bound of the loudness range in LUFS.
+    pub loudness_range_low: f64,
+    /// Upper bound of the loudness range in LUFS.
+    pub loudness_range_high: f64,
+    /// Maximum true peak in dBTP.
+    pub true_peak: f64,
+}
+
+/// Loudness compliance target, see [`LoudnessReport::complies_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessTarget {
+    /// Target integrated loudness in LUFS.
+    pub integrated: f64,
+    /// Allowed deviation from the target in LU.
+    pub tolerance: f64,
+    /// Maximum allowed true peak in dBTP.
+    pub max_true_peak: f64,
+}
+
+impl LoudnessTarget {
+    /// EBU R128 broadcast delivery: -23 LUFS ±1 LU, -1 dBTP.
+    pub const EBU_R128: LoudnessTarget = LoudnessTarget {
+        integrated: -23.,
+        tolerance: 1.,
+        max_true_peak: -1.,
+    };
+    /// ATSC A/85 (US broadcast): -24 LKFS ±2 LU, -2 dBTP.
+    pub const ATSC_A85: LoudnessTarget = LoudnessTarget {
+        integrated: -24.,
+        tolerance: 2.,
+        max_true_peak: -2.,
+    };
+}
+
+impl LoudnessReport {
+    /// Whether the integrated loudness and true peak are within the target.
+    pub fn complies_with(&self, target: &LoudnessTarget) -> bool {
+        (self.integrated - target.integrated).abs() <= target.tolerance
+            && self.true_peak <= target.max_true_peak
+    }
+}
+
+/// Measure the loudness of the first audio stream with ffmpeg's `ebur128` filter.
+///
+/// Decodes the whole audio stream.
+pub fn loudness(path: impl AsRef<Path>, config: &Config) -> Result<LoudnessReport, FfProbeError> {
+    let log = run_ffmpeg(
+        config,
+        path.as_ref(),
+        &[],
+        &["-map", "0:a:0", "-filter:a", "ebur128=peak=true"],
+    )?;
+    parse_summary(&log).ok_or_else(|| {
+        crate::error::invalid_data("could not find the ebur128 summary in the ffmpeg output")
+    })
+}
+
+/// Parse the summary printed by the ebur128 filter when it is closed:
+///
+/// ```text
+/// [Parsed_ebur128_0 @ 0x5581] Summary:
+///
+///   Integrated loudness:
+///     I:         -23.0 LUFS
+///     Threshold: -33.0 LUFS
+///
+///   Loudness range:
+///     LRA:         5.6 LU
+///     Threshold:   -43.1 LUFS
+///     LRA low:     -26.2 LUFS
+///     LRA high:    -20.6 LUFS
+///
+///   True peak:
+///     Peak:       -1.2 dBFS
+/// ```
+fn parse_summary(log: &str) -> Option<LoudnessReport> {
+    let (_, summary) = log.rsplit_once("Summary:")?;
+
+    let mut section = "";
+    let mut integrated = None;
+    let mut integrated_threshold = None;
+    let mut loudness_range = None;
+    let mut loudness_range_low = None;
+    let mut loudness_range_high = None;
+    let mut true_peak = None;
+    for line in summary.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            section = key;
+            continue;
+        }
+        let number = parse_number(value);
+        match (section, key) {
+            ("Integrated loudness", "I") => integrated = number,
+            ("Integrated loudness", "Threshold") => integrated_threshold = number,
+            ("Loudness range", "LRA") => loudness_range = number,
+            ("Loudness range", "LRA low") => loudness_range_low = number,
+            ("Loudness range", "LRA high") => loudness_range_high = number,
+            ("True peak", "Peak") => true_peak = number,
+            _ => {}
+        }
+    }
+
+    Some(LoudnessReport {
+        integrated: integrated?,
+        integrated_threshold: integrated_threshold?,
+        loudness_range: loudness_range?,
+        loudness_range_low: loudness_range_low?,
+        loudness_range_high: loudness_range_high?,
+        true_peak: true_peak?,
+    })
+}
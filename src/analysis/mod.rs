@@ -0,0 +1,121 @@
+//! Analyses that decode the input with `ffmpeg` filters or inspect its packets,
+//! complementing the container level information reported by `ffprobe`.
+//!
+//! The decoding analyses require the `ffmpeg` binary, see [`crate::Config::ffmpeg_bin`].
+//! [`gop_structure`] and [`bitrate_timeline`] only run `ffprobe`.
+
+mod audio_peaks;
+mod bitrate_timeline;
+mod custom;
+mod deep_scan;
+pub mod detect;
+mod gop;
+mod heuristics;
+mod interlace;
+mod keyframes;
+mod loudness;
+mod sampling;
+mod upscale;
+
+use std::{fmt::Display, path::Path};
+
+use serde_json::Value;
+
+use crate::{
+    command::ffmpeg_input,
+    error::{self, invalid_data, FfProbeError},
+    Config, ProbeCommand,
+};
+
+pub use audio_peaks::{audio_peaks, AudioPeaks, CLIPPING_LEVEL_DB};
+pub use bitrate_timeline::{bitrate_timeline, BitrateSample};
+pub(crate) use custom::Analyses;
+pub use custom::{run_analyses, Analysis, AnalysisOutcome, ProbeContext, ProbeSection};
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub use custom::{run_analyses_async, run_analyses_async_with};
+pub use deep_scan::{deep_scan, DecodeError, DeepScanReport, DEEP_SCAN_MAX_ERRORS};
+pub use gop::{gop_structure, GopStructure};
+pub use heuristics::{
+    BlackDetectOptions, FreezeDetectOptions, Heuristics, SilenceDetectOptions, UpscaleDetectOptions,
+};
+pub use interlace::{interlace_detect, IdetCounts, InterlaceReport, ScanType};
+pub use keyframes::{keyframe_index, Keyframe};
+pub use loudness::{loudness, LoudnessReport, LoudnessTarget};
+pub use sampling::{SampleWindow, SamplingStrategy};
+pub use upscale::{detect_upscale, ResolutionClass, UpscaleReport, UpscaleSample};
+
+/// Run ffmpeg on `path` with the given output arguments, discarding the output,
+/// and return the log written to stderr.
+pub(crate) fn run_ffmpeg(
+    config: &Config,
+    path: &Path,
+    input_args: &[&str],
+    output_args: &[&str],
+) -> Result<String, FfProbeError> {
+    let mut cmd = config.command(&config.ffmpeg_bin);
+    cmd.args(["-hide_banner", "-nostats", "-nostdin"]);
+    if config.progress.is_some() {
+        cmd.args(["-progress", "pipe:2"]);
+    }
+    cmd.args(input_args);
+    cmd.args(ffmpeg_input(config, path).map_err(FfProbeError::from_io)?);
+    cmd.args(output_args);
+    cmd.args(["-f", "null", "-"]);
+
+    let out = config
+        .output(&mut cmd)
+        .map_err(|e| FfProbeError::from_io(e).command_context(Some(path), &cmd))?;
+    if !out.status.success() {
+        return Err(FfProbeError::Status(out).command_context(Some(path), &cmd));
+    }
+    Ok(String::from_utf8_lossy(&out.stderr).into_owned())
+}
+
+/// Packets of the stream with index or specifier `stream`, eg. `v:0`, with the given
+/// `-show_entries` fields, in decode order. Fails if the stream has no packets, eg.
+/// doesn't exist.
+pub(crate) fn probe_packets(
+    path: &Path,
+    stream: impl Display,
+    fields: &str,
+    config: &Config,
+) -> Result<Vec<Value>, FfProbeError> {
+    error::check_input(path)?;
+    // Counts of the probe don't apply to the packets.
+    let mut config = config.clone();
+    config.count_frames = false;
+    config.count_packets = false;
+    let cmd = ProbeCommand::new(config)
+        .default_sections(false)
+        .args(["-select_streams", &stream.to_string()])
+        .args(["-show_entries", &format!("packet={fields}")])
+        .input(path);
+    let out = cmd.output().map_err(FfProbeError::from_io);
+    let mut value: Value = out
+        .and_then(|out| {
+            if !out.status.success() {
+                return Err(error::status_error(path, out));
+            }
+            serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)
+        })
+        .map_err(|e| e.context(path, &cmd))?;
+    match value.get_mut("packets").map(Value::take) {
+        Some(Value::Array(packets)) if !packets.is_empty() => Ok(packets),
+        _ => Err(invalid_data(format!("no packets in stream {stream}"))),
+    }
+}
+
+/// Parse the leading number of a filter log value, eg. `-23.0 LUFS`.
+pub(crate) fn parse_number(value: &str) -> Option<f64> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// Number following `key:` in a filter log line, eg. `silence_end: 3.456 | ...`.
+pub(crate) fn value_after(line: &str, key: &str) -> Option<f64> {
+    let (_, rest) = line.split_once(key)?;
+    let rest = rest.strip_prefix(':')?.trim_start();
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '|')
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
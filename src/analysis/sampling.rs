@@ -0,0 +1,183 @@
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{entries, error::FfProbeError, Config, EntriesSpec, ProbeCommand};
+
+use super::{heuristics::seconds, run_ffmpeg};
+
+/// Which parts of an input a frame based analysis decodes.
+///
+/// Sample positions only depend on the strategy and the input duration, so repeated
+/// runs inspect the same frames and the decoding cost is known upfront.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SamplingStrategy {
+    /// `count` windows of `window` length, evenly spread over the input.
+    /// The very start and end (often black or credits) are skipped.
+    Uniform {
+        count: u32,
+        #[serde(with = "seconds")]
+        window: Duration,
+    },
+    /// Every `n`th keyframe of the whole input. Only keyframes are decoded.
+    EveryNthKeyframe { n: u32 },
+    /// A single window of the input.
+    Window {
+        #[serde(with = "seconds")]
+        start: Duration,
+        #[serde(with = "seconds")]
+        duration: Duration,
+    },
+}
+
+impl Default for SamplingStrategy {
+    fn default() -> Self {
+        SamplingStrategy::Uniform {
+            count: 10,
+            window: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Time window resolved from a [`SamplingStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleWindow {
+    pub start: Duration,
+    /// `None` until the end of the input.
+    pub duration: Option<Duration>,
+}
+
+impl SampleWindow {
+    /// ffmpeg input options seeking to the window, eg. `-ss 12.5 -t 2`.
+    pub fn ffmpeg_input_args(&self) -> Vec<String> {
+        let mut args = vec!["-ss".to_string(), self.start.as_secs_f64().to_string()];
+        if let Some(duration) = self.duration {
+            args.extend(["-t".to_string(), duration.as_secs_f64().to_string()]);
+        }
+        args
+    }
+}
+
+impl SamplingStrategy {
+    /// Windows decoded for an input of the given duration.
+    ///
+    /// [`SamplingStrategy::Uniform`] needs the input duration and falls back to a
+    /// single window at the start if it is unknown.
+    pub fn windows(&self, input_duration: Option<Duration>) -> Vec<SampleWindow> {
+        match *self {
+            SamplingStrategy::Uniform { count, window } => match input_duration {
+                Some(total) if count > 0 && total > window => {
+                    let step = total.as_secs_f64() / (count + 1) as f64;
+                    (1..=count)
+                        .map(|i| SampleWindow {
+                            start: Duration::from_secs_f64(
+                                (step * i as f64 - window.as_secs_f64() / 2.).max(0.),
+                            ),
+                            duration: Some(window),
+                        })
+                        .collect()
+                }
+                _ => vec![SampleWindow {
+                    start: Duration::ZERO,
+                    duration: Some(window),
+                }],
+            },
+            SamplingStrategy::EveryNthKeyframe { .. } => vec![SampleWindow {
+                start: Duration::ZERO,
+                duration: None,
+            }],
+            SamplingStrategy::Window { start, duration } => vec![SampleWindow {
+                start,
+                duration: Some(duration),
+            }],
+        }
+    }
+
+    /// Total decoded duration, an estimate of the cost of an analysis.
+    /// `None` if the whole input is decoded and its duration is unknown.
+    pub fn decoded_duration(&self, input_duration: Option<Duration>) -> Option<Duration> {
+        if let SamplingStrategy::EveryNthKeyframe { .. } = self {
+            return input_duration;
+        }
+        self.windows(input_duration)
+            .iter()
+            .map(|w| w.duration.or(input_duration))
+            .sum()
+    }
+
+    /// ffmpeg input options and filter prefix for [`SamplingStrategy::EveryNthKeyframe`],
+    /// `None` for the window based strategies.
+    pub fn keyframe_args(&self) -> Option<([&'static str; 2], String)> {
+        match self {
+            SamplingStrategy::EveryNthKeyframe { n } => Some((
+                ["-skip_frame", "nokey"],
+                format!("select='not(mod(n,{}))'", n.max(&1)),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Duration of `path` for [`SamplingStrategy::windows`], only probed if `strategy`
+/// depends on it.
+pub(crate) fn input_duration(
+    config: &Config,
+    path: &Path,
+    strategy: &SamplingStrategy,
+) -> Result<Option<Duration>, FfProbeError> {
+    if !matches!(strategy, SamplingStrategy::Uniform { .. }) {
+        return Ok(None);
+    }
+    let probe = ProbeCommand::new(
+        config
+            .clone()
+            .count_frames(false)
+            .read_intervals(Vec::new()),
+    )
+    .show_entries(EntriesSpec::new().format(["duration"]));
+    Ok(entries::run(probe, path)?
+        .format_field::<f64>("duration")
+        .and_then(|d| Duration::try_from_secs_f64(d).ok()))
+}
+
+/// Run `filter` on the first video stream of each sample of `strategy`, returning the
+/// ffmpeg log of every sample together with its window.
+pub(crate) fn run_sampled(
+    config: &Config,
+    path: &Path,
+    strategy: &SamplingStrategy,
+    input_duration: Option<Duration>,
+    filter: &str,
+) -> Result<Vec<(SampleWindow, String)>, FfProbeError> {
+    if let Some((input_args, select)) = strategy.keyframe_args() {
+        let filter = format!("{},{}", select, filter);
+        let log = run_ffmpeg(
+            config,
+            path,
+            &input_args,
+            &["-map", "0:v:0", "-filter:v", &filter],
+        )?;
+        let whole = SampleWindow {
+            start: Duration::ZERO,
+            duration: None,
+        };
+        return Ok(vec![(whole, log)]);
+    }
+
+    strategy
+        .windows(input_duration)
+        .into_iter()
+        .map(|window| {
+            let input_args = window.ffmpeg_input_args();
+            let input_args: Vec<&str> = input_args.iter().map(String::as_str).collect();
+            let log = run_ffmpeg(
+                config,
+                path,
+                &input_args,
+                &["-map", "0:v:0", "-filter:v", filter],
+            )?;
+            Ok((window, log))
+        })
+        .collect()
+}
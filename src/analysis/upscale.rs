@@ -0,0 +1,205 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{error::FfProbeError, Config, EntriesSpec, ProbeCommand};
+
+use super::{
+    sampling::{run_sampled, SampleWindow},
+    value_after,
+};
+
+/// Common vertical resolutions, used as candidates for the source resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResolutionClass {
+    Sd,
+    Hd720,
+    Hd1080,
+    Qhd1440,
+    Uhd2160,
+    Uhd4320,
+}
+
+impl ResolutionClass {
+    pub const ALL: [ResolutionClass; 6] = [
+        ResolutionClass::Sd,
+        ResolutionClass::Hd720,
+        ResolutionClass::Hd1080,
+        ResolutionClass::Qhd1440,
+        ResolutionClass::Uhd2160,
+        ResolutionClass::Uhd4320,
+    ];
+
+    /// Nominal height in lines, eg. 1080.
+    pub fn height(self) -> i64 {
+        match self {
+            ResolutionClass::Sd => 480,
+            ResolutionClass::Hd720 => 720,
+            ResolutionClass::Hd1080 => 1080,
+            ResolutionClass::Qhd1440 => 1440,
+            ResolutionClass::Uhd2160 => 2160,
+            ResolutionClass::Uhd4320 => 4320,
+        }
+    }
+
+    /// Class of a frame height, eg. 1088 or 800 lines are [`ResolutionClass::Hd1080`].
+    pub fn from_height(height: i64) -> ResolutionClass {
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|c| height >= c.height() * 9 / 10)
+            .unwrap_or(ResolutionClass::Sd)
+    }
+}
+
+impl Display for ResolutionClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionClass::Sd => write!(f, "SD"),
+            ResolutionClass::Uhd2160 => write!(f, "4K"),
+            ResolutionClass::Uhd4320 => write!(f, "8K"),
+            c => write!(f, "{}p", c.height()),
+        }
+    }
+}
+
+/// Result of [`detect_upscale`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpscaleReport {
+    /// Height of the first video stream.
+    pub height: i64,
+    /// Estimated resolution of the source the video was made from.
+    pub source: ResolutionClass,
+    /// Share of the samples agreeing with `source`, from 0 to 1.
+    pub confidence: f64,
+    pub samples: Vec<UpscaleSample>,
+}
+
+impl UpscaleReport {
+    /// Whether the video is likely upscaled from a lower resolution source.
+    pub fn is_upscaled(&self) -> bool {
+        self.source < ResolutionClass::from_height(self.height)
+    }
+}
+
+/// Measurement of a single sample window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpscaleSample {
+    pub window: SampleWindow,
+    /// PSNR in dB between the frames and a down- and upscaled copy, per candidate
+    /// class. High values mean the frames carry no detail above that resolution.
+    pub psnr: Vec<(ResolutionClass, f64)>,
+    /// Lowest candidate reaching [`super::UpscaleDetectOptions::psnr_threshold`].
+    /// `None` if the frames have detail above all candidates.
+    pub source: Option<ResolutionClass>,
+}
+
+/// Estimate the true source resolution of the first video stream.
+///
+/// Every sample of [`super::Heuristics::sampling`] is scaled down to each lower
+/// resolution class and back up. A lossless round trip means the frames only contain
+/// detail of the lower resolution, ie. they were upscaled.
+pub fn detect_upscale(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<UpscaleReport, FfProbeError> {
+    let path = path.as_ref();
+    let options = &config.heuristics.upscale;
+
+    let probe = ProbeCommand::new(
+        config
+            .clone()
+            .count_frames(false)
+            .read_intervals(Vec::new()),
+    )
+    .show_entries(
+        EntriesSpec::new()
+            .stream(["width", "height"])
+            .format(["duration"]),
+    )
+    .args(["-select_streams", "v:0"]);
+    let entries = crate::entries::run(probe, path)?;
+    let (Some(width), Some(height)) = (
+        entries.stream_field::<i64>(0, "width"),
+        entries.stream_field::<i64>(0, "height"),
+    ) else {
+        return Err(crate::error::invalid_data("no video stream found"));
+    };
+    let duration = entries
+        .format_field::<f64>("duration")
+        .and_then(|d| std::time::Duration::try_from_secs_f64(d).ok());
+
+    let candidates: Vec<ResolutionClass> = ResolutionClass::ALL
+        .into_iter()
+        .filter(|c| c.height() < ResolutionClass::from_height(height).height())
+        .collect();
+    if candidates.is_empty() {
+        return Ok(UpscaleReport {
+            height,
+            source: ResolutionClass::from_height(height),
+            confidence: 1.,
+            samples: Vec::new(),
+        });
+    }
+
+    let filter = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            format!(
+                "split[ref{i}][low{i}];\
+                 [low{i}]scale=-2:{}:flags=bicubic,scale={width}:{height}:flags=bicubic[up{i}];\
+                 [ref{i}][up{i}]psnr@c{}",
+                c.height(),
+                c.height()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let samples: Vec<UpscaleSample> =
+        run_sampled(config, path, &config.heuristics.sampling, duration, &filter)?
+            .into_iter()
+            .map(|(window, log)| {
+                let psnr: Vec<(ResolutionClass, f64)> = candidates
+                    .iter()
+                    .filter_map(|c| {
+                        let tag = format!("psnr@c{} ", c.height());
+                        log.lines()
+                            .filter(|line| line.contains(&tag))
+                            .find_map(|line| value_after(line, "average"))
+                            .map(|psnr| (*c, psnr))
+                    })
+                    .collect();
+                let source = psnr
+                    .iter()
+                    .find(|(_, psnr)| *psnr >= options.psnr_threshold)
+                    .map(|(c, _)| *c);
+                UpscaleSample {
+                    window,
+                    psnr,
+                    source,
+                }
+            })
+            .collect();
+
+    let native = ResolutionClass::from_height(height);
+    let estimates: Vec<ResolutionClass> =
+        samples.iter().map(|s| s.source.unwrap_or(native)).collect();
+    // Most common estimate, ties go to the higher resolution.
+    let source = ResolutionClass::ALL
+        .into_iter()
+        .max_by_key(|c| estimates.iter().filter(|e| *e == c).count())
+        .unwrap_or(native);
+    let agreeing = estimates.iter().filter(|e| **e == source).count();
+    let confidence = if estimates.is_empty() {
+        0.
+    } else {
+        agreeing as f64 / estimates.len() as f64
+    };
+
+    Ok(UpscaleReport {
+        height,
+        source,
+        confidence,
+        samples,
+    })
+}
@@ -1,21 +1,47 @@
+use std::path::Path;
+#[cfg(feature = "process")]
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg(feature = "process")]
+use crate::{
+    command::{ffmpeg_input, path_arg},
+    error::FfProbeError,
+    Config, StreamIndex,
+};
+use crate::{
+    flexible_num,
+    tags::{capture, CaptureTags, Tags},
+};
+#[cfg(all(feature = "format", feature = "process"))]
+use crate::{FfProbe, StreamKinds};
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Tags specific for attachments
+#[non_exhaustive]
 pub struct AttachmentTags {
     pub filename: String,
     pub mimetype: String,
     pub title: Option<String>,
     #[serde(flatten)]
     pub extra: std::collections::HashMap<String, serde_json::Value>,
+    /// All tags, including the ones covered by the typed fields.
+    #[serde(skip)]
+    pub all: Tags,
 }
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
 /// Stream of type attachment
+#[non_exhaustive]
 pub struct AttachmentStream {
     /// Duration of the video stream in timestamp units.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub duration_ts: u64,
     /// Metadata tags associated with the video stream.
+    #[serde(deserialize_with = "capture")]
     pub tags: AttachmentTags,
     /// Long name of the codec used for the video stream.
     pub codec_long_name: Option<String>,
@@ -29,3 +55,161 @@ pub struct AttachmentStream {
     #[cfg(feature = "__internal_deny_unknown_fields")]
     duration: Option<serde_json::Value>,
 }
+
+impl AttachmentStream {
+    /// Attachment with the given filename and mimetype.
+    pub fn new(filename: impl Into<String>, mimetype: impl Into<String>) -> Self {
+        let (filename, mimetype) = (filename.into(), mimetype.into());
+        Self {
+            tags: AttachmentTags {
+                all: [("filename", &filename), ("mimetype", &mimetype)]
+                    .into_iter()
+                    .collect(),
+                filename,
+                mimetype,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Whether the attachment is a font, as embedded for styled subtitles in Matroska.
+    pub fn is_font(&self) -> bool {
+        let mimetype = self.tags.mimetype.to_ascii_lowercase();
+        mimetype.starts_with("font/")
+            || matches!(
+                mimetype.as_str(),
+                "application/x-truetype-font"
+                    | "application/x-font-ttf"
+                    | "application/x-font-otf"
+                    | "application/x-font-opentype"
+                    | "application/vnd.ms-opentype"
+                    | "application/font-sfnt"
+            )
+            || matches!(
+                extension(&self.tags.filename).as_deref(),
+                Some("ttf" | "otf" | "ttc" | "woff" | "woff2")
+            )
+    }
+
+    /// Whether the attachment is cover art, ie. an image named `cover`, `small_cover`,
+    /// `cover_land` or `small_cover_land` as recommended by the Matroska specification.
+    pub fn is_cover_art(&self) -> bool {
+        let is_image = self
+            .tags
+            .mimetype
+            .to_ascii_lowercase()
+            .starts_with("image/")
+            || matches!(
+                extension(&self.tags.filename).as_deref(),
+                Some("jpg" | "jpeg" | "png" | "webp")
+            );
+        let stem = Path::new(&self.tags.filename)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        is_image && stem.contains("cover")
+    }
+}
+
+fn extension(filename: &str) -> Option<String> {
+    Path::new(filename)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+}
+
+#[cfg(all(feature = "format", feature = "process"))]
+impl FfProbe {
+    /// Extract the attachment stream with the given index with default settings.
+    ///
+    /// See [`FfProbe::extract_attachment_config`] for details.
+    pub fn extract_attachment(
+        &self,
+        index: impl Into<StreamIndex>,
+        dest: impl AsRef<Path>,
+    ) -> Result<PathBuf, FfProbeError> {
+        self.extract_attachment_config(&Config::new(), index, dest)
+    }
+
+    /// Extract the attachment stream with the given index from the probed file
+    /// using `ffmpeg -dump_attachment`.
+    ///
+    /// If `dest` is a directory, the attachment is written to it under its `filename` tag.
+    /// Existing files are overwritten. Returns the path of the written file.
+    pub fn extract_attachment_config(
+        &self,
+        config: &Config,
+        index: impl Into<StreamIndex>,
+        dest: impl AsRef<Path>,
+    ) -> Result<PathBuf, FfProbeError> {
+        let index = index.into();
+        let attachment = self
+            .streams
+            .iter()
+            .find(|s| s.index == index)
+            .and_then(|s| match &s.stream {
+                StreamKinds::Attachment(attachment) => Some(attachment),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                FfProbeError::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("stream {index} is not an attachment"),
+                ))
+            })?;
+
+        dump_attachment(
+            config,
+            Path::new(&self.format.filename),
+            index,
+            attachment,
+            dest.as_ref(),
+        )
+    }
+}
+
+/// Write attachment stream `index` of `input` to `dest` with `ffmpeg -dump_attachment`.
+/// If `dest` is a directory, the `filename` tag is used as file name.
+#[cfg(feature = "process")]
+pub(crate) fn dump_attachment(
+    config: &Config,
+    input: &Path,
+    index: StreamIndex,
+    attachment: &AttachmentStream,
+    dest: &Path,
+) -> Result<PathBuf, FfProbeError> {
+    let dest = if dest.is_dir() {
+        // Only keep the file name, the tag is not trusted to stay inside `dest`.
+        let filename = Path::new(&attachment.tags.filename)
+            .file_name()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| format!("attachment_{index}").into());
+        dest.join(filename)
+    } else {
+        dest.to_path_buf()
+    };
+    if dest.exists() {
+        std::fs::remove_file(&dest).map_err(FfProbeError::Io)?;
+    }
+
+    let mut cmd = config.command(&config.ffmpeg_bin);
+    cmd.args(["-hide_banner", "-nostdin", "-y"]);
+    cmd.arg(format!("-dump_attachment:{index}"))
+        .arg(path_arg(dest.as_os_str()));
+    cmd.args(ffmpeg_input(config, input).map_err(FfProbeError::from_io)?);
+    let out = config
+        .output(&mut cmd)
+        .map_err(|e| FfProbeError::from_io(e).command_context(Some(input), &cmd))?;
+
+    // ffmpeg exits with an error without an output file, even though the attachment was dumped.
+    if !dest.exists() {
+        return Err(FfProbeError::Status(out).command_context(Some(input), &cmd));
+    }
+    Ok(dest)
+}
+
+impl CaptureTags for AttachmentTags {
+    fn all_mut(&mut self) -> &mut Tags {
+        &mut self.all
+    }
+}
@@ -2,10 +2,20 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::streams::{option_string_to_int, string_to_int, StreamTags};
+use crate::{
+    channel_layout::ChannelLayout,
+    flexible_num,
+    language::Language,
+    sample_format::{self, SampleFormat},
+    streams::StreamTags,
+    tags::{capture_opt, CaptureTags, Tags},
+    units::{Bitrate, SampleRate},
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Tags specific for audio
+#[non_exhaustive]
 pub struct AudioTags {
     #[serde(flatten)]
     pub tags: StreamTags,
@@ -15,26 +25,34 @@ pub struct AudioTags {
     pub source_id: Option<String>,
     #[serde(rename = "COMMENT")]
     pub comment: Option<String>,
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub track: Option<i64>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
+    /// All tags, including the ones covered by the typed fields.
+    #[serde(skip)]
+    pub all: Tags,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
 /// Stream of type audio
+#[non_exhaustive]
 pub struct AudioStream {
     /// The number of bits per sample in the audio stream.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub bits_per_sample: i64,
     /// The layout of audio channels.
     /// eg. stereo
-    pub channel_layout: Option<String>,
+    pub channel_layout: Option<ChannelLayout>,
     /// number of channels
     /// eg. 2
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub channels: i64,
     /// The initial padding in the audio stream.
     /// Padding in audio streams refers to extra bits or bytes added to the beginning of audio data to align it with specific boundaries or to provide some additional space for processing.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub initial_padding: i64,
     /// The sample format of the audio stream.
     /// Smple format defines how each audio sample is represented in binary form. It describes the encoding method and the number of bits used to represent each sample.
@@ -44,30 +62,33 @@ pub struct AudioStream {
     /// s32: Signed 32-bit integer
     /// flt: Floating point
     /// dbl: Double precision floating point
-    /// todo: enum
-    pub sample_fmt: String,
+    ///
+    /// `None` for `unknown`, eg. for streams ffprobe has no decoder for.
+    #[serde(deserialize_with = "sample_format::option", default)]
+    pub sample_fmt: Option<SampleFormat>,
     /// The sample rate of the audio stream.
     /// eg. 44100 Hz
-    #[serde(deserialize_with = "string_to_int")]
-    pub sample_rate: i64,
+    pub sample_rate: SampleRate,
     /// Bit rate of the video stream.
     /// The bit_rate represents the number of bits that are processed per unit of time in the video stream. It is a measure of the video stream's data rate, indicating how much data is encoded for each second of video.
-    #[serde(deserialize_with = "option_string_to_int", default)]
-    pub bit_rate: Option<i64>,
+    #[serde(default)]
+    pub bit_rate: Option<Bitrate>,
     /// Long name of the codec used for the video stream.
     pub codec_long_name: String,
     /// Short name of the codec used for the video stream.
     /// Example: h264
     pub codec_name: String,
     /// Duration of the video stream in timestamp units.
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub duration_ts: Option<u64>,
     /// Profile of the codec used for the video stream (e.g., Main, High).
     // todo: enum
     pub profile: Option<String>,
     ///  This specifies the number of bits used to represent each component of the pixel. For example, in an 8-bit raw sample, each color component (e.g., red, green, and blue in an RGB format) is represented by 8 bits, allowing 256 different levels per component.
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub bits_per_raw_sample: Option<i64>,
     /// Metadata tags associated with the video stream.
+    #[serde(deserialize_with = "capture_opt", default)]
     pub tags: Option<AudioTags>,
     #[cfg(feature = "__internal_deny_unknown_fields")]
     codec_type: Option<serde_json::Value>,
@@ -76,3 +97,43 @@ pub struct AudioStream {
     #[cfg(feature = "__internal_deny_unknown_fields")]
     duration: Option<serde_json::Value>,
 }
+
+impl AudioStream {
+    /// Audio stream with the given codec, sample rate and channel count.
+    pub fn new(codec_name: impl Into<String>, sample_rate: SampleRate, channels: i64) -> Self {
+        Self {
+            codec_name: codec_name.into(),
+            sample_rate,
+            channels,
+            ..Default::default()
+        }
+    }
+
+    /// Language of the `language` tag, see [`Language`].
+    /// `None` if the language is missing or undetermined (`und`).
+    pub fn language(&self) -> Option<Language> {
+        Language::parse(self.tags.as_ref()?.tags.language.as_deref()?)
+    }
+
+    /// [`AudioStream::sample_fmt`], `None` if unknown.
+    pub fn sample_format(&self) -> Option<SampleFormat> {
+        self.sample_fmt
+    }
+
+    /// Bits of precision of the source samples, eg. 24 for a 24 bit FLAC decoded to `s32`.
+    /// From `bits_per_raw_sample`, or `bits_per_sample` of PCM codecs. `None` for lossy
+    /// codecs, whose decoded [`SampleFormat`] says nothing about the source.
+    pub fn bit_depth(&self) -> Option<u32> {
+        [self.bits_per_raw_sample, Some(self.bits_per_sample)]
+            .into_iter()
+            .flatten()
+            .find(|bits| *bits > 0)
+            .and_then(|bits| u32::try_from(bits).ok())
+    }
+}
+
+impl CaptureTags for AudioTags {
+    fn all_mut(&mut self) -> &mut Tags {
+        &mut self.all
+    }
+}
@@ -0,0 +1,196 @@
+//! Probe media files with the library and print a summary, the JSON of the typed
+//! result or selected fields.
+//!
+//! ```text
+//! ffprobe-rs video.mp4
+//! ffprobe-rs --json video.mp4
+//! ffprobe-rs -f format.duration -f streams[0].codec_name video.mp4
+//! ```
+//!
+//! Built with the `cli` feature: `cargo install ffprobe --features cli`.
+
+use std::{ffi::OsString, path::PathBuf, process::ExitCode, time::Duration};
+
+use ffprobe::{Config, FfProbe, StreamKinds};
+use serde_json::Value;
+
+const USAGE: &str = "\
+usage: ffprobe-rs [options] <file>...
+
+options:
+  --json              print the typed result as JSON
+  -f, --field <path>  print the value at a path, eg. `streams[0].width`, repeatable
+  --ffprobe <bin>     ffprobe binary to run, default `ffprobe`
+  -h, --help          print this help";
+
+#[derive(Debug, Default)]
+struct Args {
+    help: bool,
+    json: bool,
+    fields: Vec<String>,
+    ffprobe: Option<PathBuf>,
+    inputs: Vec<PathBuf>,
+}
+
+fn value(args: &mut impl Iterator<Item = OsString>, name: &str) -> Result<OsString, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for `{name}`"))
+}
+
+fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-h" | "--help") => {
+                parsed.help = true;
+                return Ok(parsed);
+            }
+            Some("--json") => parsed.json = true,
+            Some(name @ ("-f" | "--field")) => parsed.fields.push(
+                value(&mut args, name)?
+                    .into_string()
+                    .map_err(|_| format!("`{name}` must be UTF-8"))?,
+            ),
+            Some(name @ "--ffprobe") => parsed.ffprobe = Some(value(&mut args, name)?.into()),
+            Some("--") => {
+                parsed.inputs.extend(args.map(PathBuf::from));
+                break;
+            }
+            Some(option) if option.starts_with('-') && option != "-" => {
+                return Err(format!("unknown option `{option}`"));
+            }
+            _ => parsed.inputs.push(arg.into()),
+        }
+    }
+    if parsed.json && !parsed.fields.is_empty() {
+        return Err("`--json` and `--field` are exclusive".into());
+    }
+    if parsed.inputs.is_empty() {
+        return Err("no input file".into());
+    }
+    Ok(parsed)
+}
+
+/// `1:02:03.500`, or `2:03.500` below an hour.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let millis = duration.subsec_millis();
+    match secs / 3600 {
+        0 => format!("{}:{:02}.{millis:03}", secs / 60, secs % 60),
+        hours => format!("{hours}:{:02}:{:02}.{millis:03}", secs / 60 % 60, secs % 60),
+    }
+}
+
+fn print_summary(probe: &FfProbe) {
+    let format = &probe.format;
+    let mut line = format!("{}: {}", format.filename, format.format_name);
+    if let Some(duration) = format.duration {
+        line += &format!(", {}", format_duration(duration));
+    }
+    line += &format!(", {}", format.size);
+    if let Some(bit_rate) = format.bit_rate {
+        line += &format!(", {bit_rate}");
+    }
+    println!("{line}");
+
+    for stream in &probe.streams {
+        let mut line = format!("  #{} ", stream.index);
+        match &stream.stream {
+            StreamKinds::Video(video) => {
+                line += &format!(
+                    "video {} {}x{}",
+                    video.codec_name, video.width, video.height
+                );
+                if let Some(fps) = stream.frame_rate().and_then(|r| r.best_guess()) {
+                    line += &format!(" {fps:.3} fps");
+                }
+                if stream.disposition.attached_pic != 0 {
+                    line += " (cover art)";
+                }
+            }
+            StreamKinds::Audio(audio) => {
+                line += &format!("audio {} {}", audio.codec_name, audio.sample_rate);
+                match &audio.channel_layout {
+                    Some(layout) => line += &format!(" {layout}"),
+                    None => line += &format!(" {}ch", audio.channels),
+                }
+            }
+            StreamKinds::Subtitle(subtitle) => line += &format!("subtitle {}", subtitle.codec_name),
+            StreamKinds::Attachment(attachment) => {
+                line += "attachment";
+                if let Some(codec) = &attachment.codec_name {
+                    line += &format!(" {codec}");
+                }
+            }
+            StreamKinds::Data(data) => {
+                line += "data";
+                if let Some(codec) = &data.codec_name {
+                    line += &format!(" {codec}");
+                }
+            }
+        }
+        if let Some(language) = stream.all_tags().get("language") {
+            line += &format!(" [{language}]");
+        }
+        println!("{line}");
+    }
+    if !probe.chapters.is_empty() {
+        println!("  {} chapters", probe.chapters.len());
+    }
+}
+
+/// Strings without quotes, like `jq -r`, missing values as `null`.
+fn print_field(value: Option<Value>) {
+    match value {
+        Some(Value::String(value)) => println!("{value}"),
+        Some(value) => println!("{value}"),
+        None => println!("null"),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args_os().skip(1)) {
+        Ok(args) if args.help => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("ffprobe-rs: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let mut config = Config::new();
+    if let Some(bin) = &args.ffprobe {
+        config = config.ffprobe_bin(bin);
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for input in &args.inputs {
+        let probe = match config.clone().run(input) {
+            Ok(probe) => probe,
+            Err(err) => {
+                eprintln!("ffprobe-rs: {}: {}", input.display(), err.inner());
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        if args.json {
+            match serde_json::to_string_pretty(&probe) {
+                Ok(json) => println!("{json}"),
+                Err(err) => {
+                    eprintln!("ffprobe-rs: {}: {err}", input.display());
+                    status = ExitCode::FAILURE;
+                }
+            }
+        } else if !args.fields.is_empty() {
+            for field in &args.fields {
+                print_field(probe.query(field));
+            }
+        } else {
+            print_summary(&probe);
+        }
+    }
+    status
+}
@@ -0,0 +1,56 @@
+use crate::{streams::StreamKinds, Bitrate, Stream};
+#[cfg(feature = "format")]
+use crate::{FfProbe, Format, StreamIndex};
+
+impl Stream {
+    /// Bit rate of the stream, from the first available source:
+    ///
+    /// 1. `bit_rate` reported by ffprobe
+    /// 2. the `BPS` statistics tag written by mkvmerge, or a language variant like `BPS-eng`
+    /// 3. the `NUMBER_OF_BYTES` and `DURATION` statistics tags, see [`Stream::mkv_stats`]
+    ///
+    /// See [`FfProbe::stream_bitrate`] to fall back to the format bit rate.
+    pub fn bitrate(&self) -> Option<Bitrate> {
+        let reported = match &self.stream {
+            StreamKinds::Video(v) => v.bit_rate,
+            StreamKinds::Audio(a) => a.bit_rate,
+            StreamKinds::Subtitle(s) => s.bit_rate,
+            StreamKinds::Attachment(_) | StreamKinds::Data(_) => None,
+        };
+        reported.or_else(|| self.mkv_stats()?.effective_bitrate())
+    }
+}
+
+#[cfg(feature = "format")]
+impl Format {
+    /// Overall bit rate of the file: `bit_rate` reported by ffprobe, or computed from
+    /// the file size and duration.
+    pub fn bitrate(&self) -> Option<Bitrate> {
+        self.bit_rate.or_else(|| {
+            let duration = self.duration.filter(|d| !d.is_zero())?;
+            Bitrate::from_size(self.size, duration).filter(|b| b.0 > 0)
+        })
+    }
+}
+
+#[cfg(feature = "format")]
+impl FfProbe {
+    /// Bit rate of the stream with the given index, see [`Stream::bitrate`].
+    ///
+    /// Falls back to [`Format::bitrate`] if the file has a single audio or video
+    /// stream besides cover art, eg. for MP3 or raw AAC files.
+    pub fn stream_bitrate(&self, index: impl Into<StreamIndex>) -> Option<Bitrate> {
+        let index = index.into();
+        let stream = self.streams.iter().find(|s| s.index == index)?;
+        stream.bitrate().or_else(|| {
+            let mut media = self.streams.iter().filter(|s| {
+                matches!(s.stream, StreamKinds::Video(_) | StreamKinds::Audio(_))
+                    && s.disposition.attached_pic == 0
+            });
+            match (media.next(), media.next()) {
+                (Some(only), None) if only.index == index => self.format.bitrate(),
+                _ => None,
+            }
+        })
+    }
+}
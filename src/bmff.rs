@@ -0,0 +1,124 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::error::{invalid_data, FfProbeError};
+
+/// Iterate over the ISO BMFF boxes in `data`, yielding (type, payload).
+pub(crate) fn boxes(mut data: &[u8]) -> impl Iterator<Item = Result<(&[u8], &[u8]), String>> {
+    std::iter::from_fn(move || {
+        if data.is_empty() {
+            return None;
+        }
+        if data.len() < 8 {
+            data = &[];
+            return Some(Err("truncated box header".to_string()));
+        }
+        let mut size = u32::from_be_bytes(data[0..4].try_into().unwrap()) as u64;
+        let kind = &data[4..8];
+        let mut header = 8;
+        if size == 1 {
+            if data.len() < 16 {
+                data = &[];
+                return Some(Err("truncated box header".to_string()));
+            }
+            size = u64::from_be_bytes(data[8..16].try_into().unwrap());
+            header = 16;
+        } else if size == 0 {
+            size = data.len() as u64;
+        }
+        if size < header as u64 || size > data.len() as u64 {
+            data = &[];
+            return Some(Err(format!(
+                "box '{}' exceeds its parent",
+                String::from_utf8_lossy(kind)
+            )));
+        }
+        let (current, rest) = data.split_at(size as usize);
+        data = rest;
+        Some(Ok((kind, &current[header..])))
+    })
+}
+
+pub(crate) fn find_box<'a>(data: &'a [u8], kind: &[u8]) -> Result<Option<&'a [u8]>, String> {
+    for item in boxes(data) {
+        let (k, payload) = item?;
+        if k == kind {
+            return Ok(Some(payload));
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| "truncated box".to_string())
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64, String> {
+    data.get(offset..offset + 8)
+        .map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| "truncated box".to_string())
+}
+
+/// Top level box of a file, see [`scan`].
+pub(crate) struct TopLevelBox {
+    pub kind: [u8; 4],
+    /// Byte offset of the box header.
+    pub offset: u64,
+    /// Size including the header.
+    pub size: u64,
+    /// Box payload, only read for the requested box types.
+    pub payload: Option<Vec<u8>>,
+}
+
+/// Walk the top level boxes of `reader`, reading the payload of boxes listed in `read`
+/// and seeking over everything else (eg. `mdat`).
+pub(crate) fn scan<R: Read + Seek>(
+    mut reader: R,
+    read: &[&[u8; 4]],
+) -> Result<Vec<TopLevelBox>, FfProbeError> {
+    let len = reader.seek(SeekFrom::End(0)).map_err(FfProbeError::Io)?;
+    let mut offset = 0;
+    let mut out = Vec::new();
+    while offset + 8 <= len {
+        reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(FfProbeError::Io)?;
+        let mut header = [0u8; 16];
+        reader
+            .read_exact(&mut header[..8])
+            .map_err(FfProbeError::Io)?;
+        let kind: [u8; 4] = header[4..8].try_into().unwrap();
+        let (size, header_len) = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            0 => (len - offset, 8),
+            1 => {
+                reader
+                    .read_exact(&mut header[8..])
+                    .map_err(FfProbeError::Io)?;
+                (u64::from_be_bytes(header[8..].try_into().unwrap()), 16)
+            }
+            size => (size as u64, 8),
+        };
+        if size < header_len || size > len.saturating_sub(offset) {
+            return Err(invalid_data(format!(
+                "box '{}' at offset {offset} exceeds the file",
+                String::from_utf8_lossy(&kind)
+            )));
+        }
+        let payload = if read.contains(&&kind) {
+            let mut payload = vec![0; (size - header_len) as usize];
+            reader.read_exact(&mut payload).map_err(FfProbeError::Io)?;
+            Some(payload)
+        } else {
+            None
+        };
+        out.push(TopLevelBox {
+            kind,
+            offset,
+            size,
+            payload,
+        });
+        offset += size;
+    }
+    Ok(out)
+}
@@ -0,0 +1,134 @@
+//! ffmpeg arguments derived from a probe, for the transcode following it.
+//!
+//! ```rust
+//! use ffprobe::{bridge::StreamSelection, StreamKinds};
+//!
+//! # let probe = ffprobe::FfProbe::new();
+//! let mut cmd = std::process::Command::new("ffmpeg");
+//! for stream in &probe.streams {
+//!     if let StreamKinds::Video(video) = &stream.stream {
+//!         cmd.args(video.decoder_args());
+//!         break;
+//!     }
+//! }
+//! cmd.args(["-i", "input.mkv"])
+//!     .args(probe.to_map_args(0, &StreamSelection::All));
+//! ```
+
+#[cfg(feature = "format")]
+use crate::Format;
+#[cfg(feature = "streams")]
+use crate::{mapping::StreamMapping, FfProbe, Stream, StreamIndex, StreamKinds, VideoStream};
+
+/// Streams for [`FfProbe::to_map_args`].
+#[cfg(feature = "streams")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StreamSelection {
+    /// Every video, audio and subtitle stream. Cover art, data and attachment streams
+    /// are left out, most muxers reject them.
+    All,
+    /// Video streams without cover art.
+    Video,
+    Audio,
+    Subtitle,
+    /// Streams by index, in this order.
+    Indices(Vec<StreamIndex>),
+    /// Streams picked by [`FfProbe::suggest_mapping`].
+    Mapping(StreamMapping),
+}
+
+#[cfg(feature = "streams")]
+impl From<StreamMapping> for StreamSelection {
+    fn from(mapping: StreamMapping) -> Self {
+        StreamSelection::Mapping(mapping)
+    }
+}
+
+#[cfg(feature = "streams")]
+impl FfProbe {
+    /// ffmpeg arguments mapping the `selection` of the input with index `input`, eg.
+    /// `["-map", "0:0", "-map", "0:1"]`. Indices the probe has no stream for are skipped.
+    pub fn to_map_args(&self, input: usize, selection: &StreamSelection) -> Vec<String> {
+        let is_cover_art = |stream: &Stream| stream.disposition.attached_pic != 0;
+        let indices: Vec<StreamIndex> = match selection {
+            StreamSelection::Indices(indices) => indices.clone(),
+            StreamSelection::Mapping(mapping) => [mapping.video, mapping.audio, mapping.subtitle]
+                .into_iter()
+                .flatten()
+                .collect(),
+            kind => self
+                .streams
+                .iter()
+                .filter(|stream| match (&stream.stream, kind) {
+                    (StreamKinds::Video(_), StreamSelection::All | StreamSelection::Video) => {
+                        !is_cover_art(stream)
+                    }
+                    (StreamKinds::Audio(_), StreamSelection::All | StreamSelection::Audio) => true,
+                    (
+                        StreamKinds::Subtitle(_),
+                        StreamSelection::All | StreamSelection::Subtitle,
+                    ) => true,
+                    _ => false,
+                })
+                .map(|stream| stream.index)
+                .collect(),
+        };
+        indices
+            .into_iter()
+            .filter(|index| self.streams.iter().any(|s| s.index == *index))
+            .flat_map(|index| ["-map".to_string(), format!("{input}:{index}")])
+            .collect()
+    }
+}
+
+#[cfg(feature = "streams")]
+impl VideoStream {
+    /// Pixel format frames of the stream have after hardware decoding, `nv12` for 8 bit
+    /// and `p010le` for 10 bit 4:2:0. `None` if hardware decoders commonly can't decode
+    /// the stream, eg. 4:2:2 or 10 bit H.264 and ProRes.
+    pub fn hwaccel_pix_fmt(&self) -> Option<&'static str> {
+        let pix_fmt = self.pix_fmt.as_deref()?;
+        let ten_bit = matches!(pix_fmt, "yuv420p10le" | "p010le");
+        if !ten_bit && !matches!(pix_fmt, "yuv420p" | "yuvj420p" | "nv12") {
+            return None;
+        }
+        match self.codec_name.as_str() {
+            "hevc" | "vp9" | "av1" => Some(if ten_bit { "p010le" } else { "nv12" }),
+            "h264" | "mpeg2video" | "vc1" | "wmv3" if !ten_bit => Some("nv12"),
+            _ => None,
+        }
+    }
+
+    /// Input options decoding the stream, placed before its `-i`: `-hwaccel auto` if
+    /// hardware decoders commonly support it, see [`VideoStream::hwaccel_pix_fmt`].
+    /// Empty otherwise, where ffmpeg would set up a device only to decode in software.
+    pub fn decoder_args(&self) -> Vec<String> {
+        match self.hwaccel_pix_fmt() {
+            Some(_) => vec!["-hwaccel".to_string(), "auto".to_string()],
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "format")]
+impl Format {
+    /// Whether ffmpeg can seek in the input, eg. with `-ss` before `-i`: it has a duration
+    /// and isn't a pipe or a live stream.
+    pub fn seekable(&self) -> bool {
+        let pipe = self.filename == "-"
+            || self.filename.starts_with("pipe:")
+            || self
+                .format_name
+                .split(',')
+                .any(|name| name.ends_with("pipe"));
+        let live = ["rtmp", "rtsp", "rtp", "udp", "srt", "tcp"]
+            .iter()
+            .any(|scheme| {
+                self.filename
+                    .strip_prefix(scheme)
+                    .is_some_and(|rest| rest.starts_with("://") || rest.starts_with("s://"))
+            });
+        self.duration.is_some() && !pipe && !live
+    }
+}
@@ -0,0 +1,377 @@
+//! Professional broadcast audio: SMPTE 302M PCM in MPEG-TS, and Dolby E carried in it
+//! or in PCM tracks as SMPTE 337M data bursts.
+
+use std::fmt::Display;
+#[cfg(feature = "process")]
+use std::path::Path;
+
+#[cfg(feature = "process")]
+use serde::Deserialize;
+
+#[cfg(feature = "process")]
+use crate::{
+    error::{self, invalid_data, FfProbeError},
+    extradata::parse_hex_dump,
+    Config, ProbeCommand, StreamIndex,
+};
+use crate::{ratio::Ratio, AudioStream};
+
+/// Broadcast audio format of a stream, see [`AudioStream::broadcast_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BroadcastAudioKind {
+    /// SMPTE 302M, AES3 PCM in MPEG-TS, eg. contribution feeds. Often carries Dolby E
+    /// instead of audio, see [`detect_dolby_e`].
+    Smpte302m,
+    /// Dolby E, as decoded by ffmpeg, eg. from SMPTE 337M bursts in WAV.
+    DolbyE,
+    /// Linear PCM, eg. BWF or AES3 tracks in MXF, which may also carry Dolby E.
+    Pcm,
+}
+
+impl AudioStream {
+    /// Broadcast audio format of the stream from its codec, `None` for distribution
+    /// codecs like AAC or AC-3.
+    ///
+    /// Dolby E in PCM or SMPTE 302M reports the PCM codec, use [`detect_dolby_e`] to
+    /// look at the samples.
+    pub fn broadcast_kind(&self) -> Option<BroadcastAudioKind> {
+        match self.codec_name.as_str() {
+            "s302m" => Some(BroadcastAudioKind::Smpte302m),
+            "dolby_e" => Some(BroadcastAudioKind::DolbyE),
+            codec if codec.starts_with("pcm_") => Some(BroadcastAudioKind::Pcm),
+            _ => None,
+        }
+    }
+}
+
+/// Dolby E program configuration: how the channels are split into programs, eg. `5.1+2`
+/// for a surround mix and a stereo mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DolbyEProgramConfig(u8);
+
+/// Name and channels per program of every program configuration.
+const PROGRAM_CONFIGS: [(&str, &[u8]); 24] = [
+    ("5.1+2", &[6, 2]),
+    ("5.1+1+1", &[6, 1, 1]),
+    ("4+4", &[4, 4]),
+    ("4+2+2", &[4, 2, 2]),
+    ("4+2+1+1", &[4, 2, 1, 1]),
+    ("4+1+1+1+1", &[4, 1, 1, 1, 1]),
+    ("2+2+2+2", &[2, 2, 2, 2]),
+    ("2+2+2+1+1", &[2, 2, 2, 1, 1]),
+    ("2+2+1+1+1+1", &[2, 2, 1, 1, 1, 1]),
+    ("2+1+1+1+1+1+1", &[2, 1, 1, 1, 1, 1, 1]),
+    ("1+1+1+1+1+1+1+1", &[1, 1, 1, 1, 1, 1, 1, 1]),
+    ("5.1", &[6]),
+    ("4+2", &[4, 2]),
+    ("4+1+1", &[4, 1, 1]),
+    ("2+2+2", &[2, 2, 2]),
+    ("2+2+1+1", &[2, 2, 1, 1]),
+    ("2+1+1+1+1", &[2, 1, 1, 1, 1]),
+    ("1+1+1+1+1+1", &[1, 1, 1, 1, 1, 1]),
+    ("4", &[4]),
+    ("2+2", &[2, 2]),
+    ("2+1+1", &[2, 1, 1]),
+    ("1+1+1+1", &[1, 1, 1, 1]),
+    ("7.1", &[8]),
+    ("7.1 screen", &[8]),
+];
+
+impl DolbyEProgramConfig {
+    /// Program configuration with the given code from the Dolby E metadata, 0 to 23.
+    pub fn new(code: u8) -> Option<Self> {
+        (usize::from(code) < PROGRAM_CONFIGS.len()).then_some(DolbyEProgramConfig(code))
+    }
+
+    pub fn code(&self) -> u8 {
+        self.0
+    }
+
+    /// Channels of each program, eg. `[6, 2]` for `5.1+2`.
+    pub fn programs(&self) -> &'static [u8] {
+        PROGRAM_CONFIGS[usize::from(self.0)].1
+    }
+
+    /// Channels of all programs, 4, 6 or 8.
+    pub fn channels(&self) -> u8 {
+        self.programs().iter().sum()
+    }
+}
+
+impl Display for DolbyEProgramConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(PROGRAM_CONFIGS[usize::from(self.0)].0)
+    }
+}
+
+/// Dolby E found by [`detect_dolby_e`], or parsed from packet data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DolbyE {
+    /// First of the two channels carrying the bursts, eg. 0 for channels 1 and 2. Always
+    /// 0 for streams ffmpeg decodes as Dolby E.
+    pub channel: u8,
+    /// Word size of the Dolby E data, 16, 20 or 24 bits.
+    pub bit_depth: u8,
+    pub program_config: DolbyEProgramConfig,
+    /// Video frame rate the Dolby E frames are aligned to, eg. 25 or 30000/1001.
+    pub frame_rate: Option<Ratio>,
+}
+
+impl DolbyE {
+    /// Parse the header of a Dolby E frame from its `bit_depth` bit words, starting with
+    /// the sync word.
+    fn parse(channel: u8, bit_depth: u8, mut words: impl Iterator<Item = u32>) -> Option<Self> {
+        let sync = words.next()?;
+        if sync & !1 != sync_word(bit_depth)? {
+            return None;
+        }
+        // Odd sync words are followed by a key the metadata is XORed with.
+        let key = if sync & 1 == 1 { words.next()? } else { 0 };
+        let mut bits = BitReader::new(words.map(|w| w ^ key), bit_depth);
+        // Segment id and metadata size.
+        bits.read(14)?;
+        let program_config = DolbyEProgramConfig::new(bits.read(6)? as u8)?;
+        let frame_rate = match bits.read(4)? {
+            1 => Some(Ratio::new(24000, 1001)),
+            2 => Some(Ratio::new(24, 1)),
+            3 => Some(Ratio::new(25, 1)),
+            4 => Some(Ratio::new(30000, 1001)),
+            5 => Some(Ratio::new(30, 1)),
+            6 => Some(Ratio::new(50, 1)),
+            7 => Some(Ratio::new(60000, 1001)),
+            8 => Some(Ratio::new(60, 1)),
+            _ => None,
+        };
+        Some(DolbyE {
+            channel,
+            bit_depth,
+            program_config,
+            frame_rate,
+        })
+    }
+
+    /// Dolby E in a packet of a stream ffmpeg demuxes as `dolby_e`: the frame as packed
+    /// big endian words.
+    pub fn from_frame(frame: &[u8]) -> Option<Self> {
+        let head = frame.get(..3)?;
+        let head = u32::from_be_bytes([0, head[0], head[1], head[2]]);
+        let bit_depth = [16, 20, 24]
+            .into_iter()
+            .find(|&bits| Some(head >> (24 - bits) & !1) == sync_word(bits))?;
+        let mut bytes = BitReader::new(frame.iter().map(|&b| u32::from(b)), 8);
+        let words = std::iter::from_fn(move || bytes.read(bit_depth));
+        DolbyE::parse(0, bit_depth, words)
+    }
+
+    /// Dolby E in consecutive packets of a SMPTE 302M stream, `None` if they carry audio.
+    pub fn from_s302m(packets: &[impl AsRef<[u8]>]) -> Option<Self> {
+        let (channels, bits, samples) = s302m_samples(packets)?;
+        find_dolby_e(&samples, channels, bits)
+    }
+}
+
+/// Dolby E sync word of `bit_depth` bit frames, without the key flag in the lowest bit.
+fn sync_word(bit_depth: u8) -> Option<u32> {
+    match bit_depth {
+        16 => Some(0x078e),
+        20 => Some(0x0788e),
+        24 => Some(0x07888e),
+        _ => None,
+    }
+}
+
+/// SMPTE 337M preamble words Pa and Pb for 16, 20 and 24 bit data.
+const PREAMBLES: [(u8, u32, u32); 3] = [
+    (16, 0xf872, 0x4e1f),
+    (20, 0x6f872, 0x54e1f),
+    (24, 0x96f872, 0xa54e1f),
+];
+
+/// SMPTE 337M data type of Dolby E.
+const DATA_TYPE_DOLBY_E: u32 = 28;
+
+/// Find a Dolby E burst in interleaved PCM samples of `bits` bits.
+fn find_dolby_e(samples: &[u32], channels: u8, bits: u8) -> Option<DolbyE> {
+    let channels = usize::from(channels);
+    for pair in (0..channels / 2).map(|p| p * 2) {
+        // Subframes of the pair in order, the bursts alternate between both channels.
+        let words: Vec<u32> = samples
+            .chunks_exact(channels)
+            .flat_map(|frame| [frame[pair], frame[pair + 1]])
+            .collect();
+        for (depth, pa, pb) in PREAMBLES.into_iter().filter(|(d, ..)| *d <= bits) {
+            // Data words are left aligned in the samples.
+            let shift = bits - depth;
+            let burst = words
+                .windows(2)
+                .position(|w| w[0] >> shift == pa && w[1] >> shift == pb);
+            let Some(start) = burst else {
+                continue;
+            };
+            let mut data = words[start + 2..].iter().map(|w| w >> shift);
+            // Pc holds the data type in its low bits, left aligned in 20 and 24 bit words.
+            let pc = data.next()? >> (depth - 16);
+            if pc & 0x1f != DATA_TYPE_DOLBY_E {
+                continue;
+            }
+            // Pd, the burst length.
+            data.next()?;
+            if let Some(dolby_e) = DolbyE::parse(pair as u8, depth, data) {
+                return Some(dolby_e);
+            }
+        }
+    }
+    None
+}
+
+/// Decode SMPTE 302M packets like ffmpeg's `s302m` decoder, returning the channel count,
+/// bits per sample and the interleaved samples.
+///
+/// Packets start with a 4 byte header with the payload size, channels, channel id and
+/// bits per sample. Each pair of samples is followed by 4 bits of AES3 flags, and every
+/// byte is stored bit reversed.
+fn s302m_samples(packets: &[impl AsRef<[u8]>]) -> Option<(u8, u8, Vec<u32>)> {
+    let mut layout = None;
+    let mut samples = Vec::new();
+    for packet in packets {
+        let packet = packet.as_ref();
+        let header = u32::from_be_bytes(packet.get(..4)?.try_into().ok()?);
+        let channels = ((header >> 14) & 3) as u8 * 2 + 2;
+        let bits = match (header >> 4) & 3 {
+            0 => 16,
+            1 => 20,
+            2 => 24,
+            _ => return None,
+        };
+        if *layout.get_or_insert((channels, bits)) != (channels, bits) {
+            return None;
+        }
+        let r = |b: u8| u32::from(b.reverse_bits());
+        let data = &packet[4..];
+        match bits {
+            16 => data.chunks_exact(5).for_each(|b| {
+                samples.push(r(b[1]) << 8 | r(b[0]));
+                samples.push(r(b[4] & 0xf0) << 12 | r(b[3]) << 4 | r(b[2]) >> 4);
+            }),
+            20 => data.chunks_exact(6).for_each(|b| {
+                samples.push(r(b[2] & 0xf0) << 16 | r(b[1]) << 8 | r(b[0]));
+                samples.push(r(b[5] & 0xf0) << 16 | r(b[4]) << 8 | r(b[3]));
+            }),
+            _ => data.chunks_exact(7).for_each(|b| {
+                samples.push(r(b[2]) << 16 | r(b[1]) << 8 | r(b[0]));
+                samples.push(
+                    r(b[6] & 0xf0) << 20 | r(b[5]) << 12 | r(b[4]) << 4 | r(b[3] & 0x0f) >> 4,
+                );
+            }),
+        }
+    }
+    let (channels, bits) = layout?;
+    Some((channels, bits, samples))
+}
+
+/// Reads bit fields from `width` bit words, most significant bit first.
+struct BitReader<I> {
+    words: I,
+    width: u8,
+    word: u32,
+    left: u8,
+}
+
+impl<I: Iterator<Item = u32>> BitReader<I> {
+    fn new(words: I, width: u8) -> Self {
+        BitReader {
+            words,
+            width,
+            word: 0,
+            left: 0,
+        }
+    }
+
+    fn read(&mut self, bits: u8) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..bits {
+            if self.left == 0 {
+                self.word = self.words.next()?;
+                self.left = self.width;
+            }
+            self.left -= 1;
+            value = value << 1 | (self.word >> self.left & 1);
+        }
+        Some(value)
+    }
+}
+
+/// Packets of the stream read by [`detect_dolby_e`]. Dolby E frames are aligned to video
+/// frames, so this covers several bursts.
+#[cfg(feature = "process")]
+const DOLBY_E_SCAN_PACKETS: u64 = 10;
+
+/// Look for Dolby E in audio stream `stream` of `path`, eg. a SMPTE 302M stream of a
+/// contribution feed, from the data of its first packets.
+///
+/// Finds the SMPTE 337M burst preamble in the samples of SMPTE 302M streams and reads
+/// the program configuration from the Dolby E frame header. Streams ffmpeg already
+/// decodes as Dolby E are read directly. `None` if the stream carries audio instead, or
+/// is linear PCM outside of MPEG-TS, which isn't inspected.
+#[cfg(feature = "process")]
+pub fn detect_dolby_e(
+    path: impl AsRef<Path>,
+    stream: impl Into<StreamIndex>,
+    config: &Config,
+) -> Result<Option<DolbyE>, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        streams: Vec<StreamInfo>,
+        #[serde(default)]
+        packets: Vec<Packet>,
+    }
+    #[derive(Deserialize)]
+    struct StreamInfo {
+        codec_name: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct Packet {
+        #[serde(default)]
+        codec_type: String,
+        #[serde(default)]
+        data: String,
+    }
+
+    let path = path.as_ref();
+    error::check_input(path)?;
+    let cmd = ProbeCommand::new(config.clone())
+        .default_sections(false)
+        .args(["-select_streams", &stream.into().to_string()])
+        .arg("-show_data")
+        .args(["-show_entries", "packet=codec_type,data:stream=codec_name"])
+        .args(["-read_intervals", &format!("%+#{DOLBY_E_SCAN_PACKETS}")])
+        .input(path);
+    let out = cmd.output().map_err(FfProbeError::from_io);
+    let output: Output = out
+        .and_then(|out| {
+            if !out.status.success() {
+                return Err(error::status_error(path, out));
+            }
+            serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)
+        })
+        .map_err(|e| e.context(path, &cmd))?;
+    let codec = output.streams.first().and_then(|s| s.codec_name.as_deref());
+    let packets = output
+        .packets
+        .iter()
+        .filter(|p| p.codec_type.is_empty() || p.codec_type == "audio")
+        .map(|p| {
+            parse_hex_dump(&p.data)
+                .ok_or_else(|| invalid_data("invalid packet data in the ffprobe output"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(match codec {
+        Some("dolby_e") => packets.iter().find_map(|p| DolbyE::from_frame(p)),
+        Some("s302m") => DolbyE::from_s302m(&packets),
+        _ => None,
+    })
+}
@@ -0,0 +1,255 @@
+use std::ops::Range;
+#[cfg(feature = "process")]
+use std::path::Path;
+
+#[cfg(feature = "process")]
+use serde::Deserialize;
+
+#[cfg(feature = "process")]
+use crate::{
+    error::{self, FfProbeError},
+    flexible_num, Config, ProbeCommand,
+};
+use crate::{FfProbe, StreamIndex};
+
+/// Where the data of a file is, computed with [`Config::byte_layout`](crate::Config::byte_layout).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ByteLayout {
+    /// Size of the file in bytes.
+    pub file_size: Option<u64>,
+    /// Byte ranges of the packets of each stream, by stream index.
+    pub streams: Vec<StreamByteRange>,
+    /// Top level boxes of MP4 and QuickTime files in file order, eg. `ftyp`, `moov` and
+    /// `mdat`, from ffprobe's trace log. Empty for other formats.
+    pub boxes: Vec<BoxLocation>,
+}
+
+/// Bytes taken by the packets of a stream, see [`ByteLayout::streams`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StreamByteRange {
+    pub index: StreamIndex,
+    /// Offset of the first packet.
+    pub first_pos: u64,
+    /// End of the last packet, ie. its offset plus its size.
+    pub end: u64,
+    pub packets: u64,
+    /// Sum of the packet sizes.
+    pub bytes: u64,
+}
+
+/// Top level box of an MP4 file, see [`ByteLayout::boxes`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BoxLocation {
+    /// eg. `moov`
+    pub kind: String,
+    /// Offset of the box header.
+    pub offset: u64,
+    /// Size including the header.
+    pub size: u64,
+}
+
+impl ByteLayout {
+    /// First top level box of type `kind`, eg. `moov`.
+    pub fn find_box(&self, kind: &str) -> Option<&BoxLocation> {
+        self.boxes.iter().find(|b| b.kind == kind)
+    }
+
+    /// Bytes from the first packet of any stream to the end of the last one.
+    pub fn data_range(&self) -> Option<Range<u64>> {
+        let start = self.streams.iter().map(|s| s.first_pos).min()?;
+        let end = self.streams.iter().map(|s| s.end).max()?;
+        Some(start..end)
+    }
+
+    /// Bytes before the first packet, eg. the `moov` box of a faststart MP4 or the
+    /// Matroska header and tracks.
+    pub fn header_size(&self) -> Option<u64> {
+        self.data_range().map(|r| r.start)
+    }
+
+    /// Bytes after the last packet, eg. the `moov` box of an MP4 that isn't faststart.
+    pub fn trailer_size(&self) -> Option<u64> {
+        Some(self.file_size?.saturating_sub(self.data_range()?.end))
+    }
+
+    /// Whether the index of the file comes before the media data, so players can start
+    /// while downloading. From the `moov` and `mdat` boxes if known, otherwise from
+    /// whether more bytes precede the packets than follow them.
+    pub fn is_faststart(&self) -> Option<bool> {
+        if let Some(moov) = self.find_box("moov") {
+            let data = self
+                .find_box("mdat")
+                .map(|mdat| mdat.offset)
+                .or(self.header_size())?;
+            return Some(moov.offset < data);
+        }
+        Some(self.header_size()? >= self.trailer_size()?)
+    }
+}
+
+impl FfProbe {
+    /// Whether an MP4 or QuickTime file can be played while downloading, ie. its `moov`
+    /// box comes before the media data, as written by `ffmpeg -movflags +faststart`.
+    /// `None` without [`FfProbe::byte_layout`] or for other formats.
+    pub fn is_faststart(&self) -> Option<bool> {
+        let layout = self.byte_layout.as_ref()?;
+        #[cfg(feature = "format")]
+        if !self
+            .format
+            .format_name
+            .split(',')
+            .any(|name| name == "mov" || name == "mp4")
+        {
+            return None;
+        }
+        layout.is_faststart()
+    }
+}
+
+/// Top level boxes logged by the mov demuxer at `-v trace`, eg.
+/// `[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'moov' parent:'root' sz: 1234 1242 5678`
+/// with the size, the bytes of the parent read so far and the size of the parent.
+///
+/// Offsets are the end of the previous box, as the demuxer reads the boxes in order. A
+/// second pass over the root, eg. for the fragments of a fragmented MP4, ends the list.
+#[cfg(feature = "process")]
+pub(crate) fn parse_box_trace(log: &str, file_size: Option<u64>) -> Vec<BoxLocation> {
+    let mut boxes: Vec<BoxLocation> = Vec::new();
+    let mut end = 0;
+    for line in log.lines() {
+        let Some((_, entry)) = line.split_once("type:'") else {
+            continue;
+        };
+        let Some((kind, rest)) = entry.split_once("' parent:'root' sz: ") else {
+            continue;
+        };
+        let mut sizes = rest.split_whitespace().map(|n| n.parse::<u64>().ok());
+        let (Some(Some(size)), Some(Some(read))) = (sizes.next(), sizes.next()) else {
+            break;
+        };
+        let size = match read.checked_sub(end) {
+            // 64 bit size, logged without the extra 8 header bytes.
+            Some(16) => size + 8,
+            // Size 0 extends to the end of the file.
+            Some(8) if size == 0 => match file_size {
+                Some(file_size) => file_size.saturating_sub(end),
+                None => break,
+            },
+            Some(8) => size,
+            _ => break,
+        };
+        boxes.push(BoxLocation {
+            kind: kind.to_string(),
+            offset: end,
+            size,
+        });
+        end += size;
+    }
+    boxes
+}
+
+/// Compute the [`ByteLayout`] of `path`: one ffprobe run at `-v trace` reading the
+/// header only for the MP4 boxes, one listing the position of every packet.
+#[cfg(feature = "process")]
+pub(crate) fn byte_layout(config: &Config, path: &Path) -> Result<ByteLayout, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        packets: Vec<Packet>,
+        format: Option<Format>,
+    }
+    #[derive(Deserialize)]
+    struct Packet {
+        stream_index: StreamIndex,
+        #[serde(deserialize_with = "flexible_num::option", default)]
+        pos: Option<u64>,
+        #[serde(deserialize_with = "flexible_num::option", default)]
+        size: Option<u64>,
+    }
+    #[derive(Deserialize)]
+    struct Format {
+        #[serde(default)]
+        format_name: String,
+        #[serde(deserialize_with = "flexible_num::option", default)]
+        size: Option<u64>,
+    }
+
+    // Read intervals and counts of the probe don't apply to the layout.
+    let mut config = config.clone();
+    config.read_intervals.clear();
+    config.count_frames = false;
+    config.count_packets = false;
+    let run = |args: &[&str]| {
+        let cmd = ProbeCommand::new(config.clone())
+            .default_sections(false)
+            .args(args)
+            .input(path);
+        let out = cmd.output().map_err(FfProbeError::from_io);
+        out.and_then(|out| {
+            if !out.status.success() {
+                return Err(error::status_error(path, out));
+            }
+            let output: Output =
+                serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)?;
+            Ok((output, out.stderr))
+        })
+        .map_err(|e| e.context(path, &cmd))
+    };
+
+    let (header, log) = run(&[
+        "-v",
+        "trace",
+        "-show_entries",
+        "format=format_name,size",
+        "-read_intervals",
+        "%+#1",
+    ])?;
+    let format = header.format.unwrap_or(Format {
+        format_name: String::new(),
+        size: None,
+    });
+    let boxes = if format.format_name.split(',').any(|n| n == "mov") {
+        parse_box_trace(&String::from_utf8_lossy(&log), format.size)
+    } else {
+        Vec::new()
+    };
+
+    let (output, _) = run(&[
+        "-v",
+        "error",
+        "-show_entries",
+        "packet=stream_index,pos,size",
+    ])?;
+    let mut streams: Vec<StreamByteRange> = Vec::new();
+    for packet in output.packets {
+        let Some(pos) = packet.pos else {
+            continue;
+        };
+        let size = packet.size.unwrap_or(0);
+        let range = match streams.iter_mut().find(|s| s.index == packet.stream_index) {
+            Some(range) => range,
+            None => {
+                streams.push(StreamByteRange {
+                    index: packet.stream_index,
+                    first_pos: pos,
+                    ..StreamByteRange::default()
+                });
+                streams.last_mut().unwrap()
+            }
+        };
+        range.first_pos = range.first_pos.min(pos);
+        range.end = range.end.max(pos + size);
+        range.packets += 1;
+        range.bytes += size;
+    }
+    streams.sort_by_key(|s| s.index);
+
+    Ok(ByteLayout {
+        file_size: format.size,
+        streams,
+        boxes,
+    })
+}
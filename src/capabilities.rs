@@ -0,0 +1,421 @@
+//! What the installed ffprobe build supports, eg. to check that it can read AV1 before
+//! queueing a job.
+//!
+//! ```rust,no_run
+//! let config = ffprobe::Config::new();
+//! let decoders = ffprobe::capabilities::decoders(&config).unwrap();
+//! if !decoders.iter().any(|d| d.name == "libdav1d" || d.name == "av1") {
+//!     eprintln!("ffprobe can't decode AV1");
+//! }
+//! ```
+
+use serde::Deserialize;
+
+use crate::{error::FfProbeError, Config};
+
+/// Pixel format, see [`pixel_formats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PixelFormat {
+    /// eg. `yuv420p10le`
+    pub name: String,
+    pub nb_components: u32,
+    /// Average bits per pixel, `None` for hardware formats.
+    pub bits_per_pixel: Option<u32>,
+    /// Largest bit depth of the components, eg. 10 for `yuv420p10le`.
+    pub bit_depth: Option<u32>,
+    pub planar: bool,
+    pub rgb: bool,
+    pub alpha: bool,
+    /// Hardware surface, eg. `vaapi` or `cuda`.
+    pub hwaccel: bool,
+}
+
+/// Media type of a [`Decoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecoderKind {
+    Video,
+    Audio,
+    Subtitle,
+    Data,
+    Attachment,
+}
+
+/// Decoder, see [`decoders`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Decoder {
+    /// Decoder name, eg. `h264` or `libdav1d`. External decoders are named after their
+    /// library, the codec is named in the description.
+    pub name: String,
+    pub kind: DecoderKind,
+    /// eg. `dav1d AV1 decoder by VideoLAN (codec av1)`
+    pub description: String,
+    /// Decoders marked experimental, which ffmpeg only uses with `-strict experimental`.
+    pub experimental: bool,
+}
+
+impl Decoder {
+    /// Codec the decoder reads, from the `(codec ...)` note of external decoders,
+    /// otherwise its name.
+    pub fn codec(&self) -> &str {
+        self.description
+            .rsplit_once("(codec ")
+            .and_then(|(_, codec)| codec.split_once(')'))
+            .map_or(&self.name, |(codec, _)| codec)
+    }
+}
+
+/// Demuxer, see [`demuxers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Demuxer {
+    /// Comma separated names, eg. `mov,mp4,m4a,3gp,3g2,mj2`, as in
+    /// [`Format::format_name`](crate::Format::format_name).
+    pub name: String,
+    /// eg. `QuickTime / MOV`
+    pub description: String,
+    /// Input device, eg. `v4l2` or `alsa`. Only reported by ffmpeg 6.1 and later.
+    pub is_device: bool,
+}
+
+impl Demuxer {
+    /// The names of [`Demuxer::name`].
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.name.split(',')
+    }
+}
+
+/// Protocols, see [`protocols`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Protocols {
+    /// Protocols ffprobe can read from, eg. `file`, `https` or `srt`.
+    pub input: Vec<String>,
+    /// Protocols ffmpeg can write to.
+    pub output: Vec<String>,
+}
+
+/// Version and configuration of the ffprobe build, see [`build_info`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// eg. `7.0.1`, or `N-113178-g2ead2d6` for builds from git.
+    pub version: String,
+    /// eg. `gcc 13.2.0 (GCC)`
+    pub compiler: Option<String>,
+    /// Arguments ffmpeg was configured with, eg. `--enable-gpl`.
+    pub configuration: Vec<String>,
+    /// Versions of the libraries ffprobe was built against, eg. libavformat.
+    pub libraries: Vec<LibraryVersion>,
+}
+
+impl BuildInfo {
+    /// Version of library `name`, with or without the `lib` prefix, eg. `avcodec`.
+    pub fn library(&self, name: &str) -> Option<&LibraryVersion> {
+        let name = name.strip_prefix("lib").unwrap_or(name);
+        self.libraries
+            .iter()
+            .find(|library| library.name.strip_prefix("lib") == Some(name))
+    }
+
+    /// Whether the build was configured with `--enable-{feature}`, eg. `libx264`.
+    pub fn enabled(&self, feature: &str) -> bool {
+        self.enabled_features().any(|enabled| enabled == feature)
+    }
+
+    /// Features enabled with `--enable-*`, eg. `gpl` or `libdav1d`.
+    pub fn enabled_features(&self) -> impl Iterator<Item = &str> {
+        self.configuration
+            .iter()
+            .filter_map(|arg| arg.strip_prefix("--enable-"))
+    }
+
+    /// Licensed under the GPL, which components like libx264 require.
+    pub fn is_gpl(&self) -> bool {
+        self.enabled("gpl")
+    }
+
+    /// Built with nonfree components, eg. `libfdk_aac`, which makes it unredistributable.
+    pub fn is_nonfree(&self) -> bool {
+        self.enabled("nonfree")
+    }
+}
+
+/// Version of a library of the ffprobe build, eg. libavformat 61.1.100.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct LibraryVersion {
+    /// eg. `libavformat`
+    pub name: String,
+    pub major: u32,
+    pub minor: u32,
+    pub micro: u32,
+}
+
+impl LibraryVersion {
+    /// Whether the version is `major.minor.micro` or later, eg. to require libavformat
+    /// 60.3.100 of ffmpeg 6.0.
+    pub fn at_least(&self, major: u32, minor: u32, micro: u32) -> bool {
+        (self.major, self.minor, self.micro) >= (major, minor, micro)
+    }
+}
+
+impl std::fmt::Display for LibraryVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}.{}.{}",
+            self.name, self.major, self.minor, self.micro
+        )
+    }
+}
+
+/// Version, configure flags and library versions of the ffprobe build
+/// (`-show_program_version -show_library_versions`), eg. to check for a GPL build or a
+/// minimum libavformat version before processing.
+pub fn build_info(config: &Config) -> Result<BuildInfo, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        program_version: ProgramVersion,
+        #[serde(default)]
+        library_versions: Vec<Library>,
+    }
+    #[derive(Deserialize, Default)]
+    struct ProgramVersion {
+        #[serde(default)]
+        version: String,
+        compiler_ident: Option<String>,
+        #[serde(default)]
+        configuration: String,
+    }
+    #[derive(Deserialize)]
+    struct Library {
+        name: String,
+        major: u32,
+        minor: u32,
+        micro: u32,
+    }
+
+    let out = list(
+        config,
+        &[
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_program_version",
+            "-show_library_versions",
+        ],
+    )?;
+    let out: Output = serde_json::from_str(&out).map_err(FfProbeError::Deserialize)?;
+    Ok(BuildInfo {
+        version: out.program_version.version,
+        compiler: out.program_version.compiler_ident,
+        configuration: configure_args(&out.program_version.configuration),
+        libraries: out
+            .library_versions
+            .into_iter()
+            .map(|library| LibraryVersion {
+                name: library.name,
+                major: library.major,
+                minor: library.minor,
+                micro: library.micro,
+            })
+            .collect(),
+    })
+}
+
+/// Split the configure command line, keeping quoted values like
+/// `--extra-cflags='-O2 -pipe'` together.
+fn configure_args(configuration: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut quote = None;
+    for c in configuration.chars() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, c) if c.is_whitespace() => {
+                if !arg.is_empty() {
+                    args.push(std::mem::take(&mut arg));
+                }
+            }
+            _ => arg.push(c),
+        }
+    }
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+    args
+}
+
+/// Pixel formats known to ffprobe (`-show_pixel_formats`).
+pub fn pixel_formats(config: &Config) -> Result<Vec<PixelFormat>, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        pixel_formats: Vec<Raw>,
+    }
+    #[derive(Deserialize)]
+    struct Raw {
+        name: String,
+        #[serde(default)]
+        nb_components: u32,
+        /// `"N/A"` for hardware formats with `-show_optional_fields always`.
+        #[serde(default)]
+        bits_per_pixel: serde_json::Value,
+        #[serde(default)]
+        flags: Flags,
+        #[serde(default)]
+        components: Vec<Component>,
+    }
+    #[derive(Deserialize, Default)]
+    struct Flags {
+        #[serde(default)]
+        planar: u8,
+        #[serde(default)]
+        rgb: u8,
+        #[serde(default)]
+        alpha: u8,
+        #[serde(default)]
+        hwaccel: u8,
+    }
+    #[derive(Deserialize)]
+    struct Component {
+        bit_depth: u32,
+    }
+
+    let out = list(
+        config,
+        &[
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_pixel_formats",
+        ],
+    )?;
+    let out: Output = serde_json::from_str(&out).map_err(FfProbeError::Deserialize)?;
+    Ok(out
+        .pixel_formats
+        .into_iter()
+        .map(|raw| PixelFormat {
+            name: raw.name,
+            nb_components: raw.nb_components,
+            bits_per_pixel: raw
+                .bits_per_pixel
+                .as_u64()
+                .and_then(|b| u32::try_from(b).ok())
+                .filter(|&b| b > 0),
+            bit_depth: raw.components.iter().map(|c| c.bit_depth).max(),
+            planar: raw.flags.planar != 0,
+            rgb: raw.flags.rgb != 0,
+            alpha: raw.flags.alpha != 0,
+            hwaccel: raw.flags.hwaccel != 0,
+        })
+        .collect())
+}
+
+/// Decoders of the ffprobe build (`-decoders`).
+pub fn decoders(config: &Config) -> Result<Vec<Decoder>, FfProbeError> {
+    let out = list(config, &["-hide_banner", "-decoders"])?;
+    Ok(table(&out)
+        .filter_map(|(flags, name, description)| {
+            let kind = match flags.chars().next()? {
+                'V' => DecoderKind::Video,
+                'A' => DecoderKind::Audio,
+                'S' => DecoderKind::Subtitle,
+                'D' => DecoderKind::Data,
+                'T' => DecoderKind::Attachment,
+                _ => return None,
+            };
+            Some(Decoder {
+                name: name.to_string(),
+                kind,
+                description: description.to_string(),
+                experimental: flags.get(3..4) == Some("X"),
+            })
+        })
+        .collect())
+}
+
+/// Demuxers of the ffprobe build (`-demuxers`).
+pub fn demuxers(config: &Config) -> Result<Vec<Demuxer>, FfProbeError> {
+    let out = list(config, &["-hide_banner", "-demuxers"])?;
+    Ok(table(&out)
+        .filter(|(flags, _, _)| flags.starts_with('D'))
+        .map(|(flags, name, description)| Demuxer {
+            name: name.to_string(),
+            description: description.to_string(),
+            is_device: flags.contains('d'),
+        })
+        .collect())
+}
+
+/// Input and output protocols of the ffprobe build (`-protocols`).
+pub fn protocols(config: &Config) -> Result<Protocols, FfProbeError> {
+    let out = list(config, &["-hide_banner", "-protocols"])?;
+    let mut protocols = Protocols::default();
+    let mut section = None;
+    for line in out.lines() {
+        match line.trim() {
+            "Input:" => section = Some(&mut protocols.input),
+            "Output:" => section = Some(&mut protocols.output),
+            "" => {}
+            name if line.starts_with(char::is_whitespace) => {
+                if let Some(section) = &mut section {
+                    section.push(name.to_string());
+                }
+            }
+            _ => section = None,
+        }
+    }
+    Ok(protocols)
+}
+
+/// Run ffprobe with the listing `args` and return its stdout.
+fn list(config: &Config, args: &[&str]) -> Result<String, FfProbeError> {
+    let mut cmd = config.command(&config.ffprobe_bin);
+    cmd.args(args);
+    let out = config
+        .output(&mut cmd)
+        .map_err(|e| FfProbeError::from_io(e).command_context(None, &cmd))?;
+    if !out.status.success() {
+        return Err(FfProbeError::Status(out).command_context(None, &cmd));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// `(flags, name, description)` rows of a `-decoders` or `-demuxers` table:
+///
+/// ```text
+/// File formats:
+///  D. = Demuxing supported
+///  .E = Muxing supported
+///  --
+///  D  mov,mp4,m4a,3gp,3g2,mj2 QuickTime / MOV
+/// ```
+///
+/// The flag columns are as wide as the `--` line separating the legend from the rows.
+fn table(out: &str) -> impl Iterator<Item = (&str, &str, &str)> {
+    let mut lines = out.lines();
+    let width = lines
+        .by_ref()
+        .find_map(|l| {
+            let l = l.trim();
+            (!l.is_empty() && l.bytes().all(|b| b == b'-')).then_some(l.len())
+        })
+        .unwrap_or(0);
+    lines.filter_map(move |line| {
+        let line = line.strip_prefix(' ').unwrap_or(line);
+        let flags = line.get(..width)?;
+        let (name, description) = line[width..]
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .unwrap_or((line[width..].trim(), ""));
+        (!name.is_empty()).then(|| (flags, name, description.trim()))
+    })
+}
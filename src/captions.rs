@@ -0,0 +1,193 @@
+use std::{
+    collections::BTreeSet,
+    ffi::{OsStr, OsString},
+    fmt::Display,
+    path::Path,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    command::{check_protocol, path_arg},
+    error::{invalid_data, FfProbeError},
+    extradata::parse_hex_dump,
+    Config,
+};
+
+/// Part of the input read by [`caption_services`].
+pub const CAPTION_SCAN_DURATION: Duration = Duration::from_secs(60);
+
+/// Closed caption channel or service carried in a video stream, see [`caption_services`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CaptionService {
+    /// EIA/CEA-608 channel 1-4, ie. CC1 to CC4. CC1 and CC2 are in field 1, CC3 and CC4
+    /// in field 2.
+    Cea608 { channel: u8 },
+    /// CEA-708 (DTVCC) service 1-63. Service 1 is the primary caption service,
+    /// 2 usually the secondary language.
+    Cea708 { service: u8 },
+}
+
+impl Display for CaptionService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptionService::Cea608 { channel } => write!(f, "CC{channel}"),
+            CaptionService::Cea708 { service } => write!(f, "Service {service}"),
+        }
+    }
+}
+
+/// List the closed caption channels and services of the first video stream, from the
+/// A53 caption data within the first [`CAPTION_SCAN_DURATION`] of the input.
+///
+/// Decodes the video with the lavfi `movie` source, whose `subcc` output carries the
+/// raw caption data, and reads it with ffprobe's `-show_data`. Empty if the video has no
+/// captions, see [`crate::VideoStream::has_closed_captions`] for a cheap check.
+pub fn caption_services(
+    path: impl AsRef<Path>,
+    config: &Config,
+) -> Result<Vec<CaptionService>, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        packets: Vec<Packet>,
+    }
+    #[derive(Deserialize)]
+    struct Packet {
+        #[serde(default)]
+        data: String,
+    }
+
+    // The movie source opens the input itself, without a `-protocol_whitelist`.
+    check_protocol(config, &path_arg(path.as_ref().as_os_str())).map_err(FfProbeError::from_io)?;
+    let mut cmd = config.command(&config.ffprobe_bin);
+    cmd.args(["-v", "quiet", "-print_format", "json"]);
+    cmd.args(["-show_packets", "-show_data", "-select_streams", "s"]);
+    cmd.arg("-read_intervals")
+        .arg(format!("%+{}", CAPTION_SCAN_DURATION.as_secs()));
+    let mut graph = OsString::from("movie=");
+    graph.push(lavfi_escape(path.as_ref().as_os_str()));
+    graph.push("[out0+subcc]");
+    cmd.args(["-f", "lavfi", "-i"]).arg(graph);
+    let out = config.output(&mut cmd).map_err(FfProbeError::from_io);
+    let output: Output = out
+        .and_then(|out| {
+            if !out.status.success() {
+                return Err(FfProbeError::Status(out));
+            }
+            serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)
+        })
+        .map_err(|e| e.command_context(Some(path.as_ref()), &cmd))?;
+    let mut decoder = CcDecoder::default();
+    for packet in &output.packets {
+        let data = parse_hex_dump(&packet.data)
+            .ok_or_else(|| invalid_data("invalid packet data in the ffprobe output"))?;
+        decoder.push(&data);
+    }
+    Ok(decoder.finish())
+}
+
+/// Escape a path as `movie` filter argument within a filtergraph. The special characters
+/// are ASCII, so on Unix the path is escaped bytewise and names that aren't valid UTF-8
+/// are passed on unchanged.
+fn lavfi_escape(path: &OsStr) -> OsString {
+    fn escape(s: &[u8], special: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(s.len());
+        for &c in s {
+            if special.contains(&c) {
+                out.push(b'\\');
+            }
+            out.push(c);
+        }
+        out
+    }
+    let escape_graph = |path: &[u8]| escape(&escape(path, b"\\':"), b"\\'[],;");
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+        OsString::from_vec(escape_graph(path.as_bytes()))
+    }
+    #[cfg(not(unix))]
+    {
+        let escaped = escape_graph(path.to_string_lossy().as_bytes());
+        String::from_utf8(escaped)
+            .expect("escaping keeps UTF-8 valid")
+            .into()
+    }
+}
+
+/// Collects the services of A53 `cc_data` triplets: a header byte with the valid flag and
+/// the type, followed by two data bytes.
+#[derive(Default)]
+struct CcDecoder {
+    services: BTreeSet<CaptionService>,
+    dtvcc: Vec<u8>,
+}
+
+impl CcDecoder {
+    fn push(&mut self, cc_data: &[u8]) {
+        for triplet in cc_data.chunks_exact(3) {
+            let valid = triplet[0] & 0x04 != 0;
+            let cc_type = triplet[0] & 0x03;
+            if !valid {
+                continue;
+            }
+            match cc_type {
+                0 | 1 => self.push_608(cc_type, triplet[1] & 0x7f, triplet[2] & 0x7f),
+                3 => {
+                    self.flush_dtvcc();
+                    self.dtvcc.extend_from_slice(&triplet[1..]);
+                }
+                _ => self.dtvcc.extend_from_slice(&triplet[1..]),
+            }
+        }
+    }
+
+    /// Control codes (`0x10`-`0x1f` followed by `0x20`-`0x7f`) carry the data channel in
+    /// bit 3 of the first byte.
+    fn push_608(&mut self, field: u8, first: u8, second: u8) {
+        if (0x10..=0x1f).contains(&first) && (0x20..=0x7f).contains(&second) {
+            let channel = 1 + field * 2 + (first & 0x08 != 0) as u8;
+            self.services.insert(CaptionService::Cea608 { channel });
+        }
+    }
+
+    /// A DTVCC packet: a header with the size, then service blocks with a header of the
+    /// service number (3 bits, 7 for an extended number byte) and block size (5 bits).
+    fn flush_dtvcc(&mut self) {
+        let packet = std::mem::take(&mut self.dtvcc);
+        let Some((&header, rest)) = packet.split_first() else {
+            return;
+        };
+        let size = match (header & 0x3f) as usize {
+            0 => 127,
+            code => code * 2 - 1,
+        };
+        let mut blocks = &rest[..size.min(rest.len())];
+        while let Some((&block_header, rest)) = blocks.split_first() {
+            let mut service = block_header >> 5;
+            let block_size = (block_header & 0x1f) as usize;
+            let mut rest = rest;
+            if service == 0 {
+                break;
+            }
+            if service == 7 {
+                let Some((&extended, after)) = rest.split_first() else {
+                    break;
+                };
+                service = extended & 0x3f;
+                rest = after;
+            }
+            if block_size > 0 && service > 0 {
+                self.services.insert(CaptionService::Cea708 { service });
+            }
+            blocks = rest.get(block_size..).unwrap_or_default();
+        }
+    }
+
+    fn finish(mut self) -> Vec<CaptionService> {
+        self.flush_dtvcc();
+        self.services.into_iter().collect()
+    }
+}
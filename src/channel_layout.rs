@@ -0,0 +1,326 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Speaker position of an audio channel, named as in ffmpeg.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// front left
+    FL,
+    /// front right
+    FR,
+    /// front center
+    FC,
+    /// low frequency effects
+    LFE,
+    /// back left
+    BL,
+    /// back right
+    BR,
+    /// front left of center
+    FLC,
+    /// front right of center
+    FRC,
+    /// back center
+    BC,
+    /// side left
+    SL,
+    /// side right
+    SR,
+    /// top center
+    TC,
+    /// top front left
+    TFL,
+    /// top front center
+    TFC,
+    /// top front right
+    TFR,
+    /// top back left
+    TBL,
+    /// top back center
+    TBC,
+    /// top back right
+    TBR,
+    /// downmix left
+    DL,
+    /// downmix right
+    DR,
+    /// wide left
+    WL,
+    /// wide right
+    WR,
+    /// surround direct left
+    SDL,
+    /// surround direct right
+    SDR,
+    /// second low frequency effects
+    LFE2,
+    /// top side left
+    TSL,
+    /// top side right
+    TSR,
+    /// bottom front center
+    BFC,
+    /// bottom front left
+    BFL,
+    /// bottom front right
+    BFR,
+    /// Position not known to this crate, eg. `USR3`.
+    Other(String),
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Channel::FL => "FL",
+            Channel::FR => "FR",
+            Channel::FC => "FC",
+            Channel::LFE => "LFE",
+            Channel::BL => "BL",
+            Channel::BR => "BR",
+            Channel::FLC => "FLC",
+            Channel::FRC => "FRC",
+            Channel::BC => "BC",
+            Channel::SL => "SL",
+            Channel::SR => "SR",
+            Channel::TC => "TC",
+            Channel::TFL => "TFL",
+            Channel::TFC => "TFC",
+            Channel::TFR => "TFR",
+            Channel::TBL => "TBL",
+            Channel::TBC => "TBC",
+            Channel::TBR => "TBR",
+            Channel::DL => "DL",
+            Channel::DR => "DR",
+            Channel::WL => "WL",
+            Channel::WR => "WR",
+            Channel::SDL => "SDL",
+            Channel::SDR => "SDR",
+            Channel::LFE2 => "LFE2",
+            Channel::TSL => "TSL",
+            Channel::TSR => "TSR",
+            Channel::BFC => "BFC",
+            Channel::BFL => "BFL",
+            Channel::BFR => "BFR",
+            Channel::Other(name) => name,
+        }
+    }
+
+    /// Whether this is a low frequency effects channel.
+    pub fn is_lfe(&self) -> bool {
+        matches!(self, Channel::LFE | Channel::LFE2)
+    }
+
+    fn parse(name: &str) -> Channel {
+        match name {
+            "FL" => Channel::FL,
+            "FR" => Channel::FR,
+            "FC" => Channel::FC,
+            "LFE" => Channel::LFE,
+            "BL" => Channel::BL,
+            "BR" => Channel::BR,
+            "FLC" => Channel::FLC,
+            "FRC" => Channel::FRC,
+            "BC" => Channel::BC,
+            "SL" => Channel::SL,
+            "SR" => Channel::SR,
+            "TC" => Channel::TC,
+            "TFL" => Channel::TFL,
+            "TFC" => Channel::TFC,
+            "TFR" => Channel::TFR,
+            "TBL" => Channel::TBL,
+            "TBC" => Channel::TBC,
+            "TBR" => Channel::TBR,
+            "DL" => Channel::DL,
+            "DR" => Channel::DR,
+            "WL" => Channel::WL,
+            "WR" => Channel::WR,
+            "SDL" => Channel::SDL,
+            "SDR" => Channel::SDR,
+            "LFE2" => Channel::LFE2,
+            "TSL" => Channel::TSL,
+            "TSR" => Channel::TSR,
+            "BFC" => Channel::BFC,
+            "BFL" => Channel::BFL,
+            "BFR" => Channel::BFR,
+            other => Channel::Other(other.to_string()),
+        }
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Named layouts of ffmpeg and their channels, in ffmpeg channel order.
+const NAMED_LAYOUTS: &[(&str, &str)] = &[
+    ("mono", "FC"),
+    ("stereo", "FL+FR"),
+    ("2.1", "FL+FR+LFE"),
+    ("3.0", "FL+FR+FC"),
+    ("3.0(back)", "FL+FR+BC"),
+    ("4.0", "FL+FR+FC+BC"),
+    ("quad", "FL+FR+BL+BR"),
+    ("quad(side)", "FL+FR+SL+SR"),
+    ("3.1", "FL+FR+FC+LFE"),
+    ("5.0", "FL+FR+FC+BL+BR"),
+    ("5.0(side)", "FL+FR+FC+SL+SR"),
+    ("4.1", "FL+FR+FC+LFE+BC"),
+    ("5.1", "FL+FR+FC+LFE+BL+BR"),
+    ("5.1(side)", "FL+FR+FC+LFE+SL+SR"),
+    ("6.0", "FL+FR+FC+BC+SL+SR"),
+    ("6.0(front)", "FL+FR+FLC+FRC+SL+SR"),
+    ("3.1.2", "FL+FR+FC+LFE+TFL+TFR"),
+    ("hexagonal", "FL+FR+FC+BL+BR+BC"),
+    ("6.1", "FL+FR+FC+LFE+BC+SL+SR"),
+    ("6.1(back)", "FL+FR+FC+LFE+BL+BR+BC"),
+    ("6.1(front)", "FL+FR+LFE+FLC+FRC+SL+SR"),
+    ("7.0", "FL+FR+FC+BL+BR+SL+SR"),
+    ("7.0(front)", "FL+FR+FC+FLC+FRC+SL+SR"),
+    ("7.1", "FL+FR+FC+LFE+BL+BR+SL+SR"),
+    ("7.1(wide)", "FL+FR+FC+LFE+BL+BR+FLC+FRC"),
+    ("7.1(wide-side)", "FL+FR+FC+LFE+FLC+FRC+SL+SR"),
+    ("5.1.2", "FL+FR+FC+LFE+BL+BR+TFL+TFR"),
+    ("octagonal", "FL+FR+FC+BL+BR+BC+SL+SR"),
+    ("cube", "FL+FR+BL+BR+TFL+TFR+TBL+TBR"),
+    ("5.1.4", "FL+FR+FC+LFE+BL+BR+TFL+TFR+TBL+TBR"),
+    ("7.1.2", "FL+FR+FC+LFE+BL+BR+SL+SR+TFL+TFR"),
+    ("7.1.4", "FL+FR+FC+LFE+BL+BR+SL+SR+TFL+TFR+TBL+TBR"),
+    ("7.2.3", "FL+FR+FC+LFE+BL+BR+SL+SR+TFL+TFR+TBC+LFE2"),
+    ("9.1.4", "FL+FR+FC+LFE+BL+BR+FLC+FRC+SL+SR+TFL+TFR+TBL+TBR"),
+    ("downmix", "DL+DR"),
+];
+
+/// Audio channel layout, as reported in `channel_layout`, eg. `5.1(side)`.
+///
+/// Parses the named layouts of ffmpeg, custom layouts like `FL+FR+LFE` and
+/// unknown layouts like `6 channels`. Displays as the original string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChannelLayout {
+    name: String,
+    channels: Option<Vec<Channel>>,
+    count: usize,
+}
+
+impl ChannelLayout {
+    /// Name as reported by ffprobe.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.count
+    }
+
+    /// Channel positions in ffmpeg order, `None` for layouts like `6 channels`
+    /// that only specify the count.
+    pub fn channels(&self) -> Option<&[Channel]> {
+        self.channels.as_deref()
+    }
+
+    /// Whether the layout contains a low frequency effects channel.
+    pub fn has_lfe(&self) -> bool {
+        self.channels()
+            .unwrap_or_default()
+            .iter()
+            .any(Channel::is_lfe)
+    }
+
+    /// Order of an ambisonic layout, eg. 1 for `ambisonic 1`, whose first (1 + 1)² channels
+    /// are the ambisonic components `AMBI0` to `AMBI3`.
+    pub fn ambisonic_order(&self) -> Option<usize> {
+        let rest = self.name.strip_prefix("ambisonic ")?;
+        rest.split('+').next()?.parse().ok()
+    }
+
+    /// Number of channels without the low frequency effects channels, eg. 5 for `5.1`.
+    pub fn main_channel_count(&self) -> usize {
+        let lfe = self
+            .channels()
+            .unwrap_or_default()
+            .iter()
+            .filter(|c| c.is_lfe())
+            .count();
+        self.count - lfe
+    }
+}
+
+impl Display for ChannelLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl FromStr for ChannelLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_string();
+        if let Some((_, channels)) = NAMED_LAYOUTS.iter().find(|(n, _)| *n == s) {
+            let channels: Vec<Channel> = channels.split('+').map(Channel::parse).collect();
+            return Ok(ChannelLayout {
+                name,
+                count: channels.len(),
+                channels: Some(channels),
+            });
+        }
+        if let Some(count) = s
+            .strip_suffix(" channels")
+            .or_else(|| s.strip_suffix(" channel"))
+        {
+            let count = count
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid channel layout: {s}"))?;
+            return Ok(ChannelLayout {
+                name,
+                channels: None,
+                count,
+            });
+        }
+        if let Some(rest) = s.strip_prefix("ambisonic ") {
+            // eg. `ambisonic 1+stereo`, with the non-diegetic channels after the `+`.
+            let (order, extra) = rest.split_once('+').unwrap_or((rest, ""));
+            let order: usize = order
+                .parse()
+                .map_err(|_| format!("invalid channel layout: {s}"))?;
+            let mut channels: Vec<Channel> = (0..(order + 1).pow(2))
+                .map(|i| Channel::Other(format!("AMBI{i}")))
+                .collect();
+            if !extra.is_empty() {
+                let extra: ChannelLayout = extra.parse()?;
+                channels.extend(extra.channels.unwrap_or_default());
+            }
+            return Ok(ChannelLayout {
+                name,
+                count: channels.len(),
+                channels: Some(channels),
+            });
+        }
+        if s.is_empty() || s.contains(char::is_whitespace) {
+            return Err(format!("invalid channel layout: {s}"));
+        }
+        // Custom layouts, eg. `FL+FR+LFE`, or a single position, eg. `FC`.
+        let channels: Vec<Channel> = s.split('+').map(Channel::parse).collect();
+        Ok(ChannelLayout {
+            name,
+            count: channels.len(),
+            channels: Some(channels),
+        })
+    }
+}
+
+impl Serialize for ChannelLayout {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelLayout {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
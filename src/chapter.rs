@@ -1,22 +1,32 @@
-use std::time::Duration;
+use std::{fmt::Display, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
-use crate::Ratio;
+use crate::{
+    flexible_num,
+    tags::{capture, CaptureTags, Tags},
+    FfProbe, Ratio, TimeBase,
+};
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
 /// Chapter parsed
+#[non_exhaustive]
 pub struct Chapter {
     /// This is an identifier for the chapter. It's a unique number that distinguishes this chapter from others.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub id: i64,
     /// This represents the time base of the chapter, which is a rational number. Time base is used to convert time stamps into seconds. It's usually formatted as a fraction, like "1/1000", meaning each unit in the time stamps is 1/1000 of a second.
     pub time_base: Ratio,
     /// This is the start time of the chapter, in units of time_base. To get the start time in seconds, you'd multiply this value by the time base.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub start: i64,
     /// This is the end time of the chapter, in units of time_base. Similar to start, this can be converted to seconds.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub end: i64,
     /// This holds additional metadata tags associated with the chapter, such as its title.
+    #[serde(deserialize_with = "capture")]
     pub tags: ChapterTags,
     #[cfg(feature = "__internal_deny_unknown_fields")]
     start_time: Option<serde_json::Value>,
@@ -25,26 +35,291 @@ pub struct Chapter {
 }
 
 impl Chapter {
+    /// Chapter from `start` to `end` in `time_base` units.
+    pub fn new(id: i64, time_base: Ratio, start: i64, end: i64, title: impl Into<String>) -> Self {
+        let title = title.into();
+        Self {
+            id,
+            time_base,
+            start,
+            end,
+            tags: ChapterTags {
+                all: [("title", title.as_str())].into_iter().collect(),
+                title,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// All chapter tags, see [`Tags`].
+    pub fn all_tags(&self) -> &Tags {
+        &self.tags.all
+    }
+
+    /// Start of the chapter, [`Chapter::start`] ticks converted with the time base.
+    /// Negative starts are clamped to zero, see [`Chapter::start_nanos`].
     pub fn start_time(&self) -> Duration {
-        Duration::from_millis(
-            ((self.start * self.time_base.numerator() as i64) as f64
-                / self.time_base.denominator() as f64
-                * 1000.) as u64,
-        )
+        to_duration(self.start_nanos())
     }
 
+    /// End of the chapter, [`Chapter::end`] ticks converted with the time base.
     pub fn end_time(&self) -> Duration {
-        Duration::from_millis(
-            ((self.end * self.time_base.numerator() as i64) as f64
-                / self.time_base.denominator() as f64
-                * 1000.) as u64,
-        )
+        to_duration(self.end_nanos())
+    }
+
+    /// Start in nanoseconds, signed as some muxers write chapters before zero.
+    /// `None` if the time base is invalid, see [`Chapter::timebase`].
+    pub fn start_nanos(&self) -> Option<i128> {
+        self.ticks_to_nanos(self.start)
+    }
+
+    pub fn end_nanos(&self) -> Option<i128> {
+        self.ticks_to_nanos(self.end)
+    }
+
+    /// Length of the chapter, `None` if it ends before it starts or the time base is invalid.
+    pub fn duration(&self) -> Option<Duration> {
+        let length = self.end_nanos()? - self.start_nanos()?;
+        (length >= 0).then(|| to_duration(Some(length)))
+    }
+
+    /// Title tag, `None` if missing or empty.
+    pub fn title(&self) -> Option<&str> {
+        Some(self.tags.title.as_str()).filter(|t| !t.is_empty())
+    }
+
+    /// Whether `time` is within the chapter, from its start up to but excluding its end.
+    pub fn contains(&self, time: Duration) -> bool {
+        let (Some(start), Some(end)) = (self.start_nanos(), self.end_nanos()) else {
+            return false;
+        };
+        (start..end).contains(&(time.as_nanos() as i128))
+    }
+
+    /// [`Chapter::time_base`] for timestamp math, `None` for a zero numerator or
+    /// denominator.
+    pub fn timebase(&self) -> Option<TimeBase> {
+        TimeBase::from_ratio(&self.time_base)
+    }
+
+    fn ticks_to_nanos(&self, ticks: i64) -> Option<i128> {
+        Some(self.timebase()?.to_nanos(ticks))
+    }
+}
+
+fn to_duration(nanos: Option<i128>) -> Duration {
+    let nanos = nanos.unwrap_or_default().clamp(0, u64::MAX as i128) as u64;
+    Duration::from_nanos(nanos)
+}
+
+/// Problem with the chapters of a file, see [`validate_chapters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChapterWarning {
+    /// Time base with a zero numerator or denominator, the times can't be converted.
+    InvalidTimeBase { id: i64 },
+    /// eg. a chapter starting at 10 s and ending at 5 s.
+    EndBeforeStart { id: i64 },
+    /// Chapter starting before the chapter listed before it.
+    OutOfOrder { id: i64, previous: i64 },
+    /// Chapter starting before the chapter listed before it has ended.
+    Overlap { id: i64, previous: i64 },
+    /// Chapter ending after the end of the file.
+    PastEnd { id: i64 },
+}
+
+impl Display for ChapterWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChapterWarning::InvalidTimeBase { id } => {
+                write!(f, "chapter {id} has an invalid time base")
+            }
+            ChapterWarning::EndBeforeStart { id } => {
+                write!(f, "chapter {id} ends before it starts")
+            }
+            ChapterWarning::OutOfOrder { id, previous } => {
+                write!(f, "chapter {id} starts before chapter {previous}")
+            }
+            ChapterWarning::Overlap { id, previous } => {
+                write!(f, "chapter {id} starts before chapter {previous} ends")
+            }
+            ChapterWarning::PastEnd { id } => {
+                write!(f, "chapter {id} ends after the end of the file")
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+/// Check chapters in the order ffprobe lists them. ffprobe passes chapters through as
+/// the container stores them, so broken files report chapters that end before they
+/// start or overlap the next one.
+///
+/// Chapters with different time bases are compared by their converted times. Chapters
+/// ending exactly where the next one starts are fine.
+pub fn validate_chapters(chapters: &[Chapter]) -> Vec<ChapterWarning> {
+    let mut warnings = Vec::new();
+    let mut previous: Option<(&Chapter, i128, i128)> = None;
+    for chapter in chapters {
+        let id = chapter.id;
+        let (Some(start), Some(end)) = (chapter.start_nanos(), chapter.end_nanos()) else {
+            warnings.push(ChapterWarning::InvalidTimeBase { id });
+            continue;
+        };
+        if end < start {
+            warnings.push(ChapterWarning::EndBeforeStart { id });
+        }
+        if let Some((prev, prev_start, prev_end)) = previous {
+            if start < prev_start {
+                warnings.push(ChapterWarning::OutOfOrder {
+                    id,
+                    previous: prev.id,
+                });
+            } else if start < prev_end {
+                warnings.push(ChapterWarning::Overlap {
+                    id,
+                    previous: prev.id,
+                });
+            }
+        }
+        previous = Some((chapter, start, end.max(start)));
+    }
+    warnings
+}
+
+impl FfProbe {
+    /// [`validate_chapters`] of the probed chapters. With the `format` feature, chapters
+    /// ending more than a second after the format duration are reported as
+    /// [`ChapterWarning::PastEnd`], as muxers often round the last chapter end.
+    pub fn chapter_warnings(&self) -> Vec<ChapterWarning> {
+        #[allow(unused_mut)]
+        let mut warnings = validate_chapters(&self.chapters);
+        #[cfg(feature = "format")]
+        if let Some(duration) = self.format.duration {
+            let limit = (duration + Duration::from_secs(1)).as_nanos() as i128;
+            warnings.extend(
+                self.chapters
+                    .iter()
+                    .filter(|c| c.end_nanos().is_some_and(|end| end > limit))
+                    .map(|c| ChapterWarning::PastEnd { id: c.id }),
+            );
+        }
+        warnings
+    }
+
+    /// Chapters ordered by start time. ffprobe lists them in container order, which
+    /// isn't necessarily sorted, see [`ChapterWarning::OutOfOrder`].
+    pub fn chapters_sorted(&self) -> Vec<&Chapter> {
+        let mut chapters: Vec<_> = self.chapters.iter().collect();
+        chapters.sort_by_key(|c| c.start_nanos());
+        chapters
+    }
+
+    /// Chapter playing at `time`, see [`Chapter::contains`]. Of overlapping chapters the
+    /// one starting last is returned.
+    pub fn chapter_at(&self, time: Duration) -> Option<&Chapter> {
+        self.chapters
+            .iter()
+            .filter(|c| c.contains(time))
+            .max_by_key(|c| c.start_nanos())
+    }
+
+    /// Chapters in ffmpeg's metadata format, eg. to add them to a file with
+    /// `ffmpeg -i input.mp4 -i chapters.txt -map_metadata 1 -codec copy output.mp4`.
+    ///
+    /// ```text
+    /// ;FFMETADATA1
+    /// [CHAPTER]
+    /// TIMEBASE=1/1000
+    /// START=0
+    /// END=60000
+    /// title=Intro
+    /// ```
+    pub fn to_ffmetadata(&self) -> String {
+        let mut out = String::from(";FFMETADATA1\n");
+        for chapter in self.chapters_sorted() {
+            out += &format!(
+                "[CHAPTER]\nTIMEBASE={}/{}\nSTART={}\nEND={}\n",
+                chapter.time_base.numerator(),
+                chapter.time_base.denominator(),
+                chapter.start,
+                chapter.end
+            );
+            for (key, value) in chapter.all_tags().iter() {
+                out += &format!("{}={}\n", ffmetadata_escape(key), ffmetadata_escape(value));
+            }
+        }
+        out
+    }
+
+    /// Chapters as a WebVTT chapters track, for `<track kind="chapters">` in browsers.
+    /// Chapters without a title are named `Chapter 1`, `Chapter 2`, ...
+    pub fn to_webvtt_chapters(&self) -> String {
+        let mut out = String::from("WEBVTT\n");
+        for (number, chapter) in self.chapters_sorted().into_iter().enumerate() {
+            let number = number + 1;
+            let title = match chapter.title() {
+                Some(title) => webvtt_escape(title),
+                None => format!("Chapter {number}"),
+            };
+            out += &format!(
+                "\n{number}\n{} --> {}\n{title}\n",
+                webvtt_time(chapter.start_time()),
+                webvtt_time(chapter.end_time().max(chapter.start_time()))
+            );
+        }
+        out
+    }
+}
+
+/// Escape the characters with a meaning in ffmetadata files with a backslash.
+fn ffmetadata_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Cue text on one line, with the characters starting markup escaped.
+fn webvtt_escape(value: &str) -> String {
+    value
+        .lines()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// `hh:mm:ss.ttt`
+fn webvtt_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        time.subsec_millis()
+    )
+}
+
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Tags for chapter
+#[non_exhaustive]
 pub struct ChapterTags {
     /// This is the title of the chapter. Titles can provide descriptive names for chapters, such as "Introduction" or "Chapter 1: Getting Started".
     pub title: String,
+    /// All tags, including the ones covered by the typed fields.
+    #[serde(skip)]
+    pub all: Tags,
+}
+
+impl CaptureTags for ChapterTags {
+    fn all_mut(&mut self) -> &mut Tags {
+        &mut self.all
+    }
 }
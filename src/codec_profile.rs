@@ -0,0 +1,152 @@
+use std::fmt::Display;
+
+use crate::{compat::DeviceProfile, VideoStream};
+
+/// H.264 profile, Baseline to High 4:4:4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum H264Profile {
+    ConstrainedBaseline,
+    Baseline,
+    Main,
+    Extended,
+    High,
+    /// 10 bit
+    High10,
+    /// 4:2:2 chroma, up to 10 bit
+    High422,
+    /// 4:4:4 chroma, up to 14 bit
+    High444,
+}
+
+/// H.265 / HEVC profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HevcProfile {
+    Main,
+    /// 10 bit, eg. HDR10 and Dolby Vision.
+    Main10,
+    MainStillPicture,
+    /// Range extensions, eg. 4:2:2 or 12 bit.
+    Rext,
+}
+
+/// AV1 profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Av1Profile {
+    /// 4:2:0 up to 10 bit.
+    Main,
+    /// Adds 4:4:4.
+    High,
+    /// Adds 4:2:2 and 12 bit.
+    Professional,
+}
+
+/// Codec aware profile of a video stream, see [`VideoStream::codec_profile`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum VideoProfile {
+    H264(H264Profile),
+    Hevc(HevcProfile),
+    /// VP9 profile number, 0 is 8 bit 4:2:0, 2 is 10 bit 4:2:0.
+    Vp9(u8),
+    Av1(Av1Profile),
+    /// Profiles of other codecs, or ones not covered above, as reported by ffprobe.
+    Other(String),
+}
+
+impl VideoProfile {
+    /// Profile from the `codec_name` and `profile` reported by ffprobe.
+    pub fn parse(codec_name: &str, profile: &str) -> VideoProfile {
+        let typed = match codec_name {
+            "h264" => match profile {
+                "Constrained Baseline" => {
+                    Some(VideoProfile::H264(H264Profile::ConstrainedBaseline))
+                }
+                "Baseline" => Some(VideoProfile::H264(H264Profile::Baseline)),
+                "Main" => Some(VideoProfile::H264(H264Profile::Main)),
+                "Extended" => Some(VideoProfile::H264(H264Profile::Extended)),
+                "High" => Some(VideoProfile::H264(H264Profile::High)),
+                "High 10" | "High 10 Intra" => Some(VideoProfile::H264(H264Profile::High10)),
+                "High 4:2:2" | "High 4:2:2 Intra" => Some(VideoProfile::H264(H264Profile::High422)),
+                "High 4:4:4" | "High 4:4:4 Predictive" | "High 4:4:4 Intra" | "CAVLC 4:4:4" => {
+                    Some(VideoProfile::H264(H264Profile::High444))
+                }
+                _ => None,
+            },
+            "hevc" => match profile {
+                "Main" => Some(VideoProfile::Hevc(HevcProfile::Main)),
+                "Main 10" => Some(VideoProfile::Hevc(HevcProfile::Main10)),
+                "Main Still Picture" => Some(VideoProfile::Hevc(HevcProfile::MainStillPicture)),
+                "Rext" | "Format Range Extensions" => Some(VideoProfile::Hevc(HevcProfile::Rext)),
+                _ => None,
+            },
+            "vp9" => profile
+                .strip_prefix("Profile ")
+                .and_then(|p| p.parse().ok())
+                .map(VideoProfile::Vp9),
+            "av1" => match profile {
+                "Main" => Some(VideoProfile::Av1(Av1Profile::Main)),
+                "High" => Some(VideoProfile::Av1(Av1Profile::High)),
+                "Professional" => Some(VideoProfile::Av1(Av1Profile::Professional)),
+                _ => None,
+            },
+            _ => None,
+        };
+        typed.unwrap_or_else(|| VideoProfile::Other(profile.to_string()))
+    }
+}
+
+/// Codec level, eg. 4.1. Limits the resolution, frame rate and bit rate a decoder has to
+/// handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CodecLevel {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl CodecLevel {
+    pub const fn new(major: u8, minor: u8) -> Self {
+        CodecLevel { major, minor }
+    }
+
+    /// Level from the `codec_name` and numeric `level` reported by ffprobe: 10 times the
+    /// level for H.264 and VP9, 30 times for HEVC, the `seq_level_idx` for AV1. `None` for
+    /// unknown levels, which ffprobe reports as -99.
+    pub fn parse(codec_name: &str, level: i64) -> Option<CodecLevel> {
+        let level = u8::try_from(level).ok()?;
+        match codec_name {
+            "h264" | "vp9" if level > 0 => Some(CodecLevel::new(level / 10, level % 10)),
+            "hevc" if level > 0 => Some(CodecLevel::new(level / 30, level % 30 / 3)),
+            // 31 is "unconstrained".
+            "av1" if level < 31 => Some(CodecLevel::new(2 + level / 4, level % 4)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CodecLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl VideoStream {
+    /// Typed [`VideoStream::profile`], `None` if ffprobe didn't report one.
+    pub fn codec_profile(&self) -> Option<VideoProfile> {
+        let profile = self.profile.as_deref().filter(|p| !p.is_empty())?;
+        Some(VideoProfile::parse(&self.codec_name, profile))
+    }
+
+    /// Typed [`VideoStream::level`], eg. 4.1 for an H.264 level of 41.
+    pub fn codec_level(&self) -> Option<CodecLevel> {
+        CodecLevel::parse(&self.codec_name, self.level)
+    }
+
+    /// Whether `device` decodes the codec, profile, level and resolution of the stream.
+    /// Streams with an unknown level are assumed to be within the device's limits.
+    pub fn is_supported_by(&self, device: &DeviceProfile) -> bool {
+        device.video_issues(self).is_empty()
+    }
+}
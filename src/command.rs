@@ -0,0 +1,378 @@
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    fmt::Display,
+    path::Path,
+};
+
+use crate::{read_interval::read_intervals_arg, Config, Dialect, EntriesSpec, LogLevel};
+
+/// Builder for the ffprobe invocation used by [`crate::ffprobe_config`].
+///
+/// Allows inspecting, logging or adjusting the exact command line before running it.
+///
+/// ```rust
+/// let cmd = ffprobe::ProbeCommand::new(ffprobe::Config::new())
+///     .arg("-analyzeduration")
+///     .arg("10M")
+///     .input("video.mp4");
+/// println!("running {}", cmd);
+/// let cmd: std::process::Command = cmd.build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProbeCommand {
+    config: Config,
+    args: Vec<OsString>,
+    input: Option<OsString>,
+    default_sections: bool,
+    show_entries: Option<EntriesSpec>,
+    dialect: Dialect,
+}
+
+impl ProbeCommand {
+    pub fn new(config: Config) -> Self {
+        ProbeCommand {
+            dialect: config.dialect.unwrap_or_default(),
+            config,
+            args: Vec::new(),
+            input: None,
+            default_sections: true,
+            show_entries: None,
+        }
+    }
+
+    /// Set the input file or URL, see [`IntoFfprobeInput`].
+    pub fn input(mut self, input: impl IntoFfprobeInput) -> Self {
+        self.input = Some(input.to_ffprobe_input().into_owned());
+        self
+    }
+
+    /// Whether to pass `-show_chapters`, `-show_format` and `-show_streams` for the
+    /// enabled features. Enabled by default, disable it to only show the sections
+    /// requested with extra arguments.
+    pub fn default_sections(mut self, default_sections: bool) -> Self {
+        self.default_sections = default_sections;
+        self
+    }
+
+    /// Only show the selected sections and fields (`-show_entries`) instead of the default
+    /// sections. The narrowed output can't be parsed into [`crate::FfProbe`], see
+    /// [`crate::ffprobe_entries`].
+    pub fn show_entries(mut self, entries: EntriesSpec) -> Self {
+        self.show_entries = Some(entries).filter(|e| !e.is_empty());
+        self
+    }
+
+    /// Command line dialect of the binary, see [`Dialect`]. Extra arguments are passed
+    /// as they are.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Append an extra argument. Extra arguments are placed after the arguments
+    /// derived from the [`Config`] and before the input.
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    /// Append multiple extra arguments, see [`ProbeCommand::arg`].
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_owned()));
+        self
+    }
+
+    /// The ffprobe binary that will be executed.
+    pub fn get_program(&self) -> &Path {
+        &self.config.ffprobe_bin
+    }
+
+    /// All arguments passed to ffprobe, excluding the program itself.
+    pub fn get_args(&self) -> Vec<OsString> {
+        let legacy = self.dialect == Dialect::Legacy;
+        // The `level` flag prefixes the lines with their level, see `LogMessage::parse`.
+        let level = match self.config.log_level() {
+            Some(level) if legacy => level.to_string(),
+            Some(level) => format!("level+{level}"),
+            None => "quiet".to_string(),
+        };
+        let format = if legacy { "-of" } else { "-print_format" };
+        let mut args: Vec<OsString> = vec!["-v".into(), level.into(), format.into(), "json".into()];
+        if legacy {
+            // Only the sections, none of the newer options.
+            if self.default_sections {
+                #[cfg(feature = "format")]
+                args.push("-show_format".into());
+                #[cfg(feature = "streams")]
+                args.push("-show_streams".into());
+            }
+            // Kept, probing without it would open any protocol.
+            if let Some(whitelist) = protocol_whitelist(&self.config, self.input.as_deref()) {
+                args.push("-protocol_whitelist".into());
+                args.push(whitelist.into());
+            }
+            args.extend(self.args.iter().cloned());
+            args.extend(self.input.iter().cloned());
+            return args;
+        }
+        if let Some(entries) = &self.show_entries {
+            args.push("-show_entries".into());
+            args.push(entries.to_string().into());
+        } else if self.default_sections {
+            #[cfg(feature = "chapters")]
+            args.push("-show_chapters".into());
+            #[cfg(feature = "format")]
+            args.push("-show_format".into());
+            #[cfg(feature = "streams")]
+            args.push("-show_streams".into());
+        }
+
+        if self.config.count_frames {
+            args.push("-count_frames".into());
+        }
+
+        if self.config.count_packets {
+            args.push("-count_packets".into());
+        }
+
+        if self.config.show_data {
+            args.push("-show_data".into());
+        }
+
+        if !self.config.read_intervals.is_empty() {
+            args.push("-read_intervals".into());
+            args.push(read_intervals_arg(&self.config.read_intervals).into());
+        }
+
+        if let Some(whitelist) = protocol_whitelist(&self.config, self.input.as_deref()) {
+            args.push("-protocol_whitelist".into());
+            args.push(whitelist.into());
+        }
+
+        args.extend(self.args.iter().cloned());
+        args.extend(self.input.iter().cloned());
+        args
+    }
+
+    /// Build a [`std::process::Command`] for the configured invocation.
+    ///
+    /// Environment, working directory and priority options of the [`Config`] are applied.
+    pub fn build(&self) -> std::process::Command {
+        self.config.executed(self.build_local())
+    }
+
+    /// [`ProbeCommand::build`] without the [`Config::executor`].
+    pub(crate) fn build_local(&self) -> std::process::Command {
+        let mut cmd = self.config.command(self.get_program());
+        cmd.args(self.get_args());
+        cmd
+    }
+
+    /// The command without its input at `-v error`, which only parses the options, see
+    /// [`Dialect`].
+    pub(crate) fn options_only(&self) -> ProbeCommand {
+        let mut command = self.clone();
+        command.config.loglevel = Some(LogLevel::Error);
+        command.input = None;
+        command
+    }
+
+    /// Fail for an input whose protocol isn't one of the [`Config::allowed_protocols`].
+    pub(crate) fn check_protocol(&self) -> std::io::Result<()> {
+        match &self.input {
+            Some(input) => check_protocol(&self.config, input),
+            None => Ok(()),
+        }
+    }
+
+    /// Run the command to completion, reporting it to [`Config::on_spawn`].
+    pub(crate) fn output(&self) -> std::io::Result<std::process::Output> {
+        self.check_protocol()?;
+        self.config.output(&mut self.build_local())
+    }
+
+    /// [`ProbeCommand::output`] writing `stdin` to ffprobe, for the `pipe:0` input.
+    #[cfg(feature = "streams")]
+    pub(crate) fn output_with_stdin(&self, stdin: &[u8]) -> std::io::Result<std::process::Output> {
+        self.check_protocol()?;
+        self.config
+            .output_with_stdin(&mut self.build_local(), stdin)
+    }
+
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Whether [`ProbeCommand::show_entries`] replaces the sections.
+    pub(crate) fn has_show_entries(&self) -> bool {
+        self.show_entries.is_some()
+    }
+
+    /// Build a [`tokio::process::Command`] for the configured invocation.
+    #[cfg(feature = "tokio")]
+    pub fn build_async(&self) -> tokio::process::Command {
+        self.build().into()
+    }
+}
+
+/// `-protocol_whitelist` and `-i` options of an ffmpeg input, failing for one whose
+/// protocol isn't one of the [`Config::allowed_protocols`], as for ffprobe.
+pub(crate) fn ffmpeg_input(config: &Config, path: &Path) -> std::io::Result<Vec<OsString>> {
+    let input = path_arg(path.as_os_str());
+    check_protocol(config, &input)?;
+    let mut args = Vec::new();
+    if let Some(whitelist) = protocol_whitelist(config, Some(&input)) {
+        args.extend(["-protocol_whitelist".into(), whitelist.into()]);
+    }
+    args.extend(["-i".into(), input.into_owned()]);
+    Ok(args)
+}
+
+/// Fail for an `input` whose protocol isn't one of the [`Config::allowed_protocols`].
+pub(crate) fn check_protocol(config: &Config, input: &OsStr) -> std::io::Result<()> {
+    let Some(allowed) = &config.allowed_protocols else {
+        return Ok(());
+    };
+    let protocol = input_protocol(input);
+    if allowed.contains(&protocol) {
+        Ok(())
+    } else {
+        Err(crate::error::protocol_not_allowed(&protocol))
+    }
+}
+
+/// `-protocol_whitelist` of the [`Config::allowed_protocols`], with the protocols they
+/// are built on. Without `file` for remote inputs.
+fn protocol_whitelist(config: &Config, input: Option<&OsStr>) -> Option<String> {
+    let allowed = config.allowed_protocols.as_ref()?;
+    let local = input.is_none_or(|input| LOCAL_PROTOCOLS.contains(&input_protocol(input).as_str()));
+    let mut whitelist: Vec<&str> = Vec::new();
+    for protocol in allowed {
+        let underlying = UNDERLYING_PROTOCOLS
+            .iter()
+            .find(|(name, _)| name == protocol)
+            .map_or(&[][..], |(_, underlying)| underlying);
+        for protocol in std::iter::once(protocol.as_str()).chain(underlying.iter().copied()) {
+            if (local || protocol != "file") && !whitelist.contains(&protocol) {
+                whitelist.push(protocol);
+            }
+        }
+    }
+    Some(whitelist.join(","))
+}
+
+/// Input passed to ffprobe and ffmpeg: a path, or a URL as string.
+///
+/// Inputs are passed to the process as `OsStr`, so file names that aren't valid UTF-8
+/// work. Relative paths starting with `-` get a `./` prefix, so they aren't taken for
+/// options, eg. `-f lavfi`. On Windows, absolute paths of `MAX_PATH` (260) characters or more get the
+/// `\\?\` prefix, which the Win32 file APIs need to open them.
+pub trait IntoFfprobeInput {
+    fn to_ffprobe_input(&self) -> Cow<'_, OsStr>;
+}
+
+/// Paths, strings and `OsStr`, ie. everything implementing `AsRef<Path>`.
+impl<T: AsRef<Path> + ?Sized> IntoFfprobeInput for T {
+    fn to_ffprobe_input(&self) -> Cow<'_, OsStr> {
+        path_arg(self.as_ref().as_os_str())
+    }
+}
+
+/// Input or output path argument, see [`IntoFfprobeInput`].
+pub(crate) fn path_arg(path: &OsStr) -> Cow<'_, OsStr> {
+    #[cfg(windows)]
+    if let Some(path) = path.to_str().and_then(extended_length_path) {
+        return Cow::Owned(path.into());
+    }
+    // `-` alone is stdin or stdout.
+    if path.as_encoded_bytes().starts_with(b"-") && path != "-" {
+        return Cow::Owned(Path::new(".").join(path).into_os_string());
+    }
+    Cow::Borrowed(path)
+}
+
+/// Protocol ffmpeg opens `input` with, as in `url_find_protocol` of libavformat: the
+/// scheme of an URL, eg. `https`, `pipe` for `-` and `file` for paths.
+pub(crate) fn input_protocol(input: &OsStr) -> String {
+    let input = input.to_string_lossy();
+    if input == "-" {
+        return "pipe".to_string();
+    }
+    let len = input
+        .find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)))
+        .unwrap_or(input.len());
+    let subfile = input.starts_with("subfile,") && input[len..].contains(':');
+    let drive = cfg!(windows) && len == 1;
+    if !(input[len..].starts_with(':') || subfile) || len == 0 || drive {
+        return "file".to_string();
+    }
+    // Nested protocols, eg. `crypto+https://`, are opened by the outer one.
+    let scheme = &input[..len];
+    scheme.split('+').next().unwrap_or(scheme).to_string()
+}
+
+/// Protocols other protocols are built on, added to the `-protocol_whitelist`.
+const UNDERLYING_PROTOCOLS: &[(&str, &[&str])] = &[
+    ("http", &["tcp", "crypto"]),
+    ("https", &["tls", "tcp", "crypto"]),
+    ("tls", &["tcp"]),
+    ("rtmp", &["tcp"]),
+    ("rtmps", &["tls", "tcp"]),
+    ("rtsp", &["tcp", "udp", "rtp"]),
+    ("pipe", &["fd"]),
+];
+
+/// Protocols that read local files themselves, which can't be used by remote inputs.
+const LOCAL_PROTOCOLS: &[&str] = &["file", "concat", "subfile"];
+
+/// Extended-length form of a long absolute Windows path, eg. `C:\long\...` =>
+/// `\\?\C:\long\...` and `\\server\share\...` => `\\?\UNC\server\share\...`.
+/// Windows doesn't normalize extended-length paths, so paths with `.` or `..`
+/// components are left as they are.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn extended_length_path(path: &str) -> Option<String> {
+    const MAX_PATH: usize = 260;
+    if path.encode_utf16().count() < MAX_PATH
+        || path.starts_with(r"\\?\")
+        || path.starts_with(r"\\.\")
+    {
+        return None;
+    }
+    let path = path.replace('/', "\\");
+    if path.split('\\').any(|c| c == "." || c == "..") {
+        return None;
+    }
+    let bytes = path.as_bytes();
+    if bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        Some(format!(r"\\?\{path}"))
+    } else {
+        path.strip_prefix(r"\\")
+            .map(|unc| format!(r"\\?\UNC\{unc}"))
+    }
+}
+
+/// Formats the command line for logging, quoting arguments that contain whitespace.
+impl Display for ProbeCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", quote(self.get_program().as_os_str()))?;
+        for arg in self.get_args() {
+            write!(f, " {}", quote(&arg))?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn quote(arg: &OsStr) -> String {
+    let arg = arg.to_string_lossy();
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    } else {
+        arg.into_owned()
+    }
+}
@@ -0,0 +1,282 @@
+//! Packets and frames read one at a time while ffprobe is still running.
+//!
+//! `-show_packets` and `-show_frames` print an entry per packet or frame, which adds up
+//! to gigabytes of JSON for long inputs. [`Records`] runs ffprobe with
+//! `-print_format compact` instead, which prints one line per entry, and parses the
+//! lines as they arrive: memory use is constant and the first records are available
+//! right away.
+//!
+//! ```rust,no_run
+//! let config = ffprobe::Config::new();
+//! for packet in ffprobe::compact::packets(&config, "video.mp4").unwrap() {
+//!     let packet = packet.unwrap();
+//!     if packet.is_keyframe() {
+//!         println!("keyframe at {:?}", packet.time("pts_time"));
+//!     }
+//! }
+//! ```
+
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdout, Output, Stdio},
+    str::FromStr,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crate::{error::FfProbeError, Config, ProbeCommand, StreamIndex, TimeBase};
+
+/// Section shown by [`Records`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    /// `-show_packets`
+    Packets,
+    /// `-show_frames`, decodes the input.
+    Frames,
+}
+
+impl Section {
+    /// Name of the entries in ffprobe's output, eg. `packet`.
+    pub fn entry_name(self) -> &'static str {
+        match self {
+            Section::Packets => "packet",
+            Section::Frames => "frame",
+        }
+    }
+
+    fn show_arg(self) -> &'static str {
+        match self {
+            Section::Packets => "-show_packets",
+            Section::Frames => "-show_frames",
+        }
+    }
+}
+
+/// A packet or frame, ie. one line of ffprobe's compact output such as
+/// `packet|codec_type=video|stream_index=0|pts_time=0.040000|size=1234|flags=K__`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Record {
+    /// `(key, value)` in ffprobe's order. Fields of nested sections are prefixed, eg.
+    /// `tag:language` or `side_data:side_data_type`.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Record {
+    /// Value of `key`, `None` if missing or `N/A`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .filter(|v| *v != "N/A")
+    }
+
+    /// Value of `key` parsed, eg. `record.parse::<u64>("size")`.
+    pub fn parse<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key)?.parse().ok()
+    }
+
+    /// Timestamp field in seconds, eg. `pts_time` or `duration_time`. Negative timestamps
+    /// are clamped to zero.
+    pub fn time(&self, key: &str) -> Option<Duration> {
+        let time: f64 = self.parse(key)?;
+        time.is_finite()
+            .then(|| Duration::from_secs_f64(time.max(0.)))
+    }
+
+    /// Timestamp field in `time_base` ticks converted, eg. `pts` with the
+    /// [`Stream::timebase`](crate::Stream::timebase) of the stream. More precise than
+    /// the rounded `pts_time`.
+    pub fn timestamp(&self, key: &str, time_base: TimeBase) -> Option<Duration> {
+        Some(time_base.to_duration(self.parse(key)?))
+    }
+
+    pub fn stream_index(&self) -> Option<StreamIndex> {
+        self.parse("stream_index")
+    }
+
+    /// Keyframe packet (`flags=K__`) or frame (`key_frame=1`).
+    pub fn is_keyframe(&self) -> bool {
+        self.get("flags").is_some_and(|f| f.starts_with('K')) || self.get("key_frame") == Some("1")
+    }
+
+    /// Parse a line of `section`, `None` for lines of other sections.
+    fn parse_line(line: &str, section: Section) -> Option<Record> {
+        let mut parts = split_escaped(line).into_iter();
+        if parts.next()? != section.entry_name() {
+            return None;
+        }
+        let fields = parts
+            .map(|part| match part.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (part, String::new()),
+            })
+            .collect();
+        Some(Record { fields })
+    }
+}
+
+/// Split a compact line at the `|` separators, undoing ffprobe's backslash escapes.
+fn split_escaped(line: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().expect("parts is never empty");
+        match c {
+            '|' => parts.push(String::new()),
+            '\\' => match chars.next() {
+                Some('n') => part.push('\n'),
+                Some('r') => part.push('\r'),
+                Some('t') => part.push('\t'),
+                Some('b') => part.push('\u{8}'),
+                Some('f') => part.push('\u{c}'),
+                Some(c) => part.push(c),
+                None => part.push('\\'),
+            },
+            c => part.push(c),
+        }
+    }
+    parts
+}
+
+/// Packets of `path`, see [`Records`].
+pub fn packets(config: &Config, path: impl AsRef<Path>) -> Result<Records, FfProbeError> {
+    Records::spawn(ProbeCommand::new(config.clone()), Section::Packets, path)
+}
+
+/// Frames of `path`, see [`Records`]. Decodes the input, restrict long inputs with
+/// [`Config::read_intervals`].
+pub fn frames(config: &Config, path: impl AsRef<Path>) -> Result<Records, FfProbeError> {
+    Records::spawn(ProbeCommand::new(config.clone()), Section::Frames, path)
+}
+
+/// Running ffprobe printing packets or frames, iterated as they are printed.
+///
+/// Yields an error for a failed ffprobe run once all records it printed were read.
+/// Dropping the iterator early kills ffprobe.
+#[derive(Debug)]
+pub struct Records {
+    command: ProbeCommand,
+    path: PathBuf,
+    section: Section,
+    child: Option<Child>,
+    stdout: BufReader<ChildStdout>,
+    stderr: Option<JoinHandle<Vec<u8>>>,
+    started: Instant,
+    line: Vec<u8>,
+}
+
+impl Records {
+    /// Run `command` with `section` shown in compact format, eg. with extra
+    /// `-select_streams v:0` arguments. With [`ProbeCommand::show_entries`] set, only the
+    /// selected fields are printed, eg. `EntriesSpec::new().section("packet", ["pts_time"])`.
+    pub fn spawn(
+        command: ProbeCommand,
+        section: Section,
+        path: impl AsRef<Path>,
+    ) -> Result<Records, FfProbeError> {
+        let path = path.as_ref();
+        crate::error::check_input(path)?;
+        let mut command = command
+            .default_sections(false)
+            .args(["-print_format", "compact"]);
+        if !command.has_show_entries() {
+            command = command.arg(section.show_arg());
+        }
+        let command = command.input(path);
+        command.check_protocol().map_err(FfProbeError::from_io)?;
+
+        let started = Instant::now();
+        let mut cmd = command.build();
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| {
+                command.config().report_spawn(&cmd, started, Err(&err));
+                FfProbeError::Io(err)
+            })?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        // Drain stderr separately so a full pipe can't block ffprobe.
+        let stderr = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf);
+            buf
+        });
+
+        Ok(Records {
+            command,
+            path: path.to_path_buf(),
+            section,
+            child: Some(child),
+            stdout: BufReader::new(stdout),
+            stderr: Some(stderr),
+            started,
+            line: Vec::new(),
+        })
+    }
+
+    /// Wait for ffprobe after its output ended, an error if it failed.
+    fn finish(&mut self) -> Option<FfProbeError> {
+        let mut child = self.child.take()?;
+        let status = match child.wait() {
+            Ok(status) => status,
+            Err(err) => return Some(FfProbeError::Io(err)),
+        };
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|s| s.join().ok())
+            .unwrap_or_default();
+        let out = Output {
+            status,
+            stdout: Vec::new(),
+            stderr,
+        };
+        let config = self.command.config();
+        config.report_spawn(&self.command.build(), self.started, Ok(&out));
+        (!out.status.success())
+            .then(|| crate::error::status_error(&self.path, out).context(&self.path, &self.command))
+    }
+}
+
+impl Iterator for Records {
+    type Item = Result<Record, FfProbeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.child.as_ref()?;
+        loop {
+            self.line.clear();
+            match self.stdout.read_until(b'\n', &mut self.line) {
+                Ok(0) => return self.finish().map(Err),
+                Ok(_) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    if let Some(mut child) = self.child.take() {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    return Some(Err(FfProbeError::Io(err)));
+                }
+            }
+            let line = String::from_utf8_lossy(&self.line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if let Some(record) = Record::parse_line(line, self.section) {
+                return Some(Ok(record));
+            }
+        }
+    }
+}
+
+impl Drop for Records {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,595 @@
+//! Compatibility checks between probe results, and of probe results with playback
+//! devices.
+
+use std::fmt::Display;
+
+use crate::{
+    AudioStream, Av1Profile, ChannelLayout, CodecLevel, FfProbe, H264Profile, HevcProfile, Ratio,
+    SampleRate, Stream, StreamIndex, StreamKinds, VideoProfile, VideoStream,
+};
+
+/// Reason why two inputs can't be concatenated losslessly, see [`can_concat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incompatibility {
+    /// The inputs have a different number of streams.
+    StreamCount { left: usize, right: usize },
+    /// Streams at the same position have different types, eg. audio and video.
+    StreamKind {
+        index: StreamIndex,
+        left: &'static str,
+        right: &'static str,
+    },
+    Codec {
+        index: StreamIndex,
+        left: String,
+        right: String,
+    },
+    Resolution {
+        index: StreamIndex,
+        left: (i64, i64),
+        right: (i64, i64),
+    },
+    PixelFormat {
+        index: StreamIndex,
+        left: Option<String>,
+        right: Option<String>,
+    },
+    SampleRate {
+        index: StreamIndex,
+        left: SampleRate,
+        right: SampleRate,
+    },
+    ChannelLayout {
+        index: StreamIndex,
+        left: (i64, Option<ChannelLayout>),
+        right: (i64, Option<ChannelLayout>),
+    },
+    TimeBase {
+        index: StreamIndex,
+        left: Ratio,
+        right: Ratio,
+    },
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn opt(value: &Option<String>) -> &str {
+            value.as_deref().unwrap_or("unknown")
+        }
+        match self {
+            Incompatibility::StreamCount { left, right } => {
+                write!(f, "stream count differs: {} vs {}", left, right)
+            }
+            Incompatibility::StreamKind { index, left, right } => {
+                write!(f, "stream {}: type differs: {} vs {}", index, left, right)
+            }
+            Incompatibility::Codec { index, left, right } => {
+                write!(f, "stream {}: codec differs: {} vs {}", index, left, right)
+            }
+            Incompatibility::Resolution { index, left, right } => write!(
+                f,
+                "stream {}: resolution differs: {}x{} vs {}x{}",
+                index, left.0, left.1, right.0, right.1
+            ),
+            Incompatibility::PixelFormat { index, left, right } => write!(
+                f,
+                "stream {}: pixel format differs: {} vs {}",
+                index,
+                opt(left),
+                opt(right)
+            ),
+            Incompatibility::SampleRate { index, left, right } => {
+                write!(
+                    f,
+                    "stream {}: sample rate differs: {} vs {}",
+                    index, left, right
+                )
+            }
+            Incompatibility::ChannelLayout { index, left, right } => write!(
+                f,
+                "stream {}: channel layout differs: {} channels ({}) vs {} channels ({})",
+                index,
+                left.0,
+                opt(&left.1.as_ref().map(ChannelLayout::to_string)),
+                right.0,
+                opt(&right.1.as_ref().map(ChannelLayout::to_string))
+            ),
+            Incompatibility::TimeBase { index, left, right } => {
+                write!(
+                    f,
+                    "stream {}: time base differs: {} vs {}",
+                    index, left, right
+                )
+            }
+        }
+    }
+}
+
+/// Check whether two inputs can be concatenated or remuxed into each other
+/// without re-encoding (eg. with the concat demuxer and `-c copy`).
+///
+/// Streams are compared by position. Returns every incompatibility found.
+pub fn can_concat(a: &FfProbe, b: &FfProbe) -> Result<(), Vec<Incompatibility>> {
+    let mut out = Vec::new();
+    if a.streams.len() != b.streams.len() {
+        out.push(Incompatibility::StreamCount {
+            left: a.streams.len(),
+            right: b.streams.len(),
+        });
+    }
+    for (left, right) in a.streams.iter().zip(&b.streams) {
+        compare_streams(left, right, &mut out);
+    }
+
+    if out.is_empty() {
+        Ok(())
+    } else {
+        Err(out)
+    }
+}
+
+fn compare_streams(left: &Stream, right: &Stream, out: &mut Vec<Incompatibility>) {
+    let index = left.index;
+    let codec = |left: &str, right: &str| {
+        (left != right).then(|| Incompatibility::Codec {
+            index,
+            left: left.to_string(),
+            right: right.to_string(),
+        })
+    };
+
+    match (&left.stream, &right.stream) {
+        (StreamKinds::Video(l), StreamKinds::Video(r)) => {
+            out.extend(codec(&l.codec_name, &r.codec_name));
+            if (l.width, l.height) != (r.width, r.height) {
+                out.push(Incompatibility::Resolution {
+                    index,
+                    left: (l.width, l.height),
+                    right: (r.width, r.height),
+                });
+            }
+            if l.pix_fmt != r.pix_fmt {
+                out.push(Incompatibility::PixelFormat {
+                    index,
+                    left: l.pix_fmt.clone(),
+                    right: r.pix_fmt.clone(),
+                });
+            }
+        }
+        (StreamKinds::Audio(l), StreamKinds::Audio(r)) => {
+            out.extend(codec(&l.codec_name, &r.codec_name));
+            if l.sample_rate != r.sample_rate {
+                out.push(Incompatibility::SampleRate {
+                    index,
+                    left: l.sample_rate,
+                    right: r.sample_rate,
+                });
+            }
+            if (l.channels, &l.channel_layout) != (r.channels, &r.channel_layout) {
+                out.push(Incompatibility::ChannelLayout {
+                    index,
+                    left: (l.channels, l.channel_layout.clone()),
+                    right: (r.channels, r.channel_layout.clone()),
+                });
+            }
+        }
+        (StreamKinds::Subtitle(l), StreamKinds::Subtitle(r)) => {
+            out.extend(codec(&l.codec_name, &r.codec_name));
+        }
+        (StreamKinds::Attachment(l), StreamKinds::Attachment(r)) => {
+            out.extend(codec(
+                l.codec_name.as_deref().unwrap_or_default(),
+                r.codec_name.as_deref().unwrap_or_default(),
+            ));
+        }
+        (StreamKinds::Data(l), StreamKinds::Data(r)) => {
+            out.extend(codec(
+                l.codec_name.as_deref().unwrap_or_default(),
+                r.codec_name.as_deref().unwrap_or_default(),
+            ));
+        }
+        (l, r) => {
+            out.push(Incompatibility::StreamKind {
+                index,
+                left: kind_name(l),
+                right: kind_name(r),
+            });
+            return;
+        }
+    }
+
+    if left.time_base != right.time_base {
+        out.push(Incompatibility::TimeBase {
+            index,
+            left: left.time_base.clone(),
+            right: right.time_base.clone(),
+        });
+    }
+}
+
+fn kind_name(kind: &StreamKinds) -> &'static str {
+    match kind {
+        StreamKinds::Audio(_) => "audio",
+        StreamKinds::Video(_) => "video",
+        StreamKinds::Subtitle(_) => "subtitle",
+        StreamKinds::Attachment(_) => "attachment",
+        StreamKinds::Data(_) => "data",
+    }
+}
+
+/// Video codec a [`DeviceProfile`] decodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VideoCodecSupport {
+    /// `codec_name`, eg. `h264`.
+    pub codec: String,
+    /// Decoded profiles, any profile if empty.
+    pub profiles: Vec<VideoProfile>,
+    /// Highest decoded level, any level if `None`.
+    pub max_level: Option<CodecLevel>,
+}
+
+impl VideoCodecSupport {
+    pub fn new(codec: impl Into<String>) -> Self {
+        VideoCodecSupport {
+            codec: codec.into(),
+            profiles: Vec::new(),
+            max_level: None,
+        }
+    }
+
+    pub fn profiles(mut self, profiles: impl IntoIterator<Item = VideoProfile>) -> Self {
+        self.profiles.extend(profiles);
+        self
+    }
+
+    pub fn max_level(mut self, max_level: CodecLevel) -> Self {
+        self.max_level = Some(max_level);
+        self
+    }
+}
+
+/// What a playback device plays without transcoding, see [`FfProbe::is_direct_playable`].
+///
+/// Start from a preset such as [`DeviceProfile::chromecast`], or describe a device:
+///
+/// ```rust
+/// use ffprobe::{compat::{DeviceProfile, VideoCodecSupport}, CodecLevel};
+///
+/// let tv = DeviceProfile::new("Living room TV")
+///     .container("matroska")
+///     .video_codec(VideoCodecSupport::new("hevc").max_level(CodecLevel::new(5, 1)))
+///     .max_resolution(3840, 2160)
+///     .audio_codec("eac3")
+///     .max_audio_channels(6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeviceProfile {
+    pub name: String,
+    /// Demuxer names, eg. `mp4` or `mpegts`, matched against the names of
+    /// [`Format::format_name`](crate::Format::format_name). Any container if empty.
+    pub containers: Vec<String>,
+    pub video_codecs: Vec<VideoCodecSupport>,
+    /// Largest `(width, height)`, either orientation. Any resolution if `None`.
+    pub max_resolution: Option<(i64, i64)>,
+    /// Audio `codec_name`s, eg. `aac`.
+    pub audio_codecs: Vec<String>,
+    pub max_audio_channels: Option<i64>,
+}
+
+impl DeviceProfile {
+    /// Device that plays nothing yet, add its capabilities with the builder methods.
+    pub fn new(name: impl Into<String>) -> Self {
+        DeviceProfile {
+            name: name.into(),
+            containers: Vec::new(),
+            video_codecs: Vec::new(),
+            max_resolution: None,
+            audio_codecs: Vec::new(),
+            max_audio_channels: None,
+        }
+    }
+
+    pub fn container(mut self, name: impl Into<String>) -> Self {
+        self.containers.push(name.into());
+        self
+    }
+
+    pub fn video_codec(mut self, codec: VideoCodecSupport) -> Self {
+        self.video_codecs.push(codec);
+        self
+    }
+
+    pub fn max_resolution(mut self, width: i64, height: i64) -> Self {
+        self.max_resolution = Some((width, height));
+        self
+    }
+
+    pub fn audio_codec(mut self, name: impl Into<String>) -> Self {
+        self.audio_codecs.push(name.into());
+        self
+    }
+
+    pub fn max_audio_channels(mut self, channels: i64) -> Self {
+        self.max_audio_channels = Some(channels);
+        self
+    }
+
+    /// Chromecast up to the 3rd generation: 1080p H.264 and VP8, stereo.
+    pub fn chromecast() -> Self {
+        Self::new("Chromecast")
+            .with_containers(["mp4", "webm", "mpegts", "hls"])
+            .video_codec(
+                VideoCodecSupport::new("h264")
+                    .profiles(h264_profiles())
+                    .max_level(CodecLevel::new(4, 1)),
+            )
+            .video_codec(VideoCodecSupport::new("vp8"))
+            .max_resolution(1920, 1080)
+            .with_audio_codecs(["aac", "mp3", "opus", "vorbis", "flac"])
+            .max_audio_channels(2)
+    }
+
+    /// Chromecast Ultra and Chromecast with Google TV: 4K HEVC and VP9, 5.1 Dolby Digital.
+    pub fn chromecast_ultra() -> Self {
+        Self::new("Chromecast Ultra")
+            .with_containers(["mp4", "webm", "mpegts", "hls"])
+            .video_codec(
+                VideoCodecSupport::new("h264")
+                    .profiles(h264_profiles())
+                    .max_level(CodecLevel::new(4, 2)),
+            )
+            .video_codec(
+                VideoCodecSupport::new("hevc")
+                    .profiles(hevc_profiles())
+                    .max_level(CodecLevel::new(5, 1)),
+            )
+            .video_codec(
+                VideoCodecSupport::new("vp9")
+                    .profiles([VideoProfile::Vp9(0), VideoProfile::Vp9(2)])
+                    .max_level(CodecLevel::new(5, 1)),
+            )
+            .video_codec(VideoCodecSupport::new("vp8"))
+            .max_resolution(3840, 2160)
+            .with_audio_codecs(["aac", "mp3", "opus", "vorbis", "flac", "ac3", "eac3"])
+            .max_audio_channels(6)
+    }
+
+    /// Apple's HLS authoring specification for iPhone, iPad and Apple TV.
+    pub fn ios_hls() -> Self {
+        Self::new("iOS HLS")
+            .with_containers(["mpegts", "mp4", "hls"])
+            .video_codec(
+                VideoCodecSupport::new("h264")
+                    .profiles(h264_profiles())
+                    .max_level(CodecLevel::new(4, 2)),
+            )
+            .video_codec(
+                VideoCodecSupport::new("hevc")
+                    .profiles(hevc_profiles())
+                    .max_level(CodecLevel::new(5, 0)),
+            )
+            .max_resolution(3840, 2160)
+            .with_audio_codecs(["aac", "mp3", "ac3", "eac3", "alac", "flac"])
+            .max_audio_channels(8)
+    }
+
+    /// Codecs current desktop and mobile browsers play in `<video>`.
+    pub fn browser() -> Self {
+        Self::new("Browser")
+            .with_containers(["mp4", "webm"])
+            .video_codec(
+                VideoCodecSupport::new("h264")
+                    .profiles(h264_profiles())
+                    .max_level(CodecLevel::new(5, 2)),
+            )
+            .video_codec(VideoCodecSupport::new("vp8"))
+            .video_codec(VideoCodecSupport::new("vp9").profiles([VideoProfile::Vp9(0)]))
+            .video_codec(
+                VideoCodecSupport::new("av1").profiles([VideoProfile::Av1(Av1Profile::Main)]),
+            )
+            .with_audio_codecs(["aac", "mp3", "opus", "vorbis", "flac"])
+    }
+
+    fn with_containers<const N: usize>(mut self, names: [&str; N]) -> Self {
+        self.containers.extend(names.map(String::from));
+        self
+    }
+
+    fn with_audio_codecs<const N: usize>(mut self, names: [&str; N]) -> Self {
+        self.audio_codecs.extend(names.map(String::from));
+        self
+    }
+
+    /// Why the device can't play the container `format_name`, eg. `mov,mp4,m4a,3gp,3g2,mj2`.
+    #[cfg(feature = "format")]
+    fn container_issue(&self, format_name: &str) -> Option<PlaybackIssue> {
+        let supported = self.containers.is_empty()
+            || format_name
+                .split(',')
+                .any(|name| self.containers.iter().any(|c| c == name));
+        (!supported).then(|| PlaybackIssue::Container {
+            format_name: format_name.to_string(),
+        })
+    }
+
+    /// Why the device can't decode `video`, empty if it can.
+    pub(crate) fn video_issues(&self, video: &VideoStream) -> Vec<PlaybackIssue> {
+        let mut issues = Vec::new();
+        let Some(support) = self
+            .video_codecs
+            .iter()
+            .find(|c| c.codec == video.codec_name)
+        else {
+            return vec![PlaybackIssue::Codec {
+                codec: video.codec_name.clone(),
+            }];
+        };
+        let profile = video.codec_profile();
+        if !support.profiles.is_empty()
+            && !profile
+                .as_ref()
+                .is_some_and(|p| support.profiles.contains(p))
+        {
+            issues.push(PlaybackIssue::Profile {
+                codec: video.codec_name.clone(),
+                profile: video.profile.clone(),
+            });
+        }
+        if let (Some(level), Some(max)) = (video.codec_level(), support.max_level) {
+            if level > max {
+                issues.push(PlaybackIssue::Level { level, max });
+            }
+        }
+        if let Some(max) = self.max_resolution {
+            let (width, height) = (video.width, video.height);
+            let fits = |(w, h): (i64, i64)| width <= w && height <= h;
+            if !fits(max) && !fits((max.1, max.0)) {
+                issues.push(PlaybackIssue::Resolution { width, height, max });
+            }
+        }
+        issues
+    }
+
+    fn audio_issues(&self, audio: &AudioStream) -> Vec<PlaybackIssue> {
+        let mut issues = Vec::new();
+        if !self.audio_codecs.contains(&audio.codec_name) {
+            issues.push(PlaybackIssue::Codec {
+                codec: audio.codec_name.clone(),
+            });
+        }
+        if let Some(max) = self.max_audio_channels.filter(|&max| audio.channels > max) {
+            issues.push(PlaybackIssue::AudioChannels {
+                channels: audio.channels,
+                max,
+            });
+        }
+        issues
+    }
+}
+
+fn h264_profiles() -> [VideoProfile; 4] {
+    [
+        H264Profile::ConstrainedBaseline,
+        H264Profile::Baseline,
+        H264Profile::Main,
+        H264Profile::High,
+    ]
+    .map(VideoProfile::H264)
+}
+
+fn hevc_profiles() -> [VideoProfile; 2] {
+    [HevcProfile::Main, HevcProfile::Main10].map(VideoProfile::Hevc)
+}
+
+/// Reason why a device can't play a stream or container, see [`PlayabilityReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PlaybackIssue {
+    Container {
+        format_name: String,
+    },
+    Codec {
+        codec: String,
+    },
+    Profile {
+        codec: String,
+        profile: Option<String>,
+    },
+    Level {
+        level: CodecLevel,
+        max: CodecLevel,
+    },
+    Resolution {
+        width: i64,
+        height: i64,
+        max: (i64, i64),
+    },
+    AudioChannels {
+        channels: i64,
+        max: i64,
+    },
+}
+
+impl Display for PlaybackIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaybackIssue::Container { format_name } => {
+                write!(f, "unsupported container: {format_name}")
+            }
+            PlaybackIssue::Codec { codec } => write!(f, "unsupported codec: {codec}"),
+            PlaybackIssue::Profile { codec, profile } => write!(
+                f,
+                "unsupported {codec} profile: {}",
+                profile.as_deref().unwrap_or("unknown")
+            ),
+            PlaybackIssue::Level { level, max } => {
+                write!(f, "level {level} above the supported {max}")
+            }
+            PlaybackIssue::Resolution { width, height, max } => write!(
+                f,
+                "resolution {width}x{height} above the supported {}x{}",
+                max.0, max.1
+            ),
+            PlaybackIssue::AudioChannels { channels, max } => {
+                write!(f, "{channels} audio channels above the supported {max}")
+            }
+        }
+    }
+}
+
+/// Result of [`FfProbe::is_direct_playable`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PlayabilityReport {
+    /// Set if the device doesn't play the container. Streams without issues can be
+    /// remuxed rather than transcoded.
+    pub container: Option<PlaybackIssue>,
+    /// Video and audio streams that need transcoding, by stream index. Cover art,
+    /// subtitle and data streams aren't checked.
+    pub streams: Vec<(StreamIndex, Vec<PlaybackIssue>)>,
+}
+
+impl PlayabilityReport {
+    /// Whether the device plays the input as is.
+    pub fn is_direct_playable(&self) -> bool {
+        self.container.is_none() && self.streams.is_empty()
+    }
+
+    /// Whether the streams play as is, but need a different container.
+    pub fn needs_remux_only(&self) -> bool {
+        self.container.is_some() && self.streams.is_empty()
+    }
+
+    /// Indexes of the streams that need transcoding.
+    pub fn transcode_streams(&self) -> impl Iterator<Item = StreamIndex> + '_ {
+        self.streams.iter().map(|(index, _)| *index)
+    }
+}
+
+impl FfProbe {
+    /// Check which parts of the input `device` can't play without transcoding, the
+    /// decision media servers make between direct play, remuxing and transcoding.
+    pub fn is_direct_playable(&self, device: &DeviceProfile) -> PlayabilityReport {
+        #[cfg(feature = "format")]
+        let container = device.container_issue(&self.format.format_name);
+        #[cfg(not(feature = "format"))]
+        let container = None;
+
+        let streams = self
+            .streams
+            .iter()
+            .filter_map(|stream| {
+                let issues = match &stream.stream {
+                    StreamKinds::Video(_) if stream.disposition.attached_pic != 0 => return None,
+                    StreamKinds::Video(video) => device.video_issues(video),
+                    StreamKinds::Audio(audio) => device.audio_issues(audio),
+                    _ => return None,
+                };
+                (!issues.is_empty()).then_some((stream.index, issues))
+            })
+            .collect();
+        PlayabilityReport { container, streams }
+    }
+}
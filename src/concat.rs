@@ -0,0 +1,161 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    error::{invalid_data, FfProbeError},
+    ffprobe_config, Config, FfProbe, ProbeCommand,
+};
+
+/// Segments probed as one input, see [`probe_concat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConcatProbe {
+    /// Probe of the concatenated input, eg. with the duration of all segments.
+    pub probe: FfProbe,
+    /// Segments in playback order.
+    pub segments: Vec<ConcatSegment>,
+}
+
+/// Segment of a [`ConcatProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConcatSegment {
+    pub path: PathBuf,
+    /// Probe of the segment on its own.
+    pub probe: FfProbe,
+    /// Start on the concatenated timeline.
+    pub offset: Duration,
+    pub duration: Duration,
+}
+
+impl ConcatSegment {
+    /// End on the concatenated timeline, ie. the offset of the next segment.
+    pub fn end(&self) -> Duration {
+        self.offset + self.duration
+    }
+}
+
+impl ConcatProbe {
+    /// Segment containing `time` of the concatenated timeline, with its index and the
+    /// time within the segment. `None` past the end.
+    pub fn segment_at(&self, time: Duration) -> Option<(usize, &ConcatSegment, Duration)> {
+        self.segments
+            .iter()
+            .enumerate()
+            .find(|(_, segment)| time < segment.end())
+            .map(|(index, segment)| (index, segment, time.saturating_sub(segment.offset)))
+    }
+}
+
+/// Probe `segments` played back to back, eg. the chunks of a DVR recording or the files
+/// a camera splits a clip into.
+///
+/// Every segment is probed on its own first, for its duration and offset. MPEG-TS
+/// segments are then joined with the `concat` protocol, other formats through a
+/// temporary list for the concat demuxer, as in `ffmpeg -f concat -safe 0 -i list`.
+/// The combined probe's [`Format::filename`](crate::Format::filename) is that list.
+pub fn probe_concat<P: AsRef<Path>>(
+    config: Config,
+    segments: &[P],
+) -> Result<ConcatProbe, FfProbeError> {
+    if segments.is_empty() {
+        return Err(invalid_data("no segments to concatenate"));
+    }
+    let mut parts = Vec::with_capacity(segments.len());
+    let mut offset = Duration::ZERO;
+    for path in segments {
+        let path = path.as_ref();
+        let probe = ffprobe_config(config.clone(), path)?;
+        let duration = probe
+            .format
+            .duration
+            .ok_or_else(|| invalid_data(format!("segment {} has no duration", path.display())))?;
+        parts.push(ConcatSegment {
+            path: path.to_path_buf(),
+            probe,
+            offset,
+            duration,
+        });
+        offset += duration;
+    }
+
+    // Transport streams can be joined byte by byte, without a list file.
+    let protocol = parts.iter().all(|segment| {
+        segment.probe.format.format_name == "mpegts"
+            && segment.path.to_str().is_some_and(|p| !p.contains('|'))
+    });
+    let mut config = config;
+    if let (true, Some(allowed)) = (protocol, &mut config.allowed_protocols) {
+        // The segments themselves were checked when probing them.
+        allowed.push("concat".to_string());
+    }
+    let command = ProbeCommand::new(config.clone());
+    let list;
+    let (command, input) = if protocol {
+        let paths: Vec<&str> = parts.iter().filter_map(|s| s.path.to_str()).collect();
+        let input = PathBuf::from(format!("concat:{}", paths.join("|")));
+        (command.input(&input), input)
+    } else {
+        list = ConcatList::write(&parts)?;
+        let command = command
+            .args(["-f", "concat", "-safe", "0"])
+            .input(&list.path);
+        (command, list.path.clone())
+    };
+    let out = command.output().map_err(FfProbeError::from_io);
+    let probe = out
+        .and_then(|out| {
+            if !out.status.success() {
+                return Err(FfProbeError::Status(out));
+            }
+            let probe = FfProbe::from_slice(&out.stdout)?;
+            Ok(if config.log_level().is_some() {
+                probe.with_stderr(&out.stderr)
+            } else {
+                probe
+            })
+        })
+        .map_err(|e| e.context(&input, &command))?;
+    Ok(ConcatProbe {
+        probe,
+        segments: parts,
+    })
+}
+
+/// Temporary `ffconcat` list, removed on drop.
+struct ConcatList {
+    path: PathBuf,
+}
+
+impl ConcatList {
+    fn write(segments: &[ConcatSegment]) -> Result<Self, FfProbeError> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let mut list = String::from("ffconcat version 1.0\n");
+        for segment in segments {
+            let path = std::path::absolute(&segment.path).map_err(FfProbeError::Io)?;
+            let path = path
+                .to_str()
+                .ok_or_else(|| invalid_data(format!("segment {} isn't UTF-8", path.display())))?;
+            // Quoted, with `'` closing the quote, escaped and reopening it.
+            list.push_str(&format!("file '{}'\n", path.replace('\'', r"'\''")));
+            // Saves the demuxer from opening every file for its duration.
+            list.push_str(&format!("duration {}\n", segment.duration.as_secs_f64()));
+        }
+        let path = std::env::temp_dir().join(format!(
+            "ffprobe-rs-concat-{}-{}.ffconcat",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, list).map_err(FfProbeError::Io)?;
+        Ok(ConcatList { path })
+    }
+}
+
+impl Drop for ConcatList {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
@@ -1,4 +1,23 @@
-use crate::{error::FfProbeError, ffprobe::FfProbe, ffprobe_config};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    analysis::{Analyses, Analysis, Heuristics},
+    error::FfProbeError,
+    executor::{Executor, ExecutorHook},
+    ffprobe::FfProbe,
+    ffprobe_config,
+    progress::{ProbeProgress, ProgressHook},
+    retry::RetryPolicy,
+    spawn::{SpawnEvent, SpawnHook},
+    Dialect, HashAlgo, LogLevel, ReadInterval,
+};
+
+/// Protocols allowed by default, see [`Config::allowed_protocols`].
+pub(crate) const DEFAULT_PROTOCOLS: &[&str] = &["file", "http", "https"];
 
 /// ffprobe configuration.
 ///
@@ -6,7 +25,43 @@ use crate::{error::FfProbeError, ffprobe::FfProbe, ffprobe_config};
 #[derive(Clone, Debug)]
 pub struct Config {
     pub(crate) count_frames: bool,
+    pub(crate) count_packets: bool,
+    pub(crate) show_data: bool,
     pub(crate) ffprobe_bin: std::path::PathBuf,
+    pub(crate) ffmpeg_bin: std::path::PathBuf,
+    pub(crate) read_intervals: Vec<ReadInterval>,
+    pub(crate) capture_warnings: bool,
+    pub(crate) loglevel: Option<LogLevel>,
+    pub(crate) heuristics: Heuristics,
+    pub(crate) analyses: Analyses,
+    pub(crate) clear_env: bool,
+    pub(crate) env: Vec<(OsString, OsString)>,
+    pub(crate) current_dir: Option<PathBuf>,
+    pub(crate) on_spawn: Option<SpawnHook>,
+    pub(crate) progress: Option<ProgressHook>,
+    pub(crate) retry: Option<RetryPolicy>,
+    pub(crate) max_output_bytes: Option<usize>,
+    pub(crate) hash: Option<HashAlgo>,
+    pub(crate) byte_layout: bool,
+    pub(crate) estimate_duration: bool,
+    pub(crate) dialect: Option<Dialect>,
+    pub(crate) allowed_protocols: Option<Vec<String>>,
+    pub(crate) executor: Option<ExecutorHook>,
+    #[cfg(unix)]
+    pub(crate) nice: Option<i32>,
+    #[cfg(unix)]
+    pub(crate) ionice: Option<IoPriority>,
+}
+
+/// I/O scheduling class and priority for [`Config::ionice`], see `ionice(1)`.
+/// Priorities range from 0 (highest) to 7 (lowest).
+#[cfg(unix)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IoPriority {
+    Realtime(u8),
+    BestEffort(u8),
+    /// Only get disk time when no other program asks for it.
+    Idle,
 }
 
 impl Config {
@@ -14,7 +69,32 @@ impl Config {
     pub fn new() -> Config {
         Config {
             count_frames: false,
+            count_packets: false,
+            show_data: false,
             ffprobe_bin: "ffprobe".into(),
+            ffmpeg_bin: "ffmpeg".into(),
+            read_intervals: Vec::new(),
+            capture_warnings: false,
+            loglevel: None,
+            heuristics: Heuristics::default(),
+            analyses: Analyses::default(),
+            clear_env: false,
+            env: Vec::new(),
+            current_dir: None,
+            on_spawn: None,
+            progress: None,
+            retry: None,
+            max_output_bytes: None,
+            hash: None,
+            byte_layout: false,
+            estimate_duration: false,
+            dialect: None,
+            allowed_protocols: Some(DEFAULT_PROTOCOLS.iter().map(|p| p.to_string()).collect()),
+            executor: None,
+            #[cfg(unix)]
+            nice: None,
+            #[cfg(unix)]
+            ionice: None,
         }
     }
 
@@ -26,6 +106,24 @@ impl Config {
         self
     }
 
+    /// Enable the -count_packets setting.
+    /// Only demuxes the file, which is much cheaper than [`Config::count_frames`].
+    /// Packet count will be available in [`Stream::nb_read_packets`].
+    pub fn count_packets(mut self, count_packets: bool) -> Self {
+        self.count_packets = count_packets;
+        self
+    }
+
+    /// Enable the -show_data setting.
+    /// Codec private data will be available in
+    /// [`Stream::extradata`](crate::Stream::extradata), eg. for
+    /// [`Stream::parameter_sets`](crate::Stream::parameter_sets).
+    #[cfg(feature = "streams")]
+    pub fn show_data(mut self, show_data: bool) -> Self {
+        self.show_data = show_data;
+        self
+    }
+
     /// Specify which binary name (e.g. `"ffprobe-6"`) or path (e.g. `"/opt/bin/ffprobe"`) to use
     /// for executing `ffprobe`.
     pub fn ffprobe_bin(mut self, ffprobe_bin: impl AsRef<std::path::Path>) -> Self {
@@ -33,12 +131,265 @@ impl Config {
         self
     }
 
+    /// Specify which binary name or path to use for executing `ffmpeg`.
+    /// Only used by the functions that decode or remux the input: the [`crate::analysis`]
+    /// functions, [`Config::hash`], `extract_cover_art`, `FfProbe::extract_attachment` and
+    /// `dev_tools::TestMedia::generate`.
+    pub fn ffmpeg_bin(mut self, ffmpeg_bin: impl AsRef<std::path::Path>) -> Self {
+        self.ffmpeg_bin = ffmpeg_bin.as_ref().to_path_buf();
+        self
+    }
+
+    /// Limit reading of packets and frames to the given intervals (`-read_intervals`).
+    /// Useful to restrict [`Config::count_frames`] to a specific time window.
+    pub fn read_intervals(mut self, read_intervals: Vec<ReadInterval>) -> Self {
+        self.read_intervals = read_intervals;
+        self
+    }
+
+    /// Log warnings (`-v level+warning` instead of `-v quiet`) and keep them in
+    /// [`FfProbe::stderr_warnings`], eg. `moov atom not found` or non monotonic DTS.
+    /// ffprobe exits successfully despite them.
+    pub fn capture_warnings(mut self, capture_warnings: bool) -> Self {
+        self.capture_warnings = capture_warnings;
+        self
+    }
+
+    /// Run ffprobe with `-v level`, eg. [`LogLevel::Info`] for its diagnostics, and keep
+    /// the lines in [`FfProbe::stderr_warnings`] and, with their level, in
+    /// [`FfProbe::log`]. Overrides [`Config::capture_warnings`], which is
+    /// `LogLevel::Warning`. [`LogLevel::Quiet`] logs and keeps nothing.
+    pub fn loglevel(mut self, level: LogLevel) -> Self {
+        self.loglevel = Some(level);
+        self
+    }
+
+    /// Effective `-v` level, `None` for `quiet`, ie. nothing to capture.
+    pub(crate) fn log_level(&self) -> Option<LogLevel> {
+        self.loglevel
+            .or(self.capture_warnings.then_some(LogLevel::Warning))
+            .filter(|level| *level != LogLevel::Quiet)
+    }
+
+    /// Thresholds used by the [`crate::analysis`] detectors.
+    pub fn heuristics(mut self, heuristics: Heuristics) -> Self {
+        self.heuristics = heuristics;
+        self
+    }
+
+    /// Register a custom analysis, executed by [`crate::analysis::run_analyses`].
+    pub fn analysis(mut self, analysis: impl Analysis + 'static) -> Self {
+        self.analyses.0.push(std::sync::Arc::new(analysis));
+        self
+    }
+
+    /// Run ffprobe and ffmpeg without inheriting the environment of the current process.
+    /// Only variables set with [`Config::env`] are passed on. Without `PATH`, set
+    /// [`Config::ffprobe_bin`] to an absolute path.
+    pub fn clear_env(mut self, clear_env: bool) -> Self {
+        self.clear_env = clear_env;
+        self
+    }
+
+    /// Set an environment variable for ffprobe and ffmpeg, eg. `LD_LIBRARY_PATH`.
+    pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Working directory of ffprobe and ffmpeg. Relative input paths are resolved against it.
+    pub fn current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Called after every ffprobe and ffmpeg process run by the crate has exited, with the
+    /// full command line, duration, exit status and the end of stderr. Useful to log or
+    /// trace probes, eg. to find out why a file failed in production.
+    ///
+    /// ```rust
+    /// let config = ffprobe::Config::new().on_spawn(|event| {
+    ///     eprintln!(
+    ///         "{} exited with {:?} after {:?}",
+    ///         event.command_line(),
+    ///         event.exit_code(),
+    ///         event.duration
+    ///     );
+    /// });
+    /// ```
+    pub fn on_spawn(mut self, hook: impl Fn(&SpawnEvent) + Send + Sync + 'static) -> Self {
+        self.on_spawn = Some(SpawnHook(std::sync::Arc::new(hook)));
+        self
+    }
+
+    /// Called every [`crate::PROGRESS_INTERVAL`] while ffprobe or ffmpeg runs, and once
+    /// it has exited. Useful for [`Config::count_frames`], packet probing or
+    /// [`crate::analysis::deep_scan`] of long files, which take minutes.
+    ///
+    /// ```rust
+    /// let config = ffprobe::Config::new()
+    ///     .count_frames(true)
+    ///     .progress(|p| eprintln!("{:?} read, at {:?}", p.bytes_read, p.out_time));
+    /// ```
+    pub fn progress(mut self, hook: impl Fn(&ProbeProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressHook(std::sync::Arc::new(hook)));
+        self
+    }
+
+    /// Retry failed probes, eg. of HLS or RTSP sources that intermittently fail.
+    /// Applies to [`crate::ffprobe_config`] and its async variant.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Kill ffprobe and ffmpeg once they wrote more than `limit` bytes to stdout or
+    /// stderr and fail with [`FfProbeError::OutputTooLarge`], so a broken or malicious
+    /// input can't make them dump gigabytes of JSON or log lines into memory.
+    pub fn max_output_bytes(mut self, limit: usize) -> Self {
+        self.max_output_bytes = Some(limit);
+        self
+    }
+
+    /// Also hash the file and each of its streams, see [`FfProbe::hashes`], eg. to find
+    /// duplicates or verify archived copies. Runs ffmpeg, which reads the whole file, after
+    /// the probe.
+    pub fn hash(mut self, algo: HashAlgo) -> Self {
+        self.hash = Some(algo);
+        self
+    }
+
+    /// Also locate the data of each stream and the top level MP4 boxes, see
+    /// [`FfProbe::byte_layout`] and [`FfProbe::is_faststart`]. Runs ffprobe twice more,
+    /// the second run reads every packet.
+    pub fn byte_layout(mut self, byte_layout: bool) -> Self {
+        self.byte_layout = byte_layout;
+        self
+    }
+
+    /// Estimate the duration of inputs the container reports none for, eg. live recorded
+    /// MPEG-TS or truncated files, from the last packet, see
+    /// [`FfProbe::estimated_duration`]. Runs ffprobe once more, reading at most 2000
+    /// packets after seeking to the end, without failing the probe if that run fails.
+    pub fn estimate_duration(mut self, estimate_duration: bool) -> Self {
+        self.estimate_duration = estimate_duration;
+        self
+    }
+
+    /// Command line dialect of [`Config::ffprobe_bin`]. Without it probes try
+    /// [`Dialect::Ffprobe`] first and retry failed runs with [`Dialect::Legacy`], once per
+    /// binary.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Only probe inputs with these protocols, eg. `["file", "https"]`, failing others with
+    /// [`FfProbeError::ProtocolNotAllowed`]. Defaults to `file`, `http` and `https`.
+    ///
+    /// ffprobe gets them as `-protocol_whitelist` for what it opens itself, eg. the
+    /// segments of an HLS playlist, together with the protocols they are built on, eg.
+    /// `tls` and `tcp` for `https`. `file` is left out for remote inputs, so a playlist
+    /// from a user supplied URL can't point ffprobe to `file:///etc/passwd`. The same
+    /// applies to the inputs of ffmpeg runs, eg. of the analyses or [`Config::hash`].
+    pub fn allowed_protocols<I, S>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.allowed_protocols = Some(
+            protocols
+                .into_iter()
+                .map(|p| p.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Probe inputs of any protocol, eg. `concat:` or `pipe:`, for trusted inputs only.
+    pub fn allow_any_protocol(mut self) -> Self {
+        self.allowed_protocols = None;
+        self
+    }
+
+    /// Run ffprobe and ffmpeg through `executor` instead of as local processes, eg. on a
+    /// remote host with [`crate::executor::Ssh`].
+    pub fn executor(mut self, executor: impl Executor + 'static) -> Self {
+        self.executor = Some(ExecutorHook(std::sync::Arc::new(executor)));
+        self
+    }
+
+    /// Run with the given niceness, eg. `19` for the lowest scheduling priority.
+    /// Uses the `nice` binary.
+    #[cfg(unix)]
+    pub fn nice(mut self, niceness: i32) -> Self {
+        self.nice = Some(niceness);
+        self
+    }
+
+    /// Run with the given I/O scheduling priority. Uses the `ionice` binary (Linux only).
+    #[cfg(unix)]
+    pub fn ionice(mut self, priority: IoPriority) -> Self {
+        self.ionice = Some(priority);
+        self
+    }
+
     /// Run ffprobe with the config produced by this builder.
     pub fn run(self, path: impl AsRef<std::path::Path>) -> Result<FfProbe, FfProbeError> {
         ffprobe_config(self, path)
     }
 }
 
+impl Config {
+    /// Command running `program` with the configured environment and priority.
+    pub(crate) fn command(&self, program: &Path) -> Command {
+        #[allow(unused_mut)]
+        let mut wrapper: Vec<OsString> = Vec::new();
+        #[cfg(unix)]
+        {
+            match self.ionice {
+                Some(IoPriority::Realtime(level)) => {
+                    wrapper.extend(["ionice".into(), "-c1".into(), format!("-n{level}").into()])
+                }
+                Some(IoPriority::BestEffort(level)) => {
+                    wrapper.extend(["ionice".into(), "-c2".into(), format!("-n{level}").into()])
+                }
+                Some(IoPriority::Idle) => wrapper.extend(["ionice".into(), "-c3".into()]),
+                None => {}
+            }
+            if let Some(niceness) = self.nice {
+                wrapper.extend(["nice".into(), "-n".into(), niceness.to_string().into()]);
+            }
+        }
+
+        let mut cmd = match wrapper.split_first() {
+            Some((first, rest)) => {
+                let mut cmd = Command::new(first);
+                cmd.args(rest).arg(program);
+                cmd
+            }
+            None => Command::new(program),
+        };
+        if self.clear_env {
+            cmd.env_clear();
+        }
+        cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+        if let Some(dir) = &self.current_dir {
+            cmd.current_dir(dir);
+        }
+        cmd
+    }
+
+    /// `cmd` as run by the [`Config::executor`], for processes not run by
+    /// [`Config::output`].
+    pub(crate) fn executed(&self, cmd: Command) -> Command {
+        match &self.executor {
+            Some(executor) => executor.0.command(&cmd),
+            None => cmd,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self::new()
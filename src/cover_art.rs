@@ -0,0 +1,98 @@
+#[cfg(feature = "process")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "process")]
+use crate::{
+    attachment_stream::dump_attachment,
+    command::{ffmpeg_input, path_arg},
+    error::FfProbeError,
+    ffprobe_config, Config,
+};
+use crate::{FfProbe, Stream, StreamKinds};
+
+impl FfProbe {
+    /// Embedded cover art of the file.
+    ///
+    /// This is the first video stream with the `attached_pic` disposition, as used by
+    /// MP3, M4A and FLAC, or otherwise a Matroska cover attachment
+    /// (see [`crate::AttachmentStream::is_cover_art`]).
+    pub fn cover_art(&self) -> Option<&Stream> {
+        self.streams
+            .iter()
+            .find(|s| matches!(s.stream, StreamKinds::Video(_)) && s.disposition.attached_pic == 1)
+            .or_else(|| {
+                self.streams.iter().find(|s| match &s.stream {
+                    StreamKinds::Attachment(attachment) => attachment.is_cover_art(),
+                    _ => false,
+                })
+            })
+    }
+}
+
+/// Extract the cover art of a file with default settings.
+///
+/// See [`extract_cover_art_config`] for details.
+#[cfg(feature = "process")]
+pub fn extract_cover_art(
+    path: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+) -> Result<Option<PathBuf>, FfProbeError> {
+    extract_cover_art_config(&Config::new(), path, dest)
+}
+
+/// Extract the cover art found by [`FfProbe::cover_art`] with ffmpeg, copying the
+/// image without re-encoding.
+///
+/// If `dest` is a directory, the image is written to it as `cover.<ext>`, or under its
+/// `filename` tag for attachments. Existing files are overwritten.
+/// Returns the path of the written file, or `None` if the file has no cover art.
+#[cfg(feature = "process")]
+pub fn extract_cover_art_config(
+    config: &Config,
+    path: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+) -> Result<Option<PathBuf>, FfProbeError> {
+    let path = path.as_ref();
+    let dest = dest.as_ref();
+    let probe = ffprobe_config(config.clone(), path)?;
+    let Some(stream) = probe.cover_art() else {
+        return Ok(None);
+    };
+
+    let video = match &stream.stream {
+        StreamKinds::Attachment(attachment) => {
+            return dump_attachment(config, path, stream.index, attachment, dest).map(Some);
+        }
+        StreamKinds::Video(video) => video,
+        _ => unreachable!("cover art is a video or attachment stream"),
+    };
+
+    let dest = if dest.is_dir() {
+        let ext = match video.codec_name.as_str() {
+            "mjpeg" => "jpg",
+            "png" => "png",
+            "bmp" => "bmp",
+            "gif" => "gif",
+            "webp" => "webp",
+            _ => "jpg",
+        };
+        dest.join(format!("cover.{ext}"))
+    } else {
+        dest.to_path_buf()
+    };
+
+    let mut cmd = config.command(&config.ffmpeg_bin);
+    cmd.args(["-hide_banner", "-nostdin", "-y"]);
+    cmd.args(ffmpeg_input(config, path).map_err(FfProbeError::from_io)?);
+    cmd.args(["-map", &format!("0:{}", stream.index)]);
+    cmd.args(["-c", "copy", "-frames:v", "1", "-update", "1"]);
+    cmd.arg(path_arg(dest.as_os_str()));
+    let out = config
+        .output(&mut cmd)
+        .map_err(|e| FfProbeError::from_io(e).command_context(Some(path), &cmd))?;
+
+    if !out.status.success() {
+        return Err(FfProbeError::Status(out).command_context(Some(path), &cmd));
+    }
+    Ok(Some(dest))
+}
@@ -0,0 +1,76 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// `creation_time` tag. Muxers write ISO 8601, eg. `2024-03-01T12:30:00.000000Z`, but
+/// some tools write other formats, which are kept as written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CreationTime {
+    Parsed(DateTime<Utc>),
+    /// Value that isn't a recognized date, as written in the file.
+    Raw(String),
+}
+
+impl CreationTime {
+    /// Parse a creation time: RFC 3339, or a date and time without offset
+    /// (eg. `2024-03-01 12:30:00`), which is taken as UTC.
+    pub fn parse(s: &str) -> Self {
+        let trimmed = s.trim();
+        DateTime::parse_from_rfc3339(trimmed)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                    .iter()
+                    .find_map(|f| NaiveDateTime::parse_from_str(trimmed, f).ok())
+                    .map(|t| t.and_utc())
+            })
+            .map_or_else(|| CreationTime::Raw(s.to_string()), CreationTime::Parsed)
+    }
+
+    /// The parsed date, `None` if it wasn't recognized.
+    pub fn as_datetime(&self) -> Option<DateTime<Utc>> {
+        match self {
+            CreationTime::Parsed(t) => Some(*t),
+            CreationTime::Raw(_) => None,
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for CreationTime {
+    fn from(time: DateTime<Utc>) -> Self {
+        CreationTime::Parsed(time)
+    }
+}
+
+impl FromStr for CreationTime {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CreationTime::parse(s))
+    }
+}
+
+impl Display for CreationTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreationTime::Parsed(t) => t
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+                .fmt(f),
+            CreationTime::Raw(s) => s.fmt(f),
+        }
+    }
+}
+
+impl Serialize for CreationTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CreationTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(CreationTime::parse(&String::deserialize(deserializer)?))
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    error::{invalid_data, FfProbeError},
+    FfProbe,
+};
+#[cfg(feature = "process")]
+use crate::{ffprobe_config, Config};
+
+/// Position on a CD, in frames of 1/75 s. eg. 03:25:40
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CueTime(pub u64);
+
+impl CueTime {
+    /// CD frames per second.
+    pub const FRAMES_PER_SECOND: u64 = 75;
+
+    pub fn frames(&self) -> u64 {
+        self.0
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_nanos(self.0 * 1_000_000_000 / Self::FRAMES_PER_SECOND)
+    }
+
+    /// Nearest position to `duration`.
+    pub fn from_duration(duration: Duration) -> Self {
+        CueTime((duration.as_secs_f64() * Self::FRAMES_PER_SECOND as f64).round() as u64)
+    }
+}
+
+impl FromStr for CueTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid cue time: {s}");
+        let mut parts = s
+            .split(':')
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()));
+        let (Some(m), Some(sec), Some(f), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let (m, sec, f) = (m?, sec?, f?);
+        if sec >= 60 || f >= Self::FRAMES_PER_SECOND {
+            return Err(invalid());
+        }
+        Ok(CueTime((m * 60 + sec) * Self::FRAMES_PER_SECOND + f))
+    }
+}
+
+impl Display for CueTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.0 / Self::FRAMES_PER_SECOND;
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            secs / 60,
+            secs % 60,
+            self.0 % Self::FRAMES_PER_SECOND
+        )
+    }
+}
+
+/// Parsed cue sheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CueSheet {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    /// Media catalog number (UPC/EAN).
+    pub catalog: Option<String>,
+    /// `REM` comments with a key, eg. (GENRE, Jazz) or (DATE, 1959)
+    pub comments: Vec<(String, String)>,
+    pub files: Vec<CueFile>,
+}
+
+/// `FILE` entry of a cue sheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CueFile {
+    /// Path as written in the cue sheet, usually relative to the sheet. When probing, a
+    /// missing file is also looked up with a flac, wv, ape, tta or wav extension.
+    pub path: String,
+    /// eg. WAVE, BINARY, MP3
+    pub file_type: String,
+    pub tracks: Vec<CueTrack>,
+}
+
+/// `TRACK` entry of a cue sheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CueTrack {
+    pub number: u32,
+    /// eg. AUDIO, MODE1/2352
+    pub track_type: String,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub isrc: Option<String>,
+    /// eg. DCP, PRE
+    pub flags: Vec<String>,
+    pub pregap: Option<CueTime>,
+    pub postgap: Option<CueTime>,
+    /// `INDEX` entries as (number, position in the file).
+    pub indexes: Vec<(u32, CueTime)>,
+}
+
+impl CueTrack {
+    /// Start of the track in its file: `INDEX 01`, or the first index if there is none.
+    pub fn start(&self) -> Option<CueTime> {
+        self.indexes
+            .iter()
+            .find(|(n, _)| *n == 1)
+            .or(self.indexes.first())
+            .map(|(_, time)| *time)
+    }
+}
+
+impl CueSheet {
+    /// Read and parse a cue sheet. Sheets that aren't valid UTF-8 are read as Latin-1,
+    /// which most older rippers wrote.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FfProbeError> {
+        let bytes = std::fs::read(path).map_err(FfProbeError::Io)?;
+        let text = match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(err) => err.into_bytes().iter().map(|&b| b as char).collect(),
+        };
+        text.parse()
+    }
+}
+
+impl FromStr for CueSheet {
+    type Err = FfProbeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sheet = CueSheet::default();
+        for (n, line) in s.trim_start_matches('\u{feff}').lines().enumerate() {
+            parse_line(&mut sheet, line)
+                .map_err(|msg| invalid_data(format!("invalid cue sheet line {}: {msg}", n + 1)))?;
+        }
+        Ok(sheet)
+    }
+}
+
+fn parse_line(sheet: &mut CueSheet, line: &str) -> Result<(), String> {
+    let (command, rest) = split_word(line.trim());
+    let track = sheet.files.last_mut().and_then(|f| f.tracks.last_mut());
+    match command.to_ascii_uppercase().as_str() {
+        "" => {}
+        "REM" => {
+            let (key, value) = split_word(rest);
+            if !key.is_empty() {
+                sheet
+                    .comments
+                    .push((key.to_string(), unquote(value).to_string()));
+            }
+        }
+        "FILE" => {
+            let (path, file_type) = split_string(rest);
+            sheet.files.push(CueFile {
+                path: path.to_string(),
+                file_type: file_type.trim().to_string(),
+                tracks: Vec::new(),
+            });
+        }
+        "TRACK" => {
+            let file = sheet.files.last_mut().ok_or("TRACK before FILE")?;
+            let (number, track_type) = split_word(rest);
+            file.tracks.push(CueTrack {
+                number: number
+                    .parse()
+                    .map_err(|_| format!("invalid track number: {number}"))?,
+                track_type: track_type.trim().to_string(),
+                ..Default::default()
+            });
+        }
+        "INDEX" => {
+            let track = track.ok_or("INDEX outside of a TRACK")?;
+            let (number, time) = split_word(rest);
+            let number = number
+                .parse()
+                .map_err(|_| format!("invalid index number: {number}"))?;
+            track.indexes.push((number, time.trim().parse()?));
+        }
+        "PREGAP" => track.ok_or("PREGAP outside of a TRACK")?.pregap = Some(rest.parse()?),
+        "POSTGAP" => track.ok_or("POSTGAP outside of a TRACK")?.postgap = Some(rest.parse()?),
+        "ISRC" => track.ok_or("ISRC outside of a TRACK")?.isrc = Some(rest.to_string()),
+        "FLAGS" => {
+            track.ok_or("FLAGS outside of a TRACK")?.flags =
+                rest.split_whitespace().map(str::to_string).collect()
+        }
+        "CATALOG" => sheet.catalog = Some(rest.to_string()),
+        command @ ("TITLE" | "PERFORMER" | "SONGWRITER") => {
+            let value = Some(unquote(rest).to_string());
+            let (title, performer, songwriter) = match track {
+                Some(t) => (&mut t.title, &mut t.performer, &mut t.songwriter),
+                None => (
+                    &mut sheet.title,
+                    &mut sheet.performer,
+                    &mut sheet.songwriter,
+                ),
+            };
+            match command {
+                "TITLE" => *title = value,
+                "PERFORMER" => *performer = value,
+                _ => *songwriter = value,
+            }
+        }
+        // CDTEXTFILE and unknown commands carry nothing we expose.
+        _ => {}
+    }
+    Ok(())
+}
+
+fn split_word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    s.split_once(char::is_whitespace)
+        .map(|(word, rest)| (word, rest.trim()))
+        .unwrap_or((s, ""))
+}
+
+/// Split a possibly quoted string from the rest of the line.
+fn split_string(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    match s.strip_prefix('"').and_then(|s| s.split_once('"')) {
+        Some((quoted, rest)) => (quoted, rest.trim()),
+        // Unquoted paths can't contain spaces, the type is the last word.
+        None => s.rsplit_once(char::is_whitespace).unwrap_or((s, "")),
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Path of a referenced file. Rippers often keep `FILE "album.wav" WAVE` after
+/// compressing the audio, so a missing file falls back to the same name with a
+/// common lossless extension.
+#[cfg(feature = "process")]
+fn resolve_media(dir: &Path, file: &str) -> PathBuf {
+    let path = dir.join(file);
+    if path.exists() {
+        return path;
+    }
+    ["flac", "wv", "ape", "tta", "wav"]
+        .iter()
+        .map(|ext| path.with_extension(ext))
+        .find(|p| p.exists())
+        .unwrap_or(path)
+}
+
+/// Cue sheet together with the probed media it references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CueProbe {
+    pub sheet: CueSheet,
+    /// Resolved path and probe result of every `FILE`, in sheet order.
+    pub files: Vec<(PathBuf, FfProbe)>,
+    /// Tracks with their boundaries, in sheet order.
+    pub tracks: Vec<LogicalTrack>,
+}
+
+/// Track of an album rip, as a range of one of the referenced files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LogicalTrack {
+    pub number: u32,
+    pub title: Option<String>,
+    /// Track performer, or the album performer if the track has none.
+    pub performer: Option<String>,
+    pub isrc: Option<String>,
+    /// Index into [`CueProbe::files`].
+    pub file: usize,
+    /// Start in the file.
+    pub start: CueTime,
+    /// End in the file: start of the next track in the same file, or the end of the file.
+    /// `None` if ffprobe reported no duration for the last track's file.
+    pub end: Option<CueTime>,
+}
+
+impl LogicalTrack {
+    pub fn duration(&self) -> Option<Duration> {
+        Some(
+            self.end?
+                .as_duration()
+                .saturating_sub(self.start.as_duration()),
+        )
+    }
+}
+
+impl CueProbe {
+    #[cfg(feature = "chapters")]
+    /// Tracks as chapters on a single timeline, with the files laid out back to back.
+    /// Time base is 1/75, ie. chapter positions are in CD frames.
+    pub fn chapters(&self) -> Vec<crate::Chapter> {
+        let mut offsets = Vec::with_capacity(self.files.len());
+        let mut offset = 0;
+        for (_, probe) in &self.files {
+            offsets.push(offset);
+            offset += probe
+                .format
+                .duration
+                .map(|d| CueTime::from_duration(d).0)
+                .unwrap_or_default();
+        }
+        self.tracks
+            .iter()
+            .enumerate()
+            .map(|(id, track)| {
+                let offset = offsets[track.file];
+                let end = track.end.unwrap_or(track.start);
+                crate::Chapter::new(
+                    id as i64,
+                    crate::Ratio::new(1, CueTime::FRAMES_PER_SECOND),
+                    (offset + track.start.0) as i64,
+                    (offset + end.0) as i64,
+                    track.title.clone().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Parse a cue sheet and probe the files it references, with default settings.
+///
+/// See [`probe_cue_config`] for details.
+#[cfg(feature = "process")]
+pub fn probe_cue(path: impl AsRef<Path>) -> Result<CueProbe, FfProbeError> {
+    probe_cue_config(Config::new(), path)
+}
+
+/// Parse a cue sheet and probe the files it references, eg. `album.cue` + `album.flac`.
+///
+/// File paths are resolved relative to the directory of the sheet, see
+/// [`CueFile::path`]. Track boundaries
+/// come from `INDEX 01`, so pregaps are part of the previous track. The last track of a
+/// file ends with the file.
+#[cfg(feature = "process")]
+pub fn probe_cue_config(config: Config, path: impl AsRef<Path>) -> Result<CueProbe, FfProbeError> {
+    let path = path.as_ref();
+    let sheet = CueSheet::from_path(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut files = Vec::with_capacity(sheet.files.len());
+    let mut tracks = Vec::new();
+    for (file_index, file) in sheet.files.iter().enumerate() {
+        let media = resolve_media(dir, &file.path);
+        let probe = ffprobe_config(config.clone(), &media)?;
+        let file_end = probe.format.duration.map(CueTime::from_duration);
+
+        for (i, track) in file.tracks.iter().enumerate() {
+            let start = track.start().ok_or_else(|| {
+                invalid_data(format!("cue sheet track {} has no INDEX", track.number))
+            })?;
+            let end = match file.tracks.get(i + 1) {
+                Some(next) => next.start(),
+                None => file_end,
+            };
+            tracks.push(LogicalTrack {
+                number: track.number,
+                title: track.title.clone(),
+                performer: track.performer.clone().or_else(|| sheet.performer.clone()),
+                isrc: track.isrc.clone(),
+                file: file_index,
+                start,
+                end,
+            });
+        }
+        files.push((media, probe));
+    }
+
+    Ok(CueProbe {
+        sheet,
+        files,
+        tracks,
+    })
+}
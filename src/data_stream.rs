@@ -2,21 +2,36 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+use crate::{
+    creation_time::CreationTime,
+    flexible_num,
+    tags::{capture, CaptureTags, Tags},
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Tags for the type data
+#[non_exhaustive]
 pub struct DataTags {
-    creation_time: Option<String>,
+    pub creation_time: Option<CreationTime>,
     language: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
+    /// All tags, including the ones covered by the typed fields.
+    #[serde(skip)]
+    pub all: Tags,
 }
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
 /// Stream of type data
+#[non_exhaustive]
 pub struct DataStream {
     /// Duration of the video stream in timestamp units.
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub duration_ts: u64,
     /// Metadata tags associated with the video stream.
+    #[serde(deserialize_with = "capture")]
     pub tags: DataTags,
     /// Long name of the codec used for the video stream. eg. binary data
     pub codec_long_name: Option<String>,
@@ -30,3 +45,19 @@ pub struct DataStream {
     #[cfg(feature = "__internal_deny_unknown_fields")]
     duration: Option<serde_json::Value>,
 }
+
+impl DataStream {
+    /// Data stream with the given codec, eg. `bin_data`.
+    pub fn new(codec_name: impl Into<String>) -> Self {
+        Self {
+            codec_name: Some(codec_name.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl CaptureTags for DataTags {
+    fn all_mut(&mut self) -> &mut Tags {
+        &mut self.all
+    }
+}
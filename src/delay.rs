@@ -0,0 +1,55 @@
+use crate::Stream;
+#[cfg(feature = "format")]
+use crate::{FfProbe, StreamIndex};
+
+impl Stream {
+    /// Delay of the stream relative to the container timeline `origin`, in seconds.
+    ///
+    /// Sign convention: positive values mean the first sample is presented after the
+    /// origin, eg. a Matroska track delay or an MP4 edit list starting with an empty edit.
+    /// Negative values mean leading samples are not presented, eg. an MP4 edit list
+    /// skipping the encoder delay of AAC.
+    ///
+    /// Use `0` for containers with a zero based timeline like Matroska and MP4.
+    /// MPEG-TS timestamps start at an arbitrary PCR value, use
+    /// [`FfProbe::container_delays`] to pick the origin automatically.
+    pub fn container_delay(&self, origin: f64) -> f64 {
+        self.start_time() - origin
+    }
+}
+
+#[cfg(feature = "format")]
+impl FfProbe {
+    /// Start of the container timeline in seconds, see [`Stream::container_delay`].
+    ///
+    /// `0` for containers with a zero based timeline. For MPEG transport and program
+    /// streams, whose timestamps start at an arbitrary clock value, this is the earliest
+    /// stream start.
+    pub fn timeline_origin(&self) -> f64 {
+        let arbitrary_origin = self
+            .format
+            .format_name
+            .split(',')
+            .any(|name| matches!(name, "mpegts" | "mpegtsraw" | "mpeg" | "mpegvideo"));
+        if !arbitrary_origin {
+            return 0.;
+        }
+        self.streams
+            .iter()
+            .map(Stream::start_time)
+            .reduce(f64::min)
+            .unwrap_or(0.)
+    }
+
+    /// [`Stream::container_delay`] of every stream as `(stream index, delay)`,
+    /// relative to [`FfProbe::timeline_origin`].
+    ///
+    /// Remuxers can pass these to eg. `-itsoffset` to keep streams in sync.
+    pub fn container_delays(&self) -> Vec<(StreamIndex, f64)> {
+        let origin = self.timeline_origin();
+        self.streams
+            .iter()
+            .map(|s| (s.index, s.container_delay(origin)))
+            .collect()
+    }
+}
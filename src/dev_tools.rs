@@ -0,0 +1,226 @@
+//! Synthesized test media, so tests and examples don't need binary files in the repo.
+//!
+//! Requires the `ffmpeg` binary, see [`crate::Config::ffmpeg_bin`]. Video and audio come
+//! from the lavfi `testsrc` and `sine` sources and are encoded with ffmpeg's built-in
+//! `mpeg4` and `aac` encoders, which every ffmpeg build ships.
+
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{error::FfProbeError, Config};
+
+/// Description of a test file, see [`TestMedia::generate`].
+///
+/// ```rust,no_run
+/// use ffprobe::dev_tools::TestMedia;
+///
+/// let path = TestMedia::all_kinds()
+///     .generate(&ffprobe::Config::new(), std::env::temp_dir().join("sample.mkv"))
+///     .unwrap();
+/// let probe = ffprobe::ffprobe(&path).unwrap();
+/// assert_eq!(probe.streams.len(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestMedia {
+    duration: Duration,
+    video: Option<(u32, u32)>,
+    frame_rate: u32,
+    audio: Option<u32>,
+    subtitles: bool,
+    chapters: u32,
+    attachment: bool,
+}
+
+impl Default for TestMedia {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(2),
+            video: Some((320, 240)),
+            frame_rate: 25,
+            audio: Some(48_000),
+            subtitles: false,
+            chapters: 0,
+            attachment: false,
+        }
+    }
+}
+
+impl TestMedia {
+    /// 2 s of 320x240 video at 25 fps and a 48 kHz sine tone.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One stream of every kind probed by this crate and two chapters. Needs a
+    /// Matroska destination, as only Matroska stores attachments.
+    pub fn all_kinds() -> Self {
+        Self::new().subtitles(true).chapters(2).attachment(true)
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Video stream of the given size, `None` for audio only files.
+    pub fn video(mut self, size: Option<(u32, u32)>) -> Self {
+        self.video = size;
+        self
+    }
+
+    pub fn frame_rate(mut self, frame_rate: u32) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// Audio stream with the given sample rate, `None` for video only files.
+    pub fn audio(mut self, sample_rate: Option<u32>) -> Self {
+        self.audio = sample_rate;
+        self
+    }
+
+    /// Subtitle stream with one cue per second. SubRip in Matroska, mov_text in MP4.
+    pub fn subtitles(mut self, subtitles: bool) -> Self {
+        self.subtitles = subtitles;
+        self
+    }
+
+    /// Split the file into `count` chapters of equal length.
+    pub fn chapters(mut self, count: u32) -> Self {
+        self.chapters = count;
+        self
+    }
+
+    /// Attach a small text file (Matroska only).
+    pub fn attachment(mut self, attachment: bool) -> Self {
+        self.attachment = attachment;
+        self
+    }
+
+    /// Write the file to `dest`, the container follows the extension, eg. `.mkv` or `.mp4`.
+    /// Existing files are overwritten.
+    pub fn generate(
+        &self,
+        config: &Config,
+        dest: impl AsRef<Path>,
+    ) -> Result<PathBuf, FfProbeError> {
+        let dest = dest.as_ref();
+        let secs = format!("{}", self.duration.as_secs_f64());
+        let mp4 = matches!(
+            dest.extension().and_then(|e| e.to_str()),
+            Some("mp4" | "m4v" | "mov")
+        );
+        let sidecar = |ext: &str| {
+            let mut name = dest.file_name().unwrap_or_default().to_os_string();
+            name.push(format!(".{ext}"));
+            dest.with_file_name(name)
+        };
+
+        let mut cmd = config.command(&config.ffmpeg_bin);
+        cmd.args(["-hide_banner", "-nostdin", "-v", "error", "-y"]);
+        let mut inputs = 0;
+        let mut maps = Vec::new();
+        let mut temp_files = Vec::new();
+
+        if let Some((width, height)) = self.video {
+            cmd.args(["-f", "lavfi", "-i"]).arg(format!(
+                "testsrc=size={width}x{height}:rate={}:duration={secs}",
+                self.frame_rate
+            ));
+            maps.push(format!("{inputs}:v"));
+            inputs += 1;
+        }
+        if let Some(sample_rate) = self.audio {
+            cmd.args(["-f", "lavfi", "-i"]).arg(format!(
+                "sine=frequency=440:sample_rate={sample_rate}:duration={secs}"
+            ));
+            maps.push(format!("{inputs}:a"));
+            inputs += 1;
+        }
+        if self.subtitles {
+            let path = sidecar("srt");
+            std::fs::write(&path, srt(self.duration)).map_err(FfProbeError::Io)?;
+            cmd.arg("-i").arg(&path);
+            temp_files.push(path);
+            maps.push(format!("{inputs}:s"));
+            inputs += 1;
+        }
+        if self.chapters > 0 {
+            let path = sidecar("ffmetadata");
+            std::fs::write(&path, ffmetadata(self.duration, self.chapters))
+                .map_err(FfProbeError::Io)?;
+            cmd.arg("-i").arg(&path);
+            temp_files.push(path);
+            cmd.arg("-map_chapters").arg(inputs.to_string());
+        }
+
+        for map in &maps {
+            cmd.args(["-map", map]);
+        }
+        cmd.args(["-c:v", "mpeg4", "-c:a", "aac"]);
+        cmd.args(["-c:s", if mp4 { "mov_text" } else { "srt" }]);
+        if self.attachment {
+            let path = sidecar("txt");
+            std::fs::write(&path, "ffprobe-rs test attachment\n").map_err(FfProbeError::Io)?;
+            cmd.arg("-attach").arg(&path);
+            cmd.args(["-metadata:s:t", "mimetype=text/plain"]);
+            temp_files.push(path);
+        }
+        cmd.arg("-t").arg(&secs).arg(dest);
+
+        let out = config.output(&mut cmd);
+        for path in temp_files {
+            let _ = std::fs::remove_file(path);
+        }
+        let out = out.map_err(|e| FfProbeError::from_io(e).command_context(None, &cmd))?;
+        if !out.status.success() {
+            return Err(FfProbeError::Status(out).command_context(None, &cmd));
+        }
+        Ok(dest.to_path_buf())
+    }
+}
+
+fn srt(duration: Duration) -> String {
+    let timestamp = |ms: u128| {
+        format!(
+            "{:02}:{:02}:{:02},{:03}",
+            ms / 3_600_000,
+            ms / 60_000 % 60,
+            ms / 1000 % 60,
+            ms % 1000
+        )
+    };
+    let mut out = String::new();
+    let total = duration.as_millis();
+    for (index, start) in (0..total).step_by(1000).enumerate() {
+        let end = (start + 1000).min(total);
+        let _ = write!(
+            out,
+            "{}\n{} --> {}\nCue {}\n\n",
+            index + 1,
+            timestamp(start),
+            timestamp(end),
+            index + 1
+        );
+    }
+    out
+}
+
+fn ffmetadata(duration: Duration, chapters: u32) -> String {
+    let mut out = String::from(";FFMETADATA1\n");
+    let total = duration.as_millis();
+    let count = chapters as u128;
+    for i in 0..count {
+        let _ = write!(
+            out,
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle=Chapter {}\n",
+            total * i / count,
+            total * (i + 1) / count,
+            i + 1
+        );
+    }
+    out
+}
@@ -0,0 +1,137 @@
+#[cfg(feature = "process")]
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::PathBuf,
+    process::Output,
+    sync::{Mutex, OnceLock},
+};
+
+#[cfg(feature = "process")]
+use serde_json::Value;
+
+#[cfg(feature = "process")]
+use crate::{error::FfProbeError, Config, FfProbe, ProbeCommand};
+
+/// Command line dialect of the probe binary, see [`FfProbe::dialect`](crate::FfProbe::dialect).
+///
+/// Probes run ffprobe with [`Dialect::Ffprobe`] first and, if it fails with
+/// `Unrecognized option`, retry once with [`Dialect::Legacy`]. The dialect is then kept
+/// for later probes with the same [`Config::ffprobe_bin`](crate::Config::ffprobe_bin).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Dialect {
+    /// ffprobe 4.0 and newer.
+    #[default]
+    Ffprobe,
+    /// avprobe of libav and old ffprobe versions: `-of json` instead of `-print_format`,
+    /// and only `-show_format` and `-show_streams`. Chapters, frame and packet counts,
+    /// read intervals, `-show_entries` and log level prefixes aren't requested. The
+    /// `-protocol_whitelist` is, binaries without it fail with
+    /// [`FfProbeError::ProtocolNotAllowed`](crate::error::FfProbeError::ProtocolNotAllowed).
+    Legacy,
+}
+
+/// Errors of binaries that don't know an option or its value, eg.
+/// `Unrecognized option 'show_chapters'` or `Invalid loglevel "level+warning"`.
+#[cfg(feature = "process")]
+const UNSUPPORTED: &[&str] = &["Unrecognized option", "Invalid loglevel"];
+
+/// Whether the failed run `out` of `command` was a binary that doesn't understand the
+/// command line. At `-v quiet` ffprobe doesn't log why it failed, the options are then
+/// checked without the input, which fails right after parsing them.
+#[cfg(feature = "process")]
+pub(crate) fn is_unsupported(command: &ProbeCommand, out: &Output) -> bool {
+    option_errors(command, out).is_some_and(|stderr| unsupported(&stderr))
+}
+
+/// Whether the failed run `out` of `command` was a binary without `-protocol_whitelist`,
+/// eg. avprobe, which mustn't probe inputs without it.
+#[cfg(feature = "process")]
+pub(crate) fn rejects_whitelist(command: &ProbeCommand, out: &Output) -> bool {
+    command.config().allowed_protocols.is_some()
+        && option_errors(command, out).is_some_and(|stderr| {
+            unsupported(&stderr) && String::from_utf8_lossy(&stderr).contains("protocol_whitelist")
+        })
+}
+
+/// Errors logged by the failed run `out` of `command`, or by its options without the input
+/// at `-v quiet`.
+#[cfg(feature = "process")]
+fn option_errors<'a>(command: &ProbeCommand, out: &'a Output) -> Option<Cow<'a, [u8]>> {
+    if !out.stderr.is_empty() || command.config().log_level().is_some() {
+        return Some(Cow::Borrowed(&out.stderr));
+    }
+    let check = command.options_only().output().ok()?;
+    Some(Cow::Owned(check.stderr))
+}
+
+#[cfg(feature = "process")]
+fn unsupported(stderr: &[u8]) -> bool {
+    let stderr = String::from_utf8_lossy(stderr);
+    UNSUPPORTED.iter().any(|marker| stderr.contains(marker))
+}
+
+/// Dialect of the binary of `config`, from the errors of its options without an input.
+#[cfg(feature = "process")]
+pub(crate) fn detect(config: &Config) -> std::io::Result<Dialect> {
+    if let Some(dialect) = known(config) {
+        return Ok(dialect);
+    }
+    let check = ProbeCommand::new(config.clone())
+        .dialect(Dialect::Ffprobe)
+        .options_only()
+        .output()?;
+    let dialect = if unsupported(&check.stderr) {
+        Dialect::Legacy
+    } else {
+        Dialect::Ffprobe
+    };
+    remember(config, dialect);
+    Ok(dialect)
+}
+
+/// Dialects of the local binaries found by earlier probes, see [`known`].
+#[cfg(feature = "process")]
+static DETECTED: OnceLock<Mutex<HashMap<PathBuf, Dialect>>> = OnceLock::new();
+
+/// Dialect of the binary of `config`, set with [`Config::dialect`] or found by an earlier
+/// probe. Binaries run by an executor aren't remembered, their path is on another host.
+#[cfg(feature = "process")]
+pub(crate) fn known(config: &Config) -> Option<Dialect> {
+    if config.dialect.is_some() || config.executor.is_some() {
+        return config.dialect;
+    }
+    let detected = DETECTED.get()?.lock().unwrap_or_else(|e| e.into_inner());
+    detected.get(&config.ffprobe_bin).copied()
+}
+
+/// Keep the `dialect` of the binary of `config` for [`known`].
+#[cfg(feature = "process")]
+pub(crate) fn remember(config: &Config, dialect: Dialect) {
+    if config.dialect.is_some() || config.executor.is_some() {
+        return;
+    }
+    let mut detected = DETECTED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    detected.insert(config.ffprobe_bin.clone(), dialect);
+}
+
+/// Parse the output of a run in `dialect`, [`Dialect::Legacy`] has no chapters section.
+#[cfg(feature = "process")]
+pub(crate) fn parse(dialect: Dialect, stdout: &[u8]) -> Result<FfProbe, FfProbeError> {
+    if dialect != Dialect::Legacy {
+        return FfProbe::from_slice(stdout).map(|probe| FfProbe { dialect, ..probe });
+    }
+    let mut raw: Value = serde_json::from_slice(stdout).map_err(FfProbeError::Deserialize)?;
+    if let Some(sections) = raw.as_object_mut() {
+        for section in ["streams", "chapters"] {
+            sections
+                .entry(section)
+                .or_insert_with(|| Value::Array(Vec::new()));
+        }
+    }
+    FfProbe::from_value(raw).map(|probe| FfProbe { dialect, ..probe })
+}
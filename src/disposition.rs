@@ -1,24 +1,46 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+use crate::flexible_num;
+
+#[derive(Default, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
 pub struct Disposition {
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub attached_pic: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub captions: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub clean_effects: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub comment: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub default: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub dependent: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub descriptions: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub dub: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub forced: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub hearing_impaired: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub karaoke: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub lyrics: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub metadata: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub non_diegetic: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub original: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub still_image: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub timed_thumbnails: i64,
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub visual_impaired: i64,
 }
@@ -0,0 +1,131 @@
+//! Duration of inputs whose container doesn't report one, eg. live recorded MPEG-TS, see
+//! [`FfProbe::best_duration`] and [`Config::estimate_duration`](crate::Config::estimate_duration).
+
+#[cfg(feature = "process")]
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(feature = "process")]
+use serde::Deserialize;
+
+use crate::FfProbe;
+#[cfg(feature = "streams")]
+use crate::Stream;
+#[cfg(feature = "process")]
+use crate::{
+    error::{self, FfProbeError},
+    flexible_num, Config, ProbeCommand, ReadInterval,
+};
+
+/// Position the tail probe seeks to, past the end of most recordings. Demuxers seek to the
+/// last keyframe before it, and it is short of the 26.5 hours after which MPEG-TS
+/// timestamps wrap around.
+#[cfg(feature = "process")]
+const TAIL_SEEK: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Packets read by the tail probe after seeking, enough for the last GOP of all streams.
+#[cfg(feature = "process")]
+const TAIL_PACKETS: u64 = 2000;
+
+impl FfProbe {
+    /// Duration of the input from the first source that has one: the format duration,
+    /// the longest stream, eg. from the `DURATION` tag of Matroska, then the
+    /// [`FfProbe::estimated_duration`].
+    pub fn best_duration(&self) -> Option<Duration> {
+        #[cfg(feature = "format")]
+        if let Some(duration) = self.format.duration.filter(|d| !d.is_zero()) {
+            return Some(duration);
+        }
+        #[cfg(feature = "streams")]
+        if let Some(duration) = self
+            .streams
+            .iter()
+            .filter_map(stream_duration)
+            .filter(|d| !d.is_zero())
+            .max()
+        {
+            return Some(duration);
+        }
+        self.estimated_duration
+    }
+
+    /// Start of the input, the format start time or the earliest stream start.
+    #[cfg(feature = "process")]
+    fn start_time(&self) -> Duration {
+        #[cfg(feature = "format")]
+        if let Some(start) = self.format.start_time {
+            return start;
+        }
+        #[cfg(feature = "streams")]
+        if let Some(start) = self
+            .streams
+            .iter()
+            .map(Stream::start_time)
+            .filter(|start| start.is_finite())
+            .reduce(f64::min)
+        {
+            return Duration::try_from_secs_f64(start).unwrap_or_default();
+        }
+        Duration::ZERO
+    }
+}
+
+/// Duration from `duration_ts`, or the `DURATION` tag of Matroska.
+#[cfg(feature = "streams")]
+pub(crate) fn stream_duration(stream: &Stream) -> Option<Duration> {
+    stream
+        .duration()
+        .or_else(|| stream.tags().and_then(|t| t.duration))
+}
+
+/// Estimate the duration of `path` from the end of its last packet, reading the packets
+/// after seeking close to the end. `None` without packets.
+#[cfg(feature = "process")]
+pub(crate) fn estimate_duration(
+    config: &Config,
+    path: &Path,
+    probe: &FfProbe,
+) -> Result<Option<Duration>, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        packets: Vec<Packet>,
+    }
+    #[derive(Deserialize)]
+    struct Packet {
+        #[serde(deserialize_with = "flexible_num::option", default)]
+        pts_time: Option<f64>,
+        #[serde(deserialize_with = "flexible_num::option", default)]
+        dts_time: Option<f64>,
+        #[serde(deserialize_with = "flexible_num::option", default)]
+        duration_time: Option<f64>,
+    }
+
+    let mut config = config.clone();
+    config.read_intervals = vec![ReadInterval::start(TAIL_SEEK).frames(TAIL_PACKETS)];
+    config.count_frames = false;
+    config.count_packets = false;
+    let out = ProbeCommand::new(config)
+        .default_sections(false)
+        .args(["-show_entries", "packet=pts_time,dts_time,duration_time"])
+        .input(path)
+        .output()
+        .map_err(FfProbeError::from_io)?;
+    if !out.status.success() {
+        return Err(error::status_error(path, out));
+    }
+    let output: Output = serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)?;
+
+    let end = output
+        .packets
+        .iter()
+        .filter_map(|packet| {
+            let time = packet.pts_time.or(packet.dts_time)?;
+            Some(time + packet.duration_time.unwrap_or(0.))
+        })
+        .filter(|end| end.is_finite())
+        .reduce(f64::max);
+    Ok(end
+        .and_then(|end| Duration::try_from_secs_f64(end).ok())
+        .map(|end| end.saturating_sub(probe.start_time())))
+}
@@ -0,0 +1,185 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::error::{invalid_data, FfProbeError};
+
+pub(crate) const EBML: u32 = 0x1A45_DFA3;
+pub(crate) const SEGMENT: u32 = 0x1853_8067;
+pub(crate) const SEEK_HEAD: u32 = 0x114D_9B74;
+pub(crate) const SEEK: u32 = 0x4DBB;
+pub(crate) const SEEK_ID: u32 = 0x53AB;
+pub(crate) const SEEK_POSITION: u32 = 0x53AC;
+pub(crate) const INFO: u32 = 0x1549_A966;
+pub(crate) const TIMESTAMP_SCALE: u32 = 0x2A_D7B1;
+pub(crate) const DURATION: u32 = 0x4489;
+pub(crate) const CLUSTER: u32 = 0x1F43_B675;
+pub(crate) const CUES: u32 = 0x1C53_BB6B;
+pub(crate) const CUE_POINT: u32 = 0xBB;
+pub(crate) const CUE_TIME: u32 = 0xB3;
+pub(crate) const CHAPTERS: u32 = 0x1043_A770;
+pub(crate) const EDITION_ENTRY: u32 = 0x45B9;
+pub(crate) const EDITION_UID: u32 = 0x45BC;
+pub(crate) const EDITION_FLAG_HIDDEN: u32 = 0x45BD;
+pub(crate) const EDITION_FLAG_DEFAULT: u32 = 0x45DB;
+pub(crate) const EDITION_FLAG_ORDERED: u32 = 0x45DD;
+pub(crate) const EDITION_DISPLAY: u32 = 0x4520;
+pub(crate) const EDITION_STRING: u32 = 0x4521;
+pub(crate) const CHAPTER_ATOM: u32 = 0xB6;
+pub(crate) const CHAPTER_UID: u32 = 0x73C4;
+pub(crate) const CHAPTER_TIME_START: u32 = 0x91;
+pub(crate) const CHAPTER_TIME_END: u32 = 0x92;
+pub(crate) const CHAPTER_FLAG_HIDDEN: u32 = 0x98;
+pub(crate) const CHAPTER_FLAG_ENABLED: u32 = 0x4598;
+pub(crate) const CHAPTER_SEGMENT_UUID: u32 = 0x6E67;
+pub(crate) const CHAPTER_DISPLAY: u32 = 0x80;
+pub(crate) const CHAP_STRING: u32 = 0x85;
+pub(crate) const CHAP_LANGUAGE: u32 = 0x437C;
+pub(crate) const CHAP_LANGUAGE_IETF: u32 = 0x437D;
+
+/// Header of an EBML element.
+pub(crate) struct Element {
+    pub id: u32,
+    /// Offset of the element data.
+    pub data_offset: u64,
+    /// `None` for elements of unknown size (eg. live streamed clusters).
+    pub size: Option<u64>,
+}
+
+/// Read a variable size integer, returning (value, length). IDs keep their marker bits.
+fn read_vint<R: Read>(reader: &mut R, keep_marker: bool) -> std::io::Result<(u64, usize)> {
+    let mut first = [0u8; 1];
+    reader.read_exact(&mut first)?;
+    let len = first[0].leading_zeros() as usize + 1;
+    if len > 8 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "invalid EBML variable size integer",
+        ));
+    }
+    let mut value = if keep_marker {
+        first[0] as u64
+    } else {
+        (first[0] as u64) & (0xFF >> len)
+    };
+    let mut rest = [0u8; 7];
+    reader.read_exact(&mut rest[..len - 1])?;
+    for byte in &rest[..len - 1] {
+        value = (value << 8) | *byte as u64;
+    }
+    Ok((value, len))
+}
+
+pub(crate) fn read_element<R: Read + Seek>(reader: &mut R) -> Result<Element, FfProbeError> {
+    let (id, _) = read_vint(reader, true).map_err(FfProbeError::Io)?;
+    let (size, len) = read_vint(reader, false).map_err(FfProbeError::Io)?;
+    let unknown = size == (1 << (7 * len)) - 1;
+    Ok(Element {
+        id: id as u32,
+        data_offset: reader.stream_position().map_err(FfProbeError::Io)?,
+        size: (!unknown).then_some(size),
+    })
+}
+
+/// Read the data of an element with a known size.
+pub(crate) fn read_data<R: Read + Seek>(
+    reader: &mut R,
+    element: &Element,
+) -> Result<Vec<u8>, FfProbeError> {
+    let size = element
+        .size
+        .ok_or_else(|| invalid_data(format!("element {:#x} has an unknown size", element.id)))?;
+    // Sizes are read from the file, only allocate what it can hold.
+    let len = reader.seek(SeekFrom::End(0)).map_err(FfProbeError::Io)?;
+    if size > len.saturating_sub(element.data_offset) {
+        return Err(invalid_data(format!(
+            "element {:#x} is larger than the file",
+            element.id
+        )));
+    }
+    let mut data = vec![0; size as usize];
+    reader
+        .seek(SeekFrom::Start(element.data_offset))
+        .map_err(FfProbeError::Io)?;
+    reader.read_exact(&mut data).map_err(FfProbeError::Io)?;
+    Ok(data)
+}
+
+/// Iterate over the child elements in `data`, yielding (id, payload).
+pub(crate) fn children(data: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+    let mut cursor = std::io::Cursor::new(data);
+    std::iter::from_fn(move || {
+        let element = read_element(&mut cursor).ok()?;
+        let start = element.data_offset as usize;
+        let end = start.checked_add(element.size? as usize)?;
+        cursor.set_position(end as u64);
+        Some((element.id, data.get(start..end)?))
+    })
+}
+
+pub(crate) fn uint(data: &[u8]) -> u64 {
+    data.iter()
+        .fold(0, |value, byte| (value << 8) | *byte as u64)
+}
+
+pub(crate) fn float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_be_bytes(data.try_into().unwrap()) as f64),
+        8 => Some(f64::from_be_bytes(data.try_into().unwrap())),
+        _ => None,
+    }
+}
+
+/// Open a Matroska file and position the reader at the start of the Segment data.
+/// Returns the reader with the offsets of the Segment data and of its end.
+pub(crate) fn open_segment(path: &Path) -> Result<(BufReader<File>, u64, u64), FfProbeError> {
+    let file = File::open(path).map_err(FfProbeError::Io)?;
+    let mut reader = BufReader::new(file);
+    let len = reader.seek(SeekFrom::End(0)).map_err(FfProbeError::Io)?;
+    reader.rewind().map_err(FfProbeError::Io)?;
+
+    let header = read_element(&mut reader)?;
+    if header.id != EBML {
+        return Err(invalid_data("not a Matroska/WebM file"));
+    }
+    let size = header
+        .size
+        .ok_or_else(|| invalid_data("EBML header of unknown size"))?;
+    let header_end = header
+        .data_offset
+        .checked_add(size)
+        .filter(|end| *end <= len)
+        .ok_or_else(|| invalid_data("EBML header larger than the file"))?;
+    reader
+        .seek(SeekFrom::Start(header_end))
+        .map_err(FfProbeError::Io)?;
+    let segment = read_element(&mut reader)?;
+    if segment.id != SEGMENT {
+        return Err(invalid_data("Matroska file without a Segment element"));
+    }
+    let end = segment
+        .size
+        .map_or(len, |size| segment.data_offset.saturating_add(size))
+        .min(len);
+    Ok((reader, segment.data_offset, end))
+}
+
+/// Entries of a SeekHead as (element id, position relative to the Segment data).
+pub(crate) fn seek_entries(seek_head: &[u8]) -> impl Iterator<Item = (u32, u64)> + '_ {
+    children(seek_head)
+        .filter(|(id, _)| *id == SEEK)
+        .filter_map(|(_, seek)| {
+            let mut target = None;
+            let mut position = None;
+            for (id, value) in children(seek) {
+                match id {
+                    SEEK_ID => target = Some(uint(value) as u32),
+                    SEEK_POSITION => position = Some(uint(value)),
+                    _ => {}
+                }
+            }
+            Some((target?, position?))
+        })
+}
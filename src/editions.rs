@@ -0,0 +1,177 @@
+use std::{
+    io::{Seek, SeekFrom},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    ebml::{self, children, read_data, read_element, uint},
+    error::FfProbeError,
+};
+
+/// Matroska edition, ie. one ChapterEdition of the Chapters element.
+///
+/// Files can carry several editions, eg. a theatrical and a director's cut, or the angles
+/// of a concert. ffprobe only reports the chapters of the first one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Edition {
+    pub uid: Option<u64>,
+    /// EditionFlagDefault
+    pub default: bool,
+    /// EditionFlagHidden
+    pub hidden: bool,
+    /// EditionFlagOrdered. Ordered editions play their chapters in order and skip
+    /// everything else, so the playback duration can differ from the file duration.
+    pub ordered: bool,
+    /// First EditionString, only written by Matroska v5 muxers.
+    pub name: Option<String>,
+    pub chapters: Vec<EditionChapter>,
+}
+
+/// Chapter of an [`Edition`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EditionChapter {
+    pub uid: Option<u64>,
+    pub start: Duration,
+    pub end: Option<Duration>,
+    /// First ChapString.
+    pub title: Option<String>,
+    /// Language of the title, BCP 47 if present, otherwise ISO 639-2. eg. eng
+    pub language: Option<String>,
+    pub hidden: bool,
+    pub enabled: bool,
+    /// Segment the chapter plays from, for ordered chapters linking other files.
+    pub segment_uuid: Option<Vec<u8>>,
+    /// Nested ChapterAtoms.
+    pub children: Vec<EditionChapter>,
+}
+
+impl Edition {
+    /// Duration of playing the edition: the sum of the enabled chapters for ordered
+    /// editions, `None` for simple editions (which play the whole file) or if a chapter
+    /// has no end.
+    pub fn playback_duration(&self) -> Option<Duration> {
+        if !self.ordered {
+            return None;
+        }
+        self.chapters
+            .iter()
+            .filter(|c| c.enabled)
+            .map(|c| Some(c.end?.saturating_sub(c.start)))
+            .sum()
+    }
+}
+
+/// Edition players pick: the first one flagged as default, otherwise the first one.
+pub fn default_edition(editions: &[Edition]) -> Option<&Edition> {
+    editions.iter().find(|e| e.default).or(editions.first())
+}
+
+/// Read the editions of a Matroska/WebM file, in file order. Empty if the file has no
+/// chapters.
+///
+/// Only the element headers are read, clusters are skipped. The Chapters element is
+/// located through the SeekHead if it comes after a cluster of unknown size.
+pub fn matroska_editions(path: impl AsRef<Path>) -> Result<Vec<Edition>, FfProbeError> {
+    let (mut reader, segment_start, segment_end) = ebml::open_segment(path.as_ref())?;
+
+    let mut seek_head_chapters = None;
+    let mut offset = segment_start;
+    while offset < segment_end {
+        reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(FfProbeError::Io)?;
+        let element = read_element(&mut reader)?;
+        match element.id {
+            ebml::SEEK_HEAD => {
+                let data = read_data(&mut reader, &element)?;
+                seek_head_chapters = ebml::seek_entries(&data)
+                    .find(|(id, _)| *id == ebml::CHAPTERS)
+                    .map(|(_, position)| segment_start.saturating_add(position));
+            }
+            ebml::CHAPTERS => return Ok(editions(&read_data(&mut reader, &element)?)),
+            _ => {}
+        }
+        match element.size {
+            Some(size) => offset = element.data_offset.saturating_add(size),
+            None => break,
+        }
+    }
+
+    if let Some(position) = seek_head_chapters.filter(|p| *p >= offset) {
+        reader
+            .seek(SeekFrom::Start(position))
+            .map_err(FfProbeError::Io)?;
+        let element = read_element(&mut reader)?;
+        if element.id == ebml::CHAPTERS {
+            return Ok(editions(&read_data(&mut reader, &element)?));
+        }
+    }
+    Ok(Vec::new())
+}
+
+fn editions(chapters: &[u8]) -> Vec<Edition> {
+    children(chapters)
+        .filter(|(id, _)| *id == ebml::EDITION_ENTRY)
+        .map(|(_, entry)| {
+            let mut edition = Edition::default();
+            for (id, value) in children(entry) {
+                match id {
+                    ebml::EDITION_UID => edition.uid = Some(uint(value)),
+                    ebml::EDITION_FLAG_DEFAULT => edition.default = uint(value) != 0,
+                    ebml::EDITION_FLAG_HIDDEN => edition.hidden = uint(value) != 0,
+                    ebml::EDITION_FLAG_ORDERED => edition.ordered = uint(value) != 0,
+                    ebml::EDITION_DISPLAY if edition.name.is_none() => {
+                        edition.name = children(value)
+                            .find(|(id, _)| *id == ebml::EDITION_STRING)
+                            .map(|(_, s)| string(s));
+                    }
+                    ebml::CHAPTER_ATOM => edition.chapters.push(parse_chapter(value)),
+                    _ => {}
+                }
+            }
+            edition
+        })
+        .collect()
+}
+
+fn parse_chapter(atom: &[u8]) -> EditionChapter {
+    let mut chapter = EditionChapter {
+        enabled: true,
+        ..Default::default()
+    };
+    for (id, value) in children(atom) {
+        match id {
+            ebml::CHAPTER_UID => chapter.uid = Some(uint(value)),
+            ebml::CHAPTER_TIME_START => chapter.start = Duration::from_nanos(uint(value)),
+            ebml::CHAPTER_TIME_END => chapter.end = Some(Duration::from_nanos(uint(value))),
+            ebml::CHAPTER_FLAG_HIDDEN => chapter.hidden = uint(value) != 0,
+            ebml::CHAPTER_FLAG_ENABLED => chapter.enabled = uint(value) != 0,
+            ebml::CHAPTER_SEGMENT_UUID => chapter.segment_uuid = Some(value.to_vec()),
+            ebml::CHAPTER_DISPLAY if chapter.title.is_none() => {
+                let mut language = None;
+                for (id, value) in children(value) {
+                    match id {
+                        ebml::CHAP_STRING => chapter.title = Some(string(value)),
+                        ebml::CHAP_LANGUAGE_IETF => language = Some(string(value)),
+                        ebml::CHAP_LANGUAGE if language.is_none() => language = Some(string(value)),
+                        _ => {}
+                    }
+                }
+                chapter.language = language;
+            }
+            ebml::CHAPTER_ATOM => chapter.children.push(parse_chapter(value)),
+            _ => {}
+        }
+    }
+    chapter
+}
+
+/// UTF-8 string element, EBML strings may be zero padded.
+fn string(data: &[u8]) -> String {
+    String::from_utf8_lossy(data)
+        .trim_end_matches('\0')
+        .to_string()
+}
@@ -0,0 +1,188 @@
+use std::{fmt::Display, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{error::FfProbeError, Config, ProbeCommand};
+
+/// Field selection passed to ffprobe's `-show_entries` option.
+///
+/// Each section is shown with the listed fields only, or with all fields if the list is empty.
+///
+/// ```rust
+/// let spec = ffprobe::EntriesSpec::new()
+///     .format(["duration", "bit_rate"])
+///     .stream(["index", "codec_name"]);
+/// assert_eq!(spec.to_string(), "format=duration,bit_rate:stream=index,codec_name");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntriesSpec {
+    sections: Vec<(String, Vec<String>)>,
+}
+
+impl EntriesSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select fields of the `format` section.
+    pub fn format<I, S>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.section("format", fields)
+    }
+
+    /// Select fields of the `stream` sections.
+    pub fn stream<I, S>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.section("stream", fields)
+    }
+
+    /// Select fields of the `chapter` sections.
+    pub fn chapter<I, S>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.section("chapter", fields)
+    }
+
+    /// Select fields of any section, eg. `stream_tags` or `stream_disposition`.
+    /// Selecting the same section twice merges the field lists.
+    pub fn section<I, S>(mut self, name: impl Into<String>, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let name = name.into();
+        let fields = fields.into_iter().map(Into::into);
+        match self.sections.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => existing.extend(fields),
+            None => self.sections.push((name, fields.collect())),
+        }
+        self
+    }
+
+    /// Whether no section was selected.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+impl Display for EntriesSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (name, fields)) in self.sections.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{}", name)?;
+            if !fields.is_empty() {
+                write!(f, "={}", fields.join(","))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Output of a probe narrowed with [`ProbeCommand::show_entries`], see [`ffprobe_entries`].
+///
+/// Sections and fields that were not selected are missing, so the values are kept
+/// untyped and parsed on access.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawEntries")]
+pub struct Entries {
+    pub streams: Vec<Map<String, Value>>,
+    pub chapters: Vec<Map<String, Value>>,
+    pub format: Option<Map<String, Value>>,
+    pub packets: Vec<Map<String, Value>>,
+    pub frames: Vec<Map<String, Value>>,
+}
+
+/// ffprobe interleaves packets and frames in `packets_and_frames` if both are shown.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RawEntries {
+    streams: Vec<Map<String, Value>>,
+    chapters: Vec<Map<String, Value>>,
+    format: Option<Map<String, Value>>,
+    packets: Vec<Map<String, Value>>,
+    frames: Vec<Map<String, Value>>,
+    packets_and_frames: Vec<Map<String, Value>>,
+}
+
+impl From<RawEntries> for Entries {
+    fn from(mut raw: RawEntries) -> Self {
+        for entry in raw.packets_and_frames {
+            match entry.get("type").and_then(Value::as_str) {
+                Some("frame") => raw.frames.push(entry),
+                _ => raw.packets.push(entry),
+            }
+        }
+        Entries {
+            streams: raw.streams,
+            chapters: raw.chapters,
+            format: raw.format,
+            packets: raw.packets,
+            frames: raw.frames,
+        }
+    }
+}
+
+impl Entries {
+    /// Parse a field of the format section, eg. `entries.format_field::<f64>("duration")`.
+    pub fn format_field<T: FromStr>(&self, field: &str) -> Option<T> {
+        parse_field(self.format.as_ref()?, field)
+    }
+
+    /// Parse a field of the stream at position `index` of the output.
+    pub fn stream_field<T: FromStr>(&self, index: usize, field: &str) -> Option<T> {
+        parse_field(self.streams.get(index)?, field)
+    }
+}
+
+/// ffprobe reports most numbers as strings, so both are accepted.
+fn parse_field<T: FromStr>(section: &Map<String, Value>, field: &str) -> Option<T> {
+    match section.get(field)? {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.to_string().parse().ok(),
+        Value::Bool(b) => b.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Execute ffprobe with the given config, showing only the sections and fields of `spec`
+/// (`-show_entries`). The narrowed output is returned as [`Entries`], as it can't be parsed
+/// into [`crate::FfProbe`].
+///
+/// ```rust,no_run
+/// let spec = ffprobe::EntriesSpec::new().format(["duration", "bit_rate"]);
+/// let entries = ffprobe::ffprobe_entries(ffprobe::Config::new(), spec, "video.mp4").unwrap();
+/// println!("{:?}", entries.format_field::<f64>("duration"));
+/// ```
+pub fn ffprobe_entries(
+    config: Config,
+    spec: EntriesSpec,
+    path: impl AsRef<Path>,
+) -> Result<Entries, FfProbeError> {
+    run(ProbeCommand::new(config).show_entries(spec), path.as_ref())
+}
+
+/// Run `cmd` on `path`, its errors with the [`FfProbeError::Context`].
+pub(crate) fn run(cmd: ProbeCommand, path: &Path) -> Result<Entries, FfProbeError> {
+    let cmd = cmd.input(path);
+    let out = cmd.output().map_err(FfProbeError::from_io);
+    out.and_then(parse).map_err(|e| e.context(path, &cmd))
+}
+
+/// [`Entries`] of a finished run, for callers spawning ffprobe themselves.
+pub(crate) fn parse(out: std::process::Output) -> Result<Entries, FfProbeError> {
+    if !out.status.success() {
+        return Err(FfProbeError::Status(out));
+    }
+    serde_json::from_slice::<Entries>(&out.stdout).map_err(FfProbeError::Deserialize)
+}
@@ -1,17 +1,49 @@
 use std::fmt::Display;
+#[cfg(feature = "process")]
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum FfProbeError {
     Io(std::io::Error),
+    #[cfg(feature = "process")]
     Status(std::process::Output),
     Deserialize(serde_json::Error),
+    /// The input is a local file that is empty, or too small to be valid media and
+    /// ffprobe failed on it. Usually an upload that never completed rather than corruption.
+    EmptyOrTruncatedInput {
+        size: u64,
+    },
+    /// ffprobe wrote more than [`Config::max_output_bytes`](crate::Config::max_output_bytes)
+    /// to stdout or stderr and was killed.
+    OutputTooLarge {
+        limit: usize,
+    },
+    /// The protocol of the input, eg. `ftp`, isn't one of the
+    /// [`Config::allowed_protocols`](crate::Config::allowed_protocols).
+    ProtocolNotAllowed {
+        protocol: String,
+    },
+    /// `source` of probing `input`, returned by [`ffprobe_config`](crate::ffprobe_config)
+    /// and the other functions running ffprobe or ffmpeg. See [`FfProbeError::input`].
+    #[cfg(feature = "process")]
+    Context {
+        /// `None` for runs without an input, eg. [`capabilities::decoders`](crate::capabilities::decoders).
+        input: Option<PathBuf>,
+        /// Program and arguments of the last ffprobe or ffmpeg run, eg. after a retry.
+        command: Vec<OsString>,
+        source: Box<FfProbeError>,
+    },
 }
 
 impl Display for FfProbeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             FfProbeError::Io(e) => e.fmt(f),
+            #[cfg(feature = "process")]
             FfProbeError::Status(o) => {
                 write!(
                     f,
@@ -21,8 +53,204 @@ impl Display for FfProbeError {
                 )
             }
             FfProbeError::Deserialize(e) => e.fmt(f),
+            FfProbeError::EmptyOrTruncatedInput { size } => {
+                write!(f, "input is empty or truncated ({size} bytes)")
+            }
+            FfProbeError::OutputTooLarge { limit } => {
+                write!(f, "ffprobe output exceeds the limit of {limit} bytes")
+            }
+            FfProbeError::ProtocolNotAllowed { protocol } => {
+                write!(f, "input protocol `{protocol}` isn't allowed")
+            }
+            // The cause is left to `source`, error reporters print both.
+            #[cfg(feature = "process")]
+            FfProbeError::Context { input, command, .. } => {
+                match input {
+                    Some(input) => write!(f, "failed to probe {} with `", input.display())?,
+                    None => f.write_str("failed to run `")?,
+                }
+                for (i, arg) in command.iter().enumerate() {
+                    let sep = if i == 0 { "" } else { " " };
+                    write!(f, "{sep}{}", crate::command::quote(arg))?;
+                }
+                f.write_str("`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FfProbeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FfProbeError::Io(e) => Some(e),
+            FfProbeError::Deserialize(e) => Some(e),
+            #[cfg(feature = "process")]
+            FfProbeError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl FfProbeError {
+    /// Input the error happened on, eg. to log which file of a batch failed.
+    #[cfg(feature = "process")]
+    pub fn input(&self) -> Option<&Path> {
+        match self {
+            FfProbeError::Context { input, .. } => input.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// ffprobe or ffmpeg command line the error happened on, starting with the program.
+    #[cfg(feature = "process")]
+    pub fn command(&self) -> Option<&[OsString]> {
+        match self {
+            FfProbeError::Context { command, .. } => Some(command),
+            _ => None,
+        }
+    }
+
+    /// The error without the [`FfProbeError::Context`], eg. to match on
+    /// [`FfProbeError::Status`].
+    pub fn inner(&self) -> &FfProbeError {
+        match self {
+            #[cfg(feature = "process")]
+            FfProbeError::Context { source, .. } => source,
+            _ => self,
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+impl FfProbeError {
+    /// Error of a failed process run, [`FfProbeError::OutputTooLarge`] for the
+    /// [`Config::max_output_bytes`](crate::Config::max_output_bytes) limit.
+    pub(crate) fn from_io(err: std::io::Error) -> FfProbeError {
+        let payload = err.get_ref();
+        if let Some(&OutputLimit(limit)) = payload.and_then(|e| e.downcast_ref()) {
+            return FfProbeError::OutputTooLarge { limit };
+        }
+        match payload.and_then(|e| e.downcast_ref::<ProtocolNotAllowed>()) {
+            Some(ProtocolNotAllowed(protocol)) => FfProbeError::ProtocolNotAllowed {
+                protocol: protocol.clone(),
+            },
+            None => FfProbeError::Io(err),
+        }
+    }
+
+    /// Attach `input` and the command line of `command`, unless the error already has them.
+    pub(crate) fn context(self, input: &Path, command: &crate::ProbeCommand) -> FfProbeError {
+        match self {
+            FfProbeError::Context { .. } => self,
+            _ => self.command_context(Some(input), &command.build()),
+        }
+    }
+
+    /// [`FfProbeError::context`] of a `command` built by hand, eg. an ffmpeg run.
+    pub(crate) fn command_context(
+        self,
+        input: Option<&Path>,
+        command: &std::process::Command,
+    ) -> FfProbeError {
+        if let FfProbeError::Context { .. } = self {
+            return self;
         }
+        FfProbeError::Context {
+            input: input.map(Path::to_path_buf),
+            command: std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .map(OsString::from)
+                .collect(),
+            source: Box::new(self),
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+/// Payload of the I/O error for output beyond the limit, see [`output_too_large`].
+#[derive(Debug)]
+struct OutputLimit(usize);
+
+#[cfg(feature = "process")]
+impl Display for OutputLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ffprobe output exceeds the limit of {} bytes", self.0)
+    }
+}
+
+#[cfg(feature = "process")]
+impl std::error::Error for OutputLimit {}
+
+#[cfg(feature = "process")]
+/// I/O error for more than `limit` bytes of output, turned into
+/// [`FfProbeError::OutputTooLarge`] by [`FfProbeError::from_io`].
+pub(crate) fn output_too_large(limit: usize) -> std::io::Error {
+    std::io::Error::other(OutputLimit(limit))
+}
+
+#[cfg(feature = "process")]
+/// Payload of the I/O error for a rejected input, see [`protocol_not_allowed`].
+#[derive(Debug)]
+struct ProtocolNotAllowed(String);
+
+#[cfg(feature = "process")]
+impl Display for ProtocolNotAllowed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input protocol `{}` isn't allowed", self.0)
+    }
+}
+
+#[cfg(feature = "process")]
+impl std::error::Error for ProtocolNotAllowed {}
+
+#[cfg(feature = "process")]
+/// I/O error for an input with a protocol that isn't allowed, turned into
+/// [`FfProbeError::ProtocolNotAllowed`] by [`FfProbeError::from_io`].
+pub(crate) fn protocol_not_allowed(protocol: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::PermissionDenied,
+        ProtocolNotAllowed(protocol.to_string()),
+    )
+}
+
+/// Error for inputs that are structurally invalid, eg. malformed container boxes.
+pub(crate) fn invalid_data(msg: impl Into<String>) -> FfProbeError {
+    FfProbeError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        msg.into(),
+    ))
+}
+
+#[cfg(feature = "process")]
+/// Files up to this size that ffprobe fails on are reported as truncated.
+pub(crate) const TRUNCATED_INPUT_MAX_BYTES: u64 = 4096;
+
+#[cfg(feature = "process")]
+/// Size of `path` if it is a regular local file, `None` for URLs, devices, pipes etc.
+fn local_file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+}
+
+#[cfg(feature = "process")]
+/// Fail early on empty local files, ffprobe can't get anything out of them.
+pub(crate) fn check_input(path: &Path) -> Result<(), FfProbeError> {
+    match local_file_size(path) {
+        Some(0) => Err(FfProbeError::EmptyOrTruncatedInput { size: 0 }),
+        _ => Ok(()),
     }
 }
 
-impl std::error::Error for FfProbeError {}
+#[cfg(feature = "process")]
+/// Error for a failed ffprobe run on `path`: [`FfProbeError::EmptyOrTruncatedInput`] for
+/// tiny local files, [`FfProbeError::Status`] otherwise.
+pub(crate) fn status_error(path: &Path, out: std::process::Output) -> FfProbeError {
+    match local_file_size(path) {
+        Some(size) if size <= TRUNCATED_INPUT_MAX_BYTES => {
+            FfProbeError::EmptyOrTruncatedInput { size }
+        }
+        _ => FfProbeError::Status(out),
+    }
+}
@@ -0,0 +1,323 @@
+//! Where ffprobe and ffmpeg run, see [`Config::executor`](crate::Config::executor).
+//!
+//! By default they are spawned as local processes. An [`Executor`] replaces the local
+//! process with one that runs the command elsewhere and forwards its output, eg.
+//! [`Ssh`] with the `ssh` feature to probe files on the storage node they live on:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "ssh")]
+//! # {
+//! use ffprobe::{executor::Ssh, Config};
+//!
+//! let config = Config::new().executor(Ssh::new("media@storage-1").option("-p", "2222"));
+//! let probe = config.run("/srv/media/movie.mkv").unwrap();
+//! # }
+//! ```
+//!
+//! Paths are resolved on the remote host. Functions that read the input themselves
+//! rather than through ffprobe, eg. [`crate::seek_index_info`], still read local files.
+//!
+//! With the `test-util` feature, [`MockExecutor`] answers with canned output instead.
+
+use std::{
+    fmt::Debug,
+    io,
+    process::{Command, Output},
+    sync::Arc,
+};
+
+/// Runs the commands of the crate, see the [module docs](self).
+pub trait Executor: Send + Sync {
+    /// Local process running `cmd`, eg. `ssh host 'ffprobe ...'`. Its output is read like
+    /// the output of `cmd` would be, so progress reports, [`Config::max_output_bytes`]
+    /// and the [`Config::on_spawn`] hook keep working.
+    ///
+    /// [`Config::max_output_bytes`]: crate::Config::max_output_bytes
+    /// [`Config::on_spawn`]: crate::Config::on_spawn
+    fn command(&self, cmd: &Command) -> Command;
+
+    /// Output of `cmd` without running a process, eg. canned output in tests. `None`, the
+    /// default, runs [`Executor::command`].
+    fn output(&self, cmd: &Command) -> Option<io::Result<Output>> {
+        let _ = cmd;
+        None
+    }
+}
+
+/// Executor registered with [`Config::executor`](crate::Config::executor).
+#[derive(Clone)]
+pub(crate) struct ExecutorHook(pub(crate) Arc<dyn Executor>);
+
+impl Debug for ExecutorHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ExecutorHook")
+    }
+}
+
+#[cfg(feature = "test-util")]
+pub use mock::MockExecutor;
+#[cfg(feature = "ssh")]
+pub use ssh::Ssh;
+
+#[cfg(feature = "test-util")]
+mod mock {
+    use std::{
+        collections::HashMap,
+        ffi::{OsStr, OsString},
+        io,
+        process::{Command, ExitStatus, Output},
+        sync::{Arc, Mutex},
+    };
+
+    use super::Executor;
+    use crate::command::path_arg;
+
+    /// Answer with canned output instead of running ffprobe, eg. the [`crate::fixtures`],
+    /// to test code that probes files without ffmpeg installed:
+    ///
+    /// ```rust
+    /// use ffprobe::{executor::MockExecutor, fixtures, Config};
+    ///
+    /// let mock = MockExecutor::new().respond("movie.mp4", fixtures::MP4);
+    /// let probe = Config::new().executor(mock.clone()).run("movie.mp4").unwrap();
+    /// assert_eq!(probe.streams.len(), 3);
+    /// assert_eq!(mock.calls().len(), 1);
+    /// ```
+    ///
+    /// Commands are matched by their input, the last argument. Commands without a
+    /// response fail with exit code 1. Clones share the recorded [`MockExecutor::calls`].
+    ///
+    /// Processes read while they run, eg. [`crate::compact::Records`], still run the
+    /// command unchanged.
+    #[derive(Debug, Clone, Default)]
+    pub struct MockExecutor {
+        responses: HashMap<OsString, Response>,
+        fallback: Option<Response>,
+        calls: Arc<Mutex<Vec<Vec<OsString>>>>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct Response {
+        code: i32,
+        stdout: Vec<u8>,
+        stderr: Vec<u8>,
+    }
+
+    impl MockExecutor {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Print `stdout` and exit successfully for commands on `input`.
+        pub fn respond(mut self, input: impl AsRef<OsStr>, stdout: impl Into<Vec<u8>>) -> Self {
+            self.responses
+                .insert(input_key(input.as_ref()), Response::success(stdout.into()));
+            self
+        }
+
+        /// Log `stderr` and exit with code 1 for commands on `input`, eg. `moov atom not
+        /// found`.
+        pub fn fail(mut self, input: impl AsRef<OsStr>, stderr: impl Into<Vec<u8>>) -> Self {
+            let response = Response {
+                code: 1,
+                stdout: Vec::new(),
+                stderr: stderr.into(),
+            };
+            self.responses.insert(input_key(input.as_ref()), response);
+            self
+        }
+
+        /// Print `stdout` for commands on any other input.
+        pub fn fallback(mut self, stdout: impl Into<Vec<u8>>) -> Self {
+            self.fallback = Some(Response::success(stdout.into()));
+            self
+        }
+
+        /// Command lines answered so far, the program followed by its arguments.
+        pub fn calls(&self) -> Vec<Vec<OsString>> {
+            self.calls
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone()
+        }
+    }
+
+    impl Response {
+        fn success(stdout: Vec<u8>) -> Self {
+            Response {
+                code: 0,
+                stdout,
+                stderr: Vec::new(),
+            }
+        }
+    }
+
+    /// Input as passed to the process, see [`path_arg`].
+    fn input_key(input: &OsStr) -> OsString {
+        path_arg(input).into_owned()
+    }
+
+    impl Executor for MockExecutor {
+        fn command(&self, cmd: &Command) -> Command {
+            let mut unchanged = Command::new(cmd.get_program());
+            unchanged.args(cmd.get_args());
+            for (key, value) in cmd.get_envs() {
+                match value {
+                    Some(value) => unchanged.env(key, value),
+                    None => unchanged.env_remove(key),
+                };
+            }
+            if let Some(dir) = cmd.get_current_dir() {
+                unchanged.current_dir(dir);
+            }
+            unchanged
+        }
+
+        fn output(&self, cmd: &Command) -> Option<io::Result<Output>> {
+            let line = std::iter::once(cmd.get_program())
+                .chain(cmd.get_args())
+                .map(OsStr::to_owned)
+                .collect();
+            self.calls
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push(line);
+
+            let response = cmd
+                .get_args()
+                .last()
+                .and_then(|input| self.responses.get(input))
+                .or(self.fallback.as_ref());
+            let out = match response {
+                Some(response) => Output {
+                    status: exit_status(response.code),
+                    stdout: response.stdout.clone(),
+                    stderr: response.stderr.clone(),
+                },
+                None => Output {
+                    status: exit_status(1),
+                    stdout: Vec::new(),
+                    stderr: b"MockExecutor: no response for this input".to_vec(),
+                },
+            };
+            Some(Ok(out))
+        }
+    }
+
+    #[cfg(unix)]
+    fn exit_status(code: i32) -> ExitStatus {
+        std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+    }
+
+    #[cfg(windows)]
+    fn exit_status(code: i32) -> ExitStatus {
+        std::os::windows::process::ExitStatusExt::from_raw(code as u32)
+    }
+}
+
+#[cfg(feature = "ssh")]
+mod ssh {
+    use std::{
+        ffi::{OsStr, OsString},
+        path::PathBuf,
+        process::Command,
+    };
+
+    use super::Executor;
+
+    /// Run commands on a remote host with the `ssh` binary, eg. `media@storage-1`.
+    ///
+    /// Authentication has to work without a prompt, eg. with an agent or key file, as
+    /// `ssh` runs in batch mode. The environment set with
+    /// [`Config::env`](crate::Config::env) and the working directory are applied on the
+    /// remote host, [`Config::clear_env`](crate::Config::clear_env) is not.
+    ///
+    /// Killing the local `ssh`, eg. after
+    /// [`Config::max_output_bytes`](crate::Config::max_output_bytes) were exceeded,
+    /// closes the connection, which ends the remote process.
+    #[derive(Debug, Clone)]
+    pub struct Ssh {
+        destination: String,
+        ssh_bin: PathBuf,
+        options: Vec<OsString>,
+    }
+
+    impl Ssh {
+        pub fn new(destination: impl Into<String>) -> Self {
+            Ssh {
+                destination: destination.into(),
+                ssh_bin: "ssh".into(),
+                options: Vec::new(),
+            }
+        }
+
+        /// Binary name or path of `ssh`.
+        pub fn ssh_bin(mut self, ssh_bin: impl Into<PathBuf>) -> Self {
+            self.ssh_bin = ssh_bin.into();
+            self
+        }
+
+        /// Pass an option with a value to `ssh`, eg. `.option("-i", "~/.ssh/probe")` or
+        /// `.option("-o", "ConnectTimeout=5")`.
+        pub fn option(mut self, option: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+            self.options.push(option.into());
+            self.options.push(value.into());
+            self
+        }
+
+        /// Shell command line running `cmd` on the remote host.
+        pub fn remote_command(&self, cmd: &Command) -> String {
+            let mut line = Vec::new();
+            if let Some(dir) = cmd.get_current_dir() {
+                line.extend([
+                    "cd".to_string(),
+                    shell_quote(dir.as_os_str()),
+                    "&&".to_string(),
+                ]);
+            }
+            // `env` takes the `-u` options before the assignments.
+            let (removed, set): (Vec<_>, Vec<_>) =
+                cmd.get_envs().partition(|(_, value)| value.is_none());
+            let envs: Vec<String> = removed
+                .into_iter()
+                .map(|(key, _)| format!("-u {}", shell_quote(key)))
+                .chain(set.into_iter().filter_map(|(key, value)| {
+                    let mut pair = key.to_owned();
+                    pair.push("=");
+                    pair.push(value?);
+                    Some(shell_quote(&pair))
+                }))
+                .collect();
+            if !envs.is_empty() {
+                line.push("env".to_string());
+                line.extend(envs);
+            }
+            line.push(shell_quote(cmd.get_program()));
+            line.extend(cmd.get_args().map(shell_quote));
+            line.join(" ")
+        }
+    }
+
+    impl Executor for Ssh {
+        fn command(&self, cmd: &Command) -> Command {
+            let mut ssh = Command::new(&self.ssh_bin);
+            ssh.args(["-T", "-o", "BatchMode=yes"])
+                .args(&self.options)
+                .arg("--")
+                .arg(&self.destination)
+                .arg(self.remote_command(cmd));
+            ssh
+        }
+    }
+
+    /// Quote `arg` for a POSIX shell, unless it only consists of safe characters.
+    fn shell_quote(arg: &OsStr) -> String {
+        let arg = arg.to_string_lossy();
+        let safe = |c: char| c.is_ascii_alphanumeric() || "_-./:=,+@%".contains(c);
+        if !arg.is_empty() && arg.chars().all(safe) {
+            arg.into_owned()
+        } else {
+            format!("'{}'", arg.replace('\'', r"'\''"))
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::{Stream, StreamKinds};
+
+/// Parse the hex dump ffprobe prints for `-show_data`:
+///
+/// ```text
+/// 00000000: fc94 20fc 942c                           .. ..,
+/// ```
+pub(crate) fn parse_hex_dump(dump: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    for line in dump.lines().filter(|l| !l.trim().is_empty()) {
+        let (_, rest) = line.split_once(": ")?;
+        // 8 groups of 4 hex digits, followed by two spaces and the ASCII column.
+        let hex = rest.split("  ").next()?;
+        for group in hex.split_whitespace() {
+            if group.len() % 2 != 0 {
+                return None;
+            }
+            for i in (0..group.len()).step_by(2) {
+                data.push(u8::from_str_radix(&group[i..i + 2], 16).ok()?);
+            }
+        }
+    }
+    Some(data)
+}
+
+/// `data` in the format of [`parse_hex_dump`], as printed by ffprobe.
+pub(crate) fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::from("\n");
+    for (line, chunk) in data.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for (i, byte) in chunk.iter().enumerate() {
+            hex.push_str(&format!("{byte:02x}"));
+            if i % 2 == 1 {
+                hex.push(' ');
+            }
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if (32..127).contains(&b) {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        dump.push_str(&format!("{:08x}: {hex:41}{ascii}\n", line * 16));
+    }
+    dump
+}
+
+/// Deserialize the `extradata` hex dump of a stream.
+pub(crate) fn option<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|dump| {
+            parse_hex_dump(&dump).ok_or_else(|| serde::de::Error::custom("invalid hex dump"))
+        })
+        .transpose()
+}
+
+/// Serialize as the hex dump [`option`] reads.
+pub(crate) fn serialize<S>(data: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match data {
+        Some(data) => serializer.serialize_some(&hex_dump(data)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Codecs with parameter sets in [`ParameterSets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NalCodec {
+    H264,
+    Hevc,
+}
+
+impl NalCodec {
+    /// Codec of an ffmpeg codec name, eg. `h264`.
+    pub fn from_codec_name(name: &str) -> Option<Self> {
+        match name {
+            "h264" => Some(NalCodec::H264),
+            "hevc" => Some(NalCodec::Hevc),
+            _ => None,
+        }
+    }
+
+    fn nal_type(self, nal: &[u8]) -> Option<u8> {
+        let header = *nal.first()?;
+        Some(match self {
+            NalCodec::H264 => header & 0x1f,
+            NalCodec::Hevc => header >> 1 & 0x3f,
+        })
+    }
+}
+
+/// Parameter set NAL units of an H.264 or HEVC stream, eg. from
+/// [`Stream::parameter_sets`]. NAL units include their header, without start code or
+/// length prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParameterSets {
+    pub codec: NalCodec,
+    /// Video parameter sets, HEVC only.
+    pub vps: Vec<Vec<u8>>,
+    pub sps: Vec<Vec<u8>>,
+    pub pps: Vec<Vec<u8>>,
+    /// Bytes of the NAL unit length prefix of the packets, eg. 4. `None` for Annex B
+    /// extradata, where packets use start codes.
+    pub nal_length_size: Option<u8>,
+}
+
+impl ParameterSets {
+    /// Parameter sets of codec private data: an `avcC` or `hvcC` record as in MP4 and
+    /// Matroska, or Annex B NAL units as in MPEG-TS.
+    pub fn parse(codec: NalCodec, extradata: &[u8]) -> Option<Self> {
+        let mut sets = ParameterSets {
+            codec,
+            vps: Vec::new(),
+            sps: Vec::new(),
+            pps: Vec::new(),
+            nal_length_size: None,
+        };
+        if extradata.starts_with(&[0, 0, 1]) || extradata.starts_with(&[0, 0, 0, 1]) {
+            for nal in annex_b_nal_units(extradata) {
+                sets.push(nal);
+            }
+        } else if extradata.first() == Some(&1) {
+            match codec {
+                NalCodec::H264 => sets.parse_avcc(extradata)?,
+                NalCodec::Hevc => sets.parse_hvcc(extradata)?,
+            }
+        } else {
+            return None;
+        }
+        Some(sets)
+    }
+
+    /// First sequence parameter set.
+    pub fn sps_info(&self) -> Option<SpsInfo> {
+        let sps = self.sps.first()?;
+        match self.codec {
+            NalCodec::H264 => SpsInfo::parse_h264(sps),
+            NalCodec::Hevc => SpsInfo::parse_hevc(sps),
+        }
+    }
+
+    fn push(&mut self, nal: &[u8]) {
+        let list = match (self.codec, self.codec.nal_type(nal)) {
+            (NalCodec::H264, Some(7)) | (NalCodec::Hevc, Some(33)) => &mut self.sps,
+            (NalCodec::H264, Some(8)) | (NalCodec::Hevc, Some(34)) => &mut self.pps,
+            (NalCodec::Hevc, Some(32)) => &mut self.vps,
+            _ => return,
+        };
+        list.push(nal.to_vec());
+    }
+
+    /// AVCDecoderConfigurationRecord of ISO/IEC 14496-15.
+    fn parse_avcc(&mut self, data: &[u8]) -> Option<()> {
+        self.nal_length_size = Some((data.get(4)? & 0x03) + 1);
+        let mut rest = data.get(5..)?;
+        for count_mask in [0x1f, 0xff] {
+            let (&count, after) = rest.split_first()?;
+            rest = after;
+            for _ in 0..count & count_mask {
+                let (nal, after) = length_prefixed(rest)?;
+                self.push(nal);
+                rest = after;
+            }
+        }
+        Some(())
+    }
+
+    /// HEVCDecoderConfigurationRecord of ISO/IEC 14496-15.
+    fn parse_hvcc(&mut self, data: &[u8]) -> Option<()> {
+        self.nal_length_size = Some((data.get(21)? & 0x03) + 1);
+        let arrays = *data.get(22)?;
+        let mut rest = data.get(23..)?;
+        for _ in 0..arrays {
+            let count = u16::from_be_bytes([*rest.get(1)?, *rest.get(2)?]);
+            rest = &rest[3..];
+            for _ in 0..count {
+                let (nal, after) = length_prefixed(rest)?;
+                self.push(nal);
+                rest = after;
+            }
+        }
+        Some(())
+    }
+}
+
+/// NAL unit with a 16 bit length prefix, and the bytes after it.
+fn length_prefixed(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = usize::from(u16::from_be_bytes([*data.first()?, *data.get(1)?]));
+    let nal = data.get(2..2 + len)?;
+    Some((nal, &data[2 + len..]))
+}
+
+/// NAL units between `00 00 01` start codes.
+fn annex_b_nal_units(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i..i + 3] == [0, 0, 1] {
+            starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .map(|&start| start - 3)
+        .chain([data.len()])
+        .collect();
+    starts.into_iter().zip(ends).map(move |(start, end)| {
+        // The zero byte of a 4 byte start code and trailing zeros belong to no NAL unit.
+        let nal = &data[start..end];
+        let len = nal.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        &nal[..len]
+    })
+}
+
+/// Basics of a sequence parameter set, see [`ParameterSets::sps_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct SpsInfo {
+    /// eg. 100 for H.264 High, 1 for HEVC Main.
+    pub profile_idc: u8,
+    /// H.264 `constraint_set` flags, 0 for HEVC.
+    pub constraint_flags: u8,
+    /// eg. 40 for H.264 level 4, 120 for HEVC level 4.
+    pub level_idc: u8,
+    /// HEVC high tier.
+    pub high_tier: bool,
+    /// 0 for monochrome, 1 for 4:2:0, 2 for 4:2:2 and 3 for 4:4:4.
+    pub chroma_format_idc: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+}
+
+impl SpsInfo {
+    /// Parse an H.264 SPS NAL unit, including its header byte.
+    pub fn parse_h264(nal: &[u8]) -> Option<Self> {
+        let rbsp = unescape(nal.get(1..)?);
+        let mut bits = Bits::new(&rbsp);
+        let profile_idc = bits.read(8)? as u8;
+        let constraint_flags = bits.read(8)? as u8;
+        let level_idc = bits.read(8)? as u8;
+        let _seq_parameter_set_id = bits.ue()?;
+        let mut info = SpsInfo {
+            profile_idc,
+            constraint_flags,
+            level_idc,
+            high_tier: false,
+            chroma_format_idc: 1,
+            bit_depth_luma: 8,
+            bit_depth_chroma: 8,
+        };
+        // Profiles with chroma format and bit depth in the SPS, High and above.
+        if matches!(
+            profile_idc,
+            100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135
+        ) {
+            info.chroma_format_idc = bits.ue()? as u8;
+            if info.chroma_format_idc == 3 {
+                let _separate_colour_plane = bits.read(1)?;
+            }
+            info.bit_depth_luma = bits.ue()? as u8 + 8;
+            info.bit_depth_chroma = bits.ue()? as u8 + 8;
+        }
+        Some(info)
+    }
+
+    /// Parse an HEVC SPS NAL unit, including its two header bytes.
+    pub fn parse_hevc(nal: &[u8]) -> Option<Self> {
+        let rbsp = unescape(nal.get(2..)?);
+        let mut bits = Bits::new(&rbsp);
+        let _vps_id = bits.read(4)?;
+        let max_sub_layers_minus1 = bits.read(3)?;
+        let _temporal_id_nesting = bits.read(1)?;
+        // profile_tier_level
+        let _profile_space = bits.read(2)?;
+        let high_tier = bits.read(1)? == 1;
+        let profile_idc = bits.read(5)? as u8;
+        // Compatibility and constraint flags.
+        bits.skip(32 + 48)?;
+        let level_idc = bits.read(8)? as u8;
+        let mut sub_layers = Vec::new();
+        for _ in 0..max_sub_layers_minus1 {
+            sub_layers.push((bits.read(1)? == 1, bits.read(1)? == 1));
+        }
+        if max_sub_layers_minus1 > 0 {
+            bits.skip(2 * (8 - max_sub_layers_minus1))?;
+        }
+        for (profile_present, level_present) in sub_layers {
+            if profile_present {
+                bits.skip(88)?;
+            }
+            if level_present {
+                bits.skip(8)?;
+            }
+        }
+        let _seq_parameter_set_id = bits.ue()?;
+        let chroma_format_idc = bits.ue()? as u8;
+        if chroma_format_idc == 3 {
+            let _separate_colour_plane = bits.read(1)?;
+        }
+        let _width = bits.ue()?;
+        let _height = bits.ue()?;
+        if bits.read(1)? == 1 {
+            // Conformance window offsets.
+            for _ in 0..4 {
+                bits.ue()?;
+            }
+        }
+        Some(SpsInfo {
+            profile_idc,
+            constraint_flags: 0,
+            level_idc,
+            high_tier,
+            chroma_format_idc,
+            bit_depth_luma: bits.ue()? as u8 + 8,
+            bit_depth_chroma: bits.ue()? as u8 + 8,
+        })
+    }
+
+    /// Chroma subsampling, eg. `4:2:0`.
+    pub fn chroma_subsampling(&self) -> Option<&'static str> {
+        match self.chroma_format_idc {
+            0 => Some("4:0:0"),
+            1 => Some("4:2:0"),
+            2 => Some("4:2:2"),
+            3 => Some("4:4:4"),
+            _ => None,
+        }
+    }
+}
+
+/// Remove the emulation prevention bytes of a NAL unit, the `03` of `00 00 03`.
+fn unescape(nal: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(nal.len());
+    let mut zeros = 0;
+    for &byte in nal {
+        if zeros >= 2 && byte == 3 {
+            zeros = 0;
+            continue;
+        }
+        zeros = if byte == 0 { zeros + 1 } else { 0 };
+        rbsp.push(byte);
+    }
+    rbsp
+}
+
+/// Reads bit fields and Exp-Golomb codes of an RBSP, most significant bit first.
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Bits<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Bits { data, pos: 0 }
+    }
+
+    fn read(&mut self, bits: u32) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self.data.get(self.pos / 8)?;
+            value = value << 1 | u32::from(byte >> (7 - self.pos % 8) & 1);
+            self.pos += 1;
+        }
+        Some(value)
+    }
+
+    fn skip(&mut self, bits: u32) -> Option<()> {
+        self.pos += bits as usize;
+        (self.pos <= self.data.len() * 8).then_some(())
+    }
+
+    /// Unsigned Exp-Golomb code.
+    fn ue(&mut self) -> Option<u32> {
+        let mut zeros = 0;
+        while self.read(1)? == 0 {
+            zeros += 1;
+            if zeros > 31 {
+                return None;
+            }
+        }
+        Some((1 << zeros) - 1 + self.read(zeros)?)
+    }
+}
+
+impl Stream {
+    /// Parameter sets of an H.264 or HEVC stream, from the extradata of
+    /// [`Config::show_data`](crate::Config::show_data).
+    pub fn parameter_sets(&self) -> Option<ParameterSets> {
+        let StreamKinds::Video(video) = &self.stream else {
+            return None;
+        };
+        let codec = NalCodec::from_codec_name(&video.codec_name)?;
+        ParameterSets::parse(codec, self.extradata.as_deref()?)
+    }
+}
@@ -4,11 +4,18 @@ use crate::Chapter;
 use crate::Format;
 #[cfg(feature = "streams")]
 use crate::Stream;
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::FfProbeError, ByteLayout, Dialect, Hashes, LogMessage};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
 /// FfProbe parsed
+#[non_exhaustive]
 pub struct FfProbe {
     #[cfg(feature = "streams")]
     /// Streams of file
@@ -19,4 +26,103 @@ pub struct FfProbe {
     #[cfg(feature = "format")]
     /// Format of file
     pub format: Format,
+    /// Lines ffprobe logged on stderr without their level, only captured with
+    /// [`Config::capture_warnings`](crate::Config::capture_warnings) or
+    /// [`Config::loglevel`](crate::Config::loglevel).
+    #[serde(skip)]
+    pub stderr_warnings: Vec<String>,
+    /// [`FfProbe::stderr_warnings`] with their level and context parsed.
+    #[serde(skip)]
+    pub log: Vec<LogMessage>,
+    /// Hashes of the file and its streams, only computed with
+    /// [`Config::hash`](crate::Config::hash).
+    #[serde(skip)]
+    pub hashes: Option<Hashes>,
+    /// Byte ranges of the streams and MP4 boxes of the file, only computed with
+    /// [`Config::byte_layout`](crate::Config::byte_layout).
+    #[serde(skip)]
+    pub byte_layout: Option<ByteLayout>,
+    /// Duration from the end of the last packet, only estimated with
+    /// [`Config::estimate_duration`](crate::Config::estimate_duration) for inputs without a
+    /// duration. See [`FfProbe::best_duration`].
+    #[serde(skip)]
+    pub estimated_duration: Option<std::time::Duration>,
+    /// Command line dialect the binary understood, [`Dialect::Legacy`] for avprobe and old
+    /// ffprobe versions.
+    #[serde(skip)]
+    pub dialect: Dialect,
+    /// ffprobe output the result was parsed from, see [`FfProbe::query`].
+    #[serde(skip)]
+    pub(crate) raw: Option<Arc<Value>>,
+}
+
+impl FfProbe {
+    /// Parse the JSON output of `ffprobe -print_format json -show_streams -show_format`,
+    /// eg. produced on another machine. Unlike deserializing with serde directly, the raw
+    /// output is kept for [`FfProbe::query`].
+    ///
+    /// ```rust
+    /// let probe = ffprobe::FfProbe::from_json(r#"{"streams": [], "chapters": []}"#);
+    /// # #[cfg(feature = "format")]
+    /// # assert!(probe.is_err(), "the format section is missing");
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, FfProbeError> {
+        Self::from_slice(json.as_bytes())
+    }
+
+    /// [`FfProbe::from_json`] for bytes, eg. as read from a file or pipe.
+    pub fn from_slice(json: &[u8]) -> Result<Self, FfProbeError> {
+        Self::from_value(serde_json::from_slice(json).map_err(FfProbeError::Deserialize)?)
+    }
+
+    /// [`FfProbe::from_json`] for already parsed output.
+    pub(crate) fn from_value(raw: Value) -> Result<Self, FfProbeError> {
+        let mut probe = FfProbe::deserialize(&raw).map_err(FfProbeError::Deserialize)?;
+        probe.raw = Some(Arc::new(raw));
+        Ok(probe)
+    }
+
+    /// Keep the non-empty lines of ffprobe's `stderr` in [`FfProbe::stderr_warnings`] and
+    /// [`FfProbe::log`].
+    #[cfg(feature = "process")]
+    pub(crate) fn with_stderr(mut self, stderr: &[u8]) -> Self {
+        for line in String::from_utf8_lossy(stderr).lines().map(str::trim_end) {
+            if line.is_empty() {
+                continue;
+            }
+            let message = LogMessage::parse(line);
+            self.stderr_warnings.push(match message.level {
+                Some(level) => line.replacen(&format!("[{level}] "), "", 1),
+                None => line.to_string(),
+            });
+            self.log.push(message);
+        }
+        self
+    }
+
+    /// Empty probe result, eg. to build fixtures for tests or mock backends.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "streams")]
+    /// Append a stream.
+    pub fn with_stream(mut self, stream: Stream) -> Self {
+        self.streams.push(stream);
+        self
+    }
+
+    #[cfg(feature = "chapters")]
+    /// Append a chapter.
+    pub fn with_chapter(mut self, chapter: Chapter) -> Self {
+        self.chapters.push(chapter);
+        self
+    }
+
+    #[cfg(feature = "format")]
+    /// Replace the format.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
 }
@@ -0,0 +1,17 @@
+//! ffprobe output of common inputs, eg. for [`MockExecutor`](crate::executor::MockExecutor)
+//! or [`FfProbe::from_json`](crate::FfProbe::from_json) in tests. Printed by ffprobe 7 with
+//! `-show_streams -show_format -show_chapters`.
+
+/// MP4 with H.264 video, 5.1 AAC audio, forced `mov_text` subtitles and two chapters.
+pub const MP4: &str = include_str!("../tests/fixtures/mp4.json");
+
+/// Matroska with HDR10 HEVC video, E-AC-3 and commentary AAC audio, forced and SDH SubRip
+/// subtitles, a font attachment and two chapters.
+pub const MKV: &str = include_str!("../tests/fixtures/mkv.json");
+
+/// MP3 with ID3 tags and front cover art.
+pub const MP3: &str = include_str!("../tests/fixtures/mp3.json");
+
+/// HLS master playlist with three variants, each a program, as printed with
+/// `-show_programs` by [`crate::streaming::probe_manifest`].
+pub const HLS: &str = include_str!("../tests/fixtures/hls.json");
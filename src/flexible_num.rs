@@ -0,0 +1,131 @@
+//! Numeric fields that ffprobe reports as numbers or strings, depending on the field,
+//! the version and the writer, eg. `"width": 1920` but `"duration_ts": "1234"`, or
+//! `"bit_rate": "N/A"` with `-show_optional_fields always`.
+//!
+//! Use with `#[serde(deserialize_with = "flexible_num::deserialize")]`, or
+//! `deserialize_with = "flexible_num::option", default` for optional fields.
+
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::{
+    de::{self, Unexpected, Visitor},
+    Deserializer,
+};
+
+/// Number type the deserializers accept.
+pub(crate) trait FlexibleNum: FromStr {
+    fn from_i64(v: i64) -> Option<Self>;
+    fn from_u64(v: u64) -> Option<Self>;
+    fn from_f64(v: f64) -> Option<Self>;
+}
+
+macro_rules! int_impls {
+    ($($t:ty),*) => {$(
+        impl FlexibleNum for $t {
+            fn from_i64(v: i64) -> Option<Self> {
+                v.try_into().ok()
+            }
+
+            fn from_u64(v: u64) -> Option<Self> {
+                v.try_into().ok()
+            }
+
+            /// Only whole numbers, eg. `25.0`.
+            fn from_f64(v: f64) -> Option<Self> {
+                if v.fract() != 0. || !v.is_finite() {
+                    return None;
+                }
+                (v as i128).try_into().ok()
+            }
+        }
+    )*};
+}
+
+int_impls!(u8, u16, u32, u64, i32, i64, usize);
+
+impl FlexibleNum for f64 {
+    fn from_i64(v: i64) -> Option<Self> {
+        Some(v as f64)
+    }
+
+    fn from_u64(v: u64) -> Option<Self> {
+        Some(v as f64)
+    }
+
+    fn from_f64(v: f64) -> Option<Self> {
+        Some(v)
+    }
+}
+
+/// `None` for null, `N/A` and empty strings.
+struct NumVisitor<T>(PhantomData<T>);
+
+impl<'de, T: FlexibleNum> Visitor<'de> for NumVisitor<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number or numeric string")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        T::from_i64(v)
+            .map(Some)
+            .ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        T::from_u64(v)
+            .map(Some)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        T::from_f64(v)
+            .map(Some)
+            .ok_or_else(|| E::invalid_value(Unexpected::Float(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let s = v.trim();
+        if s.is_empty() || s == "N/A" {
+            return Ok(None);
+        }
+        s.parse()
+            .ok()
+            .or_else(|| T::from_f64(s.parse().ok()?))
+            .map(Some)
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+/// A number, or a string holding one.
+pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FlexibleNum,
+{
+    deserializer
+        .deserialize_any(NumVisitor(PhantomData))?
+        .ok_or_else(|| de::Error::custom("expected a number, found N/A"))
+}
+
+/// An optional number, or a string holding one. `N/A` is `None`.
+pub(crate) fn option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FlexibleNum,
+{
+    deserializer.deserialize_option(NumVisitor(PhantomData))
+}
@@ -1,20 +1,27 @@
 use std::time::Duration;
 
 use crate::{
-    option_string_to_duration,
-    streams::{option_string_to_int, string_to_int},
+    creation_time::CreationTime,
+    flexible_num, option_string_to_duration,
+    tags::{capture_opt, CaptureTags, Tags, NO_TAGS},
+    units::{Bitrate, FileSize},
 };
 
 #[derive(Default, Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Parsed Format
+#[non_exhaustive]
 pub struct Format {
     /// Filename
     pub filename: String,
     /// Number of streams
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub nb_streams: u64,
     /// Number of programs
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub nb_programs: u64,
     // Number of stream groups
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub nb_stream_groups: u64,
     /// eg. matroska,webm
     pub format_name: String,
@@ -28,18 +35,38 @@ pub struct Format {
     // FIXME: wrap with Option<_> on next semver breaking release.
     #[serde(default)]
     /// Size in bytes
-    #[serde(deserialize_with = "string_to_int")]
-    pub size: i64,
-    #[serde(deserialize_with = "option_string_to_int", default)]
-    pub bit_rate: Option<i64>,
+    pub size: FileSize,
+    #[serde(default)]
+    pub bit_rate: Option<Bitrate>,
     ///value from 0-100
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub probe_score: u64,
     /// File Metadata
+    #[serde(deserialize_with = "capture_opt", default)]
     pub tags: Option<FormatTags>,
 }
 
+impl Format {
+    /// All format tags, see [`Tags`].
+    pub fn all_tags(&self) -> &Tags {
+        self.tags.as_ref().map_or(&NO_TAGS, |t| &t.all)
+    }
+
+    /// Format with the given filename and format name, eg. `matroska,webm`.
+    /// All other fields are empty.
+    pub fn new(filename: impl Into<String>, format_name: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+            format_name: format_name.into(),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Metadata Tags of format
+#[non_exhaustive]
 pub struct FormatTags {
     #[serde(rename = "WMFSDKNeeded")]
     pub wmfsdkneeded: Option<String>,
@@ -93,7 +120,7 @@ pub struct FormatTags {
     #[serde(rename = "IMDB")]
     pub imdb: Option<String>,
     #[serde(alias = "CREATION_TIME")]
-    pub creation_time: Option<String>,
+    pub creation_time: Option<CreationTime>,
     #[serde(rename = "TMDB")]
     pub tmdb: Option<String>,
     pub genre: Option<String>,
@@ -102,6 +129,9 @@ pub struct FormatTags {
     sony_xdcam: SonyXDCAM,
     #[serde(flatten)]
     pub extra: std::collections::HashMap<String, serde_json::Value>,
+    /// All tags, including the ones covered by the typed fields.
+    #[serde(skip)]
+    pub all: Tags,
 }
 
 #[derive(Default, Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -117,3 +147,9 @@ struct SonyXDCAM {
     pub material_package_umid: Option<String>,
     pub timecode: Option<String>,
 }
+
+impl CaptureTags for FormatTags {
+    fn all_mut(&mut self) -> &mut Tags {
+        &mut self.all
+    }
+}
@@ -0,0 +1,196 @@
+use std::{fs::File, io::BufReader, path::Path, time::Duration};
+
+use crate::{
+    bmff::{boxes, find_box, read_u32, read_u64, scan},
+    error::{invalid_data, FfProbeError},
+    init_segment::moov_tracks,
+};
+
+/// Movie fragment (`moof` box) of a fragmented MP4 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FragmentInfo {
+    /// Sequence number from the movie fragment header (mfhd).
+    pub sequence_number: u32,
+    /// Byte offset of the `moof` box in the file.
+    pub offset: u64,
+    /// Size of the `moof` box in bytes.
+    pub moof_size: u64,
+    /// Size of the `mdat` box following the `moof`, if any.
+    pub mdat_size: Option<u64>,
+    /// Per track runs of this fragment.
+    pub tracks: Vec<TrackFragment>,
+}
+
+/// Samples of a single track within a [`FragmentInfo`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackFragment {
+    pub track_id: u32,
+    /// Media timescale of the track, in ticks per second.
+    /// `None` if the file has no `moov` entry for the track.
+    pub timescale: Option<u32>,
+    /// `baseMediaDecodeTime` from the track fragment decode time box (tfdt), in timescale units.
+    pub base_decode_time: Option<u64>,
+    /// Number of samples over all track runs.
+    pub sample_count: u32,
+    /// Sum of the sample durations, in timescale units.
+    pub duration_ticks: u64,
+}
+
+impl TrackFragment {
+    /// Decode time of the first sample.
+    pub fn start(&self) -> Option<Duration> {
+        self.ticks_to_duration(self.base_decode_time?)
+    }
+
+    /// Duration of the fragment for this track.
+    pub fn duration(&self) -> Option<Duration> {
+        self.ticks_to_duration(self.duration_ticks)
+    }
+
+    /// `None` beyond [`Duration::MAX`], eg. for a crafted `tfdt` at a timescale of 1.
+    fn ticks_to_duration(&self, ticks: u64) -> Option<Duration> {
+        let timescale = self.timescale.filter(|t| *t != 0)?;
+        Duration::try_from_secs_f64(ticks as f64 / timescale as f64).ok()
+    }
+}
+
+impl FragmentInfo {
+    /// Longest track duration of this fragment.
+    pub fn duration(&self) -> Option<Duration> {
+        self.tracks.iter().filter_map(|t| t.duration()).max()
+    }
+}
+
+/// Enumerate the movie fragments of a fragmented MP4 file.
+///
+/// Only the `moov` and `moof` boxes are read, the media data is skipped.
+/// Sample durations fall back from the track run (trun) to the track fragment
+/// header (tfhd) and finally the `trex` defaults of the `moov` box.
+/// Returns an empty list for non fragmented files.
+pub fn fragments(path: impl AsRef<Path>) -> Result<Vec<FragmentInfo>, FfProbeError> {
+    let file = File::open(path.as_ref()).map_err(FfProbeError::Io)?;
+    let top = scan(BufReader::new(file), &[b"moov", b"moof"])?;
+
+    let mut tracks = Vec::new();
+    if let Some(moov) = top.iter().find(|b| &b.kind == b"moov") {
+        tracks = moov_tracks(moov.payload.as_deref().unwrap_or_default())
+            .map_err(|msg| invalid_data(format!("invalid moov box: {msg}")))?;
+    }
+
+    let mut out = Vec::new();
+    for (position, item) in top.iter().enumerate() {
+        if &item.kind != b"moof" {
+            continue;
+        }
+        let payload = item.payload.as_deref().unwrap_or_default();
+        let mut fragment = parse_moof(payload).map_err(|msg| {
+            invalid_data(format!("invalid moof box at offset {}: {msg}", item.offset))
+        })?;
+        fragment.offset = item.offset;
+        fragment.moof_size = item.size;
+        fragment.mdat_size = top
+            .get(position + 1)
+            .filter(|next| &next.kind == b"mdat")
+            .map(|mdat| mdat.size);
+        for traf in &mut fragment.tracks {
+            let track = tracks.iter().find(|t| t.track_id == traf.track_id);
+            traf.timescale = track.map(|t| t.timescale);
+            if traf.duration_ticks == 0 {
+                // No durations in trun or tfhd, use the trex default.
+                let default = track.and_then(|t| t.default_sample_duration).unwrap_or(0);
+                traf.duration_ticks = default as u64 * traf.sample_count as u64;
+            }
+        }
+        out.push(fragment);
+    }
+    Ok(out)
+}
+
+fn parse_moof(moof: &[u8]) -> Result<FragmentInfo, String> {
+    let mut fragment = FragmentInfo {
+        sequence_number: 0,
+        offset: 0,
+        moof_size: 0,
+        mdat_size: None,
+        tracks: Vec::new(),
+    };
+    for item in boxes(moof) {
+        let (kind, payload) = item?;
+        match kind {
+            b"mfhd" => fragment.sequence_number = read_u32(payload, 4)?,
+            b"traf" => fragment.tracks.push(parse_traf(payload)?),
+            _ => {}
+        }
+    }
+    Ok(fragment)
+}
+
+fn parse_traf(traf: &[u8]) -> Result<TrackFragment, String> {
+    let tfhd = find_box(traf, b"tfhd")?.ok_or("traf without tfhd")?;
+    let tfhd_flags = read_u32(tfhd, 0)? & 0x00ff_ffff;
+    let track_id = read_u32(tfhd, 4)?;
+    let mut offset = 8;
+    if tfhd_flags & 0x01 != 0 {
+        // base-data-offset
+        offset += 8;
+    }
+    if tfhd_flags & 0x02 != 0 {
+        // sample-description-index
+        offset += 4;
+    }
+    let default_duration = match tfhd_flags & 0x08 {
+        0 => None,
+        _ => Some(read_u32(tfhd, offset)?),
+    };
+
+    let base_decode_time = match find_box(traf, b"tfdt")? {
+        Some(tfdt) if tfdt.first() == Some(&1) => Some(read_u64(tfdt, 4)?),
+        Some(tfdt) => Some(read_u32(tfdt, 4)? as u64),
+        None => None,
+    };
+
+    let mut fragment = TrackFragment {
+        track_id,
+        timescale: None,
+        base_decode_time,
+        sample_count: 0,
+        duration_ticks: 0,
+    };
+    for item in boxes(traf) {
+        let (kind, trun) = item?;
+        if kind != b"trun" {
+            continue;
+        }
+        let flags = read_u32(trun, 0)? & 0x00ff_ffff;
+        let count = read_u32(trun, 4)?;
+        fragment.sample_count = fragment.sample_count.saturating_add(count);
+
+        let mut pos = 8;
+        if flags & 0x001 != 0 {
+            // data-offset
+            pos += 4;
+        }
+        if flags & 0x004 != 0 {
+            // first-sample-flags
+            pos += 4;
+        }
+        let per_sample = [0x100, 0x200, 0x400, 0x800]
+            .iter()
+            .filter(|f| flags & **f != 0)
+            .count()
+            * 4;
+        if flags & 0x100 != 0 {
+            for sample in 0..count as usize {
+                fragment.duration_ticks =
+                    fragment
+                        .duration_ticks
+                        .saturating_add(read_u32(trun, pos + sample * per_sample)? as u64);
+            }
+        } else if let Some(default) = default_duration {
+            fragment.duration_ticks = fragment
+                .duration_ticks
+                .saturating_add(default as u64 * count as u64);
+        }
+    }
+    Ok(fragment)
+}
@@ -0,0 +1,148 @@
+use std::fmt::Display;
+
+use crate::ratio::Ratio;
+
+/// Frame rate information of a video stream, see [`crate::Stream::frame_rate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameRate {
+    /// `r_frame_rate`: the lowest frame rate with which all timestamps can be represented exactly.
+    /// For interlaced content this is often the field rate.
+    pub r_frame_rate: Ratio,
+    /// `avg_frame_rate`: total frames divided by the stream duration.
+    pub avg_frame_rate: Ratio,
+    /// Field order reported for the stream, eg. `progressive`, `tt`, `bb`.
+    pub field_order: Option<String>,
+    /// Frames counted by the decoder divided by the stream duration. Only available if
+    /// frames were counted, see [`crate::Config::count_frames`].
+    pub measured_frame_rate: Option<Ratio>,
+}
+
+impl FrameRate {
+    /// Relative difference between average and real frame rate above which a stream
+    /// is considered variable frame rate.
+    pub const VARIABLE_TOLERANCE: f64 = 0.001;
+
+    /// Rates above this are timestamp resolutions rather than frame rates, eg. the
+    /// 90000 reported as `r_frame_rate` for some MPEG-TS streams.
+    pub const MAX_PLAUSIBLE_FPS: f64 = 1000.;
+
+    /// Declared rate (`r_frame_rate`) in frames per second, `None` if unknown.
+    pub fn declared(&self) -> Option<f64> {
+        valid(&self.r_frame_rate)
+    }
+
+    /// Average rate (`avg_frame_rate`) in frames per second, `None` if unknown.
+    pub fn average(&self) -> Option<f64> {
+        valid(&self.avg_frame_rate)
+    }
+
+    /// Measured rate in frames per second, `None` if frames weren't counted.
+    pub fn measured(&self) -> Option<f64> {
+        self.measured_frame_rate.as_ref().and_then(valid)
+    }
+
+    /// The most reliable frame rate, in order of precedence:
+    ///
+    /// 1. the measured rate, if frames were counted
+    /// 2. the average rate, which counts full frames for interlaced content and is the
+    ///    mean rate of variable frame rate content
+    /// 3. the declared rate
+    ///
+    /// The declared rate is last, as it is the field rate for some interlaced codecs
+    /// and a timestamp resolution for some containers. Implausibly high rates (see
+    /// [`Self::MAX_PLAUSIBLE_FPS`]) are skipped, eg. a bogus average rate falls back to
+    /// the declared one.
+    pub fn best_guess(&self) -> Option<f64> {
+        let plausible = |fps: &f64| *fps <= Self::MAX_PLAUSIBLE_FPS;
+        self.measured()
+            .filter(plausible)
+            .or_else(|| self.average().filter(plausible))
+            .or_else(|| self.declared().filter(plausible))
+    }
+
+    /// Frames per second, preferring the average frame rate and falling back to `r_frame_rate`.
+    /// Returns `None` if neither is known.
+    pub fn effective_fps(&self) -> Option<f64> {
+        valid(&self.avg_frame_rate).or_else(|| valid(&self.r_frame_rate))
+    }
+
+    /// Whether the average frame rate deviates from `r_frame_rate`, which indicates
+    /// variable frame rate content.
+    /// Returns `false` if either rate is unknown.
+    pub fn is_variable(&self) -> bool {
+        self.is_variable_within(Self::VARIABLE_TOLERANCE)
+    }
+
+    /// Like [`FrameRate::is_variable`] with a custom relative tolerance, eg. the
+    /// configured one of [`crate::analysis::Heuristics::is_variable_frame_rate`].
+    pub fn is_variable_within(&self, tolerance: f64) -> bool {
+        match (valid(&self.avg_frame_rate), valid(&self.r_frame_rate)) {
+            (Some(avg), Some(real)) => ((avg - real) / real).abs() > tolerance,
+            _ => false,
+        }
+    }
+
+    /// Whether the stream is interlaced according to its field order.
+    pub fn is_interlaced(&self) -> bool {
+        matches!(self.field_order.as_deref(), Some("tt" | "bb" | "tb" | "bt"))
+    }
+
+    /// Frame and field rate, reconciling `r_frame_rate` and `avg_frame_rate` for interlaced content.
+    ///
+    /// For interlaced streams ffprobe reports the field rate as `r_frame_rate` for some
+    /// codecs (eg. 50/1 for 25i H.264) and the frame rate for others. The average rate
+    /// counts full frames, so it is preferred as frame rate and the field rate is derived from it.
+    /// Returns `None` if neither rate is known.
+    pub fn effective_frame_rate(&self) -> Option<EffectiveFrameRate> {
+        if !self.is_interlaced() {
+            return Some(EffectiveFrameRate {
+                frames_per_second: self.effective_fps()?,
+                fields_per_second: None,
+            });
+        }
+
+        let frames_per_second = match (valid(&self.avg_frame_rate), valid(&self.r_frame_rate)) {
+            (Some(avg), _) => avg,
+            // Without an average rate, r_frame_rate is the field rate if the codec
+            // signals field timestamps, which can't be told apart from here.
+            (None, Some(real)) => real,
+            (None, None) => return None,
+        };
+        Some(EffectiveFrameRate {
+            frames_per_second,
+            fields_per_second: Some(frames_per_second * 2.),
+        })
+    }
+}
+
+/// Frame rate of a stream with interlacing taken into account, see [`FrameRate::effective_frame_rate`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectiveFrameRate {
+    /// Full frames per second, eg. 25 for 50i content.
+    pub frames_per_second: f64,
+    /// Fields per second for interlaced content, `None` for progressive content.
+    pub fields_per_second: Option<f64>,
+}
+
+impl EffectiveFrameRate {
+    pub fn is_interlaced(&self) -> bool {
+        self.fields_per_second.is_some()
+    }
+}
+
+/// Formats as `25p`, or with the field rate for interlaced content, eg. `59.94i`.
+impl Display for EffectiveFrameRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (rate, suffix) = match self.fields_per_second {
+            Some(fields) => (fields, 'i'),
+            None => (self.frames_per_second, 'p'),
+        };
+        let rate = format!("{:.2}", rate);
+        let rate = rate.trim_end_matches('0').trim_end_matches('.');
+        write!(f, "{}{}", rate, suffix)
+    }
+}
+
+fn valid(ratio: &Ratio) -> Option<f64> {
+    ratio.as_f64().filter(|fps| *fps > 0.0)
+}
@@ -0,0 +1,122 @@
+use std::fmt::Display;
+#[cfg(feature = "process")]
+use std::path::Path;
+
+use crate::StreamIndex;
+#[cfg(feature = "process")]
+use crate::{
+    command::ffmpeg_input,
+    error::{self, invalid_data, FfProbeError},
+    Config,
+};
+
+/// Hash algorithm for [`Config::hash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashAlgo {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    /// Not suitable for verification against tampering, only against corruption.
+    Crc32,
+}
+
+impl HashAlgo {
+    /// Name of the algorithm in ffmpeg, eg. `SHA256`. SHA-1 is called `SHA160`.
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            HashAlgo::Md5 => "MD5",
+            HashAlgo::Sha1 => "SHA160",
+            HashAlgo::Sha256 => "SHA256",
+            HashAlgo::Sha512 => "SHA512",
+            HashAlgo::Crc32 => "CRC32",
+        }
+    }
+}
+
+impl Display for HashAlgo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.ffmpeg_name())
+    }
+}
+
+/// Hashes computed with [`Config::hash`], as lowercase hex strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Hashes {
+    pub algo: HashAlgo,
+    /// Hash of the bytes of the file, eg. what `sha256sum` prints.
+    pub file: String,
+    /// `(stream index, hash)` of the packet data of each stream. Unlike the file hash,
+    /// it doesn't change when the file is remuxed, eg. from Matroska to MP4.
+    pub streams: Vec<(StreamIndex, String)>,
+}
+
+impl Hashes {
+    /// Hash of the stream with index `index`.
+    pub fn stream(&self, index: impl Into<StreamIndex>) -> Option<&str> {
+        let index = index.into();
+        self.streams
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, hash)| hash.as_str())
+    }
+}
+
+/// Hash `path` with a single ffmpeg run: the `streamhash` muxer hashes the packets of
+/// every stream, the `hash` muxer the raw bytes read by the `data` demuxer.
+#[cfg(feature = "process")]
+pub(crate) fn hash(config: &Config, path: &Path, algo: HashAlgo) -> Result<Hashes, FfProbeError> {
+    let input = ffmpeg_input(config, path).map_err(FfProbeError::from_io)?;
+    let mut cmd = config.command(&config.ffmpeg_bin);
+    cmd.args(["-hide_banner", "-nostats", "-nostdin", "-v", "error"]);
+    cmd.args(&input);
+    cmd.args(["-f", "data"]).args(&input);
+    for (map, muxer) in [("0", "streamhash"), ("1:0", "hash")] {
+        cmd.args(["-map", map, "-c", "copy", "-f", muxer]);
+        cmd.args(["-hash", algo.ffmpeg_name(), "pipe:1"]);
+    }
+
+    let out = config
+        .output(&mut cmd)
+        .map_err(|e| FfProbeError::from_io(e).command_context(Some(path), &cmd))?;
+    if !out.status.success() {
+        return Err(error::status_error(path, out).command_context(Some(path), &cmd));
+    }
+    parse_hashes(&String::from_utf8_lossy(&out.stdout), algo)
+        .ok_or_else(|| invalid_data("could not find the file hash in the ffmpeg output"))
+}
+
+/// Parse the lines of both muxers, in either order:
+///
+/// ```text
+/// 0,v,SHA256=62e8...
+/// 1,a,SHA256=0b7f...
+/// SHA256=9f86...
+/// ```
+#[cfg(feature = "process")]
+fn parse_hashes(output: &str, algo: HashAlgo) -> Option<Hashes> {
+    let mut file = None;
+    let mut streams = Vec::new();
+    for line in output.lines() {
+        let Some((prefix, hash)) = line.trim().rsplit_once('=') else {
+            continue;
+        };
+        let hash = hash.to_ascii_lowercase();
+        match prefix.split(',').collect::<Vec<_>>()[..] {
+            [name] if name.eq_ignore_ascii_case(algo.ffmpeg_name()) => file = Some(hash),
+            [index, _, name] if name.eq_ignore_ascii_case(algo.ffmpeg_name()) => {
+                if let Ok(index) = index.parse() {
+                    streams.push((index, hash));
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(Hashes {
+        algo,
+        file: file?,
+        streams,
+    })
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{
+    error::{self, invalid_data, FfProbeError},
+    rotation::Rotation,
+    side_data::SideData,
+    Config, FfProbe, ProbeCommand, Stream, StreamKinds,
+};
+
+/// Still or animated image, see [`probe_image`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ImageInfo {
+    /// eg. `png`, `mjpeg`, `gif` or `webp`
+    pub codec_name: String,
+    /// Stored size, before applying [`ImageInfo::rotation`].
+    pub width: i64,
+    pub height: i64,
+    /// eg. `rgba` or `yuvj420p`
+    pub pix_fmt: Option<String>,
+    /// Whether the image embeds an ICC color profile.
+    pub has_color_profile: bool,
+    /// EXIF orientation, 1 to 8.
+    pub exif_orientation: Option<u8>,
+    /// Orientation to display the image in, from the EXIF orientation or a display matrix.
+    pub rotation: Rotation,
+    /// Number of frames, more than one for animated GIF, APNG and WebP.
+    pub frame_count: Option<u64>,
+    /// The image stream.
+    pub stream: Stream,
+}
+
+impl ImageInfo {
+    pub fn is_animated(&self) -> bool {
+        self.frame_count.is_some_and(|n| n > 1)
+    }
+
+    /// `(width, height)` after [`ImageInfo::rotation`], ie. as image viewers show it.
+    pub fn display_dimensions(&self) -> (i64, i64) {
+        self.rotation.apply((self.width, self.height))
+    }
+}
+
+/// Probe a single image, eg. a JPEG, PNG or an animated GIF.
+pub fn probe_image(path: impl AsRef<Path>) -> Result<ImageInfo, FfProbeError> {
+    probe_image_config(&Config::new(), path)
+}
+
+/// [`probe_image`] with the given config.
+///
+/// Counts the packets of the first video stream for the frame count, and reads the
+/// side data and tags of the decoded frames for the color profile and EXIF orientation.
+pub fn probe_image_config(
+    config: &Config,
+    path: impl AsRef<Path>,
+) -> Result<ImageInfo, FfProbeError> {
+    let path = path.as_ref();
+    error::check_input(path)?;
+    let cmd = ProbeCommand::new(config.clone())
+        .args(["-select_streams", "v:0", "-count_packets", "-show_frames"])
+        .args([
+            "-show_entries",
+            "frame=media_type:frame_tags:frame_side_data_list",
+        ])
+        .input(path);
+    let out = cmd.output().map_err(FfProbeError::from_io);
+    let (probe, frames) = out
+        .and_then(|out| {
+            if !out.status.success() {
+                return Err(error::status_error(path, out));
+            }
+            let mut raw: Value =
+                serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)?;
+            let frames = raw
+                .as_object_mut()
+                .and_then(|o| o.remove("frames"))
+                .unwrap_or_default();
+            Ok((FfProbe::from_value(raw)?, frames))
+        })
+        .map_err(|e| e.context(path, &cmd))?;
+    let stream = probe
+        .streams
+        .into_iter()
+        .next()
+        .ok_or_else(|| invalid_data("no image stream in the input"))?;
+    let StreamKinds::Video(video) = &stream.stream else {
+        return Err(invalid_data("no image stream in the input"));
+    };
+    let first_frame = frames.get(0);
+
+    let has_color_profile = first_frame
+        .and_then(|f| f.get("side_data_list"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d.get("side_data_type")?.as_str())
+        .chain(stream.side_data_list.iter().map(SideData::side_data_type))
+        .any(|t| t == "ICC profile");
+
+    // mjpeg exports the EXIF data as frame tags.
+    let exif_orientation = first_frame
+        .and_then(|f| {
+            f.get("tags")?
+                .get("Orientation")?
+                .as_str()?
+                .trim()
+                .parse()
+                .ok()
+        })
+        .or_else(|| stream.all_tags().get("Orientation")?.trim().parse().ok())
+        .filter(|o| (1..=8).contains(o));
+    let rotation = exif_orientation
+        .and_then(Rotation::from_exif_orientation)
+        .unwrap_or_else(|| stream.rotation());
+
+    Ok(ImageInfo {
+        codec_name: video.codec_name.clone(),
+        width: video.width,
+        height: video.height,
+        pix_fmt: video.pix_fmt.clone(),
+        has_color_profile,
+        exif_orientation,
+        rotation,
+        frame_count: stream.nb_read_packets.and_then(|n| u64::try_from(n).ok()),
+        stream,
+    })
+}
+
+/// Probe a sequence of images matching a glob `pattern`, eg. `frames/*.png`, as one
+/// video stream (`-f image2 -pattern_type glob`). ffmpeg builds without glob support,
+/// eg. most Windows builds, fail to open the pattern.
+///
+/// The sequence is read at the image2 default of 25 frames per second.
+pub fn probe_image_sequence(config: &Config, pattern: &str) -> Result<FfProbe, FfProbeError> {
+    let cmd = ProbeCommand::new(config.clone())
+        .args(["-f", "image2", "-pattern_type", "glob"])
+        .input(pattern);
+    let out = cmd.output().map_err(FfProbeError::from_io);
+    out.and_then(|out| {
+        if !out.status.success() {
+            return Err(FfProbeError::Status(out));
+        }
+        FfProbe::from_slice(&out.stdout)
+    })
+    .map_err(|e| e.context(Path::new(pattern), &cmd))
+}
@@ -0,0 +1,265 @@
+#[cfg(feature = "process")]
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{
+    bmff::{boxes, find_box, read_u32},
+    channel_layout::ChannelLayout,
+    ratio::Ratio,
+    units::SampleRate,
+};
+#[cfg(feature = "process")]
+use crate::{
+    error::{invalid_data, FfProbeError},
+    Config, ProbeCommand,
+};
+
+#[cfg(feature = "process")]
+/// Input for [`probe_init_segment`]: either a file or the segment bytes.
+#[derive(Debug, Clone, Copy)]
+pub enum InitSegmentSource<'a> {
+    Path(&'a Path),
+    Bytes(&'a [u8]),
+}
+
+#[cfg(feature = "process")]
+impl<'a> From<&'a Path> for InitSegmentSource<'a> {
+    fn from(path: &'a Path) -> Self {
+        InitSegmentSource::Path(path)
+    }
+}
+
+#[cfg(feature = "process")]
+impl<'a> From<&'a std::path::PathBuf> for InitSegmentSource<'a> {
+    fn from(path: &'a std::path::PathBuf) -> Self {
+        InitSegmentSource::Path(path)
+    }
+}
+
+#[cfg(feature = "process")]
+impl<'a> From<&'a [u8]> for InitSegmentSource<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        InitSegmentSource::Bytes(bytes)
+    }
+}
+
+#[cfg(feature = "process")]
+impl<'a> From<&'a Vec<u8>> for InitSegmentSource<'a> {
+    fn from(bytes: &'a Vec<u8>) -> Self {
+        InitSegmentSource::Bytes(bytes)
+    }
+}
+
+/// Parsed fragmented MP4 / CMAF initialization segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitSegment {
+    /// Tracks in `moov` order.
+    pub tracks: Vec<InitTrack>,
+}
+
+/// Track of an initialization segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitTrack {
+    /// `track_ID` from the track header (tkhd).
+    pub track_id: u32,
+    /// Media timescale from the media header (mdhd), in ticks per second.
+    pub timescale: u32,
+    /// Handler type, eg. `vide`, `soun` or `text`.
+    pub handler: String,
+    /// `default_sample_duration` from the track extends box (trex), in timescale units.
+    /// `None` if the segment has no trex entry for this track.
+    pub default_sample_duration: Option<u32>,
+    /// `default_sample_size` from the trex box.
+    pub default_sample_size: Option<u32>,
+    /// `default_sample_flags` from the trex box.
+    pub default_sample_flags: Option<u32>,
+    /// Codec parameters reported by ffprobe, if ffprobe recognized the track.
+    pub codec: Option<InitCodec>,
+}
+
+/// Codec parameters of an init segment track, as reported by ffprobe.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct InitCodec {
+    /// eg. video, audio
+    pub codec_type: Option<String>,
+    /// Short name of the codec. eg. h264
+    pub codec_name: Option<String>,
+    /// Sample entry four character code. eg. avc1
+    pub codec_tag_string: Option<String>,
+    pub profile: Option<String>,
+    pub level: Option<i64>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub pix_fmt: Option<String>,
+    #[serde(default)]
+    pub sample_rate: Option<SampleRate>,
+    pub channels: Option<i64>,
+    pub channel_layout: Option<ChannelLayout>,
+    pub time_base: Option<Ratio>,
+    /// Container track id as reported by ffprobe, eg. 0x1
+    pub id: Option<String>,
+}
+
+#[cfg(feature = "process")]
+/// Inspect a fragmented MP4 initialization segment with default settings.
+///
+/// See [`probe_init_segment_config`] for details.
+pub fn probe_init_segment<'a>(
+    source: impl Into<InitSegmentSource<'a>>,
+) -> Result<InitSegment, FfProbeError> {
+    probe_init_segment_config(Config::new(), source)
+}
+
+#[cfg(feature = "process")]
+/// Inspect a fragmented MP4 initialization segment.
+///
+/// Probing a lone init segment with ffprobe yields no durations or timestamps,
+/// and fails to deserialize into [`crate::FfProbe`]. Track ids, timescales and
+/// the `trex` defaults are read from the segment boxes directly, while codec
+/// parameters are taken from ffprobe.
+pub fn probe_init_segment_config<'a>(
+    config: Config,
+    source: impl Into<InitSegmentSource<'a>>,
+) -> Result<InitSegment, FfProbeError> {
+    let source = source.into();
+    let owned;
+    let bytes = match source {
+        InitSegmentSource::Path(path) => {
+            owned = std::fs::read(path).map_err(FfProbeError::Io)?;
+            &owned[..]
+        }
+        InitSegmentSource::Bytes(bytes) => bytes,
+    };
+
+    let mut tracks =
+        parse_moov(bytes).map_err(|msg| invalid_data(format!("invalid init segment: {msg}")))?;
+
+    let codecs = probe_codecs(&config, source)?;
+    for (position, track) in tracks.iter_mut().enumerate() {
+        let by_id = codecs.iter().position(|c| {
+            c.id.as_deref()
+                .and_then(|id| id.strip_prefix("0x"))
+                .and_then(|id| u32::from_str_radix(id, 16).ok())
+                == Some(track.track_id)
+        });
+        track.codec = by_id
+            .or(Some(position))
+            .and_then(|i| codecs.get(i))
+            .cloned();
+    }
+
+    Ok(InitSegment { tracks })
+}
+
+#[cfg(feature = "process")]
+fn probe_codecs(
+    config: &Config,
+    source: InitSegmentSource<'_>,
+) -> Result<Vec<InitCodec>, FfProbeError> {
+    #[derive(Deserialize)]
+    struct Output {
+        #[serde(default)]
+        streams: Vec<InitCodec>,
+    }
+
+    let mut config = config.clone();
+    config.read_intervals.clear();
+    config.count_frames = false;
+    config.count_packets = false;
+    if let (InitSegmentSource::Bytes(_), Some(allowed)) = (source, &mut config.allowed_protocols) {
+        allowed.push("pipe".to_string());
+    }
+    // Init segments are always ISO BMFF, format probing is unreliable on a pipe.
+    let command =
+        ProbeCommand::new(config)
+            .default_sections(false)
+            .args(["-show_streams", "-f", "mp4"]);
+    let (input, stdin) = match source {
+        InitSegmentSource::Path(path) => (path, None),
+        InitSegmentSource::Bytes(bytes) => (Path::new("pipe:0"), Some(bytes)),
+    };
+    let command = command.input(input);
+    let out = match stdin {
+        Some(bytes) => command.output_with_stdin(bytes),
+        None => command.output(),
+    }
+    .map_err(FfProbeError::from_io);
+    out.and_then(|out| {
+        if !out.status.success() {
+            return Err(FfProbeError::Status(out));
+        }
+        serde_json::from_slice::<Output>(&out.stdout)
+            .map(|o| o.streams)
+            .map_err(FfProbeError::Deserialize)
+    })
+    .map_err(|e| e.context(input, &command))
+}
+
+#[cfg(feature = "process")]
+fn parse_moov(data: &[u8]) -> Result<Vec<InitTrack>, String> {
+    let moov = find_box(data, b"moov")?.ok_or("no moov box found")?;
+    moov_tracks(moov)
+}
+
+/// Read the track ids, timescales and trex defaults from the payload of a `moov` box.
+pub(crate) fn moov_tracks(moov: &[u8]) -> Result<Vec<InitTrack>, String> {
+    let mut tracks = Vec::new();
+    let mut trex = Vec::new();
+    for item in boxes(moov) {
+        let (kind, payload) = item?;
+        match kind {
+            b"trak" => {
+                let tkhd = find_box(payload, b"tkhd")?.ok_or("trak without tkhd")?;
+                // version 1 uses 64 bit creation/modification times.
+                let track_id = match tkhd.first() {
+                    Some(1) => read_u32(tkhd, 20)?,
+                    _ => read_u32(tkhd, 12)?,
+                };
+                let mdia = find_box(payload, b"mdia")?.ok_or("trak without mdia")?;
+                let mdhd = find_box(mdia, b"mdhd")?.ok_or("mdia without mdhd")?;
+                let timescale = match mdhd.first() {
+                    Some(1) => read_u32(mdhd, 20)?,
+                    _ => read_u32(mdhd, 12)?,
+                };
+                let handler = find_box(mdia, b"hdlr")?
+                    .and_then(|hdlr| hdlr.get(8..12))
+                    .map(|h| String::from_utf8_lossy(h).into_owned())
+                    .unwrap_or_default();
+                tracks.push(InitTrack {
+                    track_id,
+                    timescale,
+                    handler,
+                    default_sample_duration: None,
+                    default_sample_size: None,
+                    default_sample_flags: None,
+                    codec: None,
+                });
+            }
+            b"mvex" => {
+                for item in boxes(payload) {
+                    let (kind, payload) = item?;
+                    if kind == b"trex" {
+                        trex.push((
+                            read_u32(payload, 4)?,
+                            read_u32(payload, 12)?,
+                            read_u32(payload, 16)?,
+                            read_u32(payload, 20)?,
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (track_id, duration, size, flags) in trex {
+        if let Some(track) = tracks.iter_mut().find(|t| t.track_id == track_id) {
+            track.default_sample_duration = Some(duration);
+            track.default_sample_size = Some(size);
+            track.default_sample_flags = Some(flags);
+        }
+    }
+
+    Ok(tracks)
+}
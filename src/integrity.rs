@@ -0,0 +1,150 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{duration::stream_duration, FfProbe, Stream, StreamIndex, StreamKinds};
+
+/// Relative difference between a stream and the format duration [`FfProbe::validate`]
+/// accepts, audio and video tracks of a file rarely end at exactly the same time.
+pub const DURATION_TOLERANCE: f64 = 0.05;
+
+/// Inconsistency in ffprobe's output, see [`FfProbe::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntegrityFinding {
+    /// The format reports a different number of streams than listed, eg. when probing
+    /// with `-select_streams`.
+    StreamCountMismatch { nb_streams: u64, streams: usize },
+    /// Stream duration differs by more than [`DURATION_TOLERANCE`] from the format
+    /// duration, eg. a truncated recording with a duration written by the muxer upfront.
+    DurationMismatch {
+        index: StreamIndex,
+        stream: Duration,
+        format: Duration,
+    },
+    /// Video stream, other than cover art, with a duration of zero.
+    ZeroDurationVideo { index: StreamIndex },
+    /// ffprobe couldn't determine codec parameters, eg. `["width", "height"]`. Usually
+    /// there was not enough data to probe the stream.
+    MissingCodecParameters {
+        index: StreamIndex,
+        missing: Vec<&'static str>,
+    },
+}
+
+impl Display for IntegrityFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityFinding::StreamCountMismatch {
+                nb_streams,
+                streams,
+            } => write!(f, "format reports {nb_streams} streams, {streams} listed"),
+            IntegrityFinding::DurationMismatch {
+                index,
+                stream,
+                format,
+            } => write!(
+                f,
+                "stream {index} lasts {:.3}s, the format {:.3}s",
+                stream.as_secs_f64(),
+                format.as_secs_f64()
+            ),
+            IntegrityFinding::ZeroDurationVideo { index } => {
+                write!(f, "video stream {index} has a duration of zero")
+            }
+            IntegrityFinding::MissingCodecParameters { index, missing } => {
+                write!(f, "stream {index} is missing {}", missing.join(", "))
+            }
+        }
+    }
+}
+
+/// Result of [`FfProbe::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IntegrityReport {
+    /// Findings in stream order, the stream count first.
+    pub findings: Vec<IntegrityFinding>,
+}
+
+impl IntegrityReport {
+    /// Whether nothing was found.
+    pub fn is_ok(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+impl FfProbe {
+    /// Check the output for internal consistency: the stream count, stream durations
+    /// against the format duration, zero length video and missing codec parameters.
+    ///
+    /// Only looks at the probed data, see [`crate::validate_chapters`] for chapters.
+    pub fn validate(&self) -> IntegrityReport {
+        let mut findings = Vec::new();
+        if self.format.nb_streams != self.streams.len() as u64 {
+            findings.push(IntegrityFinding::StreamCountMismatch {
+                nb_streams: self.format.nb_streams,
+                streams: self.streams.len(),
+            });
+        }
+        for stream in &self.streams {
+            let index = stream.index;
+            let is_cover = stream.disposition.attached_pic != 0;
+            let is_media = matches!(stream.stream, StreamKinds::Video(_) | StreamKinds::Audio(_));
+            let duration = stream_duration(stream);
+
+            if let (true, false, Some(stream), Some(format)) =
+                (is_media, is_cover, duration, self.format.duration)
+            {
+                let longest = stream.max(format).as_secs_f64();
+                let difference = stream.abs_diff(format).as_secs_f64();
+                if longest > 0. && difference / longest > DURATION_TOLERANCE {
+                    findings.push(IntegrityFinding::DurationMismatch {
+                        index,
+                        stream,
+                        format,
+                    });
+                }
+            }
+            if matches!(stream.stream, StreamKinds::Video(_))
+                && !is_cover
+                && duration.or(self.format.duration) == Some(Duration::ZERO)
+            {
+                findings.push(IntegrityFinding::ZeroDurationVideo { index });
+            }
+
+            let missing = missing_parameters(stream);
+            if !missing.is_empty() {
+                findings.push(IntegrityFinding::MissingCodecParameters { index, missing });
+            }
+        }
+        IntegrityReport { findings }
+    }
+}
+
+fn missing_parameters(stream: &Stream) -> Vec<&'static str> {
+    let mut missing = Vec::new();
+    let mut check = |name, present: bool| {
+        if !present {
+            missing.push(name);
+        }
+    };
+    match &stream.stream {
+        StreamKinds::Video(v) => {
+            check("codec_name", !v.codec_name.is_empty());
+            check("width", v.width > 0);
+            check("height", v.height > 0);
+            check(
+                "pix_fmt",
+                v.pix_fmt.as_deref().is_some_and(|p| !p.is_empty()),
+            );
+        }
+        StreamKinds::Audio(a) => {
+            check("codec_name", !a.codec_name.is_empty());
+            check("sample_rate", a.sample_rate.0 > 0);
+            check("channels", a.channels > 0);
+            check("sample_fmt", a.sample_fmt.is_some());
+        }
+        StreamKinds::Subtitle(s) => check("codec_name", !s.codec_name.is_empty()),
+        StreamKinds::Attachment(_) | StreamKinds::Data(_) => {}
+    }
+    missing
+}
@@ -0,0 +1,267 @@
+//! Language code normalization.
+
+use std::{fmt::Display, str::FromStr};
+
+/// ISO 639-1 codes, their ISO 639-2/B equivalent and English name.
+const ISO_639_1: &[(&str, &str, &str)] = &[
+    ("af", "afr", "Afrikaans"),
+    ("am", "amh", "Amharic"),
+    ("ar", "ara", "Arabic"),
+    ("az", "aze", "Azerbaijani"),
+    ("be", "bel", "Belarusian"),
+    ("bg", "bul", "Bulgarian"),
+    ("bn", "ben", "Bengali"),
+    ("bo", "tib", "Tibetan"),
+    ("bs", "bos", "Bosnian"),
+    ("ca", "cat", "Catalan"),
+    ("cs", "cze", "Czech"),
+    ("cy", "wel", "Welsh"),
+    ("da", "dan", "Danish"),
+    ("de", "ger", "German"),
+    ("el", "gre", "Greek"),
+    ("en", "eng", "English"),
+    ("eo", "epo", "Esperanto"),
+    ("es", "spa", "Spanish"),
+    ("et", "est", "Estonian"),
+    ("eu", "baq", "Basque"),
+    ("fa", "per", "Persian"),
+    ("fi", "fin", "Finnish"),
+    ("fo", "fao", "Faroese"),
+    ("fr", "fre", "French"),
+    ("ga", "gle", "Irish"),
+    ("gl", "glg", "Galician"),
+    ("gu", "guj", "Gujarati"),
+    ("he", "heb", "Hebrew"),
+    ("hi", "hin", "Hindi"),
+    ("hr", "hrv", "Croatian"),
+    ("hu", "hun", "Hungarian"),
+    ("hy", "arm", "Armenian"),
+    ("id", "ind", "Indonesian"),
+    ("is", "ice", "Icelandic"),
+    ("it", "ita", "Italian"),
+    ("ja", "jpn", "Japanese"),
+    ("ka", "geo", "Georgian"),
+    ("kk", "kaz", "Kazakh"),
+    ("km", "khm", "Khmer"),
+    ("kn", "kan", "Kannada"),
+    ("ko", "kor", "Korean"),
+    ("ku", "kur", "Kurdish"),
+    ("la", "lat", "Latin"),
+    ("lb", "ltz", "Luxembourgish"),
+    ("lo", "lao", "Lao"),
+    ("lt", "lit", "Lithuanian"),
+    ("lv", "lav", "Latvian"),
+    ("mi", "mao", "Maori"),
+    ("mk", "mac", "Macedonian"),
+    ("ml", "mal", "Malayalam"),
+    ("mn", "mon", "Mongolian"),
+    ("mr", "mar", "Marathi"),
+    ("ms", "may", "Malay"),
+    ("mt", "mlt", "Maltese"),
+    ("my", "bur", "Burmese"),
+    ("nb", "nob", "Norwegian Bokmål"),
+    ("ne", "nep", "Nepali"),
+    ("nl", "dut", "Dutch"),
+    ("nn", "nno", "Norwegian Nynorsk"),
+    ("no", "nor", "Norwegian"),
+    ("pa", "pan", "Punjabi"),
+    ("pl", "pol", "Polish"),
+    ("ps", "pus", "Pashto"),
+    ("pt", "por", "Portuguese"),
+    ("ro", "rum", "Romanian"),
+    ("ru", "rus", "Russian"),
+    ("si", "sin", "Sinhala"),
+    ("sk", "slo", "Slovak"),
+    ("sl", "slv", "Slovenian"),
+    ("so", "som", "Somali"),
+    ("sq", "alb", "Albanian"),
+    ("sr", "srp", "Serbian"),
+    ("sv", "swe", "Swedish"),
+    ("sw", "swa", "Swahili"),
+    ("ta", "tam", "Tamil"),
+    ("te", "tel", "Telugu"),
+    ("tg", "tgk", "Tajik"),
+    ("th", "tha", "Thai"),
+    ("tl", "tgl", "Tagalog"),
+    ("tr", "tur", "Turkish"),
+    ("uk", "ukr", "Ukrainian"),
+    ("ur", "urd", "Urdu"),
+    ("uz", "uzb", "Uzbek"),
+    ("vi", "vie", "Vietnamese"),
+    ("yi", "yid", "Yiddish"),
+    ("zh", "chi", "Chinese"),
+    ("zu", "zul", "Zulu"),
+];
+
+/// ISO 639-2/T (terminology) codes that differ from their ISO 639-2/B (bibliographic) code.
+const ISO_639_2_T: &[(&str, &str)] = &[
+    ("bod", "tib"),
+    ("ces", "cze"),
+    ("cym", "wel"),
+    ("deu", "ger"),
+    ("ell", "gre"),
+    ("eus", "baq"),
+    ("fas", "per"),
+    ("fra", "fre"),
+    ("hye", "arm"),
+    ("isl", "ice"),
+    ("kat", "geo"),
+    ("mkd", "mac"),
+    ("mri", "mao"),
+    ("msa", "may"),
+    ("mya", "bur"),
+    ("nld", "dut"),
+    ("ron", "rum"),
+    ("slk", "slo"),
+    ("sqi", "alb"),
+    ("zho", "chi"),
+];
+
+/// English names of common languages without an ISO 639-1 code.
+const NAMES_639_2: &[(&str, &str)] = &[
+    ("fil", "Filipino"),
+    ("haw", "Hawaiian"),
+    ("yue", "Cantonese"),
+];
+
+/// Language of a stream, normalized from its `language` tag, eg. `de`, `deu`, `ger`
+/// and `de-AT` are all German.
+///
+/// Compares by the ISO 639-2/B code (the one Matroska and MP4 use) and the region.
+/// Unknown but well formed three letter codes, eg. ISO 639-3 codes, are kept as is.
+///
+/// ```rust
+/// use ffprobe::Language;
+///
+/// let track: Language = "pt-BR".parse().unwrap();
+/// assert_eq!(track.as_str(), "por");
+/// assert_eq!(track.name(), Some("Portuguese"));
+/// assert!(track.matches("pt"));
+/// assert!(!track.matches("pt-PT"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Language {
+    code: String,
+    region: Option<String>,
+}
+
+impl Language {
+    /// Parse a language tag: an ISO 639-1 or 639-2 code, optionally followed by
+    /// subtags like a region (`pt-BR`, `es_419`) or script (`zh-Hant`).
+    ///
+    /// Returns `None` for undetermined languages (`und`, `mis`, `mul`, `zxx`) and tags
+    /// that are not a language code.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let mut subtags = tag.trim().split(['-', '_']);
+        let primary = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        if !primary.bytes().all(|b| b.is_ascii_lowercase()) {
+            return None;
+        }
+        let code = match primary.len() {
+            2 => ISO_639_1
+                .iter()
+                .find(|(code, _, _)| *code == primary)
+                .map(|(_, b, _)| b.to_string())?,
+            3 if matches!(primary.as_str(), "und" | "mis" | "mul" | "zxx") => return None,
+            3 => ISO_639_2_T
+                .iter()
+                .find(|(code, _)| *code == primary)
+                .map_or(primary, |(_, b)| b.to_string()),
+            _ => return None,
+        };
+        let region = subtags
+            .find(|s| {
+                (s.len() == 2 && s.bytes().all(|b| b.is_ascii_alphabetic()))
+                    || (s.len() == 3 && s.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .map(|s| s.to_ascii_uppercase());
+        Some(Language { code, region })
+    }
+
+    /// Language with the English `name`, eg. `german`, ignoring case.
+    #[cfg(feature = "process")]
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let code = ISO_639_1
+            .iter()
+            .map(|(_, code, name)| (code, name))
+            .chain(NAMES_639_2.iter().map(|(code, name)| (code, name)))
+            .find(|(_, n)| n.eq_ignore_ascii_case(name))?
+            .0;
+        Some(Language {
+            code: code.to_string(),
+            region: None,
+        })
+    }
+
+    /// ISO 639-2/B code, eg. `ger`.
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+
+    /// ISO 639-1 code, eg. `de`, if the language has one.
+    pub fn iso639_1(&self) -> Option<&'static str> {
+        ISO_639_1
+            .iter()
+            .find(|(_, b, _)| *b == self.code)
+            .map(|(code, _, _)| *code)
+    }
+
+    /// ISO 639-2/T code, eg. `deu`, the same as [`Language::as_str`] for most languages.
+    pub fn iso639_2t(&self) -> &str {
+        ISO_639_2_T
+            .iter()
+            .find(|(_, b)| *b == self.code)
+            .map_or(&self.code, |(t, _)| t)
+    }
+
+    /// Region subtag, eg. `BR` for `pt-BR` or `419` for `es-419`.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// English name, eg. `German`, for common languages.
+    pub fn name(&self) -> Option<&'static str> {
+        ISO_639_1
+            .iter()
+            .find(|(_, b, _)| *b == self.code)
+            .map(|(_, _, name)| *name)
+            .or_else(|| {
+                NAMES_639_2
+                    .iter()
+                    .find(|(code, _)| *code == self.code)
+                    .map(|(_, name)| *name)
+            })
+    }
+
+    /// Whether the language matches a user locale or language tag like `pt_BR.UTF-8` or
+    /// `de`. Regions are only compared if both have one, so `pt` matches `pt-BR`.
+    pub fn matches(&self, tag: &str) -> bool {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default();
+        Language::parse(tag).is_some_and(|other| {
+            other.code == self.code
+                && match (&self.region, &other.region) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => true,
+                }
+        })
+    }
+}
+
+/// BCP 47 style tag, eg. `pt-BR`, with the ISO 639-1 code where there is one.
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.iso639_1().unwrap_or(&self.code))?;
+        if let Some(region) = &self.region {
+            write!(f, "-{region}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::parse(s).ok_or_else(|| format!("not a language: {s}"))
+    }
+}
@@ -8,6 +8,7 @@
 //!
 //!
 //! ```rust
+//! # #[cfg(feature = "process")]
 //! match ffprobe::ffprobe("path/to/video.mp4") {
 //!    Ok(info) => {
 //!        dbg!(info);
@@ -18,39 +19,162 @@
 //! }
 //!
 //! ```
+//!
+//! Output produced elsewhere, eg. by a server for a browser tool, is parsed with
+//! [`FfProbe::from_json`]. Without the default `process` feature, eg. with the
+//! `parse-only` feature, only the models are built, so the crate compiles for targets
+//! without `std::process` like `wasm32-unknown-unknown`.
 
 //! ## Features
 //! - streams
 //! - format
 //! - chapters
-//! - async
+//! - async / tokio: [`ffprobe_async`] on tokio
+//! - async-std: [`ffprobe_async`] on async-std or smol, see the `runtime` module
+//! - mediainfo
+//! - dev-tools: synthesized test media, see the `dev_tools` module
+//! - process (default): running ffprobe and ffmpeg
+//! - parse-only: the models without `process`, use with `default-features = false`
 //!
 
+#[cfg(feature = "process")]
 use std::path::Path;
-use std::process::Command;
 use std::time::Duration;
 
+#[cfg(feature = "process")]
 use error::FfProbeError;
+#[cfg(feature = "process")]
+pub mod analysis;
 #[cfg(feature = "streams")]
 mod attachment_stream;
 #[cfg(feature = "streams")]
 mod audio_stream;
+#[cfg(feature = "streams")]
+mod bitrate;
+#[cfg(feature = "streams")]
+mod bmff;
+#[cfg(any(feature = "streams", feature = "format"))]
+pub mod bridge;
+#[cfg(feature = "streams")]
+mod broadcast_audio;
+mod byte_layout;
+#[cfg(feature = "process")]
+pub mod capabilities;
+#[cfg(all(feature = "streams", feature = "process"))]
+mod captions;
+#[cfg(feature = "streams")]
+mod channel_layout;
 #[cfg(feature = "chapters")]
 mod chapter;
+#[cfg(feature = "streams")]
+mod codec_profile;
+#[cfg(feature = "process")]
+mod command;
+#[cfg(feature = "process")]
+pub mod compact;
+#[cfg(feature = "streams")]
+pub mod compat;
+#[cfg(all(feature = "format", feature = "process"))]
+mod concat;
+#[cfg(feature = "process")]
 mod config;
 #[cfg(feature = "streams")]
+mod cover_art;
+#[cfg(any(feature = "streams", feature = "format"))]
+mod creation_time;
+#[cfg(feature = "format")]
+mod cue;
+#[cfg(feature = "streams")]
 mod data_stream;
 #[cfg(feature = "streams")]
+mod delay;
+#[cfg(feature = "dev-tools")]
+pub mod dev_tools;
+mod dialect;
+#[cfg(feature = "streams")]
 mod disposition;
+#[cfg(any(feature = "streams", feature = "format"))]
+mod duration;
+mod ebml;
+mod editions;
+#[cfg(feature = "process")]
+mod entries;
 pub mod error;
+#[cfg(feature = "process")]
+pub mod executor;
+#[cfg(feature = "streams")]
+mod extradata;
 mod ffprobe;
+#[cfg(feature = "test-util")]
+pub mod fixtures;
+mod flexible_num;
 #[cfg(feature = "format")]
 mod format;
+#[cfg(feature = "streams")]
+mod fragments;
+mod frame_rate;
+mod hash;
+#[cfg(all(feature = "streams", feature = "process"))]
+mod image;
+#[cfg(feature = "streams")]
+mod init_segment;
+#[cfg(all(feature = "streams", feature = "format"))]
+mod integrity;
+#[cfg(feature = "streams")]
+mod language;
+mod log_level;
+#[cfg(feature = "streams")]
+pub mod mapping;
+#[cfg(feature = "mediainfo")]
+mod mediainfo;
+#[cfg(feature = "streams")]
+mod mkv_stats;
+mod multi;
+#[cfg(feature = "process")]
+mod pool;
+#[cfg(feature = "process")]
+mod progress;
+mod query;
 mod ratio;
+#[cfg(feature = "process")]
+mod raw;
+#[cfg(feature = "process")]
+mod read_interval;
+#[cfg(feature = "streams")]
+mod replay_gain;
+#[cfg(feature = "process")]
+mod retry;
+#[cfg(feature = "streams")]
+mod rotation;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub mod runtime;
+#[cfg(feature = "streams")]
+mod sample_format;
+#[cfg(feature = "process")]
+mod scanner;
+#[cfg(feature = "schemars")]
+mod schema;
+mod seek_index;
+#[cfg(feature = "streams")]
+pub mod side_data;
+#[cfg(all(feature = "streams", feature = "process"))]
+pub mod sidecar;
+#[cfg(feature = "streams")]
+mod spatial;
+#[cfg(feature = "process")]
+mod spawn;
+mod stream_index;
+#[cfg(all(feature = "streams", feature = "format", feature = "process"))]
+pub mod streaming;
 #[cfg(feature = "streams")]
 mod streams;
 #[cfg(feature = "streams")]
 mod subtitle_stream;
+#[cfg(any(feature = "streams", feature = "format", feature = "chapters"))]
+mod tags;
+mod time_base;
+mod timecode;
+mod units;
 #[cfg(feature = "streams")]
 mod video_stream;
 
@@ -62,27 +186,111 @@ pub use attachment_stream::AttachmentTags;
 pub use audio_stream::AudioStream;
 #[cfg(feature = "streams")]
 pub use audio_stream::AudioTags;
-#[cfg(feature = "chapters")]
-pub use chapter::Chapter;
+#[cfg(all(feature = "streams", feature = "process"))]
+pub use broadcast_audio::detect_dolby_e;
+#[cfg(feature = "streams")]
+pub use broadcast_audio::{BroadcastAudioKind, DolbyE, DolbyEProgramConfig};
+pub use byte_layout::{BoxLocation, ByteLayout, StreamByteRange};
+#[cfg(feature = "process")]
+pub use capabilities::build_info;
+#[cfg(all(feature = "streams", feature = "process"))]
+pub use captions::{caption_services, CaptionService, CAPTION_SCAN_DURATION};
+#[cfg(feature = "streams")]
+pub use channel_layout::{Channel, ChannelLayout};
 #[cfg(feature = "chapters")]
 pub use chapter::ChapterTags;
+#[cfg(feature = "chapters")]
+pub use chapter::{validate_chapters, Chapter, ChapterWarning};
+#[cfg(feature = "streams")]
+pub use codec_profile::{Av1Profile, CodecLevel, H264Profile, HevcProfile, VideoProfile};
+#[cfg(feature = "process")]
+pub use command::{IntoFfprobeInput, ProbeCommand};
+#[cfg(all(feature = "format", feature = "process"))]
+pub use concat::{probe_concat, ConcatProbe, ConcatSegment};
+#[cfg(feature = "process")]
 pub use config::Config;
+#[cfg(all(unix, feature = "process"))]
+pub use config::IoPriority;
+#[cfg(all(feature = "streams", feature = "process"))]
+pub use cover_art::{extract_cover_art, extract_cover_art_config};
+#[cfg(any(feature = "streams", feature = "format"))]
+pub use creation_time::CreationTime;
+#[cfg(all(feature = "format", feature = "process"))]
+pub use cue::{probe_cue, probe_cue_config};
+#[cfg(feature = "format")]
+pub use cue::{CueFile, CueProbe, CueSheet, CueTime, CueTrack, LogicalTrack};
 #[cfg(feature = "streams")]
 pub use data_stream::DataStream;
 #[cfg(feature = "streams")]
 pub use data_stream::DataTags;
+pub use dialect::Dialect;
 #[cfg(feature = "streams")]
 pub use disposition::Disposition;
+pub use editions::{default_edition, matroska_editions, Edition, EditionChapter};
+#[cfg(feature = "process")]
+pub use entries::{ffprobe_entries, Entries, EntriesSpec};
+#[cfg(feature = "streams")]
+pub use extradata::{NalCodec, ParameterSets, SpsInfo};
 pub use ffprobe::FfProbe;
 #[cfg(feature = "format")]
 pub use format::Format;
 #[cfg(feature = "format")]
 pub use format::FormatTags;
+#[cfg(feature = "streams")]
+pub use fragments::{fragments, FragmentInfo, TrackFragment};
+pub use frame_rate::{EffectiveFrameRate, FrameRate};
+pub use hash::{HashAlgo, Hashes};
+#[cfg(all(feature = "streams", feature = "process"))]
+pub use image::{probe_image, probe_image_config, probe_image_sequence, ImageInfo};
+#[cfg(all(feature = "streams", feature = "process"))]
+pub use init_segment::{probe_init_segment, probe_init_segment_config, InitSegmentSource};
+#[cfg(feature = "streams")]
+pub use init_segment::{InitCodec, InitSegment, InitTrack};
+#[cfg(all(feature = "streams", feature = "format"))]
+pub use integrity::{IntegrityFinding, IntegrityReport, DURATION_TOLERANCE};
+#[cfg(feature = "streams")]
+pub use language::Language;
+pub use log_level::{LogLevel, LogMessage};
+#[cfg(feature = "mediainfo")]
+pub use mediainfo::{MediaInfo, MediaInfoTrack, TrackKind};
+#[cfg(feature = "streams")]
+pub use mkv_stats::MkvStats;
+#[cfg(feature = "process")]
+pub use multi::ffprobe_multi;
+#[cfg(feature = "format")]
+pub use multi::{sync_report, SyncReport};
+#[cfg(feature = "process")]
+pub use pool::{ProbeHandle, ProbePool};
+#[cfg(feature = "process")]
+pub use progress::{ProbeProgress, PROGRESS_INTERVAL};
 pub use ratio::Ratio;
-use serde::Deserialize;
+#[cfg(feature = "process")]
+pub use raw::{ffprobe_raw_sections, RAW_SECTIONS_MAX_BYTES};
+#[cfg(feature = "process")]
+pub use read_interval::ReadInterval;
+#[cfg(feature = "streams")]
+pub use replay_gain::ReplayGain;
+#[cfg(feature = "process")]
+pub use retry::RetryPolicy;
+#[cfg(feature = "streams")]
+pub use rotation::Rotation;
+#[cfg(feature = "streams")]
+pub use sample_format::SampleFormat;
+#[cfg(feature = "process")]
+pub use scanner::{Scan, ScanProgress, Scanner};
+pub use seek_index::{seek_index_info, SeekIndexInfo};
 use serde::Deserializer;
 #[cfg(feature = "streams")]
-pub use streams::SideData;
+pub use side_data::SideData;
+#[cfg(feature = "streams")]
+pub use spatial::{
+    AmbisonicChannelOrder, AmbisonicNormalization, Ambisonics, Projection, ProjectionKind,
+};
+#[cfg(feature = "process")]
+pub use spawn::{SpawnEvent, STDERR_SNIPPET_BYTES};
+pub use stream_index::StreamIndex;
+#[cfg(feature = "streams")]
+pub use stream_index::StreamType;
 #[cfg(feature = "streams")]
 pub use streams::Stream;
 #[cfg(feature = "streams")]
@@ -92,11 +300,17 @@ pub use streams::StreamTags;
 #[cfg(feature = "streams")]
 pub use subtitle_stream::SubtititleTags;
 #[cfg(feature = "streams")]
-pub use subtitle_stream::SubtitleStream;
+pub use subtitle_stream::{SubtitleKind, SubtitleStream};
+#[cfg(any(feature = "streams", feature = "format", feature = "chapters"))]
+pub use tags::Tags;
+pub use time_base::TimeBase;
+pub use timecode::Timecode;
+pub use units::{Bitrate, FileSize, SampleRate};
 #[cfg(feature = "streams")]
 pub use video_stream::VideoStream;
 #[cfg(feature = "streams")]
 pub use video_stream::VideoTags;
+#[cfg(feature = "process")]
 /// Execute ffprobe with default settings and return the extracted data.
 ///
 /// See [`ffprobe_config`] if you need to customize settings.
@@ -104,82 +318,175 @@ pub fn ffprobe(path: impl AsRef<Path>) -> Result<FfProbe, FfProbeError> {
     ffprobe_config(Config::new(), path)
 }
 
+#[cfg(feature = "process")]
 /// Run ffprobe with a custom config.
 /// See [`ConfigBuilder`] for more details.
+///
+/// Empty local files fail with [`FfProbeError::EmptyOrTruncatedInput`] without running
+/// ffprobe, as do tiny files ffprobe can't read. Errors carry the input and the command
+/// line, see [`FfProbeError::input`] and [`FfProbeError::inner`].
 pub fn ffprobe_config(config: Config, path: impl AsRef<Path>) -> Result<FfProbe, FfProbeError> {
     let path = path.as_ref();
-    let mut cmd = Command::new(config.ffprobe_bin);
-    // Default args.
-    cmd.args(["-v", "quiet", "-print_format", "json"]);
-    #[cfg(feature = "chapters")]
-    cmd.arg("-show_chapters");
-    #[cfg(feature = "format")]
-    cmd.arg("-show_format");
-    #[cfg(feature = "streams")]
-    cmd.arg("-show_streams");
-
-    if config.count_frames {
-        cmd.arg("-count_frames");
+    error::check_input(path)
+        .map_err(|err| err.context(path, &ProbeCommand::new(config.clone()).input(path)))?;
+    let mut attempt = 1;
+    loop {
+        let err = match probe_once(&config, path) {
+            Ok(probe) => return Ok(probe),
+            Err(err) => err,
+        };
+        match config
+            .retry
+            .as_ref()
+            .and_then(|r| r.delay(attempt, path, err.inner()))
+        {
+            Some(delay) => std::thread::sleep(delay),
+            None => return Err(err),
+        }
+        attempt += 1;
     }
+}
+
+#[cfg(feature = "process")]
+/// One attempt of [`ffprobe_config`], errors with the [`FfProbeError::Context`] of the
+/// last command run.
+fn probe_once(config: &Config, path: &Path) -> Result<FfProbe, FfProbeError> {
+    let mut command = ProbeCommand::new(config.clone())
+        .dialect(dialect::known(config).unwrap_or_default())
+        .input(path);
+    run_once(config, path, &mut command).map_err(|err| err.context(path, &command))
+}
 
-    cmd.arg(path);
+#[cfg(feature = "process")]
+fn run_once(
+    config: &Config,
+    path: &Path,
+    command: &mut ProbeCommand,
+) -> Result<FfProbe, FfProbeError> {
+    let out = command.output().map_err(FfProbeError::from_io)?;
+    finish_probe(config, path, command, out)
+}
 
-    let out = cmd.output().map_err(FfProbeError::Io)?;
+#[cfg(feature = "process")]
+/// The rest of a probe after the first ffprobe run `out` of `command`, shared with the
+/// async functions: the [`Dialect`] fallback, parsing, and the extra runs of the config.
+fn finish_probe(
+    config: &Config,
+    path: &Path,
+    command: &mut ProbeCommand,
+    mut out: std::process::Output,
+) -> Result<FfProbe, FfProbeError> {
+    let known = dialect::known(config);
+    let mut dialect = known.unwrap_or_default();
+    if known.is_none() {
+        if !out.status.success() && dialect::is_unsupported(command, &out) {
+            dialect = Dialect::Legacy;
+        }
+        // Also for failed inputs, the binary parsed or rejected the options before them.
+        dialect::remember(config, dialect);
+        if dialect == Dialect::Legacy {
+            *command = command.clone().dialect(dialect);
+            out = command.output().map_err(FfProbeError::from_io)?;
+        }
+    }
 
     if !out.status.success() {
-        return Err(FfProbeError::Status(out));
+        if dialect == Dialect::Legacy && dialect::rejects_whitelist(command, &out) {
+            return Err(FfProbeError::ProtocolNotAllowed {
+                protocol: command::input_protocol(path.as_os_str()),
+            });
+        }
+        return Err(error::status_error(path, out));
     }
 
-    serde_json::from_slice::<FfProbe>(&out.stdout).map_err(FfProbeError::Deserialize)
+    let mut probe = dialect::parse(dialect, &out.stdout)?;
+    if let Some(algo) = config.hash {
+        probe.hashes = Some(hash::hash(config, path, algo)?);
+    }
+    if config.byte_layout {
+        probe.byte_layout = Some(byte_layout::byte_layout(config, path)?);
+    }
+    #[cfg(any(feature = "streams", feature = "format"))]
+    if config.estimate_duration && probe.best_duration().is_none() {
+        // Best effort, a failed tail probe leaves the estimate out.
+        probe.estimated_duration =
+            duration::estimate_duration(config, path, &probe).unwrap_or_default();
+    }
+    Ok(if config.log_level().is_some() {
+        probe.with_stderr(&out.stderr)
+    } else {
+        probe
+    })
 }
 
-#[cfg(feature = "async")]
+#[cfg(any(feature = "tokio", feature = "async-std"))]
 pub async fn ffprobe_async(path: impl AsRef<std::path::Path>) -> Result<FfProbe, FfProbeError> {
     ffprobe_async_config(Config::new(), path).await
 }
 
-#[cfg(feature = "async")]
+#[cfg(any(feature = "tokio", feature = "async-std"))]
 pub async fn ffprobe_async_config(
     config: Config,
     path: impl AsRef<Path>,
 ) -> Result<FfProbe, FfProbeError> {
-    let mut cmd = tokio::process::Command::new("ffprobe");
-    let path = path.as_ref();
-    cmd.args(["-v", "quiet", "-print_format", "json"]);
-    #[cfg(feature = "chapters")]
-    cmd.arg("-show_chapters");
-    #[cfg(feature = "format")]
-    cmd.arg("-show_format");
-    #[cfg(feature = "streams")]
-    cmd.arg("-show_streams");
-
-    if config.count_frames {
-        cmd.arg("-count_frames");
-    }
-
-    cmd.arg(path);
-
-    let out = cmd.output().await.map_err(FfProbeError::Io)?;
+    ffprobe_async_with::<runtime::DefaultSpawner>(config, path).await
+}
 
-    if !out.status.success() {
-        return Err(FfProbeError::Status(out));
+/// [`ffprobe_async_config`] on the runtime of `S`, eg.
+/// `ffprobe_async_with::<runtime::AsyncStd>` with both runtime features enabled.
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub async fn ffprobe_async_with<S: runtime::Spawner>(
+    config: Config,
+    path: impl AsRef<Path>,
+) -> Result<FfProbe, FfProbeError> {
+    let path = path.as_ref();
+    // Same command line as the blocking path, see [`ProbeCommand`].
+    let command = ProbeCommand::new(config.clone())
+        .dialect(dialect::known(&config).unwrap_or_default())
+        .input(path);
+    error::check_input(path).map_err(|err| err.context(path, &command))?;
+    command
+        .check_protocol()
+        .map_err(|err| FfProbeError::from_io(err).context(path, &command))?;
+    let mut attempt = 1;
+    loop {
+        let mut command = command.clone();
+        let result = match runtime::output::<S>(&config, &command).await {
+            // Parsing and the extra runs of the config block, they run off the runtime.
+            Ok(out) => {
+                let (config, path) = (config.clone(), path.to_path_buf());
+                let (result, finished) = S::unblock(move || {
+                    let result = finish_probe(&config, &path, &mut command, out);
+                    (result, command)
+                })
+                .await;
+                command = finished;
+                result
+            }
+            Err(err) => Err(FfProbeError::from_io(err)),
+        };
+        let err = match result {
+            Ok(probe) => return Ok(probe),
+            Err(err) => err.context(path, &command),
+        };
+        match config
+            .retry
+            .as_ref()
+            .and_then(|r| r.delay(attempt, path, err.inner()))
+        {
+            Some(delay) => S::sleep(delay).await,
+            None => return Err(err),
+        }
+        attempt += 1;
     }
-
-    serde_json::from_slice::<FfProbe>(&out.stdout).map_err(FfProbeError::Deserialize)
 }
 
 pub fn option_string_to_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    match s {
-        Some(s) => s
-            .parse::<f64>()
-            .map(|v| v.max(0.0))
-            .map(Duration::from_secs_f64)
-            .map(Some)
-            .map_err(serde::de::Error::custom),
-        None => Ok(None),
-    }
+    let seconds: Option<f64> = flexible_num::option(deserializer)?;
+    seconds
+        .map(|v| Duration::try_from_secs_f64(v.max(0.0)).map_err(serde::de::Error::custom))
+        .transpose()
 }
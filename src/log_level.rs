@@ -0,0 +1,101 @@
+use std::fmt::Display;
+
+/// ffmpeg log level for `-v`, from least to most verbose, see [`Config::loglevel`](crate::Config::loglevel).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum LogLevel {
+    Quiet,
+    /// Crashes, eg. failed assertions.
+    Panic,
+    /// Errors the process can't continue after.
+    Fatal,
+    Error,
+    /// eg. `moov atom not found` or non monotonic DTS.
+    Warning,
+    /// Default level of the ffmpeg tools.
+    Info,
+    Verbose,
+    Debug,
+    /// Every read, eg. the MP4 boxes.
+    Trace,
+}
+
+impl LogLevel {
+    /// Level named as in ffmpeg, eg. `warning`.
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "quiet" => LogLevel::Quiet,
+            "panic" => LogLevel::Panic,
+            "fatal" => LogLevel::Fatal,
+            "error" => LogLevel::Error,
+            "warning" => LogLevel::Warning,
+            "info" => LogLevel::Info,
+            "verbose" => LogLevel::Verbose,
+            "debug" => LogLevel::Debug,
+            "trace" => LogLevel::Trace,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Quiet => "quiet",
+            LogLevel::Panic => "panic",
+            LogLevel::Fatal => "fatal",
+            LogLevel::Error => "error",
+            LogLevel::Warning => "warning",
+            LogLevel::Info => "info",
+            LogLevel::Verbose => "verbose",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Line ffprobe logged on stderr, see [`FfProbe::log`](crate::FfProbe::log).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LogMessage {
+    /// `None` for lines without a level prefix.
+    pub level: Option<LogLevel>,
+    /// Component that logged the line, eg. `mov,mp4,m4a,3gp,3g2,mj2` or `h264`.
+    pub context: Option<String>,
+    pub message: String,
+}
+
+impl LogMessage {
+    /// Parse a line as formatted by ffmpeg with the `level` log flag, eg.
+    /// `[h264 @ 0x55d1c8] [error] non-existing PPS 0 referenced`. Lines without the
+    /// prefixes are kept as the message.
+    pub fn parse(line: &str) -> Self {
+        let mut rest = line;
+        let mut context = None;
+        // Parent and own context, eg. `[aist#0:0/h264 @ 0x55d0] [h264 @ 0x55d1] `.
+        while let Some((name, after)) = rest
+            .strip_prefix('[')
+            .and_then(|r| r.split_once("] "))
+            .and_then(|(inner, after)| Some((inner.split_once(" @ ")?.0, after)))
+        {
+            context = Some(name.to_string());
+            rest = after;
+        }
+        let level = rest
+            .strip_prefix('[')
+            .and_then(|r| r.split_once("] "))
+            .and_then(|(name, after)| Some((LogLevel::parse(name)?, after)));
+        if let Some((_, after)) = level {
+            rest = after;
+        }
+        LogMessage {
+            level: level.map(|(level, _)| level),
+            context,
+            message: rest.to_string(),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{AudioStream, FfProbe, Language, Stream, StreamIndex, StreamKinds, SubtitleStream};
+
+/// When [`FfProbe::suggest_mapping`] picks a subtitle track.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SubtitleMode {
+    /// Never.
+    None,
+    /// Only forced subtitles in the language of the selected audio, which translate
+    /// foreign language parts, eg. signs or an alien language.
+    #[default]
+    ForcedOnly,
+    /// Full subtitles in a preferred subtitle language, forced ones otherwise.
+    Always,
+    /// Full subtitles if the selected audio isn't in a preferred subtitle language,
+    /// forced ones otherwise. Usually what viewers want for foreign language films.
+    Smart,
+}
+
+/// Preferences for [`FfProbe::suggest_mapping`].
+///
+/// Languages are user locales or language tags like `de` or `pt_BR.UTF-8`, see
+/// [`Language::matches`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MappingPreferences {
+    /// Audio languages by preference.
+    pub audio_languages: Vec<String>,
+    /// Subtitle languages by preference.
+    pub subtitle_languages: Vec<String>,
+    pub subtitle_mode: SubtitleMode,
+    /// Prefer subtitles for the deaf and hard of hearing, avoided otherwise.
+    pub prefer_sdh: bool,
+}
+
+impl MappingPreferences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an audio language, after the ones added before.
+    pub fn audio_language(mut self, language: impl Into<String>) -> Self {
+        self.audio_languages.push(language.into());
+        self
+    }
+
+    /// Add a subtitle language, after the ones added before.
+    pub fn subtitle_language(mut self, language: impl Into<String>) -> Self {
+        self.subtitle_languages.push(language.into());
+        self
+    }
+
+    pub fn subtitle_mode(mut self, mode: SubtitleMode) -> Self {
+        self.subtitle_mode = mode;
+        self
+    }
+
+    pub fn prefer_sdh(mut self, prefer_sdh: bool) -> Self {
+        self.prefer_sdh = prefer_sdh;
+        self
+    }
+}
+
+/// Streams selected by [`FfProbe::suggest_mapping`], by stream index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StreamMapping {
+    pub video: Option<StreamIndex>,
+    pub audio: Option<StreamIndex>,
+    pub subtitle: Option<StreamIndex>,
+}
+
+impl StreamMapping {
+    /// ffmpeg arguments mapping the selected streams of the input with index `input`,
+    /// eg. `["-map", "0:0", "-map", "0:2"]`.
+    pub fn map_args(&self, input: usize) -> Vec<String> {
+        [self.video, self.audio, self.subtitle]
+            .into_iter()
+            .flatten()
+            .flat_map(|index| ["-map".to_string(), format!("{input}:{index}")])
+            .collect()
+    }
+}
+
+impl FfProbe {
+    /// Pick the streams to transcode or play: the largest video stream, audio in the
+    /// most preferred language and a subtitle track according to
+    /// [`MappingPreferences::subtitle_mode`].
+    ///
+    /// Default streams win ties, commentary and audio description tracks are only picked
+    /// without alternatives. Without a preferred language match, the default audio track
+    /// is picked, or the first one.
+    ///
+    /// ```rust
+    /// use ffprobe::mapping::{MappingPreferences, SubtitleMode};
+    ///
+    /// # let probe = ffprobe::FfProbe::new();
+    /// let mapping = probe.suggest_mapping(
+    ///     &MappingPreferences::new()
+    ///         .audio_language("ja")
+    ///         .subtitle_language("en")
+    ///         .subtitle_mode(SubtitleMode::Smart),
+    /// );
+    /// let mut cmd = std::process::Command::new("ffmpeg");
+    /// cmd.args(["-i", "input.mkv"]).args(mapping.map_args(0));
+    /// ```
+    pub fn suggest_mapping(&self, preferences: &MappingPreferences) -> StreamMapping {
+        let video = self
+            .streams
+            .iter()
+            .filter_map(|stream| match &stream.stream {
+                StreamKinds::Video(video) if stream.disposition.attached_pic == 0 => {
+                    Some((stream, video))
+                }
+                _ => None,
+            })
+            .max_by_key(|(stream, video)| {
+                (
+                    video.width * video.height,
+                    video.bit_rate.map(|b| b.0),
+                    stream.disposition.default,
+                    std::cmp::Reverse(stream.index),
+                )
+            })
+            .map(|(stream, _)| stream.index);
+
+        let audio = self.pick_audio(&preferences.audio_languages);
+        let audio_language = audio.and_then(|(_, audio)| audio.language());
+        StreamMapping {
+            video,
+            audio: audio.map(|(stream, _)| stream.index),
+            subtitle: self.pick_subtitle(preferences, audio_language.as_ref()),
+        }
+    }
+
+    fn pick_audio(&self, languages: &[String]) -> Option<(&Stream, &AudioStream)> {
+        let audio: Vec<(&Stream, &AudioStream)> = self
+            .streams
+            .iter()
+            .filter_map(|stream| match &stream.stream {
+                StreamKinds::Audio(audio) => Some((stream, audio)),
+                _ => None,
+            })
+            .collect();
+        languages
+            .iter()
+            .find_map(|tag| {
+                let matching: Vec<_> = audio
+                    .iter()
+                    .copied()
+                    .filter(|(_, a)| a.language().is_some_and(|l| l.matches(tag)))
+                    .collect();
+                best_audio(matching)
+            })
+            .or_else(|| {
+                audio
+                    .iter()
+                    .copied()
+                    .find(|(stream, _)| stream.disposition.default == 1)
+            })
+            .or_else(|| best_audio(audio))
+    }
+
+    fn pick_subtitle(
+        &self,
+        preferences: &MappingPreferences,
+        audio_language: Option<&Language>,
+    ) -> Option<StreamIndex> {
+        let subtitles: Vec<(&Stream, &SubtitleStream)> = self
+            .streams
+            .iter()
+            .filter_map(|stream| match &stream.stream {
+                StreamKinds::Subtitle(subtitle) => Some((stream, subtitle)),
+                _ => None,
+            })
+            .collect();
+
+        // Forced subtitles in the audio language, or without a language.
+        let forced = || {
+            subtitles
+                .iter()
+                .filter(|(stream, s)| s.is_forced(&stream.disposition))
+                .find(|(_, s)| match (s.language(), audio_language) {
+                    (Some(subtitle), Some(audio)) => subtitle.iso639_2t() == audio.iso639_2t(),
+                    _ => true,
+                })
+                .map(|(stream, _)| stream.index)
+        };
+        let full = || {
+            preferences.subtitle_languages.iter().find_map(|tag| {
+                subtitles
+                    .iter()
+                    .filter(|(stream, s)| {
+                        !s.is_forced(&stream.disposition)
+                            && s.language().is_some_and(|l| l.matches(tag))
+                    })
+                    .rev()
+                    .max_by_key(|(stream, s)| {
+                        (
+                            s.is_sdh(&stream.disposition) == preferences.prefer_sdh,
+                            stream.disposition.default,
+                        )
+                    })
+                    .map(|(stream, _)| stream.index)
+            })
+        };
+        // Unknown audio languages are assumed to be understood.
+        let understood = audio_language.is_none_or(|audio| {
+            preferences
+                .subtitle_languages
+                .iter()
+                .any(|tag| audio.matches(tag))
+        });
+
+        match preferences.subtitle_mode {
+            SubtitleMode::None => None,
+            SubtitleMode::ForcedOnly => forced(),
+            SubtitleMode::Always => full().or_else(forced),
+            SubtitleMode::Smart if understood => forced(),
+            SubtitleMode::Smart => full().or_else(forced),
+        }
+    }
+}
+
+/// Main track among `candidates`: no commentary or audio description, then default,
+/// then the most channels, then the first.
+fn best_audio<'a>(
+    candidates: Vec<(&'a Stream, &'a AudioStream)>,
+) -> Option<(&'a Stream, &'a AudioStream)> {
+    candidates.into_iter().rev().max_by_key(|(stream, audio)| {
+        let d = &stream.disposition;
+        (
+            d.comment == 0 && d.visual_impaired == 0 && d.descriptions == 0,
+            d.default,
+            audio.channels,
+        )
+    })
+}
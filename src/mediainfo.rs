@@ -0,0 +1,413 @@
+use std::fmt::Write;
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::{Bitrate, FfProbe, Stream, StreamKinds};
+
+/// Report in the layout of `mediainfo --Output=JSON` / `--Output=XML`.
+///
+/// Created with [`FfProbe::to_mediainfo`]. Only the commonly consumed fields
+/// of the General, Video, Audio and Text sections are emitted, using the
+/// MediaInfo field names and units (seconds for durations, bits per second for
+/// bit rates, bytes for sizes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaInfo {
+    /// Probed file, emitted as `@ref`.
+    pub reference: String,
+    /// Tracks in MediaInfo order: General first, then one per stream.
+    pub tracks: Vec<MediaInfoTrack>,
+}
+
+/// Single `track` entry of a [`MediaInfo`] report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaInfoTrack {
+    pub kind: TrackKind,
+    /// Field name and value pairs, in output order.
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// `@type` of a MediaInfo track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackKind {
+    General,
+    Video,
+    Audio,
+    Text,
+}
+
+impl TrackKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackKind::General => "General",
+            TrackKind::Video => "Video",
+            TrackKind::Audio => "Audio",
+            TrackKind::Text => "Text",
+        }
+    }
+}
+
+impl MediaInfoTrack {
+    /// Value of the given field, eg. `"Format"`.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn push(&mut self, field: &'static str, value: impl ToString) {
+        self.fields.push((field, value.to_string()));
+    }
+
+    fn push_opt(&mut self, field: &'static str, value: Option<impl ToString>) {
+        if let Some(value) = value {
+            self.push(field, value);
+        }
+    }
+}
+
+impl MediaInfo {
+    /// Tracks of the given kind.
+    pub fn tracks(&self, kind: TrackKind) -> impl Iterator<Item = &MediaInfoTrack> {
+        self.tracks.iter().filter(move |t| t.kind == kind)
+    }
+
+    /// Serialize as MediaInfo JSON.
+    pub fn to_json(&self) -> String {
+        // Only string keys and values, serialization can't fail.
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Serialize as MediaInfo XML.
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<MediaInfo xmlns=\"https://mediaarea.net/mediainfo\" version=\"2.0\">\n");
+        let _ = writeln!(out, "<media ref=\"{}\">", xml_escape(&self.reference));
+        for track in &self.tracks {
+            let _ = writeln!(out, "<track type=\"{}\">", track.kind.as_str());
+            for (name, value) in &track.fields {
+                let _ = writeln!(out, "<{name}>{}</{name}>", xml_escape(value));
+            }
+            out.push_str("</track>\n");
+        }
+        out.push_str("</media>\n</MediaInfo>\n");
+        out
+    }
+}
+
+impl Serialize for MediaInfoTrack {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.fields.len() + 1))?;
+        map.serialize_entry("@type", self.kind.as_str())?;
+        for (name, value) in &self.fields {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for MediaInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Media<'a> {
+            #[serde(rename = "@ref")]
+            reference: &'a str,
+            track: &'a [MediaInfoTrack],
+        }
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(
+            "media",
+            &Media {
+                reference: &self.reference,
+                track: &self.tracks,
+            },
+        )?;
+        map.end()
+    }
+}
+
+impl FfProbe {
+    /// Convert the probe result into a MediaInfo compatible report.
+    pub fn to_mediainfo(&self) -> MediaInfo {
+        let mut tracks = vec![self.general_track()];
+        tracks.extend(self.streams.iter().filter_map(stream_track));
+        MediaInfo {
+            reference: self.format.filename.clone(),
+            tracks,
+        }
+    }
+
+    fn general_track(&self) -> MediaInfoTrack {
+        let count =
+            |f: fn(&StreamKinds) -> bool| self.streams.iter().filter(|s| f(&s.stream)).count();
+        let format = &self.format;
+        let mut track = MediaInfoTrack {
+            kind: TrackKind::General,
+            fields: Vec::new(),
+        };
+        track.push_opt(
+            "VideoCount",
+            Some(count(|s| matches!(s, StreamKinds::Video(_)))).filter(|c| *c > 0),
+        );
+        track.push_opt(
+            "AudioCount",
+            Some(count(|s| matches!(s, StreamKinds::Audio(_)))).filter(|c| *c > 0),
+        );
+        track.push_opt(
+            "TextCount",
+            Some(count(|s| matches!(s, StreamKinds::Subtitle(_)))).filter(|c| *c > 0),
+        );
+        track.push_opt(
+            "FileExtension",
+            std::path::Path::new(&format.filename)
+                .extension()
+                .map(|e| e.to_string_lossy()),
+        );
+        track.push("Format", container_format(&format.format_name));
+        track.push("FileSize", format.size.bytes());
+        track.push_opt(
+            "Duration",
+            format.duration.map(|d| decimal(d.as_secs_f64())),
+        );
+        track.push_opt(
+            "OverallBitRate",
+            format.bit_rate.map(Bitrate::bits_per_second),
+        );
+        if let Some(tags) = &format.tags {
+            track.push_opt("Title", tags.title.as_ref());
+            track.push_opt("Encoded_Application", tags.encoder.as_ref());
+        }
+        track
+    }
+}
+
+fn stream_track(stream: &Stream) -> Option<MediaInfoTrack> {
+    let kind = match &stream.stream {
+        StreamKinds::Video(_) => TrackKind::Video,
+        StreamKinds::Audio(_) => TrackKind::Audio,
+        StreamKinds::Subtitle(_) => TrackKind::Text,
+        StreamKinds::Attachment(_) | StreamKinds::Data(_) => return None,
+    };
+    let mut track = MediaInfoTrack {
+        kind,
+        fields: Vec::new(),
+    };
+    track.push("StreamOrder", stream.index);
+    track.push("ID", stream_id(stream));
+
+    let duration = stream.duration().map(|d| decimal(d.as_secs_f64()));
+
+    match &stream.stream {
+        StreamKinds::Video(video) => {
+            track.push("Format", codec_format(&video.codec_name));
+            track.push_opt("Format_Profile", video.profile.as_ref());
+            track.push_opt("Format_Level", codec_level(&video.codec_name, video.level));
+            track.push_opt("CodecID", fourcc(&stream.codec_tag));
+            track.push_opt("Duration", duration);
+            track.push_opt("BitRate", video.bit_rate.map(Bitrate::bits_per_second));
+            track.push("Width", video.width);
+            track.push("Height", video.height);
+            track.push_opt(
+                "PixelAspectRatio",
+                video.sample_aspect_ratio.as_ref().and_then(ratio),
+            );
+            track.push_opt(
+                "DisplayAspectRatio",
+                video.display_aspect_ratio.as_ref().and_then(ratio),
+            );
+            track.push_opt("FrameRate", ratio(&stream.avg_frame_rate));
+            track.push_opt("FrameCount", stream.nb_frames);
+            track.push_opt("BitDepth", video.bits_per_raw_sample);
+            track.push_opt(
+                "ScanType",
+                video.field_order.as_deref().map(|order| match order {
+                    "progressive" => "Progressive",
+                    _ => "Interlaced",
+                }),
+            );
+            track.push_opt(
+                "colour_range",
+                video.color_range.as_deref().map(|range| match range {
+                    "tv" | "mpeg" => "Limited",
+                    "pc" | "jpeg" => "Full",
+                    other => other,
+                }),
+            );
+            track.push_opt(
+                "colour_primaries",
+                video.color_primaries.as_deref().map(colour),
+            );
+            track.push_opt(
+                "transfer_characteristics",
+                video.color_transfer.as_deref().map(colour),
+            );
+            track.push_opt(
+                "matrix_coefficients",
+                video.color_space.as_deref().map(colour),
+            );
+        }
+        StreamKinds::Audio(audio) => {
+            track.push("Format", codec_format(&audio.codec_name));
+            track.push_opt("Format_AdditionalFeatures", audio.profile.as_ref());
+            track.push_opt("CodecID", fourcc(&stream.codec_tag));
+            track.push_opt("Duration", duration);
+            track.push_opt("BitRate", audio.bit_rate.map(Bitrate::bits_per_second));
+            track.push("Channels", audio.channels);
+            track.push_opt("ChannelLayout", audio.channel_layout.as_ref());
+            track.push("SamplingRate", audio.sample_rate.hz());
+            track.push_opt("BitDepth", audio.bit_depth());
+        }
+        StreamKinds::Subtitle(sub) => {
+            track.push("Format", codec_format(&sub.codec_name));
+            track.push_opt("CodecID", fourcc(&stream.codec_tag));
+            track.push_opt("Duration", duration);
+            track.push_opt("BitRate", sub.bit_rate.map(Bitrate::bits_per_second));
+        }
+        StreamKinds::Attachment(_) | StreamKinds::Data(_) => unreachable!(),
+    }
+
+    if let Some(tags) = stream.tags() {
+        track.push_opt("Title", tags.title.as_ref());
+        track.push_opt(
+            "Language",
+            tags.language.as_ref().filter(|lang| lang.as_str() != "und"),
+        );
+    }
+    track.push("Default", yes_no(stream.disposition.default));
+    track.push("Forced", yes_no(stream.disposition.forced));
+    Some(track)
+}
+
+/// MediaInfo uses the container track id, which ffprobe reports in hex.
+fn stream_id(stream: &Stream) -> String {
+    stream
+        .id
+        .as_deref()
+        .and_then(|id| {
+            id.strip_prefix("0x")
+                .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        })
+        .unwrap_or(stream.index.get() + 1)
+        .to_string()
+}
+
+fn container_format(format_name: &str) -> &str {
+    match format_name {
+        "mov,mp4,m4a,3gp,3g2,mj2" => "MPEG-4",
+        "matroska,webm" => "Matroska",
+        "avi" => "AVI",
+        "mpegts" => "MPEG-TS",
+        "mpeg" => "MPEG-PS",
+        "asf" => "Windows Media",
+        "flv" => "Flash Video",
+        "ogg" => "Ogg",
+        "wav" => "Wave",
+        "flac" => "FLAC",
+        "mp3" => "MPEG Audio",
+        other => other,
+    }
+}
+
+fn codec_format(codec_name: &str) -> &str {
+    match codec_name {
+        "h264" => "AVC",
+        "hevc" => "HEVC",
+        "mpeg2video" => "MPEG Video",
+        "mpeg4" => "MPEG-4 Visual",
+        "vp8" => "VP8",
+        "vp9" => "VP9",
+        "av1" => "AV1",
+        "prores" => "ProRes",
+        "mjpeg" => "JPEG",
+        "aac" => "AAC",
+        "ac3" => "AC-3",
+        "eac3" => "E-AC-3",
+        "dts" => "DTS",
+        "truehd" => "MLP FBA",
+        "mp3" | "mp2" => "MPEG Audio",
+        "opus" => "Opus",
+        "vorbis" => "Vorbis",
+        "flac" => "FLAC",
+        "alac" => "ALAC",
+        "subrip" => "UTF-8",
+        "ass" | "ssa" => "ASS",
+        "webvtt" => "WebVTT",
+        "mov_text" => "Timed Text",
+        "hdmv_pgs_subtitle" => "PGS",
+        "dvd_subtitle" => "VobSub",
+        "dvb_subtitle" => "DVB Subtitle",
+        other => other,
+    }
+}
+
+/// ffprobe reports levels as integers (`41` for H.264 4.1, `123` for HEVC 4.1).
+fn codec_level(codec_name: &str, level: i64) -> Option<String> {
+    if level <= 0 {
+        return None;
+    }
+    match codec_name {
+        "h264" => Some(format!("{}.{}", level / 10, level % 10)),
+        "hevc" => Some(match level % 30 {
+            0 => format!("{}", level / 30),
+            _ => format!("{}.{}", level / 30, level % 30 / 3),
+        }),
+        _ => Some(level.to_string()),
+    }
+}
+
+fn colour(name: &str) -> String {
+    match name {
+        "bt709" => "BT.709".to_string(),
+        "bt470bg" => "BT.601 PAL".to_string(),
+        "smpte170m" => "BT.601 NTSC".to_string(),
+        "bt2020" | "bt2020nc" => "BT.2020".to_string(),
+        "smpte2084" => "PQ".to_string(),
+        "arib-std-b67" => "HLG".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Four character code stored little-endian in `codec_tag`, eg. `0x31637661` => `avc1`.
+fn fourcc(codec_tag: &[u8]) -> Option<String> {
+    if codec_tag.is_empty() || codec_tag.iter().all(|b| *b == 0) {
+        return None;
+    }
+    codec_tag
+        .iter()
+        .rev()
+        .map(|b| b.is_ascii_graphic().then_some(*b as char))
+        .collect()
+}
+
+fn ratio(ratio: &crate::Ratio) -> Option<String> {
+    (ratio.denominator() != 0 && ratio.numerator() != 0)
+        .then(|| decimal(ratio.numerator() as f64 / ratio.denominator() as f64))
+}
+
+fn decimal(value: f64) -> String {
+    format!("{:.3}", value)
+}
+
+fn yes_no(flag: i64) -> &'static str {
+    if flag != 0 {
+        "Yes"
+    } else {
+        "No"
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+
+use crate::{streams::StreamTags, Bitrate, FileSize, Stream};
+
+/// Per-stream statistics written by mkvmerge and ffmpeg into Matroska tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MkvStats {
+    /// `NUMBER_OF_FRAMES`
+    pub frames: Option<u64>,
+    /// `NUMBER_OF_BYTES`
+    pub bytes: Option<FileSize>,
+    /// `DURATION`, written as HH:MM:SS.nnnnnnnnn
+    pub duration: Option<Duration>,
+    /// `BPS`
+    pub bitrate: Option<Bitrate>,
+    /// `_STATISTICS_WRITING_APP` eg. mkvmerge v81.0 ('Milord Mine') 64-bit
+    pub writing_app: Option<String>,
+    /// `_STATISTICS_WRITING_DATE_UTC`
+    pub writing_date_utc: Option<NaiveDateTime>,
+    /// Tags covered by the statistics, from `_STATISTICS_TAGS`. eg. BPS, DURATION
+    pub tags: Vec<String>,
+}
+
+impl MkvStats {
+    /// Whether none of the statistics tags are set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Bit rate from `BPS`, or computed from `NUMBER_OF_BYTES` and `DURATION`.
+    pub fn effective_bitrate(&self) -> Option<Bitrate> {
+        self.bitrate
+            .or_else(|| Bitrate::from_size(self.bytes?, self.duration?))
+    }
+}
+
+impl StreamTags {
+    /// Matroska statistics tags, `None` if the stream has none.
+    pub fn mkv_stats(&self) -> Option<MkvStats> {
+        let stats = MkvStats {
+            frames: self.number_of_frames.and_then(|n| u64::try_from(n).ok()),
+            bytes: self
+                .number_of_bytes
+                .and_then(|n| u64::try_from(n).ok())
+                .map(FileSize),
+            duration: self.duration,
+            bitrate: self.bps.and_then(|n| u64::try_from(n).ok()).map(Bitrate),
+            writing_app: self.statistics_writing_app.clone(),
+            writing_date_utc: self.statistics_writing_date_utc,
+            tags: self
+                .statistics_tags
+                .as_deref()
+                .map(|t| t.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+        };
+        (!stats.is_empty()).then_some(stats)
+    }
+}
+
+impl Stream {
+    /// Matroska statistics tags of the stream, see [`StreamTags::mkv_stats`].
+    ///
+    /// Also picks up the language variants written by mkvmerge, eg. `BPS-eng` or
+    /// `DURATION-eng`.
+    pub fn mkv_stats(&self) -> Option<MkvStats> {
+        let mut stats = self
+            .tags()
+            .and_then(StreamTags::mkv_stats)
+            .unwrap_or_default();
+        if stats.frames.is_none() {
+            stats.frames = self
+                .statistics_tag("NUMBER_OF_FRAMES")
+                .and_then(|v| v.parse().ok());
+        }
+        if stats.bytes.is_none() {
+            stats.bytes = self
+                .statistics_tag("NUMBER_OF_BYTES")
+                .and_then(|v| v.parse().ok());
+        }
+        if stats.duration.is_none() {
+            stats.duration = self.statistics_tag("DURATION").and_then(parse_mkv_duration);
+        }
+        if stats.bitrate.is_none() {
+            stats.bitrate = self.statistics_tag("BPS").and_then(|v| v.parse().ok());
+        }
+        (!stats.is_empty()).then_some(stats)
+    }
+
+    /// Statistics tag with a language suffix, eg. `BPS-eng`.
+    fn statistics_tag(&self, name: &str) -> Option<&str> {
+        self.extra_tags()?.iter().find_map(|(key, value)| {
+            key.strip_prefix(name)?.strip_prefix('-')?;
+            value.as_str().map(str::trim)
+        })
+    }
+}
+
+/// Parse a Matroska `DURATION` tag, eg. `01:23:45.678000000`. Hours may exceed 24.
+pub(crate) fn parse_mkv_duration(s: &str) -> Option<Duration> {
+    let mut parts = s.trim().splitn(3, ':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let rest = parts.next()?;
+    let (secs, frac) = rest.split_once('.').unwrap_or((rest, ""));
+    let secs: u64 = secs.parse().ok()?;
+    if minutes >= 60 || secs >= 60 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = frac
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(9)
+        .fold(0u32, |n, b| n * 10 + u32::from(b - b'0'));
+    Some(Duration::new(hours * 3600 + minutes * 60 + secs, nanos))
+}
@@ -0,0 +1,91 @@
+#[cfg(feature = "process")]
+use std::path::Path;
+#[cfg(feature = "format")]
+use std::time::Duration;
+
+#[cfg(any(feature = "format", feature = "process"))]
+use crate::FfProbe;
+#[cfg(feature = "process")]
+use crate::{
+    error::{self, FfProbeError},
+    ffprobe_config, Config,
+};
+
+/// Probe several inputs with the same config, eg. separately delivered audio and video
+/// masters, returning one result per input in the same order.
+///
+/// All inputs are checked before ffprobe runs, so an empty local file fails the call
+/// without probing the others. The probes run in parallel, the first failure in input
+/// order is returned.
+#[cfg(feature = "process")]
+pub fn ffprobe_multi(
+    config: Config,
+    inputs: &[impl AsRef<Path> + Sync],
+) -> Result<Vec<FfProbe>, FfProbeError> {
+    for input in inputs {
+        error::check_input(input.as_ref())?;
+    }
+    std::thread::scope(|scope| {
+        let probes: Vec<_> = inputs
+            .iter()
+            .map(|input| {
+                let config = config.clone();
+                scope.spawn(move || ffprobe_config(config, input))
+            })
+            .collect();
+        probes
+            .into_iter()
+            .map(|probe| probe.join().expect("ffprobe thread panicked"))
+            .collect()
+    })
+}
+
+/// Start and duration differences of two inputs, see [`sync_report`].
+#[cfg(feature = "format")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct SyncReport {
+    /// Seconds the other input starts after the reference, negative if it starts before.
+    /// Inputs without a start time start at zero.
+    pub start_offset: f64,
+    /// Seconds the other input lasts longer than the reference, `None` if a duration is
+    /// unknown.
+    pub duration_difference: Option<f64>,
+}
+
+#[cfg(feature = "format")]
+impl SyncReport {
+    /// Whether the inputs start and end within `tolerance` of each other. An unknown
+    /// duration only fails the check if the start differs.
+    pub fn is_in_sync(&self, tolerance: Duration) -> bool {
+        let tolerance = tolerance.as_secs_f64();
+        self.start_offset.abs() <= tolerance
+            && self
+                .duration_difference
+                .is_none_or(|difference| difference.abs() <= tolerance)
+    }
+}
+
+/// Compare the format start time and duration of `other` to `reference`, eg. an audio
+/// master to the matching video master probed with [`ffprobe_multi`].
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// let video = ffprobe::FfProbe::new();
+/// let audio = ffprobe::FfProbe::new();
+/// let report = ffprobe::sync_report(&video, &audio);
+/// assert!(report.is_in_sync(Duration::from_millis(40)));
+/// ```
+#[cfg(feature = "format")]
+pub fn sync_report(reference: &FfProbe, other: &FfProbe) -> SyncReport {
+    let start = |probe: &FfProbe| probe.format.start_time.unwrap_or_default().as_secs_f64();
+    SyncReport {
+        start_offset: start(other) - start(reference),
+        duration_difference: other
+            .format
+            .duration
+            .zip(reference.format.duration)
+            .map(|(other, reference)| other.as_secs_f64() - reference.as_secs_f64()),
+    }
+}
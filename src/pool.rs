@@ -0,0 +1,223 @@
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{mpsc, Arc, Condvar, Mutex, PoisonError},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use crate::{dialect, error::FfProbeError, ffprobe_config, Config, FfProbe};
+
+/// Fixed set of worker threads probing submitted files, eg. to probe a large number of
+/// small files without starting a thread per file or overloading the machine.
+///
+/// The binary is looked up in `PATH` and its [`Dialect`](crate::Dialect) is detected
+/// once, when the pool is created. Each submitted file still runs its own ffprobe process.
+///
+/// ```rust,no_run
+/// use ffprobe::{Config, ProbePool};
+///
+/// let pool = ProbePool::new(Config::new(), 4)?;
+/// let handles: Vec<_> = ["a.mp4", "b.mp4"].iter().map(|p| pool.submit(p)).collect();
+/// for handle in handles {
+///     println!("{} streams", handle.wait()?.streams.len());
+/// }
+/// # Ok::<(), ffprobe::error::FfProbeError>(())
+/// ```
+///
+/// [`ProbeHandle`] is also a future, which doesn't need a particular async runtime:
+///
+/// ```rust,no_run
+/// # async fn probe(pool: &ffprobe::ProbePool) -> Result<(), ffprobe::error::FfProbeError> {
+/// let probe = pool.submit("a.mp4").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ProbePool {
+    queue: mpsc::Sender<Job>,
+    workers: usize,
+}
+
+struct Job {
+    path: PathBuf,
+    slot: Arc<Slot>,
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        // No-op after the worker finished the job, otherwise the handle gets an error
+        // instead of waiting forever.
+        self.slot.finish(Err(FfProbeError::Io(std::io::Error::other(
+            "the probe pool stopped before probing the file",
+        ))));
+    }
+}
+
+impl ProbePool {
+    /// Pool of `workers` threads, at least 1, probing with `config`. Fails if ffprobe
+    /// can't be run.
+    pub fn new(config: Config, workers: usize) -> Result<Self, FfProbeError> {
+        let mut config = config;
+        if config.executor.is_none() {
+            config.ffprobe_bin = resolve_bin(&config);
+        }
+        if config.dialect.is_none() {
+            config.dialect = Some(dialect::detect(&config).map_err(FfProbeError::from_io)?);
+        }
+
+        let workers = workers.max(1);
+        let (queue, jobs) = mpsc::channel::<Job>();
+        let jobs = Arc::new(Mutex::new(jobs));
+        for _ in 0..workers {
+            let jobs = Arc::clone(&jobs);
+            let config = config.clone();
+            thread::spawn(move || loop {
+                // Lock only to take the next job, the others keep probing meanwhile.
+                let job = match jobs.lock() {
+                    Ok(jobs) => jobs.recv(),
+                    Err(_) => break,
+                };
+                let Ok(job) = job else {
+                    break;
+                };
+                job.slot.finish(ffprobe_config(config.clone(), &job.path));
+            });
+        }
+        Ok(ProbePool { queue, workers })
+    }
+
+    /// Pool with as many workers as there are CPUs.
+    pub fn with_available_parallelism(config: Config) -> Result<Self, FfProbeError> {
+        Self::new(
+            config,
+            thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        )
+    }
+
+    /// Number of worker threads, ie. ffprobe processes running at once.
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Queue `path` for probing. Files are probed in the order they are submitted.
+    ///
+    /// Dropping the pool doesn't cancel queued files, the workers stop once the queue is
+    /// empty.
+    pub fn submit(&self, path: impl AsRef<Path>) -> ProbeHandle {
+        let slot = Arc::new(Slot::default());
+        // Only fails without workers, the dropped job then fails the handle.
+        let _ = self.queue.send(Job {
+            path: path.as_ref().to_path_buf(),
+            slot: Arc::clone(&slot),
+        });
+        ProbeHandle { slot }
+    }
+}
+
+/// Result of a file submitted to a [`ProbePool`], either awaited or with
+/// [`ProbeHandle::wait`].
+pub struct ProbeHandle {
+    slot: Arc<Slot>,
+}
+
+impl ProbeHandle {
+    /// Block until the file is probed.
+    pub fn wait(self) -> Result<FfProbe, FfProbeError> {
+        let mut state = self.slot.lock();
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            state = self
+                .slot
+                .done
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Whether the file is probed, ie. [`ProbeHandle::wait`] won't block.
+    pub fn is_finished(&self) -> bool {
+        self.slot.lock().finished
+    }
+}
+
+impl Future for ProbeHandle {
+    type Output = Result<FfProbe, FfProbeError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Checked and registered under the lock, so a worker finishing meanwhile sees
+        // the waker.
+        let mut state = self.slot.lock();
+        if state.finished {
+            return Poll::Ready(
+                state
+                    .result
+                    .take()
+                    .expect("ProbeHandle polled after completion"),
+            );
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    state: Mutex<SlotState>,
+    done: Condvar,
+}
+
+#[derive(Default)]
+struct SlotState {
+    finished: bool,
+    result: Option<Result<FfProbe, FfProbeError>>,
+    waker: Option<Waker>,
+}
+
+impl Slot {
+    fn lock(&self) -> std::sync::MutexGuard<'_, SlotState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Store the result unless there already is one, and wake the handle.
+    fn finish(&self, result: Result<FfProbe, FfProbeError>) {
+        let mut state = self.lock();
+        if state.finished {
+            return;
+        }
+        state.finished = true;
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.done.notify_all();
+    }
+}
+
+/// Absolute path of [`Config::ffprobe_bin`] if it's a bare name found in `PATH`, as set
+/// with [`Config::env`] or inherited.
+fn resolve_bin(config: &Config) -> PathBuf {
+    let bin = &config.ffprobe_bin;
+    if bin.components().count() != 1 || bin.is_absolute() {
+        return bin.clone();
+    }
+    let path = match config.env.iter().rev().find(|(key, _)| key == "PATH") {
+        Some((_, path)) => Some(path.clone()),
+        None if config.clear_env => None,
+        None => std::env::var_os("PATH"),
+    };
+    path.iter()
+        .flat_map(std::env::split_paths)
+        .flat_map(|dir| {
+            let candidate = dir.join(bin);
+            // `ffprobe` is `ffprobe.exe` on Windows.
+            let exe = (cfg!(windows) && candidate.extension().is_none())
+                .then(|| candidate.with_extension("exe"));
+            exe.into_iter().chain([candidate])
+        })
+        .find(|candidate| candidate.is_file())
+        .unwrap_or_else(|| bin.clone())
+}
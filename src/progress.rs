@@ -0,0 +1,216 @@
+use std::{
+    fmt::Debug,
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::output_too_large,
+    spawn::{stdin_pipe, write_stdin},
+};
+
+/// Interval between the [`Config::progress`](crate::Config::progress) reports of a
+/// running process.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often to check whether the process has exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// No [`ProbeProgress::out_time`] yet.
+const NO_TIME: u64 = u64::MAX;
+
+/// Progress of a running ffprobe or ffmpeg process, see
+/// [`Config::progress`](crate::Config::progress).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProbeProgress {
+    /// Position reached in the input: the `out_time` of ffmpeg's `-progress` reports, or
+    /// the timestamp of the last packet or frame ffprobe printed. `None` when the
+    /// process doesn't report one, eg. ffprobe with only `-count_frames`.
+    pub out_time: Option<Duration>,
+    /// Bytes the process has read so far, including network input (Linux only).
+    pub bytes_read: Option<u64>,
+    /// Wall clock time since the process was spawned.
+    pub elapsed: Duration,
+    /// Whether the process has exited, the last report of a run.
+    pub done: bool,
+}
+
+/// Hook registered with [`Config::progress`](crate::Config::progress).
+#[derive(Clone)]
+pub(crate) struct ProgressHook(pub(crate) Arc<dyn Fn(&ProbeProgress) + Send + Sync>);
+
+impl Debug for ProgressHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
+
+impl ProgressHook {
+    /// Run `cmd` to completion like [`Command::output`], reporting every
+    /// [`PROGRESS_INTERVAL`] and once it has exited. The process is killed once it
+    /// wrote more than `limit` bytes to stdout or stderr. `stdin` is written to it.
+    pub(crate) fn output(
+        &self,
+        cmd: &mut Command,
+        limit: Option<usize>,
+        stdin: Option<&[u8]>,
+    ) -> io::Result<Output> {
+        let started = Instant::now();
+        let mut child = cmd
+            .stdin(stdin_pipe(stdin))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let pipe = child.stdin.take();
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+        let out_time = AtomicU64::new(NO_TIME);
+        let over_limit = AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            write_stdin(scope, pipe, stdin);
+            let stdout = scope.spawn(|| read_stdout(stdout, &out_time, limit, &over_limit));
+            let stderr = scope.spawn(|| read_stderr(stderr, &out_time, limit, &over_limit));
+            let mut bytes_read = None;
+            let report = |bytes_read, done| {
+                let out_time = match out_time.load(Ordering::Relaxed) {
+                    NO_TIME => None,
+                    us => Some(Duration::from_micros(us)),
+                };
+                (self.0)(&ProbeProgress {
+                    out_time,
+                    bytes_read,
+                    elapsed: started.elapsed(),
+                    done,
+                });
+            };
+
+            let mut last_report = Instant::now();
+            let status = loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => break Ok(status),
+                    Ok(None) => {}
+                    Err(err) => {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        break Err(err);
+                    }
+                }
+                if over_limit.load(Ordering::Relaxed) {
+                    let _ = child.kill();
+                }
+                bytes_read = read_bytes(&child).or(bytes_read);
+                if last_report.elapsed() >= PROGRESS_INTERVAL {
+                    report(bytes_read, false);
+                    last_report = Instant::now();
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            };
+            let stdout = stdout.join().unwrap_or_else(|_| Ok(Vec::new()));
+            let stderr = stderr.join().unwrap_or_default();
+            let status = status?;
+            report(bytes_read, true);
+            if let Some(limit) = limit.filter(|&l| stderr.len() > l) {
+                return Err(output_too_large(limit));
+            }
+            Ok(Output {
+                status,
+                stdout: stdout?,
+                stderr,
+            })
+        })
+    }
+}
+
+/// Read stdout, tracking the last `"pts_time": "12.345"` of ffprobe's JSON packets and
+/// frames. Stops with [`output_too_large`] and sets `over_limit` beyond `limit` bytes.
+fn read_stdout(
+    mut stdout: impl Read,
+    out_time: &AtomicU64,
+    limit: Option<usize>,
+    over_limit: &AtomicBool,
+) -> io::Result<Vec<u8>> {
+    const KEY: &[u8] = b"\"pts_time\": \"";
+    let mut buf = Vec::new();
+    let mut chunk = [0; 64 * 1024];
+    loop {
+        let read = match stdout.read(&mut chunk) {
+            Ok(0) => return Ok(buf),
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        // Include the end of the previous chunk, a timestamp may span both.
+        let from = buf.len().saturating_sub(32);
+        buf.extend_from_slice(&chunk[..read]);
+        if let Some(limit) = limit.filter(|&l| buf.len() > l) {
+            over_limit.store(true, Ordering::Relaxed);
+            return Err(output_too_large(limit));
+        }
+        let time = buf[from..]
+            .windows(KEY.len())
+            .rposition(|w| w == KEY)
+            .and_then(|pos| {
+                let value = &buf[from + pos + KEY.len()..];
+                let end = value.iter().position(|&b| b == b'"')?;
+                std::str::from_utf8(&value[..end]).ok()?.parse::<f64>().ok()
+            });
+        if let Some(time) = time.filter(|t| t.is_finite() && *t >= 0.) {
+            out_time.store((time * 1e6) as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Read stderr, tracking the `out_time_us=5000000` lines of ffmpeg's `-progress pipe:2`.
+/// Stops and sets `over_limit` beyond `limit` bytes.
+fn read_stderr(
+    stderr: impl Read,
+    out_time: &AtomicU64,
+    limit: Option<usize>,
+    over_limit: &AtomicBool,
+) -> Vec<u8> {
+    let mut stderr = BufReader::new(stderr.take(limit.map_or(u64::MAX, |l| l as u64 + 1)));
+    let mut buf = Vec::new();
+    loop {
+        let start = buf.len();
+        match stderr.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => {
+                if limit.is_some_and(|l| buf.len() > l) {
+                    over_limit.store(true, Ordering::Relaxed);
+                }
+                return buf;
+            }
+            Ok(_) => {}
+        }
+        let line = String::from_utf8_lossy(&buf[start..]);
+        if let Some(us) = line
+            .trim()
+            .strip_prefix("out_time_us=")
+            .and_then(|us| us.parse().ok())
+        {
+            out_time.store(us, Ordering::Relaxed);
+        }
+    }
+}
+
+/// `rchar` of `/proc/<pid>/io`, all bytes read with `read` and similar calls.
+#[cfg(target_os = "linux")]
+fn read_bytes(child: &Child) -> Option<u64> {
+    let io = std::fs::read_to_string(format!("/proc/{}/io", child.id())).ok()?;
+    io.lines()
+        .find_map(|line| line.strip_prefix("rchar:"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_bytes(_: &Child) -> Option<u64> {
+    None
+}
@@ -0,0 +1,62 @@
+use serde_json::Value;
+
+use crate::FfProbe;
+
+impl FfProbe {
+    /// Look up a value by a dotted path into the ffprobe output, eg. `streams[0].width`,
+    /// `format.tags.title` or `chapters.1.start_time`.
+    ///
+    /// Object keys are matched exactly first, then ignoring case, so `format.tags.TITLE`
+    /// finds `title`. Queries run on the raw ffprobe output and see fields the typed
+    /// model doesn't have, also for [`FfProbe::from_json`]. Results deserialized by the
+    /// caller or built in code are queried through their serialized form instead.
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "process")]
+    /// # {
+    /// let probe = ffprobe::ffprobe("video.mp4").unwrap();
+    /// println!("{:?}", probe.query("streams[0].codec_name"));
+    /// # }
+    /// ```
+    pub fn query(&self, path: &str) -> Option<Value> {
+        match &self.raw {
+            Some(raw) => query(raw, path).cloned(),
+            None => query(&serde_json::to_value(self).ok()?, path).cloned(),
+        }
+    }
+}
+
+pub(crate) fn query<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.') {
+        let (key, indexes) = match segment.find('[') {
+            Some(i) => segment.split_at(i),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = step(current, key)?;
+        }
+        let mut indexes = indexes;
+        while let Some(rest) = indexes.strip_prefix('[') {
+            let (index, rest) = rest.split_once(']')?;
+            current = current.get(index.trim().parse::<usize>().ok()?)?;
+            indexes = rest;
+        }
+        if !indexes.is_empty() {
+            return None;
+        }
+    }
+    Some(current)
+}
+
+fn step<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map.get(key).or_else(|| {
+            map.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v)
+        }),
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    }
+}
@@ -1,22 +1,114 @@
 use core::fmt;
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, ops::Mul, str::FromStr};
 
 use serde::{
     de::{self, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 /// Ratio eg. 1/1000
 pub struct Ratio((u64, u64));
 
 impl Ratio {
+    /// Aspect ratios commonly used for video, from narrow to wide.
+    pub const COMMON_ASPECT_RATIOS: &'static [Ratio] = &[
+        Ratio::new(1, 1),
+        Ratio::new(5, 4),
+        Ratio::new(4, 3),
+        Ratio::new(3, 2),
+        Ratio::new(16, 10),
+        Ratio::new(16, 9),
+        Ratio::new(185, 100),
+        Ratio::new(2, 1),
+        Ratio::new(235, 100),
+        Ratio::new(239, 100),
+        Ratio::new(64, 27),
+    ];
+
+    pub const fn new(numerator: u64, denominator: u64) -> Self {
+        Ratio((numerator, denominator))
+    }
+
     pub fn numerator(&self) -> u64 {
         self.0 .0
     }
     pub fn denominator(&self) -> u64 {
         self.0 .1
     }
+
+    /// Value as floating point number.
+    /// Returns `None` for a zero denominator, which ffprobe uses for unknown values (eg. `0/0`).
+    pub fn as_f64(&self) -> Option<f64> {
+        (self.denominator() != 0).then(|| self.numerator() as f64 / self.denominator() as f64)
+    }
+
+    /// Ratio reduced to lowest terms. eg. 1920:1080 => 16:9
+    pub fn reduce(&self) -> Ratio {
+        match gcd(self.numerator(), self.denominator()) {
+            0 => self.clone(),
+            divisor => Ratio::new(self.numerator() / divisor, self.denominator() / divisor),
+        }
+    }
+
+    /// The entry of `candidates` closest to this ratio, eg. 1920:804 => 239:100.
+    /// See [`Ratio::COMMON_ASPECT_RATIOS`] for a default list.
+    pub fn approximate<'a>(&self, candidates: &'a [Ratio]) -> Option<&'a Ratio> {
+        let value = self.as_f64()?;
+        candidates
+            .iter()
+            .filter_map(|candidate| Some((candidate, candidate.as_f64()?)))
+            .min_by(|(_, a), (_, b)| (a - value).abs().total_cmp(&(b - value).abs()))
+            .map(|(candidate, _)| candidate)
+    }
+
+    /// Multiply an integer (eg. a width in pixels) with this ratio, rounded to the nearest integer.
+    /// Returns `None` for a zero denominator.
+    pub fn scale(&self, value: u64) -> Option<u64> {
+        if self.denominator() == 0 {
+            return None;
+        }
+        let scaled = (value as u128 * self.numerator() as u128 * 2 + self.denominator() as u128)
+            / (self.denominator() as u128 * 2);
+        u64::try_from(scaled).ok()
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Product in lowest terms. Products that don't fit in `u64` even then, eg. of large
+/// prime time bases, are approximated by one that does.
+impl Mul for Ratio {
+    type Output = Ratio;
+
+    fn mul(self, rhs: Ratio) -> Ratio {
+        let mut numerator = self.numerator() as u128 * rhs.numerator() as u128;
+        let mut denominator = self.denominator() as u128 * rhs.denominator() as u128;
+        let divisor = gcd_u128(numerator, denominator);
+        if divisor > 1 {
+            numerator /= divisor;
+            denominator /= divisor;
+        }
+        // Drop the low bits of both terms until the larger one fits.
+        let shift = (128 - numerator.max(denominator).leading_zeros()).saturating_sub(64);
+        if shift > 0 {
+            numerator >>= shift;
+            denominator = (denominator >> shift).max(1);
+        }
+        Ratio::new(numerator as u64, denominator as u64)
+    }
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
 impl Display for Ratio {
@@ -29,7 +121,7 @@ impl FromStr for Ratio {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(|c| c == ':' || c == '/').collect();
+        let parts: Vec<&str> = s.split([':', '/']).collect();
         if parts.len() != 2 {
             return Err("Invalid ratio format".to_string());
         }
@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::{error::FfProbeError, Config, ProbeCommand};
+
+/// Maximum size of the ffprobe output read by [`ffprobe_raw_sections`].
+pub const RAW_SECTIONS_MAX_BYTES: usize = 64 << 20;
+
+/// Execute ffprobe with `-show_<section>` for every given section and return the
+/// untyped output, one entry per section.
+///
+/// Allows probing sections the typed model doesn't cover, eg. `programs`, `frames`
+/// or `stream_groups`. Frame and packet dumps of long files get big, the output is
+/// limited to [`RAW_SECTIONS_MAX_BYTES`], or a lower [`Config::max_output_bytes`], and
+/// ffprobe is killed beyond that.
+///
+/// ```rust,no_run
+/// let sections = ffprobe::ffprobe_raw_sections(
+///     ffprobe::Config::new(),
+///     "video.ts",
+///     &["programs"],
+/// ).unwrap();
+/// println!("{}", sections["programs"]);
+/// ```
+pub fn ffprobe_raw_sections(
+    config: Config,
+    path: impl AsRef<Path>,
+    sections: &[&str],
+) -> Result<Map<String, Value>, FfProbeError> {
+    let path = path.as_ref();
+    let limit = config
+        .max_output_bytes
+        .map_or(RAW_SECTIONS_MAX_BYTES, |l| l.min(RAW_SECTIONS_MAX_BYTES));
+    let cmd = ProbeCommand::new(config.max_output_bytes(limit))
+        .default_sections(false)
+        .args(sections.iter().map(|s| format!("-show_{s}")))
+        .input(path);
+    let out = cmd.output().map_err(FfProbeError::from_io);
+    out.and_then(|out| {
+        if !out.status.success() {
+            return Err(FfProbeError::Status(out));
+        }
+        serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)
+    })
+    .map_err(|e| e.context(path, &cmd))
+}
@@ -0,0 +1,97 @@
+use std::{fmt::Display, time::Duration};
+
+/// Interval passed to ffprobe's `-read_intervals` option.
+///
+/// Limits reading of packets and frames (eg. for [`crate::Config::count_frames`])
+/// to a part of the input.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use ffprobe::ReadInterval;
+///
+/// let interval = ReadInterval::start(Duration::from_secs(60)).duration(Duration::from_secs(10));
+/// assert_eq!(interval.to_string(), "60%+10");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadInterval {
+    start: Option<Duration>,
+    end: Option<IntervalEnd>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntervalEnd {
+    At(Duration),
+    After(Duration),
+    Packets(u64),
+}
+
+impl ReadInterval {
+    /// Interval beginning at the given position. ffprobe seeks to it before reading.
+    pub fn start(start: Duration) -> Self {
+        ReadInterval {
+            start: Some(start),
+            end: None,
+        }
+    }
+
+    /// Interval beginning at the start of the input.
+    pub fn from_beginning() -> Self {
+        ReadInterval {
+            start: None,
+            end: None,
+        }
+    }
+
+    /// Stop reading at the given absolute position.
+    pub fn end(mut self, end: Duration) -> Self {
+        self.end = Some(IntervalEnd::At(end));
+        self
+    }
+
+    /// Stop reading after the given duration, relative to the start.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.end = Some(IntervalEnd::After(duration));
+        self
+    }
+
+    /// Stop reading after the given number of packets.
+    /// ffprobe counts packets rather than decoded frames, which is the same for most
+    /// video codecs.
+    pub fn frames(mut self, count: u64) -> Self {
+        self.end = Some(IntervalEnd::Packets(count));
+        self
+    }
+}
+
+impl Display for ReadInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", seconds(start))?;
+        }
+        match self.end {
+            Some(IntervalEnd::At(end)) => write!(f, "%{}", seconds(end)),
+            Some(IntervalEnd::After(duration)) => write!(f, "%+{}", seconds(duration)),
+            Some(IntervalEnd::Packets(count)) => write!(f, "%+#{}", count),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Format a duration as seconds, without a fractional part for whole seconds.
+fn seconds(duration: Duration) -> String {
+    match duration.subsec_nanos() {
+        0 => duration.as_secs().to_string(),
+        nanos => format!("{}.{:09}", duration.as_secs(), nanos)
+            .trim_end_matches('0')
+            .to_string(),
+    }
+}
+
+/// Join intervals into the comma separated `-read_intervals` argument.
+pub(crate) fn read_intervals_arg(intervals: &[ReadInterval]) -> String {
+    intervals
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,138 @@
+use crate::{
+    audio_stream::AudioStream,
+    side_data::{self, SideData},
+    streams::{Stream, StreamKinds},
+    tags::Tags,
+};
+
+/// Loudness normalization values of an audio stream, see [`AudioStream::replay_gain`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ReplayGain {
+    /// Gain to apply in dB for playback of the track on its own, eg. `-6.54`.
+    pub track_gain: Option<f64>,
+    /// Highest sample of the track, 1.0 being full scale.
+    pub track_peak: Option<f64>,
+    /// Gain to apply in dB for playback in the context of its album.
+    pub album_gain: Option<f64>,
+    /// Highest sample of the album.
+    pub album_peak: Option<f64>,
+}
+
+impl ReplayGain {
+    /// Values of the `REPLAYGAIN_*` tags, eg. `REPLAYGAIN_TRACK_GAIN=-6.54 dB`, with the
+    /// `R128_*_GAIN` tags of Opus as fallback. `None` if there are none.
+    pub fn from_tags(tags: &Tags) -> Option<Self> {
+        let gain = |kind: &str| {
+            tags.get(&format!("REPLAYGAIN_{kind}_GAIN"))
+                .and_then(parse_value)
+                .or_else(|| {
+                    // Q7.8 relative to -23 LUFS, ReplayGain 2.0 targets -18 LUFS.
+                    let r128: i16 = tags
+                        .get(&format!("R128_{kind}_GAIN"))?
+                        .trim()
+                        .parse()
+                        .ok()?;
+                    Some(r128 as f64 / 256. + 5.)
+                })
+        };
+        let peak = |kind: &str| {
+            tags.get(&format!("REPLAYGAIN_{kind}_PEAK"))
+                .and_then(parse_value)
+        };
+        Self {
+            track_gain: gain("TRACK"),
+            track_peak: peak("TRACK"),
+            album_gain: gain("ALBUM"),
+            album_peak: peak("ALBUM"),
+        }
+        .non_empty()
+    }
+
+    /// Fill the values missing in `self` from `other`.
+    fn or(self, other: Self) -> Self {
+        Self {
+            track_gain: self.track_gain.or(other.track_gain),
+            track_peak: self.track_peak.or(other.track_peak),
+            album_gain: self.album_gain.or(other.album_gain),
+            album_peak: self.album_peak.or(other.album_peak),
+        }
+    }
+
+    fn non_empty(self) -> Option<Self> {
+        (self != Self::default()).then_some(self)
+    }
+}
+
+/// `-6.54 dB` or `0.988553`
+fn parse_value(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let value = ["dB", "db", "DB"]
+        .iter()
+        .find_map(|unit| value.strip_suffix(unit))
+        .unwrap_or(value);
+    value.trim().parse().ok().filter(|v: &f64| v.is_finite())
+}
+
+impl From<&side_data::ReplayGain> for ReplayGain {
+    /// Gains of `i32::MIN` and peaks of 0 mean unknown.
+    fn from(data: &side_data::ReplayGain) -> Self {
+        let gain = |v: Option<i64>| {
+            v.filter(|&v| v != i32::MIN as i64)
+                .map(|v| v as f64 / 100_000.)
+        };
+        let peak = |v: Option<i64>| v.filter(|&v| v != 0).map(|v| v as f64 / 100_000.);
+        Self {
+            track_gain: gain(data.track_gain),
+            track_peak: peak(data.track_peak),
+            album_gain: gain(data.album_gain),
+            album_peak: peak(data.album_peak),
+        }
+    }
+}
+
+impl AudioStream {
+    /// Replay gain from the stream's tags, see [`Stream::replay_gain`] to include side data.
+    pub fn replay_gain(&self) -> Option<ReplayGain> {
+        ReplayGain::from_tags(&self.tags.as_ref()?.all)
+    }
+}
+
+impl Stream {
+    /// Replay gain of an audio stream from its Replay Gain side data, which demuxers fill
+    /// from container tags (eg. ID3 or Vorbis comments), and its stream tags.
+    pub fn replay_gain(&self) -> Option<ReplayGain> {
+        let StreamKinds::Audio(audio) = &self.stream else {
+            return None;
+        };
+        let side_data = self
+            .side_data_list
+            .iter()
+            .find_map(|side_data| match side_data {
+                SideData::ReplayGain(data) => Some(ReplayGain::from(data)),
+                _ => None,
+            })
+            .unwrap_or_default();
+        side_data
+            .or(audio.replay_gain().unwrap_or_default())
+            .non_empty()
+    }
+}
+
+#[cfg(feature = "format")]
+impl crate::FfProbe {
+    /// Replay gain of the first audio stream, falling back to the format tags, where MP3
+    /// and FLAC files keep them.
+    pub fn replay_gain(&self) -> Option<ReplayGain> {
+        let stream = self
+            .streams
+            .iter()
+            .find(|s| matches!(s.stream, StreamKinds::Audio(_)))?;
+        let format = ReplayGain::from_tags(self.format.all_tags()).unwrap_or_default();
+        stream
+            .replay_gain()
+            .unwrap_or_default()
+            .or(format)
+            .non_empty()
+    }
+}
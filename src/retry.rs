@@ -0,0 +1,156 @@
+use std::{fmt::Debug, io::ErrorKind, path::Path, sync::Arc, time::Duration};
+
+use crate::error::FfProbeError;
+
+/// When and how often to retry a failed probe, see [`Config::retry`](crate::Config::retry).
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// let config = ffprobe::Config::new().retry(
+///     ffprobe::RetryPolicy::new(4)
+///         .backoff(Duration::from_millis(200))
+///         .max_backoff(Duration::from_secs(2)),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    retry_if: Option<RetryIf>,
+}
+
+type RetryIf = Arc<dyn Fn(&FfProbeError) -> bool + Send + Sync>;
+
+/// Log lines of failures that go away on their own, eg. `Server returned 503 Service Unavailable`.
+const TRANSIENT_MARKERS: [&str; 7] = [
+    "server returned 5",
+    "connection reset",
+    "connection refused",
+    "connection timed out",
+    "timed out",
+    "temporary failure in name resolution",
+    "broken pipe",
+];
+
+/// Log lines of failures that won't change when retrying.
+const PERMANENT_MARKERS: [&str; 6] = [
+    "server returned 4",
+    "no such file or directory",
+    "permission denied",
+    "invalid data found",
+    "protocol not found",
+    "invalid argument",
+];
+
+impl RetryPolicy {
+    /// Try up to `max_attempts` times in total, waiting 500 ms before the first retry and
+    /// doubling the wait for every further one, up to 10 s.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            retry_if: None,
+        }
+    }
+
+    /// Wait before the first retry, doubled for every further one.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Upper bound for the wait between attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Only retry errors `retry_if` returns true for, replacing [`RetryPolicy::is_transient`].
+    pub fn retry_if(
+        mut self,
+        retry_if: impl Fn(&FfProbeError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_if = Some(Arc::new(retry_if));
+        self
+    }
+
+    /// Default classification: I/O errors like timeouts and resets, and ffprobe failures
+    /// logging a transient error such as an HTTP 5xx. Failures without a recognized log
+    /// are retried for network inputs (`scheme://...`), but not for local files. Empty
+    /// inputs and unparsable output are never retried.
+    ///
+    /// [`crate::ffprobe_config`] runs ffprobe with `-v quiet`, so usually there is no log
+    /// and the input decides.
+    pub fn is_transient(error: &FfProbeError, input: &Path) -> bool {
+        match error {
+            FfProbeError::Io(err) => matches!(
+                err.kind(),
+                ErrorKind::TimedOut
+                    | ErrorKind::Interrupted
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::ConnectionRefused
+                    | ErrorKind::BrokenPipe
+            ),
+            FfProbeError::Status(out) => {
+                let log = String::from_utf8_lossy(&out.stderr).to_lowercase();
+                if PERMANENT_MARKERS.iter().any(|m| log.contains(m)) {
+                    false
+                } else if TRANSIENT_MARKERS.iter().any(|m| log.contains(m)) {
+                    true
+                } else {
+                    is_url(input)
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Wait before the next attempt after `attempt` (starting at 1) failed with `error`,
+    /// `None` to give up.
+    pub(crate) fn delay(
+        &self,
+        attempt: u32,
+        input: &Path,
+        error: &FfProbeError,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let retry = match &self.retry_if {
+            Some(retry_if) => retry_if(error),
+            None => Self::is_transient(error, input),
+        };
+        let factor = 2u32.saturating_pow(attempt - 1);
+        retry.then(|| self.backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+/// Input with a protocol, eg. `https://` or `rtsp://`, other than `file:`.
+fn is_url(input: &Path) -> bool {
+    input
+        .to_str()
+        .and_then(|s| s.split_once("://"))
+        .is_some_and(|(scheme, _)| {
+            !scheme.is_empty()
+                && scheme != "file"
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        })
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("retry_if", &self.retry_if.as_ref().map(|_| "custom"))
+            .finish()
+    }
+}
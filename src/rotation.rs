@@ -0,0 +1,165 @@
+use std::fmt::Display;
+
+use crate::{
+    side_data::{DisplayMatrix, SideData},
+    streams::{Stream, StreamKinds},
+    tags::Tags,
+};
+
+/// Orientation a video has to be shown in, see [`Stream::rotation`].
+///
+/// Phones record in the sensor orientation and store the rotation in the container,
+/// players that ignore it show portrait footage sideways.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Rotation {
+    /// Clockwise rotation to apply for display: 0, 90, 180 or 270.
+    pub degrees: u16,
+    /// Mirror horizontally, after rotating.
+    pub hflip: bool,
+    /// Mirror vertically, after rotating.
+    pub vflip: bool,
+}
+
+impl Rotation {
+    pub const NONE: Rotation = Rotation {
+        degrees: 0,
+        hflip: false,
+        vflip: false,
+    };
+
+    /// Clockwise rotation by `degrees`, rounded to the nearest multiple of 90.
+    pub fn clockwise(degrees: f64) -> Self {
+        let quarter = (degrees / 90.).round().rem_euclid(4.) as u16;
+        Rotation {
+            degrees: quarter * 90,
+            ..Self::NONE
+        }
+    }
+
+    /// Rotation of a display matrix, the 3x3 matrix of 16.16 fixed point values in the
+    /// order ffprobe prints it, as used by `av_display_rotation_get`. Mirroring matrices
+    /// have a negative determinant, a vertical flip is reported as such rather than as a
+    /// horizontal flip rotated by 180 degrees.
+    pub fn from_display_matrix(matrix: &[i32; 9]) -> Self {
+        let (a, b, c, d) = (
+            matrix[0] as f64,
+            matrix[1] as f64,
+            matrix[3] as f64,
+            matrix[4] as f64,
+        );
+        let hflip = a * d - b * c < 0.;
+        let (a, c) = if hflip { (-a, -c) } else { (a, c) };
+        let scale_0 = a.hypot(c);
+        let scale_1 = b.hypot(d);
+        if scale_0 == 0. || scale_1 == 0. {
+            return Self::NONE;
+        }
+        let mut rotation = Self::clockwise((b / scale_1).atan2(a / scale_0).to_degrees());
+        if hflip {
+            if rotation.degrees >= 180 {
+                rotation.degrees -= 180;
+                rotation.vflip = true;
+            } else {
+                rotation.hflip = true;
+            }
+        }
+        rotation
+    }
+
+    /// Orientation of the EXIF `Orientation` tag, `None` for values other than 1 to 8.
+    pub fn from_exif_orientation(orientation: u8) -> Option<Self> {
+        let (degrees, hflip, vflip) = match orientation {
+            1 => (0, false, false),
+            2 => (0, true, false),
+            3 => (180, false, false),
+            4 => (0, false, true),
+            // Mirrored then rotated by 270 degrees, ie. rotated by 90 degrees then mirrored.
+            5 => (90, true, false),
+            6 => (90, false, false),
+            // Mirrored then rotated by 90 degrees.
+            7 => (270, true, false),
+            8 => (270, false, false),
+            _ => return None,
+        };
+        Some(Rotation {
+            degrees,
+            hflip,
+            vflip,
+        })
+    }
+
+    /// Whether width and height trade places, ie. a rotation by 90 or 270 degrees.
+    pub fn swaps_dimensions(&self) -> bool {
+        self.degrees % 180 == 90
+    }
+
+    /// `(width, height)` after the rotation.
+    pub fn apply(&self, (width, height): (i64, i64)) -> (i64, i64) {
+        if self.swaps_dimensions() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// The legacy `rotate` tag, written by older muxers, eg. `90`.
+    fn from_tags(tags: &Tags) -> Option<Self> {
+        let degrees: f64 = tags.get("rotate")?.trim().parse().ok()?;
+        Some(Self::clockwise(degrees))
+    }
+}
+
+impl Display for Rotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}°", self.degrees)?;
+        if self.hflip {
+            f.write_str(" hflip")?;
+        }
+        if self.vflip {
+            f.write_str(" vflip")?;
+        }
+        Ok(())
+    }
+}
+
+impl DisplayMatrix {
+    fn to_rotation(&self) -> Option<Rotation> {
+        if let Some(matrix) = self.values() {
+            return Some(Rotation::from_display_matrix(&matrix));
+        }
+        // ffprobe's rotation is counterclockwise.
+        self.rotation
+            .as_ref()
+            .and_then(|r| r.as_f64())
+            .map(|r| Rotation::clockwise(-r))
+    }
+}
+
+impl Stream {
+    /// Display rotation of video streams, from the Display Matrix side data or the legacy
+    /// `rotate` tag. [`Rotation::NONE`] for unrotated videos and other stream kinds.
+    pub fn rotation(&self) -> Rotation {
+        if !matches!(self.stream, StreamKinds::Video(_)) {
+            return Rotation::NONE;
+        }
+        self.side_data_list
+            .iter()
+            .find_map(|side_data| match side_data {
+                SideData::DisplayMatrix(matrix) => matrix.to_rotation(),
+                _ => None,
+            })
+            .or_else(|| Rotation::from_tags(self.all_tags()))
+            .unwrap_or_default()
+    }
+
+    /// Display dimensions of a video stream with the sample aspect ratio and
+    /// [`Stream::rotation`] applied, ie. the size the video appears in players.
+    /// eg. 1920x1080 phone footage rotated by 90 degrees => 1080x1920.
+    pub fn display_dimensions_after_rotation(&self) -> Option<(i64, i64)> {
+        match &self.stream {
+            StreamKinds::Video(video) => Some(self.rotation().apply(video.display_dimensions())),
+            _ => None,
+        }
+    }
+}
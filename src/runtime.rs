@@ -0,0 +1,113 @@
+//! Async runtimes running ffprobe for [`ffprobe_async`](crate::ffprobe_async).
+//!
+//! The `tokio` feature (or `async`, its old name) selects [`Tokio`], the `async-std`
+//! feature [`AsyncStd`], which also runs on smol. With both enabled [`DefaultSpawner`]
+//! is [`Tokio`], pick the other one with
+//! [`ffprobe_async_with`](crate::ffprobe_async_with).
+
+use std::{
+    future::Future,
+    io,
+    process::{Command, Output},
+    time::{Duration, Instant},
+};
+
+use crate::{Config, ProbeCommand};
+
+/// Process spawning and timers of an async runtime.
+pub trait Spawner {
+    /// Run `cmd` to completion like [`Command::output`], without blocking the runtime.
+    fn output(cmd: Command) -> impl Future<Output = io::Result<Output>> + Send;
+
+    /// Wait for `duration`, eg. between [`Config::retry`](crate::Config::retry) attempts.
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send;
+
+    /// Run the blocking `f` without blocking the runtime, eg. parsing the output and the
+    /// extra runs of [`Config::hash`](crate::Config::hash). Runs it in place by default.
+    fn unblock<T, F>(f: F) -> impl Future<Output = T> + Send
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        async move { f() }
+    }
+}
+
+/// [`Spawner`] of the tokio runtime, through `tokio::process`.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tokio;
+
+#[cfg(feature = "tokio")]
+impl Spawner for Tokio {
+    async fn output(cmd: Command) -> io::Result<Output> {
+        tokio::process::Command::from(cmd).output().await
+    }
+
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+
+    async fn unblock<T, F>(f: F) -> T
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        match tokio::task::spawn_blocking(f).await {
+            Ok(value) => value,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+}
+
+/// [`Spawner`] of async-std and smol, through `async-process` and `async-io`.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStd;
+
+#[cfg(feature = "async-std")]
+impl Spawner for AsyncStd {
+    async fn output(cmd: Command) -> io::Result<Output> {
+        async_process::Command::from(cmd).output().await
+    }
+
+    async fn sleep(duration: Duration) {
+        async_io::Timer::after(duration).await;
+    }
+
+    fn unblock<T, F>(f: F) -> impl Future<Output = T> + Send
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        blocking::unblock(f)
+    }
+}
+
+/// [`Spawner`] of [`ffprobe_async`](crate::ffprobe_async).
+#[cfg(feature = "tokio")]
+pub type DefaultSpawner = Tokio;
+
+/// [`Spawner`] of [`ffprobe_async`](crate::ffprobe_async).
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+pub type DefaultSpawner = AsyncStd;
+
+/// Run `command` on the runtime of `S`, like [`Config::output`] does for the blocking
+/// functions.
+pub(crate) async fn output<S: Spawner>(
+    config: &Config,
+    command: &ProbeCommand,
+) -> io::Result<Output> {
+    // Limited and reported runs are read by the blocking implementation.
+    if config.max_output_bytes.is_some() || config.progress.is_some() {
+        let (config, command) = (config.clone(), command.clone());
+        return S::unblock(move || config.output(&mut command.build_local())).await;
+    }
+    if let Some(out) = config.canned_output(&command.build_local()) {
+        return out;
+    }
+    let started = Instant::now();
+    let out = S::output(command.build()).await;
+    config.report_spawn(&command.build(), started, out.as_ref());
+    out
+}
@@ -0,0 +1,166 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Audio sample format, as reported in `sample_fmt`.
+///
+/// Planar formats store each channel in a separate plane, packed (interleaved)
+/// formats store the samples of all channels next to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SampleFormat {
+    /// unsigned 8 bits
+    U8,
+    /// signed 16 bits
+    S16,
+    /// signed 32 bits
+    S32,
+    /// signed 64 bits
+    S64,
+    /// float
+    Flt,
+    /// double
+    Dbl,
+    /// unsigned 8 bits, planar
+    U8p,
+    /// signed 16 bits, planar
+    S16p,
+    /// signed 32 bits, planar
+    S32p,
+    /// signed 64 bits, planar
+    S64p,
+    /// float, planar
+    Fltp,
+    /// double, planar
+    Dblp,
+}
+
+impl SampleFormat {
+    /// ffmpeg name of the format, eg. `fltp`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SampleFormat::U8 => "u8",
+            SampleFormat::S16 => "s16",
+            SampleFormat::S32 => "s32",
+            SampleFormat::S64 => "s64",
+            SampleFormat::Flt => "flt",
+            SampleFormat::Dbl => "dbl",
+            SampleFormat::U8p => "u8p",
+            SampleFormat::S16p => "s16p",
+            SampleFormat::S32p => "s32p",
+            SampleFormat::S64p => "s64p",
+            SampleFormat::Fltp => "fltp",
+            SampleFormat::Dblp => "dblp",
+        }
+    }
+
+    /// Whether channels are stored in separate planes.
+    pub fn is_planar(&self) -> bool {
+        matches!(
+            self,
+            SampleFormat::U8p
+                | SampleFormat::S16p
+                | SampleFormat::S32p
+                | SampleFormat::S64p
+                | SampleFormat::Fltp
+                | SampleFormat::Dblp
+        )
+    }
+
+    /// Size of a single sample of one channel in bytes.
+    pub fn bytes_per_sample(&self) -> usize {
+        match self.packed() {
+            SampleFormat::U8 => 1,
+            SampleFormat::S16 => 2,
+            SampleFormat::S32 | SampleFormat::Flt => 4,
+            _ => 8,
+        }
+    }
+
+    /// Size of a single sample of one channel in bits, eg. 32 for `fltp`. Samples decoded
+    /// from a 24 bit source are stored as `s32`, see [`crate::AudioStream::bit_depth`]
+    /// for the precision of the source.
+    pub fn bits_per_sample(&self) -> u32 {
+        self.bytes_per_sample() as u32 * 8
+    }
+
+    /// Whether samples are floating point, `flt(p)` and `dbl(p)`, rather than integers.
+    pub fn is_float(&self) -> bool {
+        matches!(self.packed(), SampleFormat::Flt | SampleFormat::Dbl)
+    }
+
+    /// Interleaved equivalent of this format, eg. `fltp` => `flt`.
+    pub fn packed(&self) -> SampleFormat {
+        match self {
+            SampleFormat::U8p => SampleFormat::U8,
+            SampleFormat::S16p => SampleFormat::S16,
+            SampleFormat::S32p => SampleFormat::S32,
+            SampleFormat::S64p => SampleFormat::S64,
+            SampleFormat::Fltp => SampleFormat::Flt,
+            SampleFormat::Dblp => SampleFormat::Dbl,
+            packed => *packed,
+        }
+    }
+
+    /// Planar equivalent of this format, eg. `s16` => `s16p`.
+    pub fn planar(&self) -> SampleFormat {
+        match self {
+            SampleFormat::U8 => SampleFormat::U8p,
+            SampleFormat::S16 => SampleFormat::S16p,
+            SampleFormat::S32 => SampleFormat::S32p,
+            SampleFormat::S64 => SampleFormat::S64p,
+            SampleFormat::Flt => SampleFormat::Fltp,
+            SampleFormat::Dbl => SampleFormat::Dblp,
+            planar => *planar,
+        }
+    }
+}
+
+impl Display for SampleFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SampleFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "u8" => SampleFormat::U8,
+            "s16" => SampleFormat::S16,
+            "s32" => SampleFormat::S32,
+            "s64" => SampleFormat::S64,
+            "flt" => SampleFormat::Flt,
+            "dbl" => SampleFormat::Dbl,
+            "u8p" => SampleFormat::U8p,
+            "s16p" => SampleFormat::S16p,
+            "s32p" => SampleFormat::S32p,
+            "s64p" => SampleFormat::S64p,
+            "fltp" => SampleFormat::Fltp,
+            "dblp" => SampleFormat::Dblp,
+            other => return Err(format!("Unknown sample format: {other}")),
+        })
+    }
+}
+
+impl Serialize for SampleFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SampleFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// `sample_fmt` of a stream, `None` for `unknown`, which ffprobe prints for streams it has
+/// no decoder for, and formats unknown to this crate.
+pub(crate) fn option<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<SampleFormat>, D::Error> {
+    let s = Option::<String>::deserialize(deserializer)?;
+    Ok(s.and_then(|s| s.parse().ok()))
+}
@@ -0,0 +1,235 @@
+use std::{
+    ffi::OsStr,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use crate::{error::FfProbeError, ffprobe_config, Config, FfProbe};
+
+/// Probe every media file in a directory, eg. for a library indexer.
+///
+/// ```rust,no_run
+/// use ffprobe::{Config, Scanner};
+///
+/// let scan = Scanner::new(Config::new())
+///     .extensions(["mkv", "mp4"])
+///     .recursive(true)
+///     .progress(|p| eprintln!("{}/{}", p.done, p.total))
+///     .scan("/media/movies");
+/// for (path, probe) in scan {
+///     match probe {
+///         Ok(probe) => println!("{}: {} streams", path.display(), probe.streams.len()),
+///         Err(err) => eprintln!("{}: {}", path.display(), err.inner()),
+///     }
+/// }
+/// ```
+pub struct Scanner {
+    config: Config,
+    extensions: Vec<String>,
+    recursive: bool,
+    hidden: bool,
+    parallelism: usize,
+    filter: Option<FilterFn>,
+    progress: Option<ProgressFn>,
+}
+
+type FilterFn = Box<dyn Fn(&Path) -> bool + Send>;
+type ProgressFn = Box<dyn FnMut(&ScanProgress) + Send>;
+
+/// Progress of a [`Scan`], passed to [`Scanner::progress`] after every probed file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ScanProgress {
+    /// Files probed so far, including failed ones.
+    pub done: usize,
+    /// Files found in the directory.
+    pub total: usize,
+    pub failed: usize,
+}
+
+impl Scanner {
+    /// Scanner probing with `config`. By default all files of the directory are probed,
+    /// without subdirectories and hidden files, on as many threads as there are CPUs.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            extensions: Vec::new(),
+            recursive: false,
+            hidden: false,
+            parallelism: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            filter: None,
+            progress: None,
+        }
+    }
+
+    /// Only probe files with one of these extensions, ignoring case, eg. `["mkv", "mp4"]`.
+    /// Empty to probe all files.
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.extensions = extensions
+            .into_iter()
+            .map(|e| e.as_ref().trim_start_matches('.').to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Descend into subdirectories. Symlinked directories aren't followed.
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Include files and directories starting with a dot.
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Number of ffprobe processes running at once, at least 1.
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Only probe the files `filter` returns true for, eg. to skip files that are already
+    /// indexed. Runs after the extension check, while listing the directory.
+    pub fn filter(mut self, filter: impl Fn(&Path) -> bool + Send + 'static) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Called on the thread iterating the [`Scan`], after every probed file.
+    pub fn progress(mut self, progress: impl FnMut(&ScanProgress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// List the files in `dir` and start probing them.
+    ///
+    /// Directories that can't be read are yielded with their error, so one unreadable
+    /// folder doesn't hide the rest of the library.
+    pub fn scan(self, dir: impl AsRef<Path>) -> Scan {
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        self.walk(dir.as_ref(), &mut files, &mut errors);
+        files.sort();
+
+        let total = files.len() + errors.len();
+        let queue = Arc::new(Mutex::new(files.into_iter()));
+        let (sender, results) = mpsc::channel();
+        for _ in 0..self.parallelism.min(total) {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            let config = self.config.clone();
+            thread::spawn(move || {
+                while let Some(path) = queue.lock().ok().and_then(|mut q| q.next()) {
+                    let probe = ffprobe_config(config.clone(), &path);
+                    if sender.send((path, probe)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Scan {
+            errors,
+            results,
+            progress: self.progress,
+            state: ScanProgress {
+                done: 0,
+                total,
+                failed: 0,
+            },
+        }
+    }
+
+    fn walk(
+        &self,
+        dir: &Path,
+        files: &mut Vec<PathBuf>,
+        errors: &mut Vec<(PathBuf, Result<FfProbe, FfProbeError>)>,
+    ) {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) => return errors.push((dir.to_path_buf(), Err(FfProbeError::Io(err)))),
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    errors.push((dir.to_path_buf(), Err(FfProbeError::Io(err))));
+                    continue;
+                }
+            };
+            let path = entry.path();
+            if !self.hidden && entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if self.recursive {
+                    self.walk(&path, files, errors);
+                }
+            } else if path.is_file()
+                && self.matches(path.extension())
+                && self.filter.as_ref().is_none_or(|f| f(&path))
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    fn matches(&self, extension: Option<&OsStr>) -> bool {
+        self.extensions.is_empty()
+            || extension
+                .and_then(OsStr::to_str)
+                .is_some_and(|e| self.extensions.iter().any(|x| x.eq_ignore_ascii_case(e)))
+    }
+}
+
+/// Results of [`Scanner::scan`], in the order the probes finish.
+///
+/// Dropping the iterator stops the scan after the probes already running.
+pub struct Scan {
+    errors: Vec<(PathBuf, Result<FfProbe, FfProbeError>)>,
+    results: mpsc::Receiver<(PathBuf, Result<FfProbe, FfProbeError>)>,
+    progress: Option<ProgressFn>,
+    state: ScanProgress,
+}
+
+impl Scan {
+    pub fn progress(&self) -> ScanProgress {
+        self.state
+    }
+}
+
+impl Iterator for Scan {
+    type Item = (PathBuf, Result<FfProbe, FfProbeError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = match self.errors.pop() {
+            Some(item) => item,
+            None => self.results.recv().ok()?,
+        };
+        self.state.done += 1;
+        if item.1.is_err() {
+            self.state.failed += 1;
+        }
+        if let Some(progress) = &mut self.progress {
+            progress(&self.state);
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.state.total - self.state.done;
+        (left, Some(left))
+    }
+}
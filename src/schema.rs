@@ -0,0 +1,68 @@
+//! [`JsonSchema`] of the models with their own `Serialize`, see the `schemars` feature.
+//!
+//! ```rust
+//! let schema = schemars::schema_for!(ffprobe::FfProbe);
+//! println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+//! ```
+
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+#[cfg(any(feature = "streams", feature = "format"))]
+use crate::CreationTime;
+use crate::Ratio;
+#[cfg(feature = "streams")]
+use crate::{
+    AttachmentStream, AudioStream, ChannelLayout, DataStream, SampleFormat, StreamKinds,
+    SubtitleStream, VideoStream,
+};
+
+/// Types serialized as their `Display` string.
+macro_rules! string_schemas {
+    ($($(#[$cfg:meta])* $ty:ident => $description:literal,)*) => {
+        $($(#[$cfg])*
+        impl JsonSchema for $ty {
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($ty).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "description": $description,
+                })
+            }
+        })*
+    };
+}
+
+string_schemas! {
+    Ratio => "Ratio of two integers, eg. `16:9`.",
+    #[cfg(feature = "streams")]
+    ChannelLayout => "Channel layout as printed by ffprobe, eg. `5.1(side)` or `FL+FR+LFE`.",
+    #[cfg(feature = "streams")]
+    SampleFormat => "Audio sample format, eg. `fltp`.",
+    #[cfg(any(feature = "streams", feature = "format"))]
+    CreationTime => "RFC 3339 timestamp, or the tag as written if it isn't one.",
+}
+
+/// The fields of the stream of its `codec_type`, flattened into [`Stream`](crate::Stream).
+#[cfg(feature = "streams")]
+impl JsonSchema for StreamKinds {
+    fn schema_name() -> Cow<'static, str> {
+        "StreamKinds".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "anyOf": [
+                generator.subschema_for::<AudioStream>(),
+                generator.subschema_for::<VideoStream>(),
+                generator.subschema_for::<SubtitleStream>(),
+                generator.subschema_for::<AttachmentStream>(),
+                generator.subschema_for::<DataStream>(),
+            ]
+        })
+    }
+}
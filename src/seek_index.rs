@@ -0,0 +1,160 @@
+use std::{
+    fs::File,
+    io::{BufReader, Seek, SeekFrom},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    ebml::{self, children, float, read_data, read_element, uint},
+    error::FfProbeError,
+};
+
+/// Seek index (cues) information of a Matroska/WebM file, see [`seek_index_info`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeekIndexInfo {
+    /// Whether the segment has a SeekHead.
+    pub has_seek_head: bool,
+    /// Whether the SeekHead references the Cues element.
+    pub seek_head_references_cues: bool,
+    /// Whether a Cues element was found.
+    pub has_cues: bool,
+    /// Byte offset of the Cues element.
+    pub cues_offset: Option<u64>,
+    /// Whether the Cues are placed before the first Cluster.
+    /// Players can then seek without an additional range request to the end of the file.
+    pub cues_before_clusters: bool,
+    /// Number of cue points.
+    pub cue_points: u64,
+    /// Largest distance between two consecutive cue points (or the last cue point and the end).
+    pub max_cue_gap: Option<Duration>,
+    /// Segment duration from the Info element.
+    pub duration: Option<Duration>,
+}
+
+impl SeekIndexInfo {
+    /// Average number of cue points per minute of content.
+    pub fn cues_per_minute(&self) -> Option<f64> {
+        let duration = self.duration.filter(|d| !d.is_zero())?;
+        Some(self.cue_points as f64 / (duration.as_secs_f64() / 60.))
+    }
+
+    /// Whether browsers can seek in the file without downloading it entirely:
+    /// cues exist and can be located without scanning all clusters.
+    pub fn is_web_seekable(&self) -> bool {
+        self.has_cues
+            && self.cue_points > 0
+            && (self.cues_before_clusters || self.seek_head_references_cues)
+    }
+}
+
+/// Inspect the seek index of a Matroska/WebM file.
+///
+/// Only the element headers are read, clusters are skipped.
+/// Files with clusters of unknown size (live recordings) are only scanned up
+/// to the first cluster, cues are then located through the SeekHead.
+pub fn seek_index_info(path: impl AsRef<Path>) -> Result<SeekIndexInfo, FfProbeError> {
+    let (mut reader, segment_start, segment_end) = ebml::open_segment(path.as_ref())?;
+    let len = reader.seek(SeekFrom::End(0)).map_err(FfProbeError::Io)?;
+
+    let mut info = SeekIndexInfo {
+        has_seek_head: false,
+        seek_head_references_cues: false,
+        has_cues: false,
+        cues_offset: None,
+        cues_before_clusters: false,
+        cue_points: 0,
+        max_cue_gap: None,
+        duration: None,
+    };
+    let mut seek_head_cues = None;
+    let mut first_cluster = None;
+    let mut cue_times = Vec::new();
+    let mut timestamp_scale = 1_000_000;
+
+    let mut read_cues = |reader: &mut BufReader<File>, element: &ebml::Element| {
+        let data = read_data(reader, element)?;
+        for (id, point) in children(&data) {
+            if id == ebml::CUE_POINT {
+                if let Some((_, time)) = children(point).find(|(id, _)| *id == ebml::CUE_TIME) {
+                    cue_times.push(uint(time));
+                }
+            }
+        }
+        Ok::<_, FfProbeError>(())
+    };
+
+    let mut offset = segment_start;
+    while offset < segment_end {
+        reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(FfProbeError::Io)?;
+        let element = read_element(&mut reader)?;
+        match element.id {
+            ebml::SEEK_HEAD => {
+                info.has_seek_head = true;
+                let data = read_data(&mut reader, &element)?;
+                let cues = ebml::seek_entries(&data).find(|(id, _)| *id == ebml::CUES);
+                if let Some((_, position)) = cues {
+                    info.seek_head_references_cues = true;
+                    seek_head_cues = Some(segment_start.saturating_add(position));
+                }
+            }
+            ebml::INFO => {
+                let data = read_data(&mut reader, &element)?;
+                let mut duration = None;
+                for (id, value) in children(&data) {
+                    match id {
+                        ebml::TIMESTAMP_SCALE => timestamp_scale = uint(value),
+                        ebml::DURATION => duration = float(value),
+                        _ => {}
+                    }
+                }
+                info.duration = duration
+                    .filter(|d| d.is_finite() && *d >= 0.)
+                    .map(|d| Duration::from_nanos((d * timestamp_scale as f64) as u64));
+            }
+            ebml::CUES => {
+                info.has_cues = true;
+                info.cues_offset = Some(offset);
+                info.cues_before_clusters = first_cluster.is_none();
+                read_cues(&mut reader, &element)?;
+            }
+            ebml::CLUSTER => {
+                first_cluster.get_or_insert(offset);
+            }
+            _ => {}
+        }
+        match element.size {
+            Some(size) => offset = element.data_offset.saturating_add(size),
+            // Can't skip an element of unknown size.
+            None => break,
+        }
+    }
+
+    if !info.has_cues {
+        if let Some(position) = seek_head_cues.filter(|p| *p < len) {
+            reader
+                .seek(SeekFrom::Start(position))
+                .map_err(FfProbeError::Io)?;
+            let element = read_element(&mut reader)?;
+            if element.id == ebml::CUES {
+                info.has_cues = true;
+                info.cues_offset = Some(position);
+                info.cues_before_clusters = first_cluster.is_none_or(|c| position < c);
+                read_cues(&mut reader, &element)?;
+            }
+        }
+    }
+
+    info.cue_points = cue_times.len() as u64;
+    cue_times.sort_unstable();
+    let to_duration = |ticks: u64| Duration::from_nanos(ticks.saturating_mul(timestamp_scale));
+    let mut max_gap = cue_times.windows(2).map(|w| to_duration(w[1] - w[0])).max();
+    if let (Some(last), Some(duration)) = (cue_times.last(), info.duration) {
+        max_gap = max_gap.max(Some(duration.saturating_sub(to_duration(*last))));
+    }
+    info.max_cue_gap = max_gap;
+
+    Ok(info)
+}
@@ -0,0 +1,395 @@
+//! Typed side data of streams, see [`SideData`].
+//!
+//! The fields of every type are the ones ffprobe prints for it, in ffprobe's units.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+
+use crate::{flexible_num, ratio::Ratio};
+
+/// Side data of a stream, by `side_data_type`. Types without a variant of their own, and
+/// entries that don't match the layout of their type, are kept as [`SideData::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SideData {
+    /// `Display Matrix`, see [`crate::Rotation`].
+    DisplayMatrix(DisplayMatrix),
+    /// `Stereo 3D`
+    Stereo3D(Stereo3D),
+    /// `Spherical Mapping`, 360° video.
+    SphericalMapping(SphericalMapping),
+    /// `Mastering display metadata`, HDR10 color volume (SMPTE ST 2086).
+    MasteringDisplay(MasteringDisplay),
+    /// `Content light level metadata`, HDR10 MaxCLL and MaxFALL.
+    ContentLightLevel(ContentLightLevel),
+    /// `CPB properties`, the decoder buffer parameters of the encoder.
+    CpbProperties(CpbProperties),
+    /// `Replay Gain`
+    ReplayGain(ReplayGain),
+    /// `Audio Service Type`
+    AudioServiceType(AudioServiceType),
+    /// `DOVI configuration record`, Dolby Vision.
+    DolbyVision(DolbyVision),
+    /// `GOP timecode` of MPEG-2 video, see [`crate::Timecode`].
+    GopTimecode(GopTimecode),
+    /// `SMPTE 12-1 timecode`, eg. from H.264 picture timing SEI, see [`crate::Timecode`].
+    SmpteTimecode(SmpteTimecode),
+    /// Any other side data, with the full entry as printed by ffprobe.
+    Unknown { side_data_type: String, raw: Value },
+}
+
+macro_rules! side_data_kinds {
+    ($($name:literal => $variant:ident,)*) => {
+        impl SideData {
+            /// Type as printed by ffprobe, eg. `Display Matrix`.
+            pub fn side_data_type(&self) -> &str {
+                match self {
+                    $(SideData::$variant(_) => $name,)*
+                    SideData::Unknown { side_data_type, .. } => side_data_type,
+                }
+            }
+
+            /// `None` for types without a variant.
+            fn from_entry(
+                side_data_type: &str,
+                fields: Map<String, Value>,
+            ) -> Option<serde_json::Result<Self>> {
+                let fields = Value::Object(fields);
+                match side_data_type {
+                    $($name => Some(serde_json::from_value(fields).map(SideData::$variant)),)*
+                    _ => None,
+                }
+            }
+
+            fn fields(&self) -> serde_json::Result<Value> {
+                match self {
+                    $(SideData::$variant(data) => serde_json::to_value(data),)*
+                    SideData::Unknown { raw, .. } => Ok(raw.clone()),
+                }
+            }
+        }
+
+        /// The fields of the type of its `side_data_type`, any other object for
+        /// [`SideData::Unknown`].
+        #[cfg(feature = "schemars")]
+        impl schemars::JsonSchema for SideData {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                "SideData".into()
+            }
+
+            fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                let variants = vec![
+                    $(schemars::json_schema!({
+                        "allOf": [
+                            generator.subschema_for::<$variant>(),
+                            {
+                                "properties": { "side_data_type": { "const": $name } },
+                                "required": ["side_data_type"],
+                            },
+                        ]
+                    }),)*
+                    schemars::json_schema!({
+                        "type": "object",
+                        "properties": { "side_data_type": { "type": "string" } },
+                    }),
+                ];
+                schemars::json_schema!({ "anyOf": variants })
+            }
+        }
+    };
+}
+
+side_data_kinds! {
+    "Display Matrix" => DisplayMatrix,
+    "Stereo 3D" => Stereo3D,
+    "Spherical Mapping" => SphericalMapping,
+    "Mastering display metadata" => MasteringDisplay,
+    "Content light level metadata" => ContentLightLevel,
+    "CPB properties" => CpbProperties,
+    "Replay Gain" => ReplayGain,
+    "Audio Service Type" => AudioServiceType,
+    "DOVI configuration record" => DolbyVision,
+    "GOP timecode" => GopTimecode,
+    "SMPTE 12-1 timecode" => SmpteTimecode,
+}
+
+impl<'de> Deserialize<'de> for SideData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Value::deserialize(deserializer)?;
+        let side_data_type = raw
+            .get("side_data_type")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let mut fields = match &raw {
+            Value::Object(fields) => fields.clone(),
+            _ => return Err(serde::de::Error::custom("side data is not an object")),
+        };
+        fields.remove("side_data_type");
+        match SideData::from_entry(&side_data_type, fields) {
+            Some(Ok(side_data)) => Ok(side_data),
+            #[cfg(feature = "__internal_deny_unknown_fields")]
+            Some(Err(err)) => Err(serde::de::Error::custom(err)),
+            _ => Ok(SideData::Unknown {
+                side_data_type,
+                raw,
+            }),
+        }
+    }
+}
+
+impl Serialize for SideData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut value = self.fields().map_err(serde::ser::Error::custom)?;
+        if let Value::Object(fields) = &mut value {
+            fields.insert(
+                "side_data_type".to_string(),
+                Value::String(self.side_data_type().to_string()),
+            );
+        }
+        value.serialize(serializer)
+    }
+}
+
+/// Display transformation of a video, see [`crate::Stream::rotation`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct DisplayMatrix {
+    /// The 3x3 matrix, one row per line with a row number:
+    ///
+    /// ```text
+    /// 00000000:            0       65536           0
+    /// 00000001:       -65536           0           0
+    /// 00000002:            0           0  1073741824
+    /// ```
+    #[serde(rename = "displaymatrix")]
+    pub matrix: Option<String>,
+    /// Counterclockwise rotation in degrees.
+    pub rotation: Option<Number>,
+}
+
+impl DisplayMatrix {
+    /// The 16.16 fixed point values of [`DisplayMatrix::matrix`], row by row.
+    pub fn values(&self) -> Option<[i32; 9]> {
+        let values: Vec<i32> = self
+            .matrix
+            .as_deref()?
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .flat_map(|(_, row)| row.split_whitespace())
+            .map(|v| v.parse().ok())
+            .collect::<Option<_>>()?;
+        values.try_into().ok()
+    }
+}
+
+/// Stereoscopic 3D packing of a video.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Stereo3D {
+    /// Packing, eg. `side by side`, `top and bottom` or `2D`.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    /// 1 if the views are swapped, ie. the right view comes first.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub inverted: Option<i64>,
+    /// Views in the stream, eg. `packed`, `left` or `right`.
+    pub view: Option<String>,
+    /// eg. `none`, `left` or `right`.
+    pub primary_eye: Option<String>,
+    /// Distance between the camera centers in micrometers.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub baseline: Option<i64>,
+    /// Rational, eg. `0/1`.
+    pub horizontal_disparity_adjustment: Option<String>,
+    /// Rational degrees, eg. `0/1`.
+    pub horizontal_field_of_view: Option<String>,
+}
+
+/// Projection of a 360° video.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SphericalMapping {
+    /// eg. `equirectangular`, `cubemap` or `tiled equirectangular`.
+    pub projection: Option<String>,
+    /// Pixels between the faces of a cubemap.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub padding: Option<i64>,
+    /// Cropping of a tiled equirectangular projection, 0.32 fixed point.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub bound_left: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub bound_top: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub bound_right: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub bound_bottom: Option<i64>,
+    /// Initial view orientation in degrees.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub yaw: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub pitch: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub roll: Option<i64>,
+}
+
+/// Color volume of the display the content was mastered on. Chromaticities are CIE 1931
+/// xy coordinates, luminances are in cd/m² (nits).
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct MasteringDisplay {
+    /// eg. `13250/50000`
+    pub red_x: Option<Ratio>,
+    pub red_y: Option<Ratio>,
+    pub green_x: Option<Ratio>,
+    pub green_y: Option<Ratio>,
+    pub blue_x: Option<Ratio>,
+    pub blue_y: Option<Ratio>,
+    pub white_point_x: Option<Ratio>,
+    pub white_point_y: Option<Ratio>,
+    /// eg. `50/10000`
+    pub min_luminance: Option<Ratio>,
+    /// eg. `10000000/10000`
+    pub max_luminance: Option<Ratio>,
+}
+
+impl MasteringDisplay {
+    /// Peak luminance in nits, eg. 1000.
+    pub fn max_luminance_nits(&self) -> Option<f64> {
+        self.max_luminance.as_ref()?.as_f64()
+    }
+
+    /// Black level in nits, eg. 0.005.
+    pub fn min_luminance_nits(&self) -> Option<f64> {
+        self.min_luminance.as_ref()?.as_f64()
+    }
+}
+
+/// Light levels of the content in nits.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct ContentLightLevel {
+    /// MaxCLL, the brightest pixel.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub max_content: Option<i64>,
+    /// MaxFALL, the brightest frame on average.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub max_average: Option<i64>,
+}
+
+/// Coded picture buffer parameters, bitrates are in bit/s and the buffer size in bits.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CpbProperties {
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub max_bitrate: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub min_bitrate: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub avg_bitrate: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub buffer_size: Option<i64>,
+    /// In 90 kHz ticks, `u64::MAX` if unknown.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub vbv_delay: Option<u64>,
+}
+
+/// Replay Gain values. Gains are in 1/100000 dB, peaks in 1/100000 of full scale.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct ReplayGain {
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub track_gain: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub track_peak: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub album_gain: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub album_peak: Option<i64>,
+}
+
+/// Audio service type of AC-3 and E-AC-3 audio.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct AudioServiceType {
+    /// eg. 0 for main audio, 1 for effects, 3 for visually impaired.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub service_type: Option<i64>,
+}
+
+/// Dolby Vision configuration record.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct DolbyVision {
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub dv_version_major: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub dv_version_minor: Option<i64>,
+    /// eg. 5, 7 or 8
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub dv_profile: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub dv_level: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub rpu_present_flag: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub el_present_flag: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub bl_present_flag: Option<i64>,
+    /// Base layer compatibility for profile 8, eg. 1 for HDR10, 4 for HLG.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub dv_bl_signal_compatibility_id: Option<i64>,
+}
+
+/// Timecode of the first picture of an MPEG-2 GOP, eg. `01:00:00:00`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GopTimecode {
+    pub timecode: Option<String>,
+}
+
+/// Up to three SMPTE ST 12-1 timecodes, eg. of the fields of a frame.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SmpteTimecode {
+    #[serde(default)]
+    pub timecodes: Vec<SmpteTimecodeValue>,
+}
+
+/// Entry of [`SmpteTimecode::timecodes`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SmpteTimecodeValue {
+    /// eg. `01:00:00;02`
+    pub value: String,
+}
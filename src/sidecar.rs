@@ -0,0 +1,176 @@
+//! External subtitle files next to a video, as media players and library managers
+//! pick them up.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use crate::{error::FfProbeError, ffprobe_config, language::Language, Config, Stream, StreamKinds};
+
+/// File extensions of sidecar subtitles. VobSub `.sub` files are probed through their
+/// `.idx` index.
+pub const SIDECAR_EXTENSIONS: [&str; 7] = ["srt", "ass", "ssa", "vtt", "sub", "idx", "sup"];
+
+/// Folders next to the video searched for subtitles, ignoring case.
+pub const SIDECAR_FOLDERS: [&str; 2] = ["subs", "subtitles"];
+
+/// Subtitle file found by [`find_subtitles`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SidecarSubtitle {
+    pub path: PathBuf,
+    /// From a language code or English name in the file name, eg. `Movie.en.srt`,
+    /// `Movie.pt-BR.srt` or `Subs/English.srt`.
+    pub language: Option<Language>,
+    /// `Movie.en.forced.srt`
+    pub forced: bool,
+    /// For the deaf and hard of hearing, `Movie.en.sdh.srt`, `.cc.` or `.hi.`.
+    pub sdh: bool,
+    /// `Movie.en.default.srt`
+    pub default: bool,
+    /// Remaining parts of the file name, eg. `Commentary` for `Movie.en.Commentary.srt`.
+    pub label: Option<String>,
+    /// The subtitle stream, `None` if ffprobe can't read the file.
+    pub stream: Option<Stream>,
+}
+
+/// Find and probe the subtitle files belonging to `video_path`, see
+/// [`find_subtitles_config`].
+pub fn find_subtitles(video_path: impl AsRef<Path>) -> Result<Vec<SidecarSubtitle>, FfProbeError> {
+    find_subtitles_config(&Config::new(), video_path)
+}
+
+/// Find the subtitle files belonging to `video_path` and probe each one with `config`:
+///
+/// - files in the same folder named after the video, eg. `Movie.srt` or
+///   `Movie.eng.forced.srt` for `Movie.mkv`
+/// - any subtitle file in a `Subs` folder next to the video, or in its subfolder named
+///   after the video, eg. `Subs/Movie/2_English.srt`
+///
+/// Results are sorted by path.
+pub fn find_subtitles_config(
+    config: &Config,
+    video_path: impl AsRef<Path>,
+) -> Result<Vec<SidecarSubtitle>, FfProbeError> {
+    let video_path = video_path.as_ref();
+    let Some(stem) = video_path.file_stem().and_then(OsStr::to_str) else {
+        return Ok(Vec::new());
+    };
+    let dir = match video_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut found = Vec::new();
+    for path in subtitle_files(dir)? {
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+        let rest = if name == stem {
+            String::new()
+        } else if let Some(rest) = name.strip_prefix(stem).and_then(|r| r.strip_prefix('.')) {
+            rest.to_string()
+        } else {
+            continue;
+        };
+        found.push((path, rest));
+    }
+    for entry in std::fs::read_dir(dir).map_err(FfProbeError::Io)?.flatten() {
+        let folder = entry.path();
+        let is_subs = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| SIDECAR_FOLDERS.iter().any(|f| f.eq_ignore_ascii_case(name)));
+        if !is_subs || !folder.is_dir() {
+            continue;
+        }
+        let per_video = folder.join(stem);
+        let folder = if per_video.is_dir() {
+            per_video
+        } else {
+            folder
+        };
+        for path in subtitle_files(&folder)? {
+            let name = path
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default()
+                .to_string();
+            found.push((path, name));
+        }
+    }
+
+    // VobSub: probe the index, which references the `.sub` file.
+    let has_index = |path: &Path| path.with_extension("idx").is_file();
+    found.retain(|(path, _)| !(has_extension(path, "sub") && has_index(path)));
+    found.sort();
+
+    Ok(found
+        .into_iter()
+        .map(|(path, name)| sidecar(config, path, &name))
+        .collect())
+}
+
+/// Files with one of the [`SIDECAR_EXTENSIONS`] in `dir`.
+fn subtitle_files(dir: &Path) -> Result<Vec<PathBuf>, FfProbeError> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(FfProbeError::Io)?.flatten() {
+        let path = entry.path();
+        if SIDECAR_EXTENSIONS.iter().any(|e| has_extension(&path, e)) && path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+/// Probe `path` and read the markers of the file `name`, eg. `en.forced` or
+/// `2_English`.
+fn sidecar(config: &Config, path: PathBuf, name: &str) -> SidecarSubtitle {
+    let mut subtitle = SidecarSubtitle {
+        stream: ffprobe_config(config.clone(), &path)
+            .ok()
+            .and_then(|probe| {
+                probe
+                    .streams
+                    .into_iter()
+                    .find(|s| matches!(s.stream, StreamKinds::Subtitle(_)))
+            }),
+        path,
+        language: None,
+        forced: false,
+        sdh: false,
+        default: false,
+        label: None,
+    };
+    let mut label = Vec::new();
+    // Numbered files of release `Subs` folders, eg. `2_English`.
+    let name = name.trim_start_matches(|c: char| c.is_ascii_digit() || c == '_');
+    for part in name.split('.').filter(|p| !p.is_empty()) {
+        match part.to_ascii_lowercase().as_str() {
+            "forced" | "foreign" => subtitle.forced = true,
+            "sdh" | "cc" => subtitle.sdh = true,
+            // Hindi unless the language came first, eg. `Movie.en.hi.srt`.
+            "hi" if subtitle.language.is_some() => subtitle.sdh = true,
+            "default" => subtitle.default = true,
+            _ if subtitle.language.is_none() => {
+                // Only known languages, `Movie.one.srt` isn't in a language `one`.
+                subtitle.language = Language::parse(part)
+                    .filter(|l| l.name().is_some())
+                    .or_else(|| Language::from_name(part));
+                if subtitle.language.is_none() {
+                    label.push(part);
+                }
+            }
+            _ => label.push(part),
+        }
+    }
+    subtitle.label = (!label.is_empty()).then(|| label.join(" "));
+    subtitle
+}
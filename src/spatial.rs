@@ -0,0 +1,261 @@
+use std::fmt::Display;
+
+use crate::{
+    audio_stream::AudioStream,
+    side_data::{SideData, SphericalMapping},
+    streams::{Stream, StreamKinds},
+    tags::Tags,
+    video_stream::VideoStream,
+};
+
+/// Projection of a 360° video, see [`Stream::projection`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Projection {
+    pub kind: ProjectionKind,
+    /// Initial view orientation in degrees: heading, positive to the right.
+    pub yaw: f64,
+    /// Positive looking up.
+    pub pitch: f64,
+    /// Positive tilting clockwise.
+    pub roll: f64,
+    /// Pixels between the faces of a cubemap.
+    pub padding: Option<i64>,
+    /// Cropping of a tiled equirectangular projection as fractions of the full sphere,
+    /// left, top, right and bottom, eg. `[0.25, 0., 0.25, 0.]` for the front half.
+    pub bounds: Option<[f64; 4]>,
+}
+
+impl Projection {
+    pub fn new(kind: ProjectionKind) -> Self {
+        Projection {
+            kind,
+            yaw: 0.,
+            pitch: 0.,
+            roll: 0.,
+            padding: None,
+            bounds: None,
+        }
+    }
+
+    /// Projection of `Spherical Mapping` side data, `None` without a projection.
+    pub fn from_side_data(mapping: &SphericalMapping) -> Option<Self> {
+        let kind = ProjectionKind::parse(mapping.projection.as_deref()?);
+        let fraction = |bound: Option<i64>| bound.unwrap_or(0) as f64 / (1u64 << 32) as f64;
+        let bounds = [
+            mapping.bound_left,
+            mapping.bound_top,
+            mapping.bound_right,
+            mapping.bound_bottom,
+        ];
+        Some(Projection {
+            bounds: bounds
+                .iter()
+                .any(Option::is_some)
+                .then(|| bounds.map(fraction)),
+            yaw: mapping.yaw.unwrap_or(0) as f64,
+            pitch: mapping.pitch.unwrap_or(0) as f64,
+            roll: mapping.roll.unwrap_or(0) as f64,
+            padding: mapping.padding,
+            ..Projection::new(kind)
+        })
+    }
+
+    /// Projection of the Spherical Video V1 tags, eg. `ProjectionType=equirectangular`
+    /// and `InitialViewHeadingDegrees=90`, as written by Google's spatial media tools.
+    pub fn from_tags(tags: &Tags) -> Option<Self> {
+        let kind = match tags.get("ProjectionType") {
+            Some(kind) => ProjectionKind::parse(kind),
+            // Version 1 only knows equirectangular.
+            None if tags.get("Spherical")?.eq_ignore_ascii_case("true") => {
+                ProjectionKind::Equirectangular
+            }
+            None => return None,
+        };
+        let degrees = |key| {
+            tags.get(key)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(0.)
+        };
+        Some(Projection {
+            yaw: degrees("InitialViewHeadingDegrees"),
+            pitch: degrees("InitialViewPitchDegrees"),
+            roll: degrees("InitialViewRollDegrees"),
+            ..Projection::new(kind)
+        })
+    }
+}
+
+/// Projection of the sphere onto the frame, named as in ffmpeg.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ProjectionKind {
+    Equirectangular,
+    /// Six faces, laid out 3x2.
+    Cubemap,
+    /// Equirectangular cropped to [`Projection::bounds`].
+    TiledEquirectangular,
+    /// The front half of the sphere, eg. VR180.
+    HalfEquirectangular,
+    Rectilinear,
+    Fisheye,
+    /// Apple Vision Pro immersive video, with the lens parameters in the metadata.
+    ParametricImmersive,
+    Other(String),
+}
+
+impl ProjectionKind {
+    /// Kind of a projection name, ignoring case, eg. `tiled equirectangular`.
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "equirectangular" => ProjectionKind::Equirectangular,
+            "cubemap" => ProjectionKind::Cubemap,
+            "tiled equirectangular" => ProjectionKind::TiledEquirectangular,
+            "half equirectangular" => ProjectionKind::HalfEquirectangular,
+            "rectilinear" => ProjectionKind::Rectilinear,
+            "fisheye" => ProjectionKind::Fisheye,
+            "parametric immersive" => ProjectionKind::ParametricImmersive,
+            _ => ProjectionKind::Other(name.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ProjectionKind::Equirectangular => "equirectangular",
+            ProjectionKind::Cubemap => "cubemap",
+            ProjectionKind::TiledEquirectangular => "tiled equirectangular",
+            ProjectionKind::HalfEquirectangular => "half equirectangular",
+            ProjectionKind::Rectilinear => "rectilinear",
+            ProjectionKind::Fisheye => "fisheye",
+            ProjectionKind::ParametricImmersive => "parametric immersive",
+            ProjectionKind::Other(name) => name,
+        }
+    }
+}
+
+impl Display for ProjectionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Ambisonic (scene based) audio of a stream, see [`AudioStream::ambisonics`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Ambisonics {
+    /// eg. 1 for first order, 4 channels.
+    pub order: usize,
+    pub channel_order: AmbisonicChannelOrder,
+    pub normalization: AmbisonicNormalization,
+    /// Head-locked channels after the ambisonic ones, eg. a stereo music bed.
+    pub non_diegetic_channels: usize,
+}
+
+impl Ambisonics {
+    /// Channels carrying the ambisonic components, (order + 1)².
+    pub fn ambisonic_channels(&self) -> usize {
+        (self.order + 1).pow(2)
+    }
+}
+
+/// Order of the ambisonic components in the channels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AmbisonicChannelOrder {
+    /// Ambisonic Channel Number, as in AmbiX and ffmpeg's ambisonic layouts.
+    Acn,
+    /// Furse-Malham, `W X Y Z` for first order.
+    FuMa,
+    Other(String),
+}
+
+impl AmbisonicChannelOrder {
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "acn" => AmbisonicChannelOrder::Acn,
+            "fuma" => AmbisonicChannelOrder::FuMa,
+            _ => AmbisonicChannelOrder::Other(name.to_string()),
+        }
+    }
+}
+
+/// Normalization of the ambisonic components.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AmbisonicNormalization {
+    /// Schmidt semi-normalized, as in AmbiX and YouTube spatial audio.
+    Sn3d,
+    /// Fully normalized.
+    N3d,
+    /// Furse-Malham, `W` attenuated by 3 dB.
+    FuMa,
+    Other(String),
+}
+
+impl AmbisonicNormalization {
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sn3d" => AmbisonicNormalization::Sn3d,
+            "n3d" => AmbisonicNormalization::N3d,
+            "fuma" | "maxn" => AmbisonicNormalization::FuMa,
+            _ => AmbisonicNormalization::Other(name.to_string()),
+        }
+    }
+}
+
+impl VideoStream {
+    /// Projection of a 360° video from its tags, see [`Stream::projection`] to include
+    /// side data.
+    pub fn projection(&self) -> Option<Projection> {
+        Projection::from_tags(&self.tags.as_ref()?.all)
+    }
+}
+
+impl AudioStream {
+    /// Ambisonics of an `ambisonic N` channel layout, as ffmpeg reports for MP4 files with
+    /// a spatial audio (`SA3D`) box, or of the `ambisonic_order` tag for files without
+    /// one. `ambisonic_channel_ordering` and `ambisonic_normalization` tags override the
+    /// defaults of ACN and SN3D.
+    pub fn ambisonics(&self) -> Option<Ambisonics> {
+        let tags = self.tags.as_ref().map(|t| &t.all);
+        let tag = |key| tags.and_then(|t| t.get(key));
+        let order = match self
+            .channel_layout
+            .as_ref()
+            .and_then(|l| l.ambisonic_order())
+        {
+            Some(order) => order,
+            None => tag("ambisonic_order")?.trim().parse().ok()?,
+        };
+        let ambisonic_channels = (order + 1).pow(2);
+        let channels = usize::try_from(self.channels).unwrap_or(0);
+        if channels < ambisonic_channels {
+            return None;
+        }
+        Some(Ambisonics {
+            order,
+            channel_order: tag("ambisonic_channel_ordering")
+                .map_or(AmbisonicChannelOrder::Acn, AmbisonicChannelOrder::parse),
+            normalization: tag("ambisonic_normalization")
+                .map_or(AmbisonicNormalization::Sn3d, AmbisonicNormalization::parse),
+            non_diegetic_channels: channels - ambisonic_channels,
+        })
+    }
+}
+
+impl Stream {
+    /// Projection of a 360° video stream, from its Spherical Mapping side data or the
+    /// Spherical Video V1 tags. `None` for flat videos and other stream kinds.
+    pub fn projection(&self) -> Option<Projection> {
+        let StreamKinds::Video(video) = &self.stream else {
+            return None;
+        };
+        self.side_data_list
+            .iter()
+            .find_map(|side_data| match side_data {
+                SideData::SphericalMapping(mapping) => Projection::from_side_data(mapping),
+                _ => None,
+            })
+            .or_else(|| video.projection())
+    }
+}
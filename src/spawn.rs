@@ -0,0 +1,238 @@
+use std::{
+    ffi::OsString,
+    fmt::Debug,
+    io::{self, Read, Write},
+    process::{ChildStdin, Command, ExitStatus, Output, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+    thread::Scope,
+    time::{Duration, Instant},
+};
+
+use crate::{command::quote, error::output_too_large, Config};
+
+/// Maximum length of [`SpawnEvent::stderr`], longer logs are cut at the front.
+pub const STDERR_SNIPPET_BYTES: usize = 2048;
+
+/// ffprobe or ffmpeg run of the crate, passed to the [`Config::on_spawn`] hook once the
+/// process has exited.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SpawnEvent {
+    /// Program that was executed, eg. `ffprobe`, or `nice` for [`Config::nice`].
+    pub program: OsString,
+    /// Arguments, excluding the program.
+    pub args: Vec<OsString>,
+    /// Wall clock time from spawning until exit.
+    pub duration: Duration,
+    /// Exit status, `None` if the process couldn't be spawned.
+    pub status: Option<ExitStatus>,
+    /// Why the process couldn't be spawned or waited for, eg. `No such file or directory`.
+    pub error: Option<String>,
+    /// End of the log written to stderr, see [`STDERR_SNIPPET_BYTES`].
+    pub stderr: String,
+}
+
+impl SpawnEvent {
+    /// Exit code, `None` if the process couldn't be spawned or was killed by a signal.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.status?.code()
+    }
+
+    /// Whether the process ran and exited successfully.
+    pub fn success(&self) -> bool {
+        self.status.is_some_and(|s| s.success())
+    }
+
+    /// Command line for logging, quoting arguments that contain whitespace.
+    pub fn command_line(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|arg| quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn new(cmd: &Command, started: Instant, out: Result<&Output, &io::Error>) -> Self {
+        let (status, error, stderr) = match out {
+            Ok(out) => (Some(out.status), None, stderr_snippet(&out.stderr)),
+            Err(err) => (None, Some(err.to_string()), String::new()),
+        };
+        SpawnEvent {
+            program: cmd.get_program().to_owned(),
+            args: cmd.get_args().map(|a| a.to_owned()).collect(),
+            duration: started.elapsed(),
+            status,
+            error,
+            stderr,
+        }
+    }
+}
+
+fn stderr_snippet(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stderr = stderr.trim_end();
+    let mut start = stderr.len().saturating_sub(STDERR_SNIPPET_BYTES);
+    while !stderr.is_char_boundary(start) {
+        start += 1;
+    }
+    stderr[start..].to_string()
+}
+
+/// Hook registered with [`Config::on_spawn`].
+#[derive(Clone)]
+pub(crate) struct SpawnHook(pub(crate) Arc<dyn Fn(&SpawnEvent) + Send + Sync>);
+
+impl Debug for SpawnHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SpawnHook")
+    }
+}
+
+impl Config {
+    /// Run `cmd` to completion like [`Command::output`], reporting it to the spawn hook
+    /// and its progress to the progress hook. Output beyond [`Config::max_output_bytes`]
+    /// fails with an error [`crate::error::FfProbeError::from_io`] turns into
+    /// [`crate::error::FfProbeError::OutputTooLarge`].
+    pub(crate) fn output(&self, cmd: &mut Command) -> io::Result<Output> {
+        self.spawn_output(cmd, None)
+    }
+
+    /// [`Config::output`] writing `stdin` to the process, eg. an init segment piped to
+    /// ffprobe.
+    #[cfg(feature = "streams")]
+    pub(crate) fn output_with_stdin(&self, cmd: &mut Command, stdin: &[u8]) -> io::Result<Output> {
+        self.spawn_output(cmd, Some(stdin))
+    }
+
+    fn spawn_output(&self, cmd: &mut Command, stdin: Option<&[u8]>) -> io::Result<Output> {
+        if let Some(out) = self.canned_output(cmd) {
+            return out;
+        }
+        let mut executed;
+        let cmd = match &self.executor {
+            Some(executor) => {
+                executed = executor.0.command(cmd);
+                &mut executed
+            }
+            None => cmd,
+        };
+        let started = Instant::now();
+        let out = match (&self.progress, self.max_output_bytes, stdin) {
+            (Some(hook), limit, stdin) => hook.output(cmd, limit, stdin),
+            (None, Some(limit), stdin) => output_limited(cmd, limit, stdin),
+            (None, None, Some(stdin)) => output_piped(cmd, stdin),
+            (None, None, None) => cmd.output(),
+        };
+        self.report_spawn(cmd, started, out.as_ref());
+        out
+    }
+
+    /// Output of `cmd` from [`crate::executor::Executor::output`], reported to the spawn
+    /// hook like a process that ran.
+    pub(crate) fn canned_output(&self, cmd: &Command) -> Option<io::Result<Output>> {
+        let started = Instant::now();
+        let out = self.executor.as_ref()?.0.output(cmd)?;
+        self.report_spawn(cmd, started, out.as_ref());
+        Some(out)
+    }
+
+    /// Pass a finished run of `cmd` to the spawn hook, for processes not run by
+    /// [`Config::output`].
+    pub(crate) fn report_spawn(
+        &self,
+        cmd: &Command,
+        started: Instant,
+        out: Result<&Output, &io::Error>,
+    ) {
+        if let Some(hook) = &self.on_spawn {
+            (hook.0)(&SpawnEvent::new(cmd, started, out));
+        }
+    }
+}
+
+/// Run `cmd` like [`Command::output`], writing `stdin` to it.
+fn output_piped(cmd: &mut Command, stdin: &[u8]) -> io::Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pipe = child.stdin.take();
+    std::thread::scope(|scope| {
+        write_stdin(scope, pipe, Some(stdin));
+        child.wait_with_output()
+    })
+}
+
+/// Run `cmd` like [`Command::output`], but kill it and fail once more than `limit` bytes
+/// were written to stdout or stderr.
+fn output_limited(cmd: &mut Command, limit: usize, stdin: Option<&[u8]>) -> io::Result<Output> {
+    let mut child = cmd
+        .stdin(stdin_pipe(stdin))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pipe = child.stdin.take();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let child = Mutex::new(child);
+    let kill = || {
+        let _ = child.lock().unwrap_or_else(PoisonError::into_inner).kill();
+    };
+
+    // Drain stderr from a separate thread so a full pipe can't block the child.
+    let (stdout, stderr) = std::thread::scope(|scope| {
+        write_stdin(scope, pipe, stdin);
+        let stderr = scope.spawn(|| {
+            let mut buf = Vec::new();
+            let read = stderr.take(limit as u64 + 1).read_to_end(&mut buf);
+            if read.is_ok() && buf.len() > limit {
+                kill();
+            }
+            buf
+        });
+        let mut buf = Vec::new();
+        let read = stdout.take(limit as u64 + 1).read_to_end(&mut buf);
+        // stdout is closed here, so writers other than the killed child fail as well.
+        if read.is_ok() && buf.len() > limit {
+            kill();
+        }
+        (read.map(|_| buf), stderr.join().unwrap_or_default())
+    });
+    let stdout = stdout?;
+    let status = child
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .wait()?;
+    if stdout.len() > limit || stderr.len() > limit {
+        return Err(output_too_large(limit));
+    }
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Stdin of a process getting `stdin`, or none.
+pub(crate) fn stdin_pipe(stdin: Option<&[u8]>) -> Stdio {
+    match stdin {
+        Some(_) => Stdio::piped(),
+        None => Stdio::null(),
+    }
+}
+
+/// Write `stdin` to the `pipe` of a process from a thread of `scope`, so a full stdout
+/// pipe can't deadlock it.
+pub(crate) fn write_stdin<'scope>(
+    scope: &'scope Scope<'scope, '_>,
+    pipe: Option<ChildStdin>,
+    stdin: Option<&'scope [u8]>,
+) {
+    if let (Some(mut pipe), Some(stdin)) = (pipe, stdin) {
+        // The process may exit before reading everything, which is fine.
+        scope.spawn(move || {
+            let _ = pipe.write_all(stdin);
+        });
+    }
+}
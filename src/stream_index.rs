@@ -0,0 +1,145 @@
+//! Stream indexes, see [`StreamIndex`].
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[cfg(feature = "streams")]
+use crate::{FfProbe, Stream, StreamKinds};
+
+/// Global index of a stream in the input, ffprobe's `index` and `stream_index`, eg. the `3`
+/// of `-map 0:3`.
+///
+/// Not the position in [`FfProbe::streams`], which differs once streams are selected or
+/// filtered, nor the ordinal among the streams of a type, the `1` of `-map 0:a:1`. Look
+/// those up with [`FfProbe::by_index`] and [`FfProbe::by_type_ordinal`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StreamIndex(pub u64);
+
+impl StreamIndex {
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for StreamIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for StreamIndex {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim().parse().map(StreamIndex)
+    }
+}
+
+impl From<u64> for StreamIndex {
+    fn from(index: u64) -> Self {
+        StreamIndex(index)
+    }
+}
+
+impl From<StreamIndex> for u64 {
+    fn from(index: StreamIndex) -> Self {
+        index.0
+    }
+}
+
+impl PartialEq<u64> for StreamIndex {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Accepts both numbers and the numeric strings reported by ffprobe.
+impl<'de> Deserialize<'de> for StreamIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::flexible_num::deserialize(deserializer).map(StreamIndex)
+    }
+}
+
+/// Type of a stream, as in stream specifiers, eg. the `a` of `-map 0:a:1`.
+#[cfg(feature = "streams")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum StreamType {
+    Video,
+    Audio,
+    Subtitle,
+    Attachment,
+    Data,
+}
+
+#[cfg(feature = "streams")]
+impl StreamType {
+    /// Letter of the type in stream specifiers, eg. `a`.
+    pub fn specifier(self) -> char {
+        match self {
+            StreamType::Video => 'v',
+            StreamType::Audio => 'a',
+            StreamType::Subtitle => 's',
+            StreamType::Attachment => 't',
+            StreamType::Data => 'd',
+        }
+    }
+}
+
+#[cfg(feature = "streams")]
+impl StreamKinds {
+    pub fn stream_type(&self) -> StreamType {
+        match self {
+            StreamKinds::Video(_) => StreamType::Video,
+            StreamKinds::Audio(_) => StreamType::Audio,
+            StreamKinds::Subtitle(_) => StreamType::Subtitle,
+            StreamKinds::Attachment(_) => StreamType::Attachment,
+            StreamKinds::Data(_) => StreamType::Data,
+        }
+    }
+}
+
+#[cfg(feature = "streams")]
+impl Stream {
+    pub fn stream_type(&self) -> StreamType {
+        self.stream.stream_type()
+    }
+}
+
+#[cfg(feature = "streams")]
+impl FfProbe {
+    /// Stream with the global `index`, wherever it is in [`FfProbe::streams`].
+    pub fn by_index(&self, index: impl Into<StreamIndex>) -> Option<&Stream> {
+        let index = index.into();
+        self.streams.iter().find(|stream| stream.index == index)
+    }
+
+    /// The `ordinal`th stream of `kind`, counting from 0 in index order like ffmpeg's
+    /// stream specifiers: `by_type_ordinal(StreamType::Audio, 1)` is `-map 0:a:1`.
+    pub fn by_type_ordinal(&self, kind: StreamType, ordinal: usize) -> Option<&Stream> {
+        self.streams_in_order()
+            .filter(|stream| stream.stream_type() == kind)
+            .nth(ordinal)
+    }
+
+    /// Type and ordinal of the stream with the global `index`, the inverse of
+    /// [`FfProbe::by_type_ordinal`].
+    pub fn type_ordinal(&self, index: impl Into<StreamIndex>) -> Option<(StreamType, usize)> {
+        let stream = self.by_index(index)?;
+        let kind = stream.stream_type();
+        let ordinal = self
+            .streams_in_order()
+            .filter(|other| other.stream_type() == kind)
+            .position(|other| other.index == stream.index)?;
+        Some((kind, ordinal))
+    }
+
+    /// [`FfProbe::streams`] by index, ffprobe prints them in this order.
+    fn streams_in_order(&self) -> impl Iterator<Item = &Stream> {
+        let mut streams: Vec<&Stream> = self.streams.iter().collect();
+        streams.sort_by_key(|stream| stream.index);
+        streams.into_iter()
+    }
+}
@@ -0,0 +1,237 @@
+//! HLS and DASH manifests.
+//!
+//! ffprobe opens every variant of a manifest and lists the streams of all of them
+//! together. The functions here group the streams back into the variants, eg. the
+//! `EXT-X-STREAM-INF` entries of an HLS master playlist or the representations of a
+//! DASH manifest.
+
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{
+    error::{invalid_data, FfProbeError},
+    Bitrate, Config, FfProbe, ProbeCommand, Stream, StreamIndex, StreamKinds, Tags,
+};
+
+/// Probing limits used by [`probe_manifest_all_variants`], so ffprobe reads enough
+/// segments to find the codec parameters of every variant.
+const ALL_VARIANTS_ARGS: [&str; 4] = ["-probesize", "100M", "-analyzeduration", "60M"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManifestKind {
+    /// HTTP Live Streaming, `.m3u8`
+    Hls,
+    /// MPEG-DASH, `.mpd`
+    Dash,
+}
+
+/// Variant of a manifest: one rendition of the content at a given bandwidth, with its
+/// video, audio and subtitle streams.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Variant {
+    /// Program of the variant, HLS only.
+    pub program_id: Option<i64>,
+    /// Bandwidth declared in the manifest (`variant_bitrate` tag).
+    pub bandwidth: Option<Bitrate>,
+    /// Indexes of the streams in [`FfProbe::streams`].
+    pub streams: Vec<StreamIndex>,
+    /// Largest video size of the variant.
+    pub resolution: Option<(i64, i64)>,
+    /// Codec names of the streams, eg. `["h264", "aac"]`.
+    pub codecs: Vec<String>,
+    /// Whether ffprobe found the codec parameters of every stream of the variant. ffprobe
+    /// stops reading after its probing limits, variants reached late are only partially
+    /// described, see [`probe_manifest_all_variants`].
+    pub probed: bool,
+    /// Program tags for HLS, tags of the first stream for DASH.
+    pub tags: Tags,
+}
+
+/// Result of [`probe_manifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ManifestProbe {
+    pub kind: ManifestKind,
+    /// Variants by descending bandwidth.
+    pub variants: Vec<Variant>,
+    /// Full ffprobe output, with the streams of all variants.
+    pub probe: FfProbe,
+}
+
+impl ManifestProbe {
+    /// Highest bandwidth variant ffprobe fully probed, ie. the rendition the stream
+    /// details describe best.
+    pub fn probed_variant(&self) -> Option<&Variant> {
+        self.variants.iter().find(|v| v.probed)
+    }
+
+    /// Streams of `variant`.
+    pub fn streams<'a>(&'a self, variant: &'a Variant) -> impl Iterator<Item = &'a Stream> + 'a {
+        self.probe
+            .streams
+            .iter()
+            .filter(|s| variant.streams.contains(&s.index))
+    }
+}
+
+/// Probe an HLS or DASH manifest, eg. `https://example.com/master.m3u8`.
+pub fn probe_manifest(url: impl AsRef<Path>) -> Result<ManifestProbe, FfProbeError> {
+    probe_manifest_config(Config::new(), url)
+}
+
+/// [`probe_manifest`] with the given config.
+pub fn probe_manifest_config(
+    config: Config,
+    url: impl AsRef<Path>,
+) -> Result<ManifestProbe, FfProbeError> {
+    run(
+        ProbeCommand::new(config).arg("-show_programs"),
+        url.as_ref(),
+    )
+}
+
+/// [`probe_manifest_config`] with raised probing limits, so every variant gets probed
+/// rather than only the ones ffprobe reaches first. Downloads segments of every
+/// variant, which takes longer on manifests with many variants.
+pub fn probe_manifest_all_variants(
+    config: Config,
+    url: impl AsRef<Path>,
+) -> Result<ManifestProbe, FfProbeError> {
+    run(
+        ProbeCommand::new(config)
+            .args(ALL_VARIANTS_ARGS)
+            .arg("-show_programs"),
+        url.as_ref(),
+    )
+}
+
+fn run(cmd: ProbeCommand, url: &Path) -> Result<ManifestProbe, FfProbeError> {
+    let cmd = cmd.input(url);
+    parse_manifest(&cmd).map_err(|e| e.context(url, &cmd))
+}
+
+fn parse_manifest(cmd: &ProbeCommand) -> Result<ManifestProbe, FfProbeError> {
+    let out = cmd.output().map_err(FfProbeError::from_io)?;
+    if !out.status.success() {
+        return Err(FfProbeError::Status(out));
+    }
+    let raw: Value = serde_json::from_slice(&out.stdout).map_err(FfProbeError::Deserialize)?;
+    let mut typed = raw.clone();
+    let programs = typed
+        .as_object_mut()
+        .and_then(|o| o.remove("programs"))
+        .unwrap_or_default();
+    let mut probe = FfProbe::from_value(typed)?;
+    probe.raw = Some(Arc::new(raw));
+    ManifestProbe::new(probe, &programs)
+}
+
+impl ManifestProbe {
+    /// Group the streams of ffprobe output with `-show_programs` into variants.
+    fn new(probe: FfProbe, programs: &Value) -> Result<Self, FfProbeError> {
+        let kind = match probe.format.format_name.as_str() {
+            "hls" | "applehttp" => ManifestKind::Hls,
+            "dash" => ManifestKind::Dash,
+            other => return Err(invalid_data(format!("not an HLS or DASH input: {other}"))),
+        };
+
+        let programs = programs.as_array().map(Vec::as_slice).unwrap_or_default();
+        let mut variants: Vec<Variant> = if programs.len() > 1 {
+            programs
+                .iter()
+                .map(|program| {
+                    let tags: Tags = program
+                        .get("tags")
+                        .and_then(|t| serde_json::from_value(t.clone()).ok())
+                        .unwrap_or_default();
+                    Variant {
+                        program_id: program.get("program_id").and_then(Value::as_i64),
+                        bandwidth: variant_bitrate(&tags),
+                        streams: program
+                            .get("streams")
+                            .and_then(Value::as_array)
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|s| s.get("index")?.as_u64().map(StreamIndex))
+                            .collect(),
+                        tags,
+                        ..Default::default()
+                    }
+                })
+                .collect()
+        } else {
+            // DASH and single variant HLS: representations only differ by their streams' tags.
+            let mut groups: BTreeMap<Option<u64>, Variant> = BTreeMap::new();
+            for stream in &probe.streams {
+                let bandwidth = variant_bitrate(stream.all_tags());
+                let variant = groups
+                    .entry(bandwidth.map(|b| b.0))
+                    .or_insert_with(|| Variant {
+                        bandwidth,
+                        tags: stream.all_tags().clone(),
+                        ..Default::default()
+                    });
+                variant.streams.push(stream.index);
+            }
+            groups.into_values().collect()
+        };
+
+        for variant in &mut variants {
+            let streams: Vec<&Stream> = probe
+                .streams
+                .iter()
+                .filter(|s| variant.streams.contains(&s.index))
+                .collect();
+            variant.resolution = streams
+                .iter()
+                .filter_map(|s| match &s.stream {
+                    StreamKinds::Video(v) if v.width > 0 => Some((v.width, v.height)),
+                    _ => None,
+                })
+                .max_by_key(|(w, h)| w * h);
+            variant.codecs = streams
+                .iter()
+                .filter_map(|s| codec_name(s))
+                .map(str::to_string)
+                .collect();
+            variant.probed = !streams.is_empty() && streams.iter().all(|s| has_parameters(s));
+        }
+        variants.sort_by_key(|v| std::cmp::Reverse(v.bandwidth));
+
+        Ok(ManifestProbe {
+            kind,
+            variants,
+            probe,
+        })
+    }
+}
+
+fn variant_bitrate(tags: &Tags) -> Option<Bitrate> {
+    tags.get("variant_bitrate")?
+        .trim()
+        .parse()
+        .ok()
+        .map(Bitrate)
+}
+
+fn codec_name(stream: &Stream) -> Option<&str> {
+    let name = match &stream.stream {
+        StreamKinds::Video(v) => &v.codec_name,
+        StreamKinds::Audio(a) => &a.codec_name,
+        StreamKinds::Subtitle(s) => &s.codec_name,
+        StreamKinds::Attachment(_) | StreamKinds::Data(_) => return None,
+    };
+    Some(name.as_str()).filter(|n| !n.is_empty())
+}
+
+/// Whether ffprobe found the codec parameters, it reports zero sizes and channel counts
+/// for streams it gave up on.
+fn has_parameters(stream: &Stream) -> bool {
+    match &stream.stream {
+        StreamKinds::Video(v) => v.width > 0 && v.height > 0,
+        StreamKinds::Audio(a) => a.channels > 0,
+        _ => true,
+    }
+}
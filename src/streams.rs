@@ -1,22 +1,36 @@
 use std::time::Duration;
 
-use chrono::{DateTime, FixedOffset, NaiveDateTime, NaiveTime, Timelike};
+use chrono::NaiveDateTime;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
 use crate::{
-    attachment_stream::AttachmentStream, audio_stream::AudioStream, data_stream::DataStream,
-    disposition::Disposition, ratio::Ratio, subtitle_stream::SubtitleStream,
+    attachment_stream::AttachmentStream,
+    audio_stream::AudioStream,
+    creation_time::CreationTime,
+    data_stream::DataStream,
+    disposition::Disposition,
+    extradata, flexible_num,
+    frame_rate::FrameRate,
+    mkv_stats::parse_mkv_duration,
+    ratio::Ratio,
+    side_data::SideData,
+    stream_index::StreamIndex,
+    subtitle_stream::SubtitleStream,
+    tags::{Tags, NO_TAGS},
+    time_base::TimeBase,
     video_stream::VideoStream,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Stream parsed
+#[non_exhaustive]
 pub struct Stream {
     /// The identifier of the stream, if available.
     pub id: Option<String>,
-    // The index of the stream.
-    pub index: u64,
+    /// The index of the stream, see [`StreamIndex`].
+    pub index: StreamIndex,
     /// Disposition flags indicating various properties of the stream.
     pub disposition: Disposition,
     /// The average frame rate of the stream.
@@ -29,22 +43,38 @@ pub struct Stream {
     pub time_base: Ratio,
     /// The start presentation timestamp (PTS) of the stream.
     /// ptr * timebase = start in seconds
+    #[serde(deserialize_with = "flexible_num::deserialize")]
     pub start_pts: i64,
     #[serde(default)]
     /// A list of side data associated with the stream.
     pub side_data_list: Vec<SideData>,
     /// The size of the extra data associated with the stream, if available.
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub extradata_size: Option<i64>,
+    /// Codec private data, eg. the `avcC` record of H.264 in MP4. Only available if the
+    /// 'show_data' setting was enabled.
+    #[serde(
+        deserialize_with = "extradata::option",
+        serialize_with = "extradata::serialize",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub extradata: Option<Vec<u8>>,
     /// The real frame rate of the stream.
     pub r_frame_rate: Ratio,
     /// The total number of frames in the stream, if available.
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub nb_frames: Option<i64>,
     /// Number of frames seen by the decoder.
     /// Requires full decoding and is only available if the 'count_frames'
     /// setting was enabled.
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub nb_read_frames: Option<i64>,
+    /// Number of packets read from the container.
+    /// Only available if the 'count_packets' setting was enabled.
+    #[serde(deserialize_with = "flexible_num::option", default)]
+    pub nb_read_packets: Option<i64>,
     #[cfg(feature = "__internal_deny_unknown_fields")]
     codec_tag_string: Value,
     #[serde(flatten)]
@@ -52,6 +82,30 @@ pub struct Stream {
 }
 
 impl Stream {
+    /// Stream at `index` with a time base of 1/1000 and empty disposition. Frame rates are 0/0,
+    /// like ffprobe reports them for streams without one.
+    pub fn new(index: impl Into<StreamIndex>, stream: impl Into<StreamKinds>) -> Self {
+        Self {
+            id: None,
+            index: index.into(),
+            disposition: Disposition::default(),
+            avg_frame_rate: Ratio::default(),
+            codec_tag: Vec::new(),
+            time_base: Ratio::new(1, 1000),
+            start_pts: 0,
+            side_data_list: Vec::new(),
+            extradata_size: None,
+            extradata: None,
+            r_frame_rate: Ratio::default(),
+            nb_frames: None,
+            nb_read_frames: None,
+            nb_read_packets: None,
+            #[cfg(feature = "__internal_deny_unknown_fields")]
+            codec_tag_string: Value::Null,
+            stream: stream.into(),
+        }
+    }
+
     pub fn start_time(&self) -> f64 {
         (self.start_pts * self.time_base.numerator() as i64) as f64
             / (self.time_base.denominator() as f64)
@@ -64,12 +118,69 @@ impl Stream {
             StreamKinds::Attachment(attach) => Some(attach.duration_ts),
             StreamKinds::Data(v) => Some(v.duration_ts),
         }
-        .map(|d| {
-            Duration::from_millis(
-                ((d * self.time_base.numerator()) as f64 / self.time_base.denominator() as f64
-                    * 1000.) as u64,
-            )
-        })
+        .and_then(|d| Some(self.timebase()?.to_duration(i64::try_from(d).ok()?)))
+    }
+
+    /// [`Stream::time_base`] for timestamp math, eg. to convert the pts of packets and
+    /// frames of the stream. `None` for a zero numerator or denominator.
+    pub fn timebase(&self) -> Option<TimeBase> {
+        TimeBase::from_ratio(&self.time_base)
+    }
+
+    /// Tags common to all stream kinds. `None` for attachment and data streams, or if the
+    /// stream has no tags.
+    pub fn tags(&self) -> Option<&StreamTags> {
+        match &self.stream {
+            StreamKinds::Video(v) => v.tags.as_ref().map(|t| &t.tags),
+            StreamKinds::Audio(a) => a.tags.as_ref().map(|t| &t.tags),
+            StreamKinds::Subtitle(s) => s.tags.as_ref().map(|t| &t.tags),
+            StreamKinds::Attachment(_) | StreamKinds::Data(_) => None,
+        }
+    }
+
+    /// All tags of the stream, see [`Tags`].
+    pub fn all_tags(&self) -> &Tags {
+        let tags = match &self.stream {
+            StreamKinds::Video(v) => v.tags.as_ref().map(|t| &t.all),
+            StreamKinds::Audio(a) => a.tags.as_ref().map(|t| &t.all),
+            StreamKinds::Subtitle(s) => s.tags.as_ref().map(|t| &t.all),
+            StreamKinds::Attachment(a) => Some(&a.tags.all),
+            StreamKinds::Data(d) => Some(&d.tags.all),
+        };
+        tags.unwrap_or(&NO_TAGS)
+    }
+
+    /// Tags not covered by the typed tag structs.
+    pub(crate) fn extra_tags(&self) -> Option<&std::collections::HashMap<String, Value>> {
+        match &self.stream {
+            StreamKinds::Video(v) => v.tags.as_ref().map(|t| &t.extra),
+            StreamKinds::Audio(a) => a.tags.as_ref().map(|t| &t.extra),
+            StreamKinds::Subtitle(s) => s.tags.as_ref().map(|t| &t.extra),
+            StreamKinds::Attachment(a) => Some(&a.tags.extra),
+            StreamKinds::Data(_) => None,
+        }
+    }
+
+    /// Frame rate helpers for video streams, `None` for other stream kinds. Includes the
+    /// measured rate if frames were counted, see [`FrameRate::best_guess`].
+    pub fn frame_rate(&self) -> Option<FrameRate> {
+        match &self.stream {
+            StreamKinds::Video(video) => Some(FrameRate {
+                r_frame_rate: self.r_frame_rate.clone(),
+                avg_frame_rate: self.avg_frame_rate.clone(),
+                field_order: video.field_order.clone(),
+                measured_frame_rate: self.nb_read_frames.zip(video.duration_ts).and_then(
+                    |(frames, duration_ts)| {
+                        // frames / (duration_ts * time_base)
+                        let frames = u64::try_from(frames).ok()?;
+                        let ticks = duration_ts.checked_mul(self.time_base.numerator())?;
+                        let rate = frames.checked_mul(self.time_base.denominator())?;
+                        (ticks > 0 && frames > 0).then(|| Ratio::new(rate, ticks).reduce())
+                    },
+                ),
+            }),
+            _ => None,
+        }
     }
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -82,6 +193,24 @@ pub enum StreamKinds {
     Data(DataStream),
 }
 
+macro_rules! stream_kind_from {
+    ($($variant:ident($ty:ty)),*) => {
+        $(impl From<$ty> for StreamKinds {
+            fn from(stream: $ty) -> Self {
+                StreamKinds::$variant(stream)
+            }
+        })*
+    };
+}
+
+stream_kind_from!(
+    Audio(AudioStream),
+    Video(VideoStream),
+    Subtitle(SubtitleStream),
+    Attachment(AttachmentStream),
+    Data(DataStream)
+);
+
 impl Serialize for StreamKinds {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -181,35 +310,22 @@ impl<'de> Deserialize<'de> for StreamKinds {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
-pub struct SideData {
-    side_data_type: Option<String>,
-    service_type: Option<i64>,
-    dv_version_major: Option<i64>,
-    dv_version_minor: Option<i64>,
-    dv_profile: Option<i64>,
-    dv_level: Option<i64>,
-    rpu_present_flag: Option<i64>,
-    el_present_flag: Option<i64>,
-    bl_present_flag: Option<i64>,
-    dv_bl_signal_compatibility_id: Option<i64>,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Stream tags for video, audio, subtitle
+#[non_exhaustive]
 pub struct StreamTags {
     #[serde(rename = "BPS")]
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub bps: Option<i64>,
     #[serde(rename = "DURATION")]
     #[serde(deserialize_with = "option_chronostring_to_duration", default)]
     pub duration: Option<Duration>,
     #[serde(rename = "NUMBER_OF_BYTES")]
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub number_of_bytes: Option<i64>,
     #[serde(rename = "NUMBER_OF_FRAMES")]
-    #[serde(deserialize_with = "option_string_to_int", default)]
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub number_of_frames: Option<i64>,
     #[serde(rename = "_STATISTICS_TAGS")]
     pub statistics_tags: Option<String>,
@@ -220,8 +336,7 @@ pub struct StreamTags {
     pub statistics_writing_date_utc: Option<NaiveDateTime>,
     #[serde(alias = "HANDLER_NAME")]
     pub handler_name: Option<String>,
-    #[serde(deserialize_with = "option_string_to_datetime", default)]
-    pub creation_time: Option<DateTime<FixedOffset>>,
+    pub creation_time: Option<CreationTime>,
     #[serde(alias = "ENCODER")]
     pub encoder: Option<String>,
     #[serde(alias = "VENDOR_ID")]
@@ -230,25 +345,6 @@ pub struct StreamTags {
     pub language: Option<String>,
 }
 
-pub fn option_string_to_int<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    match s {
-        Some(s) => s.parse::<i64>().map(Some).map_err(serde::de::Error::custom),
-        None => Ok(None),
-    }
-}
-
-pub fn string_to_int<'de, D>(deserializer: D) -> Result<i64, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    s.parse::<i64>().map_err(serde::de::Error::custom)
-}
-
 pub fn string_to_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
     D: Deserializer<'de>,
@@ -301,13 +397,9 @@ where
 {
     let s: Option<String> = Option::deserialize(deserializer)?;
     match s {
-        Some(s) => NaiveTime::parse_from_str(&s, "%H:%M:%S%.f")
-            .map(|time| {
-                let seconds = time.hour() * 3600 + time.minute() * 60 + time.second();
-                Duration::new(seconds as u64, time.nanosecond())
-            })
+        Some(s) => parse_mkv_duration(&s)
             .map(Some)
-            .map_err(serde::de::Error::custom),
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid duration: {s}"))),
         None => Ok(None),
     }
 }
@@ -326,18 +418,3 @@ where
         None => Ok(None),
     }
 }
-
-pub fn option_string_to_datetime<'de, D>(
-    deserializer: D,
-) -> Result<Option<DateTime<FixedOffset>>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    match s {
-        Some(s) => DateTime::parse_from_rfc3339(&s)
-            .map(Some)
-            .map_err(serde::de::Error::custom),
-        None => Ok(None),
-    }
-}
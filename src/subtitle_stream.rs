@@ -2,36 +2,53 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::streams::{option_string_to_int, StreamTags};
+use crate::{
+    disposition::Disposition,
+    flexible_num,
+    language::Language,
+    streams::StreamTags,
+    tags::{capture_opt, CaptureTags, Tags},
+    units::Bitrate,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Tags specific for subtitles
-
+#[non_exhaustive]
 pub struct SubtititleTags {
     #[serde(flatten)]
     pub tags: StreamTags,
     pub filename: Option<String>,
     pub mimetype: Option<String>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub width: Option<i64>,
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub height: Option<i64>,
-    #[serde(deserialize_with = "option_string_to_int", default)]
-    pub bit_rate: Option<i64>,
+    #[serde(default)]
+    pub bit_rate: Option<Bitrate>,
     #[serde(rename = "SOURCE_ID")]
     pub source_id: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
+    /// All tags, including the ones covered by the typed fields.
+    #[serde(skip)]
+    pub all: Tags,
 }
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "__internal_deny_unknown_fields", serde(deny_unknown_fields))]
 /// Stream of type subtitle
+#[non_exhaustive]
 pub struct SubtitleStream {
     /// Bit rate of the video stream.
     /// The bit_rate represents the number of bits that are processed per unit of time in the video stream. It is a measure of the video stream's data rate, indicating how much data is encoded for each second of video.
-    #[serde(deserialize_with = "option_string_to_int", default)]
-    pub bit_rate: Option<i64>,
+    #[serde(default)]
+    pub bit_rate: Option<Bitrate>,
     /// width of video
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub width: Option<i64>,
     /// height of video
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub height: Option<i64>,
     /// Long name of the codec used for the video stream.
     pub codec_long_name: String,
@@ -39,8 +56,10 @@ pub struct SubtitleStream {
     /// Example: h264
     pub codec_name: String,
     /// Duration of the video stream in timestamp units.
+    #[serde(deserialize_with = "flexible_num::option", default)]
     pub duration_ts: Option<u64>,
     /// Metadata tags associated with the video stream.
+    #[serde(deserialize_with = "capture_opt", default)]
     pub tags: Option<SubtititleTags>,
     #[cfg(feature = "__internal_deny_unknown_fields")]
     codec_type: Option<serde_json::Value>,
@@ -49,3 +68,77 @@ pub struct SubtitleStream {
     #[cfg(feature = "__internal_deny_unknown_fields")]
     duration: Option<serde_json::Value>,
 }
+
+/// Representation of a subtitle codec, see [`SubtitleStream::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubtitleKind {
+    /// Text that can be restyled or converted, eg. subrip, ass or mov_text.
+    TextBased,
+    /// Pre-rendered images that need OCR for conversion to text, eg. PGS or VobSub.
+    Bitmap,
+    /// Closed captions, eg. EIA-608.
+    ClosedCaptions,
+    /// Codec not known to this crate.
+    Unknown,
+}
+
+impl SubtitleStream {
+    /// Subtitle stream with the given codec, eg. `subrip`.
+    pub fn new(codec_name: impl Into<String>) -> Self {
+        Self {
+            codec_name: codec_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Classify the subtitle codec.
+    pub fn kind(&self) -> SubtitleKind {
+        match self.codec_name.as_str() {
+            "ass" | "ssa" | "subrip" | "srt" | "webvtt" | "mov_text" | "text" | "ttml"
+            | "microdvd" | "mpl2" | "jacosub" | "pjs" | "realtext" | "sami" | "stl"
+            | "subviewer" | "subviewer1" | "vplayer" | "dvb_teletext" => SubtitleKind::TextBased,
+            "hdmv_pgs_subtitle" | "dvd_subtitle" | "dvb_subtitle" | "xsub"
+            | "hdmv_text_subtitle" => SubtitleKind::Bitmap,
+            "eia_608" | "cea_608" | "eia_708" | "cea_708" => SubtitleKind::ClosedCaptions,
+            _ => SubtitleKind::Unknown,
+        }
+    }
+
+    /// Language of the `language` tag, see [`Language`].
+    /// `None` if the language is missing or undetermined (`und`).
+    pub fn language(&self) -> Option<Language> {
+        Language::parse(self.tags.as_ref()?.tags.language.as_deref()?)
+    }
+
+    /// Whether the track has subtitles for the deaf and hard of hearing, from the
+    /// `hearing_impaired` disposition or a title like `English (SDH)`.
+    pub fn is_sdh(&self, disposition: &Disposition) -> bool {
+        disposition.hearing_impaired == 1
+            || self.title_contains(&["sdh", "cc", "hearing impaired", "hoh"])
+    }
+
+    /// Whether the track only covers foreign language parts, from the `forced`
+    /// disposition or a title like `Forced`.
+    pub fn is_forced(&self, disposition: &Disposition) -> bool {
+        disposition.forced == 1 || self.title_contains(&["forced"])
+    }
+
+    /// Whether the title contains one of the lowercase `words` as a whole word.
+    fn title_contains(&self, words: &[&str]) -> bool {
+        let Some(title) = self.tags.as_ref().and_then(|t| t.tags.title.as_deref()) else {
+            return false;
+        };
+        let title = title.to_lowercase();
+        let tokens: Vec<&str> = title
+            .split(|c: char| !c.is_alphanumeric() && c != ' ')
+            .flat_map(|part| [part.trim()].into_iter().chain(part.split_whitespace()))
+            .collect();
+        words.iter().any(|w| tokens.contains(w))
+    }
+}
+
+impl CaptureTags for SubtititleTags {
+    fn all_mut(&mut self) -> &mut Tags {
+        &mut self.all
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+
+use serde::{
+    de::{self, DeserializeOwned, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value};
+
+/// All tags of a stream, format or chapter in file order, including the ones the
+/// typed tag structs don't cover.
+///
+/// Keys are compared case-insensitively, as muxers disagree on the case,
+/// eg. `ENCODER` in Matroska and `encoder` in MP4.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tags(Vec<(String, String)>);
+
+#[cfg(any(feature = "streams", feature = "format"))]
+pub(crate) static NO_TAGS: Tags = Tags(Vec::new());
+
+impl Tags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Value of the first tag named `key`, ignoring case.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Set `key`, replacing the value of an existing tag with the same name ignoring case.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        match self
+            .0
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(&key))
+        {
+            Some((_, v)) => *v = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    /// Tags as (key, value), with the keys as written in the file.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Tags {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut tags = Tags::new();
+        for (k, v) in iter {
+            tags.insert(k, v);
+        }
+        tags
+    }
+}
+
+impl Serialize for Tags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (k, v) in &self.0 {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Tags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = deserializer.deserialize_map(EntriesVisitor)?;
+        Ok(Tags(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k, tag_value(v)))
+                .collect(),
+        ))
+    }
+}
+
+/// Map entries in input order.
+struct EntriesVisitor;
+
+impl<'de> Visitor<'de> for EntriesVisitor {
+    type Value = Vec<(String, Value)>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of tags")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+fn tag_value(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+/// Typed tag structs keeping a copy of all tags, see [`capture`].
+pub(crate) trait CaptureTags: DeserializeOwned {
+    fn all_mut(&mut self) -> &mut Tags;
+}
+
+/// Deserialize a typed tag struct and capture every tag into its [`Tags`].
+pub(crate) fn capture<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: CaptureTags,
+{
+    let entries = deserializer.deserialize_map(EntriesVisitor)?;
+    let all = Tags(
+        entries
+            .iter()
+            .map(|(k, v)| (k.clone(), tag_value(v.clone())))
+            .collect(),
+    );
+    let mut typed: T =
+        serde_json::from_value(Value::Object(entries.into_iter().collect::<Map<_, _>>()))
+            .map_err(de::Error::custom)?;
+    *typed.all_mut() = all;
+    Ok(typed)
+}
+
+/// [`capture`] for optional tags.
+#[cfg(any(feature = "streams", feature = "format"))]
+pub(crate) fn capture_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: CaptureTags,
+{
+    struct Wrapper<T>(T);
+    impl<'de, T: CaptureTags> Deserialize<'de> for Wrapper<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            capture(deserializer).map(Wrapper)
+        }
+    }
+    Ok(Option::<Wrapper<T>>::deserialize(deserializer)?.map(|w| w.0))
+}
@@ -0,0 +1,114 @@
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use crate::Ratio;
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Unit of the timestamps of a stream or chapter in seconds, eg. 1/90000 for MPEG-TS.
+/// A pts of `n` is `n * numerator / denominator` seconds.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use ffprobe::TimeBase;
+///
+/// let mpeg: TimeBase = "1/90000".parse().unwrap();
+/// assert_eq!(mpeg.to_duration(180_000), Duration::from_secs(2));
+/// assert_eq!(mpeg.from_duration(Duration::from_millis(500)), 45_000);
+/// assert_eq!(mpeg.rescale(90_000, TimeBase::new(1, 1000).unwrap()), 1000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeBase {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl TimeBase {
+    /// `None` for a zero numerator or denominator, which ffprobe reports for unknown
+    /// time bases.
+    pub const fn new(numerator: u64, denominator: u64) -> Option<TimeBase> {
+        if numerator == 0 || denominator == 0 {
+            return None;
+        }
+        Some(TimeBase {
+            numerator,
+            denominator,
+        })
+    }
+
+    /// Time base from a `time_base` as reported by ffprobe, see [`TimeBase::new`].
+    pub fn from_ratio(ratio: &Ratio) -> Option<TimeBase> {
+        TimeBase::new(ratio.numerator(), ratio.denominator())
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    /// `pts` in nanoseconds, truncated. Signed as timestamps before zero are common, eg.
+    /// for edit lists or B-frame delay.
+    pub fn to_nanos(&self, pts: i64) -> i128 {
+        pts as i128 * self.numerator as i128 * NANOS_PER_SEC / self.denominator as i128
+    }
+
+    /// `pts` as a duration since zero. Negative timestamps are clamped to zero.
+    pub fn to_duration(&self, pts: i64) -> Duration {
+        let nanos = self.to_nanos(pts).clamp(0, u64::MAX as i128);
+        Duration::from_nanos(nanos as u64)
+    }
+
+    /// Timestamp of `duration`, rounded to the nearest tick, eg. to seek.
+    pub fn from_duration(&self, duration: Duration) -> i64 {
+        rescale(
+            duration.as_nanos().min(i128::MAX as u128) as i128,
+            self.denominator as i128,
+            self.numerator as i128 * NANOS_PER_SEC,
+        )
+    }
+
+    /// `pts` converted to the time base `to`, rounded to the nearest tick, eg. to compare
+    /// timestamps of streams with different time bases.
+    pub fn rescale(&self, pts: i64, to: TimeBase) -> i64 {
+        rescale(
+            pts as i128,
+            self.numerator as i128 * to.denominator as i128,
+            self.denominator as i128 * to.numerator as i128,
+        )
+    }
+}
+
+/// `value * mul / div` rounded to the nearest integer, ties away from zero, saturating.
+fn rescale(value: i128, mul: i128, div: i128) -> i64 {
+    let product = value.saturating_mul(mul);
+    let half = div / 2;
+    let rounded = if product < 0 {
+        product.saturating_sub(half) / div
+    } else {
+        product.saturating_add(half) / div
+    };
+    rounded.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+impl From<TimeBase> for Ratio {
+    fn from(time_base: TimeBase) -> Ratio {
+        Ratio::new(time_base.numerator, time_base.denominator)
+    }
+}
+
+impl Display for TimeBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for TimeBase {
+    type Err = String;
+
+    /// Parse `1/90000` or `1:90000`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TimeBase::from_ratio(&s.parse()?).ok_or_else(|| format!("invalid time base {s}"))
+    }
+}
@@ -0,0 +1,206 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{ratio::Ratio, time_base::TimeBase};
+#[cfg(feature = "streams")]
+use crate::{side_data::SideData, FfProbe, StreamKinds};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// SMPTE timecode `hh:mm:ss:ff` at a frame rate, eg. the start timecode of a file, see
+/// [`FfProbe::start_timecode`].
+///
+/// Drop-frame timecodes, written `hh:mm:ss;ff`, skip frame numbers 0 and 1 (0 to 3 at
+/// 59.94 fps) at the start of every minute except every tenth, so they follow the wall
+/// clock at 29.97 fps. Arithmetic wraps at 24 hours like timecode does.
+///
+/// ```rust
+/// use ffprobe::{Ratio, Timecode};
+///
+/// let start = Timecode::parse("00:59:59;29", Ratio::new(30000, 1001)).unwrap();
+/// assert_eq!(start.add_frames(1).to_string(), "01:00:00;00");
+/// assert_eq!(start.add_frames(1).frame_number(), 107_892);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u32,
+    pub drop_frame: bool,
+    /// Frame rate of the timecode, eg. 25 or 30000/1001.
+    pub rate: Ratio,
+}
+
+impl Timecode {
+    /// Parse `hh:mm:ss:ff`, or drop-frame `hh:mm:ss;ff` (also `hh;mm;ss;ff` and
+    /// `hh:mm:ss.ff`), at `rate`.
+    pub fn parse(timecode: &str, rate: Ratio) -> Result<Timecode, String> {
+        let invalid = || format!("invalid timecode {timecode}");
+        let fps = nominal_fps(&rate).ok_or_else(|| format!("invalid frame rate {rate}"))?;
+        let drop_frame = timecode.contains([';', '.']);
+        let mut parts = timecode.trim().split([':', ';', '.']);
+        let mut next = || parts.next().and_then(|p| p.parse::<u32>().ok());
+        let (hours, minutes, seconds, frames) = (
+            next().ok_or_else(invalid)?,
+            next().ok_or_else(invalid)?,
+            next().ok_or_else(invalid)?,
+            next().ok_or_else(invalid)?,
+        );
+        if next().is_some() || hours >= 24 || minutes >= 60 || seconds >= 60 || frames >= fps {
+            return Err(invalid());
+        }
+        if drop_frame && (fps % 30 != 0 || (seconds == 0 && minutes % 10 != 0 && frames < fps / 15))
+        {
+            return Err(invalid());
+        }
+        Ok(Timecode {
+            hours: hours as u8,
+            minutes: minutes as u8,
+            seconds: seconds as u8,
+            frames,
+            drop_frame,
+            rate,
+        })
+    }
+
+    /// Timecode of the frame with number `frame` since `00:00:00:00`.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is zero, or `drop_frame` is set for a rate other than 29.97 or 59.94.
+    pub fn from_frame_number(frame: u64, rate: Ratio, drop_frame: bool) -> Timecode {
+        let fps = u64::from(nominal_fps(&rate).expect("timecode frame rate is zero"));
+        assert!(
+            !drop_frame || fps % 30 == 0,
+            "no drop-frame timecode at {rate}"
+        );
+        let mut frame = frame % frames_per_day(fps, drop_frame);
+        if drop_frame {
+            let dropped = fps / 15;
+            let per_ten_minutes = fps * 600 - dropped * 9;
+            let per_minute = fps * 60 - dropped;
+            let (tens, rest) = (frame / per_ten_minutes, frame % per_ten_minutes);
+            frame += dropped * 9 * tens;
+            if rest > dropped {
+                frame += dropped * ((rest - dropped) / per_minute);
+            }
+        }
+        let seconds = frame / fps;
+        Timecode {
+            hours: (seconds / 3600) as u8,
+            minutes: (seconds / 60 % 60) as u8,
+            seconds: (seconds % 60) as u8,
+            frames: (frame % fps) as u32,
+            drop_frame,
+            rate,
+        }
+    }
+
+    /// Number of frames since `00:00:00:00`, eg. to compare timecodes.
+    pub fn frame_number(&self) -> u64 {
+        let fps = u64::from(nominal_fps(&self.rate).unwrap_or(1));
+        let minutes = u64::from(self.hours) * 60 + u64::from(self.minutes);
+        let frame = (minutes * 60 + u64::from(self.seconds)) * fps + u64::from(self.frames);
+        if self.drop_frame {
+            frame - fps / 15 * (minutes - minutes / 10)
+        } else {
+            frame
+        }
+    }
+
+    /// Timecode `frames` frames later, or earlier for negative values.
+    pub fn add_frames(&self, frames: i64) -> Timecode {
+        let fps = u64::from(nominal_fps(&self.rate).unwrap_or(1));
+        let day = frames_per_day(fps, self.drop_frame) as i64;
+        let frame = (self.frame_number() as i64 + frames % day).rem_euclid(day);
+        Timecode::from_frame_number(frame as u64, self.rate.clone(), self.drop_frame)
+    }
+
+    /// Time since `00:00:00:00` at the real frame rate, eg. 1:00:00;00 drop-frame is
+    /// 3599.9964 s at 29.97 fps.
+    pub fn to_duration(&self) -> Duration {
+        // A frame lasts denominator / numerator seconds.
+        TimeBase::new(self.rate.denominator(), self.rate.numerator())
+            .map(|frame| frame.to_duration(self.frame_number() as i64))
+            .unwrap_or_default()
+    }
+}
+
+/// Frames per second timecode counts, eg. 30 for 29.97 fps. `None` for a zero rate.
+fn nominal_fps(rate: &Ratio) -> Option<u32> {
+    let fps = rate.as_f64()?.round();
+    (fps >= 1.).then_some(fps as u32)
+}
+
+fn frames_per_day(fps: u64, drop_frame: bool) -> u64 {
+    let frames = SECONDS_PER_DAY * fps;
+    if drop_frame {
+        // Dropped at 9 of every 10 minutes.
+        frames - fps / 15 * (24 * 60 - 24 * 6)
+    } else {
+        frames
+    }
+}
+
+impl Display for Timecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.drop_frame { ';' } else { ':' };
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{separator}{:02}",
+            self.hours, self.minutes, self.seconds, self.frames
+        )
+    }
+}
+
+#[cfg(feature = "streams")]
+impl FfProbe {
+    /// Start timecode of the file, from the `timecode` format tag, the `timecode` tag of a
+    /// stream, eg. a QuickTime `tmcd` data stream, or GOP or SMPTE 12-1 timecode side
+    /// data of a video stream, in that order.
+    ///
+    /// The rate is the frame rate of the first video stream, or of the stream carrying
+    /// the timecode. `None` without a timecode or a frame rate.
+    pub fn start_timecode(&self) -> Option<Timecode> {
+        #[cfg(feature = "format")]
+        let format_tag = self.format.all_tags().get("timecode");
+        #[cfg(not(feature = "format"))]
+        let format_tag: Option<&str> = None;
+
+        let rate_of = |stream: &crate::Stream| {
+            [&stream.avg_frame_rate, &stream.r_frame_rate]
+                .into_iter()
+                .find(|r| r.numerator() != 0 && r.denominator() != 0)
+                .cloned()
+        };
+        let video_rate = self
+            .streams
+            .iter()
+            .find(|s| matches!(s.stream, StreamKinds::Video(_)))
+            .and_then(rate_of);
+
+        let (timecode, stream) = format_tag
+            .map(|tc| (tc.to_string(), None))
+            .or_else(|| {
+                self.streams.iter().find_map(|s| {
+                    let tc = s.all_tags().get("timecode")?;
+                    Some((tc.to_string(), Some(s)))
+                })
+            })
+            .or_else(|| {
+                self.streams.iter().find_map(|s| {
+                    let tc = s.side_data_list.iter().find_map(|d| match d {
+                        SideData::GopTimecode(gop) => gop.timecode.clone(),
+                        SideData::SmpteTimecode(smpte) => {
+                            smpte.timecodes.first().map(|t| t.value.clone())
+                        }
+                        _ => None,
+                    })?;
+                    Some((tc, Some(s)))
+                })
+            })?;
+        let rate = video_rate.or_else(|| stream.and_then(rate_of))?;
+        Timecode::parse(&timecode, rate).ok()
+    }
+}
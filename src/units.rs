@@ -0,0 +1,187 @@
+use std::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Sub},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::flexible_num;
+
+/// Bit rate in bits per second.
+///
+/// Displayed with decimal prefixes, eg. `4.50 Mb/s`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Bitrate(pub u64);
+
+/// Size in bytes.
+///
+/// Displayed with binary prefixes, eg. `1.50 GiB`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FileSize(pub u64);
+
+/// Audio sample rate in Hz.
+///
+/// Displayed in kHz, eg. `44.1 kHz`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SampleRate(pub u32);
+
+impl Bitrate {
+    pub fn bits_per_second(self) -> u64 {
+        self.0
+    }
+
+    /// Bit rate needed to store `size` in `duration`.
+    /// `None` for a zero duration.
+    pub fn from_size(size: FileSize, duration: Duration) -> Option<Bitrate> {
+        if duration.is_zero() {
+            return None;
+        }
+        Some(Bitrate(
+            (size.0 as f64 * 8. / duration.as_secs_f64()).round() as u64,
+        ))
+    }
+
+    /// Amount of data at this bit rate over `duration`.
+    pub fn size_for(self, duration: Duration) -> FileSize {
+        FileSize((self.0 as f64 * duration.as_secs_f64() / 8.).round() as u64)
+    }
+}
+
+impl FileSize {
+    pub fn bytes(self) -> u64 {
+        self.0
+    }
+}
+
+impl SampleRate {
+    pub fn hz(self) -> u32 {
+        self.0
+    }
+
+    /// Duration of `samples` samples at this rate.
+    /// `None` for a zero rate, or beyond [`Duration::MAX`].
+    pub fn duration_of(self, samples: u64) -> Option<Duration> {
+        if self.0 == 0 {
+            return None;
+        }
+        Duration::try_from_secs_f64(samples as f64 / self.0 as f64).ok()
+    }
+}
+
+impl Display for Bitrate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bps = self.0 as f64;
+        match self.0 {
+            0..=999 => write!(f, "{} b/s", self.0),
+            1_000..=999_999 => write!(f, "{:.0} kb/s", bps / 1e3),
+            1_000_000..=999_999_999 => write!(f, "{:.2} Mb/s", bps / 1e6),
+            _ => write!(f, "{:.2} Gb/s", bps / 1e9),
+        }
+    }
+}
+
+impl Display for FileSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64;
+        let mut unit = UNITS[0];
+        for u in UNITS {
+            value /= 1024.;
+            unit = u;
+            if value < 1024. {
+                break;
+            }
+        }
+        write!(f, "{:.2} {}", value, unit)
+    }
+}
+
+impl Display for SampleRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} kHz", self.0 as f64 / 1000.)
+    }
+}
+
+macro_rules! unit_impls {
+    ($name:ident, $inner:ty) => {
+        impl From<$inner> for $name {
+            fn from(value: $inner) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+            fn add(self, rhs: $name) -> $name {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: $name) {
+                self.0 += rhs.0;
+            }
+        }
+
+        /// Saturates at zero.
+        impl Sub for $name {
+            type Output = $name;
+            fn sub(self, rhs: $name) -> $name {
+                $name(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl Mul<$inner> for $name {
+            type Output = $name;
+            fn mul(self, rhs: $inner) -> $name {
+                $name(self.0 * rhs)
+            }
+        }
+
+        impl Div<$inner> for $name {
+            type Output = $name;
+            fn div(self, rhs: $inner) -> $name {
+                $name(self.0 / rhs)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> $name {
+                iter.fold($name(0), Add::add)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = std::num::ParseIntError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.trim().parse().map($name)
+            }
+        }
+
+        /// Accepts both numbers and the numeric strings reported by ffprobe.
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                flexible_num::deserialize(deserializer).map($name)
+            }
+        }
+    };
+}
+
+unit_impls!(Bitrate, u64);
+unit_impls!(FileSize, u64);
+unit_impls!(SampleRate, u32);
@@ -0,0 +1,108 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::{path::PathBuf, time::Duration};
+
+use ffprobe::{Config, ProbeCommand, ReadInterval};
+
+mod common;
+
+fn config(bin: &PathBuf) -> Config {
+    Config::new()
+        .ffprobe_bin(bin)
+        .count_frames(true)
+        .count_packets(true)
+        .read_intervals(vec![ReadInterval::start(Duration::from_secs(10)).frames(5)])
+}
+
+fn expected_args(bin: &PathBuf, input: &PathBuf) -> String {
+    ProbeCommand::new(config(bin))
+        .input(input)
+        .get_args()
+        .iter()
+        .map(|a| format!("{}\n", a.to_str().unwrap()))
+        .collect()
+}
+
+#[test]
+fn blocking_matches_probe_command() {
+    let dir = common::temp_dir("parity", "blocking");
+    let (bin, args) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let input = common::input(&dir, "video.mp4");
+    config(&bin).run(&input).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(args).unwrap(),
+        expected_args(&bin, &input)
+    );
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+fn async_matches_probe_command() {
+    use ffprobe::{ffprobe_async_with, runtime::AsyncStd};
+
+    let dir = common::temp_dir("parity", "async");
+    let (bin, args) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let input = common::input(&dir, "video.mp4");
+    common::block_on(ffprobe_async_with::<AsyncStd>(config(&bin), &input)).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(args).unwrap(),
+        expected_args(&bin, &input)
+    );
+}
+
+/// ffprobe body without `-show_chapters`, printing `probe.json` and one packet for the
+/// tail probe.
+const LEGACY_FFPROBE: &str = r#"case "$*" in
+  *packet=*) echo '{"packets": [{"pts_time": "12.5", "duration_time": "0.5"}]}' ;;
+  *-show_chapters*) echo "Unrecognized option 'show_chapters'" >&2; exit 1 ;;
+  *) cat probe.json ;;
+esac"#;
+
+/// Temp dir with the input and `probe.json`, the mp4 fixture without durations.
+fn legacy_dir(name: &str) -> (PathBuf, PathBuf) {
+    let dir = common::temp_dir("parity", name);
+    let mut json = common::fixture_json("mp4.json");
+    json["format"].as_object_mut().unwrap().remove("duration");
+    for stream in json["streams"].as_array_mut().unwrap() {
+        stream.as_object_mut().unwrap().remove("duration_ts");
+    }
+    common::write(&dir, "probe.json", json.to_string());
+    let input = common::input(&dir, "video.mp4");
+    (dir, input)
+}
+
+#[test]
+fn blocking_fallback_and_estimate() {
+    let (dir, input) = legacy_dir("legacy-blocking");
+    let (bin, _) = common::appending(&dir, "ffprobe", LEGACY_FFPROBE);
+    let config = Config::new().ffprobe_bin(bin).estimate_duration(true);
+    let probe = config.run(&input).unwrap();
+    assert_eq!(probe.dialect, ffprobe::Dialect::Legacy);
+    assert!(probe.estimated_duration.is_some());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_matches_blocking() {
+    use ffprobe::{ffprobe_async_with, runtime::Tokio};
+
+    let (dir, input) = legacy_dir("legacy-tokio");
+    let (bin, _) = common::appending(&dir, "ffprobe", LEGACY_FFPROBE);
+    let config = Config::new().ffprobe_bin(bin).estimate_duration(true);
+    let blocking = config.clone().run(&input).unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let probe = runtime
+        .block_on(ffprobe_async_with::<Tokio>(config, &input))
+        .unwrap();
+    // The dialect fallback and the duration estimate as well.
+    assert_eq!(probe, blocking);
+}
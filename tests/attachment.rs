@@ -0,0 +1,24 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::AttachmentStream;
+
+fn attachment(filename: &str, mimetype: &str) -> AttachmentStream {
+    serde_json::from_value(serde_json::json!({
+        "duration_ts": 0,
+        "tags": { "filename": filename, "mimetype": mimetype },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn attachment_kinds() {
+    let font = attachment("Arial.TTF", "application/x-truetype-font");
+    assert!(font.is_font());
+    assert!(!font.is_cover_art());
+    assert!(attachment("font.otf", "application/octet-stream").is_font());
+
+    let cover = attachment("small_cover.jpg", "image/jpeg");
+    assert!(cover.is_cover_art());
+    assert!(!cover.is_font());
+    assert!(!attachment("screenshot.png", "image/png").is_cover_art());
+}
@@ -0,0 +1,53 @@
+#![cfg(all(unix, feature = "process"))]
+
+use ffprobe::{analysis::audio_peaks, Config};
+
+mod common;
+
+const CLIPPED: &str = "\
+[Parsed_volumedetect_0 @ 0x5581] n_samples: 960000
+[Parsed_volumedetect_0 @ 0x5581] mean_volume: -21.3 dB
+[Parsed_volumedetect_0 @ 0x5581] max_volume: 0.0 dB
+[Parsed_volumedetect_0 @ 0x5581] histogram_0db: 40
+[Parsed_astats_1 @ 0x5582] Channel: 1
+[Parsed_astats_1 @ 0x5582] DC offset: 0.000012
+[Parsed_astats_1 @ 0x5582] Peak level dB: 0.000000
+[Parsed_astats_1 @ 0x5582] Peak count: 12
+[Parsed_astats_1 @ 0x5582] Channel: 2
+[Parsed_astats_1 @ 0x5582] Peak level dB: -3.521000
+[Parsed_astats_1 @ 0x5582] Peak count: 2
+[Parsed_astats_1 @ 0x5582] Overall
+[Parsed_astats_1 @ 0x5582] Peak level dB: 0.000000
+[Parsed_astats_1 @ 0x5582] Peak count: 7.000000
+";
+
+#[test]
+fn clipped_master() {
+    let (bin, args) = common::fake_ffmpeg(&common::temp_dir("audio-peaks", "clipped"), CLIPPED);
+    let peaks = audio_peaks("master.wav", 1, &Config::new().ffmpeg_bin(&bin)).unwrap();
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-map\n0:1\n-filter:a\nvolumedetect,astats\n"));
+
+    assert_eq!(peaks.max_volume, 0.);
+    assert_eq!(peaks.mean_volume, -21.3);
+    assert_eq!(peaks.channel_peaks, [0., -3.521]);
+    assert_eq!(peaks.clipped_samples, 12);
+    assert!(peaks.is_clipped());
+}
+
+#[test]
+fn headroom() {
+    let log = CLIPPED
+        .replace("max_volume: 0.0", "max_volume: -1.2")
+        .replace("Peak level dB: 0.000000", "Peak level dB: -1.200000");
+    let (bin, _) = common::fake_ffmpeg(&common::temp_dir("audio-peaks", "headroom"), &log);
+    let peaks = audio_peaks("master.wav", 1, &Config::new().ffmpeg_bin(&bin)).unwrap();
+    assert_eq!(peaks.max_volume, -1.2);
+    assert!(!peaks.is_clipped());
+
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("audio-peaks", "missing"),
+        "Output #0, null, to 'pipe:':\n",
+    );
+    assert!(audio_peaks("master.wav", 1, &Config::new().ffmpeg_bin(&bin)).is_err());
+}
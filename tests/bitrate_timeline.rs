@@ -0,0 +1,49 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::time::Duration;
+
+use ffprobe::{analysis::bitrate_timeline, Bitrate, Config};
+use serde_json::json;
+
+mod common;
+
+#[test]
+fn packets_in_buckets() {
+    let dir = common::temp_dir("bitrate", "buckets");
+    let packets = json!({ "packets": [
+        { "pts_time": "0.000000", "dts_time": "-0.040000", "size": "1000" },
+        { "pts_time": "0.500000", "dts_time": "0.460000", "size": "1000" },
+        { "dts_time": "2.200000", "size": "500" },
+        { "pts_time": "2.900000" },
+    ]});
+    let (bin, _) = common::fake_ffprobe(&dir, packets.to_string());
+    let input = common::input(&dir, "video.mp4");
+    let config = Config::new().ffprobe_bin(&bin);
+
+    let samples = bitrate_timeline(&input, 0, Duration::from_secs(1), &config).unwrap();
+    let samples: Vec<_> = samples
+        .iter()
+        .map(|s| (s.timestamp, s.bitrate, s.bytes))
+        .collect();
+    assert_eq!(
+        samples,
+        [
+            (Duration::ZERO, Bitrate(16_000), 2000),
+            (Duration::from_secs(1), Bitrate(0), 0),
+            (Duration::from_secs(2), Bitrate(4000), 500),
+        ]
+    );
+
+    let halves = bitrate_timeline(&input, 0, Duration::from_millis(500), &config).unwrap();
+    assert_eq!(halves.len(), 5);
+    assert_eq!(halves[4].timestamp, Duration::from_millis(2000));
+    assert_eq!(halves[0].bitrate, Bitrate(16_000));
+
+    assert!(bitrate_timeline(&input, 0, Duration::ZERO, &config).is_err());
+}
@@ -0,0 +1,92 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use std::time::Duration;
+
+use ffprobe::{
+    bridge::StreamSelection, mapping::MappingPreferences, FfProbe, Format, StreamIndex, StreamKinds,
+};
+use serde_json::{json, Value};
+
+fn fixture() -> Value {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+    serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+}
+
+fn probe(json: Value) -> FfProbe {
+    FfProbe::from_json(&json.to_string()).unwrap()
+}
+
+#[test]
+fn map_args() {
+    let mut json = fixture();
+    let mut cover = json["streams"][0].clone();
+    cover["index"] = json!(3);
+    cover["codec_name"] = json!("mjpeg");
+    cover["disposition"]["attached_pic"] = json!(1);
+    json["streams"].as_array_mut().unwrap().push(cover);
+    let probe = probe(json);
+
+    assert_eq!(
+        probe.to_map_args(0, &StreamSelection::All),
+        ["-map", "0:0", "-map", "0:1", "-map", "0:2"]
+    );
+    assert_eq!(
+        probe.to_map_args(1, &StreamSelection::Video),
+        ["-map", "1:0"]
+    );
+    assert_eq!(
+        probe.to_map_args(0, &StreamSelection::Subtitle),
+        ["-map", "0:2"]
+    );
+    // Cover art by index, a missing stream is skipped.
+    assert_eq!(
+        probe.to_map_args(
+            0,
+            &StreamSelection::Indices(vec![StreamIndex(3), StreamIndex(1), StreamIndex(7)])
+        ),
+        ["-map", "0:3", "-map", "0:1"]
+    );
+
+    let mapping = probe.suggest_mapping(&MappingPreferences::new());
+    assert_eq!(probe.to_map_args(0, &mapping.into()), mapping.map_args(0));
+}
+
+#[test]
+fn decoder_args() {
+    let probe = probe(fixture());
+    let StreamKinds::Video(video) = &probe.streams[0].stream else {
+        panic!("not a video stream");
+    };
+    assert_eq!(video.hwaccel_pix_fmt(), Some("nv12"));
+    assert_eq!(video.decoder_args(), ["-hwaccel", "auto"]);
+
+    let mut video = video.clone();
+    video.pix_fmt = Some("yuv420p10le".to_string());
+    assert_eq!(video.hwaccel_pix_fmt(), None);
+    assert!(video.decoder_args().is_empty());
+    video.codec_name = "hevc".to_string();
+    assert_eq!(video.hwaccel_pix_fmt(), Some("p010le"));
+    video.pix_fmt = Some("yuv422p10le".to_string());
+    assert_eq!(video.hwaccel_pix_fmt(), None);
+    video.pix_fmt = None;
+    assert!(video.decoder_args().is_empty());
+}
+
+#[test]
+fn seekable() {
+    let probe = probe(fixture());
+    assert!(probe.format.seekable());
+
+    let mut format = Format::new("pipe:0", "mov,mp4,m4a,3gp,3g2,mj2");
+    format.duration = Some(Duration::from_secs(60));
+    assert!(!format.seekable());
+    format.filename = "rtmps://live.example.com/app/key".to_string();
+    assert!(!format.seekable());
+    format.filename = "https://example.com/video.mp4".to_string();
+    assert!(format.seekable());
+    format.format_name = "png_pipe".to_string();
+    assert!(!format.seekable());
+    format.format_name = "mpegts".to_string();
+    format.duration = None;
+    assert!(!format.seekable());
+}
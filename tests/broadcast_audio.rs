@@ -0,0 +1,149 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{AudioStream, BroadcastAudioKind, DolbyEProgramConfig, SampleRate};
+
+#[cfg(all(unix, feature = "process"))]
+mod common;
+
+#[test]
+fn broadcast_kind() {
+    let kind = |codec| AudioStream::new(codec, SampleRate(48000), 2).broadcast_kind();
+    assert_eq!(kind("s302m"), Some(BroadcastAudioKind::Smpte302m));
+    assert_eq!(kind("dolby_e"), Some(BroadcastAudioKind::DolbyE));
+    assert_eq!(kind("pcm_s24le"), Some(BroadcastAudioKind::Pcm));
+    assert_eq!(kind("aac"), None);
+}
+
+#[test]
+fn program_configs() {
+    let config = DolbyEProgramConfig::new(0).unwrap();
+    assert_eq!(config.to_string(), "5.1+2");
+    assert_eq!(config.programs(), [6, 2]);
+    assert_eq!(config.channels(), 8);
+    let config = DolbyEProgramConfig::new(20).unwrap();
+    assert_eq!(config.to_string(), "2+1+1");
+    assert_eq!(config.channels(), 4);
+    assert_eq!(
+        DolbyEProgramConfig::new(23).unwrap().to_string(),
+        "7.1 screen"
+    );
+    assert_eq!(DolbyEProgramConfig::new(24), None);
+}
+
+#[cfg(all(unix, feature = "process"))]
+mod detect {
+    use ffprobe::{detect_dolby_e, Config, Ratio};
+
+    use crate::common;
+
+    /// Format `data` like ffprobe's `-show_data`.
+    fn hex_dump(data: &[u8]) -> String {
+        let mut dump = String::from("\n");
+        for (line, chunk) in data.chunks(16).enumerate() {
+            let hex: Vec<String> = chunk
+                .chunks(2)
+                .map(|g| g.iter().map(|b| format!("{b:02x}")).collect())
+                .collect();
+            dump.push_str(&format!("{:08x}: {:<40} ....\n", line * 16, hex.join(" ")));
+        }
+        dump
+    }
+
+    /// ffprobe output of a stream with `codec` and the given packets.
+    fn packets_json(codec: &str, packets: &[Vec<u8>]) -> String {
+        let packets: Vec<String> = packets
+            .iter()
+            .map(|p| format!(r#"{{"codec_type": "audio", "data": {:?}}}"#, hex_dump(p)))
+            .collect();
+        format!(
+            r#"{{"packets": [{}], "streams": [{{"codec_name": "{codec}"}}]}}"#,
+            packets.join(",")
+        )
+    }
+
+    /// SMPTE 302M packet of interleaved 20 bit samples, with bytes bit reversed as on the
+    /// wire and no AES3 flags.
+    fn s302m_packet(channels: u32, samples: &[u32]) -> Vec<u8> {
+        let r = |v: u32| (v as u8).reverse_bits();
+        let mut data = Vec::new();
+        for &s in samples {
+            data.extend([r(s), r(s >> 8), r(s >> 16 & 0x0f)]);
+        }
+        let header = (data.len() as u32) << 16 | ((channels - 2) / 2) << 14 | 1 << 4;
+        let mut packet = header.to_be_bytes().to_vec();
+        packet.extend(data);
+        packet
+    }
+
+    #[test]
+    fn dolby_e_in_s302m() {
+        let burst = [
+            // Preamble: Pa, Pb, Pc for Dolby E in 20 bit mode, Pd with the burst length.
+            0x6f872,
+            0x54e1f,
+            0x3c << 4,
+            0x1000,
+            // Sync word, metadata size 3, 5.1+1+1, 25 fps.
+            0x0788e,
+            3 << 6 | 1,
+            3 << 16 | 3 << 12,
+            0,
+        ];
+        // Audio on channels 1 and 2, Dolby E on 3 and 4 after the guard band.
+        let mut samples = [0x12345, 0x54321, 0, 0].repeat(6);
+        for words in burst.chunks(2) {
+            samples.extend([0x12345, 0x54321, words[0], words[1]]);
+        }
+        // The burst starts in one packet and continues in the next.
+        let packets = [
+            s302m_packet(4, &samples[..28]),
+            s302m_packet(4, &samples[28..]),
+        ];
+        let dir = common::temp_dir("dolby-e", "s302m");
+        let (bin, args) = common::fake_ffprobe(&dir, packets_json("s302m", &packets));
+        let config = Config::new().ffprobe_bin(&bin);
+        let input = common::input(&dir, "feed.ts");
+
+        let dolby_e = detect_dolby_e(&input, 1, &config).unwrap().unwrap();
+        assert_eq!(dolby_e.channel, 2);
+        assert_eq!(dolby_e.bit_depth, 20);
+        assert_eq!(dolby_e.program_config.to_string(), "5.1+1+1");
+        assert_eq!(dolby_e.program_config.programs().len(), 3);
+        assert_eq!(dolby_e.frame_rate, Some(Ratio::new(25, 1)));
+
+        let args = std::fs::read_to_string(args).unwrap();
+        assert!(args.contains("-select_streams\n1\n"));
+        assert!(args.contains("-show_data\n"));
+        assert!(args.contains("%+#10\n"));
+
+        // Audio only.
+        let dir = common::temp_dir("dolby-e", "pcm");
+        let (bin, _) = common::fake_ffprobe(
+            &dir,
+            packets_json("s302m", &[s302m_packet(4, &samples[..24])]),
+        );
+        let config = Config::new().ffprobe_bin(&bin);
+        let input = common::input(&dir, "feed.ts");
+        assert_eq!(detect_dolby_e(&input, 1, &config).unwrap(), None);
+    }
+
+    #[test]
+    fn dolby_e_stream() {
+        // 16 bit frame with a key, 7.1 at 29.97 fps.
+        let key = 0x1234;
+        let mut frame = vec![0x07, 0x8f, 0x12, 0x34];
+        for word in [3 << 2 | 22 >> 4, (22 & 0xf) << 12 | 4 << 8 | 4 << 4, 0] {
+            frame.extend(((word ^ key) as u16).to_be_bytes());
+        }
+        let dir = common::temp_dir("dolby-e", "native");
+        let (bin, _) = common::fake_ffprobe(&dir, packets_json("dolby_e", &[frame]));
+        let config = Config::new().ffprobe_bin(&bin);
+        let input = common::input(&dir, "feed.ts");
+
+        let dolby_e = detect_dolby_e(&input, 0, &config).unwrap().unwrap();
+        assert_eq!(dolby_e.channel, 0);
+        assert_eq!(dolby_e.bit_depth, 16);
+        assert_eq!(dolby_e.program_config.to_string(), "7.1");
+        assert_eq!(dolby_e.frame_rate, Some(Ratio::new(30000, 1001)));
+    }
+}
@@ -0,0 +1,118 @@
+#![cfg(all(unix, feature = "streams", feature = "format", feature = "process"))]
+
+use ffprobe::{ByteLayout, Config, StreamByteRange};
+
+mod common;
+
+/// ffprobe body printing the mp4 fixture, the `trace` file on stderr for the header run
+/// and `packets.json` for the packet run.
+const LAYOUT_FFPROBE: &str = r#"case "$*" in
+  *trace*) cat trace >&2; echo '{"format": {"format_name": "mov,mp4,m4a,3gp,3g2,mj2", "size": "366300000"}}' ;;
+  *packet=*) cat packets.json ;;
+  *) cat "$fixture" ;;
+esac"#;
+
+const PACKETS: &str = r#"{"packets": [
+    {"stream_index": 0, "pos": "5048", "size": "1000"},
+    {"stream_index": 1, "pos": "6048", "size": "200"},
+    {"stream_index": 0, "pos": "6248", "size": "366293752"},
+    {"stream_index": 2, "pos": "N/A", "size": "10"}
+]}"#;
+
+#[test]
+fn faststart() {
+    let trace = "\
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] Format mov,mp4,m4a,3gp,3g2,mj2 probed with size=2048
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'ftyp' parent:'root' sz: 32 8 366300000
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'moov' parent:'root' sz: 5000 40 366300000
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'mvhd' parent:'moov' sz: 108 8 4992
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'mdat' parent:'root' sz: 366294960 5048 366300000
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'mdat' parent:'root' sz: 100 8 366294968
+";
+    let dir = common::temp_dir("layout", "faststart");
+    common::write(&dir, "trace", trace);
+    common::write(&dir, "packets.json", PACKETS);
+    let (bin, args) = common::appending(&dir, "ffprobe", LAYOUT_FFPROBE);
+    let input = common::input(&dir, "movie.mp4");
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .byte_layout(true)
+        .run(&input)
+        .unwrap();
+    let layout = probe.byte_layout.as_ref().unwrap();
+
+    let boxes: Vec<_> = layout
+        .boxes
+        .iter()
+        .map(|b| (b.kind.as_str(), b.offset, b.size))
+        .collect();
+    assert_eq!(
+        boxes,
+        [
+            ("ftyp", 0, 32),
+            ("moov", 32, 5000),
+            ("mdat", 5032, 366_294_968)
+        ]
+    );
+    assert_eq!(layout.file_size, Some(366_300_000));
+    assert_eq!(layout.streams.len(), 2);
+    assert_eq!(layout.streams[0].first_pos, 5048);
+    assert_eq!(layout.streams[0].end, 366_300_000);
+    assert_eq!(layout.streams[0].packets, 2);
+    assert_eq!(layout.streams[1].bytes, 200);
+    assert_eq!(layout.header_size(), Some(5048));
+    assert_eq!(layout.trailer_size(), Some(0));
+    assert_eq!(probe.is_faststart(), Some(true));
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-v\ntrace\n"));
+    assert!(args.contains("packet=stream_index,pos,size\n"));
+}
+
+#[test]
+fn moov_at_the_end() {
+    let trace = "\
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'ftyp' parent:'root' sz: 32 8 366300000
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'free' parent:'root' sz: 8 40 366300000
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'mdat' parent:'root' sz: 366294952 56 366300000
+[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d0] type:'moov' parent:'root' sz: 5000 366295008 366300000
+";
+    let dir = common::temp_dir("layout", "moov-end");
+    common::write(&dir, "trace", trace);
+    common::write(&dir, "packets.json", r#"{"packets": []}"#);
+    let (bin, _) = common::appending(&dir, "ffprobe", LAYOUT_FFPROBE);
+    let input = common::input(&dir, "movie.mp4");
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .byte_layout(true)
+        .run(&input)
+        .unwrap();
+    let layout = probe.byte_layout.as_ref().unwrap();
+    assert_eq!(layout.find_box("moov").unwrap().offset, 366_295_000);
+    assert_eq!(layout.find_box("mdat").unwrap().offset, 40);
+    assert_eq!(layout.header_size(), None);
+    assert_eq!(probe.is_faststart(), Some(false));
+
+    // Without the layout.
+    let probe = Config::new()
+        .ffprobe_bin(input.with_file_name("ffprobe"))
+        .run(&input)
+        .unwrap();
+    assert_eq!(probe.byte_layout, None);
+    assert_eq!(probe.is_faststart(), None);
+}
+
+#[test]
+fn packet_heuristic() {
+    let mut layout = ByteLayout::default();
+    layout.file_size = Some(10_000);
+    let mut video = StreamByteRange::default();
+    video.first_pos = 48;
+    video.end = 9_000;
+    layout.streams.push(video);
+    assert_eq!(layout.data_range(), Some(48..9_000));
+    assert_eq!(layout.trailer_size(), Some(1_000));
+    assert_eq!(layout.is_faststart(), Some(false));
+    layout.streams[0].first_pos = 1_200;
+    assert_eq!(layout.is_faststart(), Some(true));
+}
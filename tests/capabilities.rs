@@ -0,0 +1,100 @@
+#![cfg(all(unix, feature = "process"))]
+
+use ffprobe::{
+    capabilities::{build_info, decoders, demuxers, pixel_formats, protocols, DecoderKind},
+    Config,
+};
+
+mod common;
+
+#[test]
+fn list_decoders() {
+    let bin = common::capabilities_ffprobe(&common::temp_dir("capabilities", "decoders"));
+    let decoders = decoders(&Config::new().ffprobe_bin(bin)).unwrap();
+    let summary: Vec<_> = decoders
+        .iter()
+        .map(|d| (d.name.as_str(), d.kind, d.codec(), d.experimental))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("012v", DecoderKind::Video, "012v", false),
+            ("h264", DecoderKind::Video, "h264", false),
+            ("libdav1d", DecoderKind::Video, "av1", false),
+            ("aac", DecoderKind::Audio, "aac", false),
+            ("opus_exp", DecoderKind::Audio, "opus_exp", true),
+            ("subrip", DecoderKind::Subtitle, "subrip", false),
+        ]
+    );
+    assert_eq!(
+        decoders[1].description,
+        "H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10"
+    );
+}
+
+#[test]
+fn list_demuxers() {
+    let bin = common::capabilities_ffprobe(&common::temp_dir("capabilities", "demuxers"));
+    let demuxers = demuxers(&Config::new().ffprobe_bin(bin)).unwrap();
+    assert_eq!(demuxers.len(), 3);
+    assert!(demuxers[1].is_device && demuxers[1].name == "alsa");
+    assert_eq!(demuxers[2].description, "QuickTime / MOV");
+    assert!(demuxers[2].names().any(|n| n == "mp4"));
+    assert!(!demuxers[2].is_device);
+}
+
+#[test]
+fn list_protocols() {
+    let bin = common::capabilities_ffprobe(&common::temp_dir("capabilities", "protocols"));
+    let protocols = protocols(&Config::new().ffprobe_bin(bin)).unwrap();
+    assert_eq!(protocols.input, ["file", "http", "https", "srt"]);
+    assert_eq!(protocols.output, ["file", "rtmp"]);
+}
+
+#[test]
+fn list_pixel_formats() {
+    let bin = common::capabilities_ffprobe(&common::temp_dir("capabilities", "pixel-formats"));
+    let formats = pixel_formats(&Config::new().ffprobe_bin(bin)).unwrap();
+    let summary: Vec<_> = formats
+        .iter()
+        .map(|f| {
+            let flags = (f.planar, f.rgb, f.alpha, f.hwaccel);
+            (f.name.as_str(), f.bits_per_pixel, f.bit_depth, flags)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                "yuv420p10le",
+                Some(15),
+                Some(10),
+                (true, false, false, false)
+            ),
+            ("rgba", Some(32), Some(8), (false, true, true, false)),
+            ("vaapi", None, None, (false, false, false, true)),
+        ]
+    );
+}
+
+#[test]
+fn build_configuration() {
+    let bin = common::capabilities_ffprobe(&common::temp_dir("capabilities", "build-info"));
+    let info = build_info(&Config::new().ffprobe_bin(bin)).unwrap();
+    assert_eq!(info.version, "7.0.1");
+    assert_eq!(info.compiler.as_deref(), Some("gcc 13.2.0 (GCC)"));
+    assert_eq!(info.configuration[1], "--extra-cflags=-O2 -pipe");
+    assert!(info.is_gpl() && !info.is_nonfree());
+    assert!(info.enabled("libdav1d") && !info.enabled("doc"));
+    assert_eq!(
+        info.enabled_features().collect::<Vec<_>>(),
+        ["gpl", "version3", "libdav1d", "libx264"]
+    );
+
+    let avformat = info.library("avformat").unwrap();
+    assert_eq!(avformat, info.library("libavformat").unwrap());
+    assert_eq!(avformat.to_string(), "libavformat 61.1.100");
+    assert!(avformat.at_least(61, 1, 100) && avformat.at_least(60, 16, 100));
+    assert!(!avformat.at_least(61, 2, 0));
+    assert!(info.library("swscale").is_none());
+}
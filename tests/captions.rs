@@ -0,0 +1,72 @@
+#![cfg(all(unix, feature = "streams", feature = "process"))]
+
+use ffprobe::{caption_services, CaptionService, Config, VideoStream};
+
+mod common;
+
+/// Format `data` like ffprobe's `-show_data`.
+fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::from("\n");
+    for (line, chunk) in data.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk
+            .chunks(2)
+            .map(|g| g.iter().map(|b| format!("{b:02x}")).collect())
+            .collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+            .collect();
+        dump.push_str(&format!(
+            "{:08x}: {:<40} {ascii}\n",
+            line * 16,
+            hex.join(" ")
+        ));
+    }
+    dump
+}
+
+#[test]
+fn detect_caption_services() {
+    let packets = [
+        // CC1 resume caption loading, CC3 end of caption, padding.
+        vec![0xfc, 0x94, 0x20, 0xfd, 0x15, 0x2f, 0xfa, 0x00, 0x00],
+        // CC2 control code, then a DTVCC packet with blocks for services 1 and 2.
+        vec![
+            0xfc, 0x1c, 0x20, 0xff, 0x03, 0x21, 0xfe, 0x41, 0x42, 0xfe, 0x00, 0x00,
+        ],
+        // Extended service 9, the service number byte follows the block header.
+        vec![0xff, 0x02, 0xe1, 0xfe, 0x09, 0x41],
+        // Invalid triplets are ignored.
+        vec![0xf8, 0x97, 0x20],
+    ];
+    let packets: Vec<String> = packets
+        .iter()
+        .map(|p| format!(r#"{{"codec_type": "subtitle", "data": {:?}}}"#, hex_dump(p)))
+        .collect();
+
+    let dir = common::temp_dir("captions", "services");
+    let (bin, args) =
+        common::fake_ffprobe(&dir, format!(r#"{{"packets": [{}]}}"#, packets.join(",")));
+
+    let services =
+        caption_services("/media/news: 10pm.ts", &Config::new().ffprobe_bin(&bin)).unwrap();
+    assert_eq!(
+        services,
+        [
+            CaptionService::Cea608 { channel: 1 },
+            CaptionService::Cea608 { channel: 2 },
+            CaptionService::Cea608 { channel: 3 },
+            CaptionService::Cea708 { service: 1 },
+            CaptionService::Cea708 { service: 2 },
+            CaptionService::Cea708 { service: 9 },
+        ]
+    );
+    assert_eq!(services[0].to_string(), "CC1");
+    let args = std::fs::read_to_string(&args).unwrap();
+    assert!(args.contains(r"movie=/media/news\\: 10pm.ts[out0+subcc]"));
+
+    let mut video = VideoStream::new("mpeg2video", 1920, 1080);
+    assert!(!video.has_closed_captions());
+    video.closed_captions = 1;
+    assert!(video.has_closed_captions());
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{Channel, ChannelLayout};
+
+#[test]
+fn parse_channel_layouts() {
+    let layout: ChannelLayout = "5.1(side)".parse().unwrap();
+    assert_eq!(layout.channel_count(), 6);
+    assert_eq!(layout.main_channel_count(), 5);
+    assert!(layout.has_lfe());
+    assert_eq!(layout.channels().unwrap()[4], Channel::SL);
+    assert_eq!(layout.to_string(), "5.1(side)");
+
+    let stereo: ChannelLayout = "stereo".parse().unwrap();
+    assert_eq!(stereo.channels(), Some(&[Channel::FL, Channel::FR][..]));
+    assert!(!stereo.has_lfe());
+
+    let custom: ChannelLayout = "FL+FR+LFE+USR3".parse().unwrap();
+    assert_eq!(custom.channel_count(), 4);
+    assert_eq!(custom.channels().unwrap()[3], Channel::Other("USR3".into()));
+
+    let unknown: ChannelLayout = "6 channels".parse().unwrap();
+    assert_eq!(unknown.channel_count(), 6);
+    assert_eq!(unknown.channels(), None);
+
+    let ambisonic: ChannelLayout = "ambisonic 2+stereo".parse().unwrap();
+    assert_eq!(ambisonic.channel_count(), 11);
+    assert_eq!(ambisonic.ambisonic_order(), Some(2));
+    assert_eq!(
+        ambisonic.channels().unwrap()[8],
+        Channel::Other("AMBI8".into())
+    );
+    assert_eq!(ambisonic.channels().unwrap()[10], Channel::FR);
+    assert_eq!(stereo.ambisonic_order(), None);
+
+    assert!("".parse::<ChannelLayout>().is_err());
+    let json = serde_json::to_string(&layout).unwrap();
+    assert_eq!(json, r#""5.1(side)""#);
+    assert_eq!(
+        serde_json::from_str::<ChannelLayout>(&json).unwrap(),
+        layout
+    );
+}
@@ -0,0 +1,97 @@
+#![cfg(all(feature = "chapters", feature = "format"))]
+
+use std::time::Duration;
+
+use ffprobe::{validate_chapters, Chapter, ChapterWarning, FfProbe, Format, Ratio};
+
+#[test]
+fn chapter_times() {
+    let chapter = Chapter::new(1, Ratio::new(1, 44_100), 44_100, 66_150, "One");
+    assert_eq!(chapter.start_time(), Duration::from_secs(1));
+    assert_eq!(chapter.end_time(), Duration::from_millis(1500));
+    assert_eq!(chapter.duration(), Some(Duration::from_millis(500)));
+
+    let before_zero = Chapter::new(2, Ratio::new(1, 1000), -500, 1000, "Pre-roll");
+    assert_eq!(before_zero.start_nanos(), Some(-500_000_000));
+    assert_eq!(before_zero.start_time(), Duration::ZERO);
+    assert_eq!(before_zero.duration(), Some(Duration::from_millis(1500)));
+
+    let backwards = Chapter::new(3, Ratio::new(1, 1000), 2000, 1000, "Backwards");
+    assert_eq!(backwards.duration(), None);
+}
+
+#[test]
+fn chapter_warnings() {
+    let ms = || Ratio::new(1, 1000);
+    let chapters = [
+        Chapter::new(0, ms(), 0, 10_000, "A"),
+        // Touching the previous chapter is fine, mixed time bases are compared in time.
+        Chapter::new(1, Ratio::new(1, 90_000), 900_000, 1_800_000, "B"),
+        Chapter::new(2, ms(), 15_000, 25_000, "C"),
+        Chapter::new(3, ms(), 30_000, 28_000, "D"),
+        Chapter::new(4, ms(), 5_000, 6_000, "E"),
+        Chapter::new(5, Ratio::new(1, 0), 0, 1, "F"),
+    ];
+    assert_eq!(
+        validate_chapters(&chapters),
+        [
+            ChapterWarning::Overlap { id: 2, previous: 1 },
+            ChapterWarning::EndBeforeStart { id: 3 },
+            ChapterWarning::OutOfOrder { id: 4, previous: 3 },
+            ChapterWarning::InvalidTimeBase { id: 5 },
+        ]
+    );
+    assert_eq!(
+        ChapterWarning::Overlap { id: 2, previous: 1 }.to_string(),
+        "chapter 2 starts before chapter 1 ends"
+    );
+
+    let mut format = Format::new("movie.mkv", "matroska,webm");
+    format.duration = Some(Duration::from_secs(20));
+    let probe = FfProbe::new()
+        .with_format(format)
+        .with_chapter(Chapter::new(0, ms(), 0, 10_000, "A"))
+        .with_chapter(Chapter::new(1, ms(), 10_000, 20_500, "B"))
+        .with_chapter(Chapter::new(2, ms(), 20_500, 40_000, "C"));
+    assert_eq!(
+        probe.chapter_warnings(),
+        [ChapterWarning::PastEnd { id: 2 }]
+    );
+}
+
+#[test]
+fn chapter_lookup_and_export() {
+    let ms = || Ratio::new(1, 1000);
+    let probe = FfProbe::new()
+        .with_chapter(Chapter::new(
+            1,
+            ms(),
+            90_000,
+            3_725_500,
+            "Part 2: <b> & =more=",
+        ))
+        .with_chapter(Chapter::new(0, ms(), 0, 90_000, "Intro"))
+        .with_chapter(Chapter::new(2, ms(), 3_725_500, 3_726_000, ""));
+
+    let sorted: Vec<_> = probe.chapters_sorted().iter().map(|c| c.id).collect();
+    assert_eq!(sorted, [0, 1, 2]);
+    assert_eq!(probe.chapter_at(Duration::ZERO).unwrap().id, 0);
+    assert_eq!(probe.chapter_at(Duration::from_secs(90)).unwrap().id, 1);
+    assert_eq!(probe.chapter_at(Duration::from_secs(3726)), None);
+    assert_eq!(probe.chapters[2].title(), None);
+
+    assert_eq!(
+        probe.to_ffmetadata(),
+        ";FFMETADATA1\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=90000\ntitle=Intro\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=90000\nEND=3725500\ntitle=Part 2: <b> & \\=more\\=\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=3725500\nEND=3726000\ntitle=\n"
+    );
+    assert_eq!(
+        probe.to_webvtt_chapters(),
+        "WEBVTT\n\
+         \n1\n00:00:00.000 --> 00:01:30.000\nIntro\n\
+         \n2\n00:01:30.000 --> 01:02:05.500\nPart 2: &lt;b&gt; &amp; =more=\n\
+         \n3\n01:02:05.500 --> 01:02:06.000\nChapter 3\n"
+    );
+}
@@ -0,0 +1,72 @@
+#![cfg(all(unix, feature = "cli"))]
+
+use std::{path::PathBuf, process::Command};
+
+mod common;
+
+fn cli(bin: &PathBuf, args: &[&str], input: &PathBuf) -> (i32, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_ffprobe-rs"))
+        .arg("--ffprobe")
+        .arg(bin)
+        .args(args)
+        .arg(input)
+        .output()
+        .unwrap();
+    (
+        out.status.code().unwrap(),
+        String::from_utf8(out.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn summary() {
+    let dir = common::temp_dir("cli", "summary");
+    let (bin, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let input = common::input(&dir, "sample.mp4");
+    let (status, out) = cli(&bin, &[], &input);
+    assert_eq!(status, 0);
+    let lines: Vec<_> = out.lines().collect();
+    assert!(lines[0].starts_with("sample.mp4: mov,mp4,m4a,3gp,3g2,mj2, 10:00.000"));
+    assert!(lines[1].starts_with("  #0 video h264 "));
+    assert!(lines[2].starts_with("  #1 audio aac "));
+    assert!(lines[3].starts_with("  #2 subtitle mov_text"));
+    assert_eq!(lines[4], "  2 chapters");
+}
+
+#[test]
+fn fields_and_json() {
+    let dir = common::temp_dir("cli", "fields");
+    let (bin, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let input = common::input(&dir, "sample.mp4");
+    let (status, out) = cli(
+        &bin,
+        &[
+            "-f",
+            "streams[0].codec_name",
+            "--field",
+            "format.nb_streams",
+            "-f",
+            "nope",
+        ],
+        &input,
+    );
+    assert_eq!(status, 0);
+    assert_eq!(out, "h264\n3\nnull\n");
+
+    let (status, out) = cli(&bin, &["--json"], &input);
+    assert_eq!(status, 0);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["streams"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn usage_errors() {
+    let dir = common::temp_dir("cli", "usage");
+    let (bin, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let input = common::input(&dir, "sample.mp4");
+    assert_eq!(cli(&bin, &["--bogus"], &input).0, 2);
+    assert_eq!(cli(&bin, &["--json", "-f", "format"], &input).0, 2);
+    let empty = input.with_file_name("empty.mp4");
+    std::fs::write(&empty, "").unwrap();
+    assert_eq!(cli(&bin, &[], &empty).0, 1);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{
+    compat::DeviceProfile, Av1Profile, CodecLevel, H264Profile, HevcProfile, VideoProfile,
+    VideoStream,
+};
+
+fn video(codec: &str, profile: &str, level: i64) -> VideoStream {
+    let mut video = VideoStream::new(codec, 1920, 1080);
+    video.profile = Some(profile.to_string());
+    video.level = level;
+    video
+}
+
+#[test]
+fn typed_profiles_and_levels() {
+    let h264 = video("h264", "High", 41);
+    assert_eq!(
+        h264.codec_profile(),
+        Some(VideoProfile::H264(H264Profile::High))
+    );
+    assert_eq!(h264.codec_level(), Some(CodecLevel::new(4, 1)));
+    assert_eq!(h264.codec_level().unwrap().to_string(), "4.1");
+
+    let hevc = video("hevc", "Main 10", 153);
+    assert_eq!(
+        hevc.codec_profile(),
+        Some(VideoProfile::Hevc(HevcProfile::Main10))
+    );
+    assert_eq!(hevc.codec_level(), Some(CodecLevel::new(5, 1)));
+
+    let av1 = video("av1", "Main", 8);
+    assert_eq!(
+        av1.codec_profile(),
+        Some(VideoProfile::Av1(Av1Profile::Main))
+    );
+    assert_eq!(av1.codec_level(), Some(CodecLevel::new(4, 0)));
+
+    let vp9 = video("vp9", "Profile 2", -99);
+    assert_eq!(vp9.codec_profile(), Some(VideoProfile::Vp9(2)));
+    assert_eq!(vp9.codec_level(), None);
+
+    let mpeg2 = video("mpeg2video", "Main", 8);
+    assert_eq!(
+        mpeg2.codec_profile(),
+        Some(VideoProfile::Other("Main".to_string()))
+    );
+    assert_eq!(mpeg2.codec_level(), None);
+    assert_eq!(VideoStream::new("h264", 1, 1).codec_profile(), None);
+}
+
+#[test]
+fn device_support() {
+    let h264 = video("h264", "High", 41);
+    assert!(h264.is_supported_by(&DeviceProfile::chromecast()));
+    assert!(h264.is_supported_by(&DeviceProfile::ios_hls()));
+    let h264_51 = video("h264", "High", 51);
+    assert!(!h264_51.is_supported_by(&DeviceProfile::chromecast()));
+    assert!(!h264_51.is_supported_by(&DeviceProfile::ios_hls()));
+    assert!(h264_51.is_supported_by(&DeviceProfile::browser()));
+    assert!(!video("h264", "High 10", 41).is_supported_by(&DeviceProfile::ios_hls()));
+
+    let hevc = video("hevc", "Main 10", 150);
+    assert!(!hevc.is_supported_by(&DeviceProfile::chromecast()));
+    assert!(hevc.is_supported_by(&DeviceProfile::chromecast_ultra()));
+    assert!(hevc.is_supported_by(&DeviceProfile::ios_hls()));
+    assert!(!video("hevc", "Main 10", 153).is_supported_by(&DeviceProfile::ios_hls()));
+
+    // Unknown levels are assumed to be supported.
+    assert!(video("vp9", "Profile 0", -99).is_supported_by(&DeviceProfile::browser()));
+    assert!(!video("vp9", "Profile 2", -99).is_supported_by(&DeviceProfile::browser()));
+    assert!(!video("mpeg2video", "Main", 8).is_supported_by(&DeviceProfile::browser()));
+}
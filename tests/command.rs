@@ -0,0 +1,162 @@
+#![cfg(feature = "process")]
+
+use std::{ffi::OsStr, path::Path};
+
+use ffprobe::{error::FfProbeError, Config, EntriesSpec, ProbeCommand};
+
+#[test]
+fn command_environment() {
+    let config = Config::new()
+        .ffprobe_bin("/opt/bin/ffprobe")
+        .clear_env(true)
+        .env("LD_LIBRARY_PATH", "/opt/lib")
+        .current_dir("/srv/media");
+    let cmd = ProbeCommand::new(config).input("video.mp4").build();
+
+    assert_eq!(cmd.get_program(), "/opt/bin/ffprobe");
+    assert_eq!(cmd.get_current_dir(), Some(Path::new("/srv/media")));
+    let envs: Vec<_> = cmd.get_envs().collect();
+    assert_eq!(
+        envs,
+        [(OsStr::new("LD_LIBRARY_PATH"), Some(OsStr::new("/opt/lib")))]
+    );
+}
+
+#[test]
+fn command_args() {
+    let config = Config::new().count_frames(true).count_packets(true);
+    let args = ProbeCommand::new(config).input("video.mp4").get_args();
+    assert!(args.iter().any(|a| a == "-count_frames"));
+    assert!(args.iter().any(|a| a == "-count_packets"));
+    assert_eq!(args.last().unwrap(), "video.mp4");
+}
+
+#[test]
+fn command_show_entries() {
+    let args = ProbeCommand::new(Config::new())
+        .show_entries(EntriesSpec::new().format(["duration"]))
+        .get_args();
+    let position = args.iter().position(|a| a == "-show_entries").unwrap();
+    assert_eq!(args[position + 1], "format=duration");
+    assert!(!args
+        .iter()
+        .any(|a| a == "-show_format" || a == "-show_streams"));
+}
+
+#[cfg(unix)]
+#[test]
+fn command_priority() {
+    let config = Config::new().nice(19).ionice(ffprobe::IoPriority::Idle);
+    let cmd = ProbeCommand::new(config).input("video.mp4").build();
+
+    assert_eq!(cmd.get_program(), "ionice");
+    let args: Vec<_> = cmd.get_args().take(5).collect();
+    assert_eq!(args, ["-c3", "nice", "-n", "19", "ffprobe"]);
+}
+
+#[test]
+fn command_inputs() {
+    let url = ProbeCommand::new(Config::new()).input(String::from("https://example.com/a b.m3u8"));
+    assert_eq!(
+        url.get_args().last().unwrap(),
+        "https://example.com/a b.m3u8"
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        // Latin-1 encoded "é", not valid UTF-8.
+        let name = OsStr::from_bytes(b"/media/caf\xe9.mkv");
+        let cmd = ProbeCommand::new(Config::new()).input(Path::new(name));
+        assert_eq!(cmd.get_args().last().unwrap(), name);
+    }
+}
+
+#[test]
+fn command_option_injection() {
+    let cmd = ProbeCommand::new(Config::new()).input("-f");
+    assert_eq!(
+        cmd.get_args().last().unwrap(),
+        Path::new(".").join("-f").as_os_str()
+    );
+    // Stdin stays as it is.
+    let cmd = ProbeCommand::new(Config::new().allow_any_protocol()).input("-");
+    assert_eq!(cmd.get_args().last().unwrap(), "-");
+}
+
+#[test]
+fn command_protocol_whitelist() {
+    let whitelist = |cmd: ProbeCommand| {
+        let args = cmd.get_args();
+        let position = args.iter().position(|a| a == "-protocol_whitelist")?;
+        Some(args[position + 1].to_string_lossy().into_owned())
+    };
+    let local = ProbeCommand::new(Config::new()).input("/media/video.mkv");
+    assert_eq!(
+        whitelist(local).as_deref(),
+        Some("file,http,tcp,crypto,https,tls")
+    );
+    // Remote playlists can't reference local files.
+    let remote = ProbeCommand::new(Config::new()).input("https://example.com/master.m3u8");
+    assert_eq!(
+        whitelist(remote).as_deref(),
+        Some("http,tcp,crypto,https,tls")
+    );
+    let rtsp = ProbeCommand::new(Config::new().allowed_protocols(["rtsp"])).input("rtsp://camera");
+    assert_eq!(whitelist(rtsp).as_deref(), Some("rtsp,tcp,udp,rtp"));
+    let any = ProbeCommand::new(Config::new().allow_any_protocol()).input("concat:a.ts|b.ts");
+    assert_eq!(whitelist(any), None);
+}
+
+#[test]
+fn command_rejects_protocols() {
+    // Checked before spawning ffprobe.
+    let config = Config::new().ffprobe_bin("/nonexistent/ffprobe");
+    for (input, protocol) in [
+        ("ftp://example.com/video.mp4", "ftp"),
+        ("concat:/etc/passwd|/etc/shadow", "concat"),
+        ("crypto+http://example.com/a.ts", "crypto"),
+        ("subfile,,start,0,end,0,,:/etc/passwd", "subfile"),
+        ("-", "pipe"),
+    ] {
+        match config
+            .clone()
+            .run(input)
+            .as_ref()
+            .map_err(FfProbeError::inner)
+        {
+            Err(FfProbeError::ProtocolNotAllowed { protocol: rejected }) => {
+                assert_eq!(rejected, protocol, "{input}")
+            }
+            other => panic!("{input}: {other:?}"),
+        }
+    }
+    // Allowed protocols and paths get to run ffprobe.
+    let https = config.clone().run("https://example.com/video.mp4");
+    let err = https.unwrap_err();
+    assert!(matches!(err.inner(), FfProbeError::Io(_)), "{err:?}");
+    assert_eq!(
+        err.input(),
+        Some(Path::new("https://example.com/video.mp4"))
+    );
+    let path = config.allowed_protocols(["file"]).run("/media/co:lon.mkv");
+    assert!(matches!(path.unwrap_err().inner(), FfProbeError::Io(_)));
+}
+
+#[cfg(windows)]
+#[test]
+fn command_long_windows_paths() {
+    let long = format!(r"C:\media\{}\video.mkv", "a".repeat(300));
+    let args = ProbeCommand::new(Config::new()).input(&long).get_args();
+    assert_eq!(args.last().unwrap(), OsStr::new(&format!(r"\\?\{long}")));
+
+    let unc = format!(r"\\server\share\{}.mkv", "b".repeat(300));
+    let args = ProbeCommand::new(Config::new()).input(&unc).get_args();
+    assert_eq!(
+        args.last().unwrap(),
+        OsStr::new(&format!(r"\\?\UNC\server\share\{}.mkv", "b".repeat(300)))
+    );
+
+    let short = ProbeCommand::new(Config::new()).input(r"C:\media\video.mkv");
+    assert_eq!(short.get_args().last().unwrap(), r"C:\media\video.mkv");
+}
@@ -0,0 +1,239 @@
+//! Temp dirs and fixtures of the integration tests, and fake ffprobe and ffmpeg binaries:
+//! shell scripts in a temp dir per test and process, so concurrent runs don't clash.
+
+// Each test crate uses some of the helpers.
+#![allow(dead_code)]
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Empty temp dir `ffprobe-rs-<area>-<name>-<pid>`.
+pub fn temp_dir(area: &str, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ffprobe-rs-{area}-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Path of a fixture in `tests/fixtures`, eg. `mp4.json`.
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Parsed JSON fixture, eg. to change fields before a fake ffprobe prints it.
+pub fn fixture_json(name: &str) -> Value {
+    serde_json::from_slice(&std::fs::read(fixture(name)).unwrap()).unwrap()
+}
+
+/// Write `contents` to the file `name` in `dir`.
+pub fn write(dir: &Path, name: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Input file `name` in `dir`, not empty so it passes the empty input check.
+pub fn input(dir: &Path, name: &str) -> PathBuf {
+    write(dir, name, "not empty")
+}
+
+/// Executable shell script `name` in `dir` running `body`.
+#[cfg(unix)]
+pub fn script(dir: &Path, name: &str, body: &str) -> PathBuf {
+    let path = write(dir, name, format!("#!/bin/sh\n{body}\n"));
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+/// Script `name` in `dir` recording its arguments in `<name>-args`, one per line, then
+/// running `body`. Returns the script and the arguments file.
+#[cfg(unix)]
+pub fn recording(dir: &Path, name: &str, body: &str) -> (PathBuf, PathBuf) {
+    let args = dir.join(format!("{name}-args"));
+    let bin = script(
+        dir,
+        name,
+        &format!("printf '%s\\n' \"$@\" > '{}'\n{body}", args.display()),
+    );
+    (bin, args)
+}
+
+/// ffprobe in `dir` recording its arguments and printing `output`.
+#[cfg(unix)]
+pub fn fake_ffprobe(dir: &Path, output: impl AsRef<[u8]>) -> (PathBuf, PathBuf) {
+    printing(dir, "ffprobe", output)
+}
+
+/// Script `name` in `dir` recording its arguments and printing `output`, eg. an ffmpeg
+/// writing hashes to stdout.
+#[cfg(unix)]
+pub fn printing(dir: &Path, name: &str, output: impl AsRef<[u8]>) -> (PathBuf, PathBuf) {
+    let output = write(dir, &format!("{name}-output"), output);
+    recording(dir, name, &format!("cat '{}'", output.display()))
+}
+
+/// ffprobe in `dir` recording its arguments, writing the `log` lines to stderr
+/// and printing the fixture `name`, eg. `mp4.json`.
+#[cfg(unix)]
+pub fn fixture_ffprobe(dir: &Path, name: &str, log: &[&str]) -> (PathBuf, PathBuf) {
+    let log = write(
+        dir,
+        "ffprobe-log",
+        log.iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>(),
+    );
+    recording(
+        dir,
+        "ffprobe",
+        &format!(
+            "cat '{}' >&2\ncat '{}'",
+            log.display(),
+            fixture(name).display()
+        ),
+    )
+}
+
+/// ffmpeg in `dir` recording its arguments and writing `log` to stderr.
+#[cfg(unix)]
+pub fn fake_ffmpeg(dir: &Path, log: &str) -> (PathBuf, PathBuf) {
+    exiting_ffmpeg(dir, log, 0)
+}
+
+/// [`fake_ffmpeg`] exiting with `code`.
+#[cfg(unix)]
+pub fn exiting_ffmpeg(dir: &Path, log: &str, code: i32) -> (PathBuf, PathBuf) {
+    let log = write(dir, "ffmpeg-log", log);
+    recording(
+        dir,
+        "ffmpeg",
+        &format!("cat '{}' >&2\nexit {code}", log.display()),
+    )
+}
+
+/// Script `name` in `dir` appending its arguments to `<name>-args`, one per line and each
+/// run ended by `--`, then running `body` in `dir` with `$fixture`, the mp4 fixture.
+/// Returns the script and the arguments file, see [`runs`].
+#[cfg(unix)]
+pub fn appending(dir: &Path, name: &str, body: &str) -> (PathBuf, PathBuf) {
+    let args = dir.join(format!("{name}-args"));
+    let bin = script(
+        dir,
+        name,
+        &format!(
+            "printf '%s\\n' \"$@\" '--' >> '{}'\nfixture='{}'\ncd '{}'\n{body}",
+            args.display(),
+            fixture("mp4.json").display(),
+            dir.display()
+        ),
+    );
+    (bin, args)
+}
+
+/// Arguments of each run recorded by [`appending`].
+pub fn runs(args: &Path) -> Vec<String> {
+    std::fs::read_to_string(args)
+        .unwrap_or_default()
+        .split_terminator("--\n")
+        .map(str::to_string)
+        .collect()
+}
+
+/// ffprobe in `dir` failing `failures` times with `log` on stderr, then printing the mp4
+/// fixture. Returns the script and the file counting its runs, one line each.
+#[cfg(unix)]
+pub fn flaky_ffprobe(dir: &Path, failures: u32, log: &str) -> (PathBuf, PathBuf) {
+    let counter = dir.join("attempts");
+    let bin = script(
+        dir,
+        "ffprobe",
+        &format!(
+            "echo x >> '{counter}'\nif [ $(wc -l < '{counter}') -le {failures} ]; then echo '{log}' >&2; exit 1; fi\ncat '{fixture}'",
+            counter = counter.display(),
+            fixture = fixture("mp4.json").display(),
+        ),
+    );
+    (bin, counter)
+}
+
+/// ffprobe in `dir` printing the fixture in `capabilities` of the listing it's asked for,
+/// eg. `decoders.txt` for `-decoders`.
+#[cfg(unix)]
+pub fn capabilities_ffprobe(dir: &Path) -> PathBuf {
+    script(
+        dir,
+        "ffprobe",
+        &format!(
+            "cd '{}'\nfor arg; do case \"$arg\" in\n\
+             -decoders) cat decoders.txt;;\n-demuxers) cat demuxers.txt;;\n\
+             -protocols) cat protocols.txt;;\n-show_pixel_formats) cat pixel_formats.json;;\n\
+             -show_library_versions) cat build_info.json;;\n\
+             esac; done",
+            fixture("capabilities").display()
+        ),
+    )
+}
+
+/// Script `name` in `dir` writing `first`, sleeping past a progress interval, then
+/// writing `second`. Outputs go to stderr with `to_stderr`.
+#[cfg(unix)]
+pub fn pausing(dir: &Path, name: &str, first: &str, second: &str, to_stderr: bool) -> PathBuf {
+    let first = write(dir, &format!("{name}-first"), first);
+    let second = write(dir, &format!("{name}-second"), second);
+    let redirect = if to_stderr { " >&2" } else { "" };
+    script(
+        dir,
+        name,
+        &format!(
+            "cat '{}'{redirect}\nsleep 0.8\ncat '{}'{redirect}",
+            first.display(),
+            second.display()
+        ),
+    )
+}
+
+/// ffprobe in `dir` failing for inputs matching the shell `pattern`, eg. `*broken*`, and
+/// printing the mp4 fixture for the others.
+#[cfg(unix)]
+pub fn failing_ffprobe(dir: &Path, pattern: &str) -> PathBuf {
+    script(
+        dir,
+        "ffprobe",
+        &format!(
+            "for last; do :; done\ncase \"$last\" in {pattern}) exit 1;; esac\ncat '{}'",
+            fixture("mp4.json").display()
+        ),
+    )
+}
+
+/// Minimal executor polling `future` on the current thread, async-process and the
+/// blocking pool run their own threads.
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    use std::{
+        sync::Arc,
+        task::{Context, Poll, Wake},
+        thread::Thread,
+    };
+
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(Unpark(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::time::Duration;
+
+use ffprobe::{
+    compact::{frames, packets, Records, Section},
+    error::FfProbeError,
+    Config, EntriesSpec, ProbeCommand,
+};
+
+mod common;
+
+#[test]
+fn packets_line_by_line() {
+    let dir = common::temp_dir("compact", "packets");
+    let (bin, args) = common::recording(
+        &dir,
+        "ffprobe",
+        r"printf '%s\n' \
+            'packet|codec_type=video|stream_index=0|pts=3600|pts_time=0.040000|size=1234|flags=K__' \
+            'side_data|side_data_type=ignored' \
+            'packet|codec_type=audio|stream_index=1|pts_time=N/A|size=20|flags=__|tag:title=a\|b'",
+    );
+    let input = common::input(&dir, "video.mp4");
+    let packets: Vec<_> = packets(&Config::new().ffprobe_bin(&bin), &input)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let args = std::fs::read_to_string(&args).unwrap();
+    assert!(args.contains("-print_format\ncompact\n-show_packets\n"));
+    assert!(!args.contains("-show_streams"));
+
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[0].stream_index(), Some(ffprobe::StreamIndex(0)));
+    assert_eq!(packets[0].time("pts_time"), Some(Duration::from_millis(40)));
+    let mpeg = ffprobe::TimeBase::new(1, 90000).unwrap();
+    assert_eq!(
+        packets[0].timestamp("pts", mpeg),
+        Some(Duration::from_millis(40))
+    );
+    assert_eq!(packets[1].timestamp("pts", mpeg), None);
+    assert_eq!(packets[0].parse::<u64>("size"), Some(1234));
+    assert!(packets[0].is_keyframe());
+    assert!(!packets[1].is_keyframe());
+    assert_eq!(packets[1].time("pts_time"), None);
+    assert_eq!(packets[1].get("tag:title"), Some("a|b"));
+}
+
+#[test]
+fn records_before_exit() {
+    // Keeps running until the test has received the first frame.
+    let dir = common::temp_dir("compact", "running");
+    let (bin, args) = common::recording(
+        &dir,
+        "ffprobe",
+        "echo 'frame|key_frame=1|pts_time=0.000000'\n\
+         i=0; while [ ! -e \"$(dirname \"$0\")/received\" ] && [ $i -lt 100 ]; do sleep 0.1; i=$((i+1)); done\n\
+         echo 'frame|key_frame=0|pts_time=0.040000'",
+    );
+    let input = common::input(&dir, "video.mp4");
+    let mut frames = frames(&Config::new().ffprobe_bin(&bin), &input).unwrap();
+    let started = std::time::Instant::now();
+    assert!(frames.next().unwrap().unwrap().is_keyframe());
+    assert!(started.elapsed() < Duration::from_secs(5));
+    std::fs::write(dir.join("received"), "").unwrap();
+    let args = std::fs::read_to_string(&args).unwrap();
+    assert!(args.contains("-show_frames"));
+    assert!(!frames.next().unwrap().unwrap().is_keyframe());
+    assert!(frames.next().is_none());
+}
+
+#[test]
+fn show_entries_and_failure() {
+    let dir = common::temp_dir("compact", "failure");
+    let (bin, args) = common::recording(
+        &dir,
+        "ffprobe",
+        "echo 'packet|pts_time=1.000000'\necho 'broken' >&2\nexit 1",
+    );
+    let input = common::input(&dir, "video.mp4");
+    let command = ProbeCommand::new(Config::new().ffprobe_bin(&bin))
+        .show_entries(EntriesSpec::new().section("packet", ["pts_time"]));
+    let mut packets = Records::spawn(command, Section::Packets, &input).unwrap();
+    assert!(packets.next().unwrap().is_ok());
+    match packets.next() {
+        Some(Err(err)) if matches!(err.inner(), FfProbeError::EmptyOrTruncatedInput { .. }) => {}
+        other => panic!("expected a failed run, got {other:?}"),
+    }
+    assert!(packets.next().is_none());
+    let args = std::fs::read_to_string(&args).unwrap();
+    assert!(args.contains("-show_entries\npacket=pts_time\n"));
+    assert!(!args.contains("-show_packets"));
+}
@@ -0,0 +1,107 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::{path::PathBuf, time::Duration};
+
+use ffprobe::{probe_concat, Config};
+use serde_json::json;
+
+mod common;
+
+/// ffprobe body printing `segment.json` for single files and `concat.json` for the
+/// concatenated input, copying a concat list to `list` and its arguments to `args`.
+const CONCAT_FFPROBE: &str = r#"cd "$(dirname "$0")"
+for last; do :; done
+case "$*" in
+  *"-f concat"*) printf '%s\n' "$@" > args; cp "$last" list; cat concat.json ;;
+  *concat:*) printf '%s\n' "$@" > args; cat concat.json ;;
+  *) cat segment.json ;;
+esac"#;
+
+/// Temp dir with three segments and the output of [`CONCAT_FFPROBE`] in `format_name`.
+fn segment_dir(name: &str, format_name: &str) -> (PathBuf, Vec<PathBuf>) {
+    let dir = common::temp_dir("concat", name);
+    let mut json = common::fixture_json("mp4.json");
+    json["format"]["format_name"] = json!(format_name);
+    common::write(&dir, "segment.json", json.to_string());
+    json["format"]["duration"] = json!("1800.000000");
+    common::write(&dir, "concat.json", json.to_string());
+    let segments = ["chunk'0.mp4", "chunk1.mp4", "chunk2.mp4"]
+        .iter()
+        .map(|name| common::input(&dir, name))
+        .collect();
+    (dir, segments)
+}
+
+#[test]
+fn concat_demuxer() {
+    let (dir, segments) = segment_dir("demuxer", "mov,mp4,m4a,3gp,3g2,mj2");
+    let bin = common::script(&dir, "ffprobe", CONCAT_FFPROBE);
+    let concat = probe_concat(Config::new().ffprobe_bin(&bin), &segments).unwrap();
+    assert_eq!(
+        concat.probe.format.duration,
+        Some(Duration::from_secs(1800))
+    );
+    let offsets: Vec<u64> = concat.segments.iter().map(|s| s.offset.as_secs()).collect();
+    assert_eq!(offsets, [0, 600, 1200]);
+    assert_eq!(concat.segments[2].end(), Duration::from_secs(1800));
+    assert_eq!(concat.segments[1].path, segments[1]);
+
+    let args = std::fs::read_to_string(dir.join("args")).unwrap();
+    assert!(args.contains("-f\nconcat\n-safe\n0\n"));
+    let list = std::fs::read_to_string(dir.join("list")).unwrap();
+    assert_eq!(
+        list,
+        format!(
+            "ffconcat version 1.0\nfile '{0}/chunk'\\''0.mp4'\nduration 600\nfile '{0}/chunk1.mp4'\nduration 600\nfile '{0}/chunk2.mp4'\nduration 600\n",
+            dir.display()
+        )
+    );
+    // The list is removed after the probe.
+    let list_path = args.lines().last().unwrap();
+    assert!(!std::path::Path::new(list_path).exists());
+}
+
+#[test]
+fn concat_protocol() {
+    let (dir, segments) = segment_dir("protocol", "mpegts");
+    let bin = common::script(&dir, "ffprobe", CONCAT_FFPROBE);
+    let concat = probe_concat(Config::new().ffprobe_bin(&bin), &segments).unwrap();
+    assert_eq!(concat.segments.len(), 3);
+    let args = std::fs::read_to_string(dir.join("args")).unwrap();
+    assert_eq!(
+        args.lines().last().unwrap(),
+        format!(
+            "concat:{}",
+            segments
+                .iter()
+                .map(|s| s.display().to_string())
+                .collect::<Vec<_>>()
+                .join("|")
+        )
+    );
+    assert!(!args.contains("-f\nconcat"));
+}
+
+#[test]
+fn segment_at() {
+    let (dir, segments) = segment_dir("segment-at", "matroska,webm");
+    let bin = common::script(&dir, "ffprobe", CONCAT_FFPROBE);
+    let concat = probe_concat(Config::new().ffprobe_bin(&bin), &segments).unwrap();
+    let (index, segment, within) = concat.segment_at(Duration::from_secs(1000)).unwrap();
+    assert_eq!(index, 1);
+    assert_eq!(segment.path, segments[1]);
+    assert_eq!(within, Duration::from_secs(400));
+    assert_eq!(concat.segment_at(Duration::from_secs(600)).unwrap().0, 1);
+    assert!(concat.segment_at(Duration::from_secs(1800)).is_none());
+}
+
+#[test]
+fn no_segments() {
+    assert!(probe_concat::<PathBuf>(Config::new(), &[]).is_err());
+}
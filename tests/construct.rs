@@ -0,0 +1,39 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use std::time::Duration;
+
+use ffprobe::{
+    AttachmentStream, AudioStream, Bitrate, Chapter, FfProbe, FileSize, Format, Ratio, SampleRate,
+    Stream, StreamIndex, SubtitleStream, VideoStream,
+};
+
+#[test]
+fn build_fixture_in_code() {
+    let mut format = Format::new("movie.mkv", "matroska,webm");
+    format.size = FileSize(1_000_000);
+    format.duration = Some(Duration::from_secs(8));
+
+    let mut cover = Stream::new(3, VideoStream::new("mjpeg", 600, 600));
+    cover.disposition.attached_pic = 1;
+
+    let probe = FfProbe::new()
+        .with_format(format)
+        .with_stream(Stream::new(0, VideoStream::new("h264", 1920, 1080)))
+        .with_stream(Stream::new(
+            1,
+            AudioStream::new("aac", SampleRate(48_000), 2),
+        ))
+        .with_stream(Stream::new(2, SubtitleStream::new("subrip")))
+        .with_stream(cover)
+        .with_stream(Stream::new(
+            4,
+            AttachmentStream::new("font.ttf", "font/ttf"),
+        ))
+        .with_chapter(Chapter::new(0, Ratio::new(1, 1000), 0, 4000, "Intro"));
+
+    assert_eq!(probe.streams.len(), 5);
+    assert_eq!(probe.cover_art().map(|s| s.index), Some(StreamIndex(3)));
+    assert_eq!(probe.format.bitrate(), Some(Bitrate(1_000_000)));
+    assert_eq!(probe.chapters[0].end_time(), Duration::from_secs(4));
+    assert_eq!(probe.streams[0].start_time(), 0.);
+}
@@ -0,0 +1,45 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use chrono::{TimeZone, Utc};
+use ffprobe::{CreationTime, FfProbe};
+
+#[test]
+fn creation_time() {
+    let expected = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+    for s in [
+        "2024-03-01T12:30:00.000000Z",
+        "2024-03-01T14:30:00+02:00",
+        "2024-03-01 12:30:00",
+        "2024-03-01T12:30:00",
+    ] {
+        assert_eq!(CreationTime::parse(s).as_datetime(), Some(expected), "{s}");
+    }
+    let raw = CreationTime::parse("Fri Mar  1 12:30:00 2024");
+    assert_eq!(
+        raw,
+        CreationTime::Raw("Fri Mar  1 12:30:00 2024".to_string())
+    );
+    assert_eq!(raw.to_string(), "Fri Mar  1 12:30:00 2024");
+    assert_eq!(
+        CreationTime::from(expected).to_string(),
+        "2024-03-01T12:30:00Z"
+    );
+}
+
+#[test]
+fn unparsable_creation_time_keeps_probe() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+    let mut json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+    json["streams"][0]["tags"]["creation_time"] = "yesterday".into();
+    json["format"]["tags"]["creation_time"] = "2024-03-01T12:30:00.000000Z".into();
+    let probe: FfProbe = serde_json::from_value(json).unwrap();
+
+    let tags = probe.streams[0].tags().unwrap();
+    assert_eq!(
+        tags.creation_time,
+        Some(CreationTime::Raw("yesterday".to_string()))
+    );
+    let format_time = probe.format.tags.as_ref().unwrap().creation_time.as_ref();
+    assert!(format_time.and_then(|t| t.as_datetime()).is_some());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "format")]
+
+use std::time::Duration;
+
+use ffprobe::{CueSheet, CueTime};
+
+mod common;
+
+const SHEET: &str = "\u{feff}REM GENRE Jazz
+REM DATE 1959
+PERFORMER \"Miles Davis\"
+TITLE \"Kind of Blue\"
+FILE \"Kind of Blue.wav\" WAVE
+  TRACK 01 AUDIO
+    TITLE \"So What\"
+    ISRC USSM15900113
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE \"Freddie Freeloader\"
+    PERFORMER \"Miles Davis Sextet\"
+    FLAGS DCP PRE
+    INDEX 00 09:22:10
+    INDEX 01 09:24:00
+";
+
+#[test]
+fn parse_cue_sheet() {
+    let sheet: CueSheet = SHEET.parse().unwrap();
+    assert_eq!(sheet.title.as_deref(), Some("Kind of Blue"));
+    assert_eq!(sheet.performer.as_deref(), Some("Miles Davis"));
+    assert_eq!(
+        sheet.comments,
+        vec![
+            ("GENRE".to_string(), "Jazz".to_string()),
+            ("DATE".to_string(), "1959".to_string())
+        ]
+    );
+    assert_eq!(sheet.files.len(), 1);
+    let file = &sheet.files[0];
+    assert_eq!(file.path, "Kind of Blue.wav");
+    assert_eq!(file.file_type, "WAVE");
+    assert_eq!(file.tracks.len(), 2);
+    assert_eq!(file.tracks[0].isrc.as_deref(), Some("USSM15900113"));
+    let second = &file.tracks[1];
+    assert_eq!(second.performer.as_deref(), Some("Miles Davis Sextet"));
+    assert_eq!(second.flags, vec!["DCP", "PRE"]);
+    assert_eq!(second.start(), Some("09:24:00".parse().unwrap()));
+    assert_eq!(second.indexes[0], (0, CueTime(42_160)));
+
+    let err = "INDEX 01 00:00:00".parse::<CueSheet>().unwrap_err();
+    assert!(err.to_string().contains("line 1"));
+    assert!("FILE a.wav WAVE\nTRACK 01 AUDIO\nINDEX 01 00:61:00"
+        .parse::<CueSheet>()
+        .is_err());
+}
+
+#[test]
+fn cue_time() {
+    let time: CueTime = "03:25:40".parse().unwrap();
+    assert_eq!(time.frames(), (3 * 60 + 25) * 75 + 40);
+    assert_eq!(time.to_string(), "03:25:40");
+    assert_eq!(CueTime(75).as_duration(), Duration::from_secs(1));
+    assert_eq!(
+        CueTime::from_duration(Duration::from_millis(1500)),
+        CueTime(113)
+    );
+}
+
+#[cfg(all(unix, feature = "chapters", feature = "process"))]
+#[test]
+fn probe_cue_sheet() {
+    use ffprobe::{probe_cue_config, Config};
+
+    let dir = common::temp_dir("cue", "sheet");
+    common::write(&dir, "album.cue", SHEET);
+    // Compressed after ripping, the sheet still references the wav.
+    common::write(&dir, "Kind of Blue.flac", b"fLaC");
+    let bin = common::script(
+        &dir,
+        "ffprobe",
+        r#"case "$*" in
+  *"Kind of Blue.flac") echo '{"streams":[],"chapters":[],"format":{"filename":"Kind of Blue.flac","nb_streams":1,"nb_programs":0,"nb_stream_groups":0,"format_name":"flac","format_long_name":"raw FLAC","duration":"1000.000000","probe_score":100}}';;
+  *) exit 1;;
+esac"#,
+    );
+
+    let probe = probe_cue_config(Config::new().ffprobe_bin(&bin), dir.join("album.cue")).unwrap();
+    assert_eq!(probe.files[0].0, dir.join("Kind of Blue.flac"));
+    assert_eq!(probe.tracks.len(), 2);
+    assert_eq!(probe.tracks[0].end, Some(CueTime(42_300)));
+    assert_eq!(probe.tracks[0].duration(), Some(Duration::from_secs(564)));
+    assert_eq!(
+        probe.tracks[1].performer.as_deref(),
+        Some("Miles Davis Sextet")
+    );
+    assert_eq!(probe.tracks[1].end, Some(CueTime(75_000)));
+
+    let chapters = probe.chapters();
+    assert_eq!(chapters[1].tags.title, "Freddie Freeloader");
+    assert_eq!(chapters[1].start_time(), Duration::from_secs(564));
+    assert_eq!(chapters[1].end_time(), Duration::from_secs(1000));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "process")]
+
+use ffprobe::{
+    analysis::{run_analyses, Analysis, ProbeContext},
+    error::FfProbeError,
+    Config,
+};
+use serde_json::Value;
+
+mod common;
+
+struct FileName;
+
+impl Analysis for FileName {
+    fn name(&self) -> &str {
+        "file_name"
+    }
+
+    fn run(&self, ctx: &ProbeContext) -> Result<Value, FfProbeError> {
+        Ok(ctx.path().file_name().unwrap().to_string_lossy().into())
+    }
+}
+
+struct Failing;
+
+impl Analysis for Failing {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    fn run(&self, _: &ProbeContext) -> Result<Value, FfProbeError> {
+        Err(FfProbeError::Io(std::io::ErrorKind::Unsupported.into()))
+    }
+}
+
+#[test]
+fn registered_analyses() {
+    let config = Config::new().analysis(Failing).analysis(FileName);
+    assert!(format!("{:?}", config).contains(r#"["failing", "file_name"]"#));
+
+    let outcomes = run_analyses("/media/video.mp4", &config).unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[0].name, "failing");
+    assert!(outcomes[0].result.is_err());
+    assert_eq!(outcomes[1].result.as_ref().unwrap(), "video.mp4");
+}
+
+#[cfg(all(unix, feature = "async-std"))]
+#[test]
+fn async_analyses() {
+    use ffprobe::{
+        analysis::{run_analyses_async_with, ProbeSection},
+        runtime::AsyncStd,
+    };
+
+    struct Codec;
+
+    impl Analysis for Codec {
+        fn name(&self) -> &str {
+            "codec"
+        }
+
+        fn sections(&self) -> &[ProbeSection] {
+            &[ProbeSection::Streams]
+        }
+
+        fn run(&self, ctx: &ProbeContext) -> Result<Value, FfProbeError> {
+            Ok(ctx.entries().stream_field::<String>(0, "codec_name").into())
+        }
+    }
+
+    let dir = common::temp_dir("analysis", "async");
+    let (bin, args) = common::fake_ffprobe(&dir, r#"{"streams": [{"codec_name": "h264"}]}"#);
+    let input = common::input(&dir, "video.mp4");
+    let config = Config::new()
+        .ffprobe_bin(&bin)
+        .analysis(Codec)
+        .analysis(FileName);
+
+    let outcomes = common::block_on(run_analyses_async_with::<AsyncStd>(&input, &config)).unwrap();
+    assert_eq!(outcomes[0].result.as_ref().unwrap(), "h264");
+    assert_eq!(outcomes[1].result.as_ref().unwrap(), "video.mp4");
+    assert!(std::fs::read_to_string(args)
+        .unwrap()
+        .contains("-show_entries\nstream\n"));
+}
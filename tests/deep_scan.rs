@@ -0,0 +1,98 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::time::Duration;
+
+use ffprobe::{
+    analysis::{deep_scan, DEEP_SCAN_MAX_ERRORS},
+    error::FfProbeError,
+    Config, StreamIndex,
+};
+
+mod common;
+
+fn progress(out_time_us: u64, state: &str) -> String {
+    format!("frame=10\nfps=0.00\nout_time_us={out_time_us}\nout_time=00:00:00.000000\nspeed=N/A\nprogress={state}\n")
+}
+
+#[test]
+fn typed_errors_per_stream() {
+    let log = [
+        "[h264 @ 0x55d1c8] SEI type 5 size 2040 truncated at 320\n".to_string(),
+        progress(1_500_000, "continue"),
+        "[vist#0:0/h264 @ 0x55d1c8] [dec:h264 @ 0x55d1d0] error while decoding MB 12 34, bytestream -5\n".to_string(),
+        "Error while decoding stream #0:1: Invalid data found when processing input\n".to_string(),
+        progress(3_000_000, "end"),
+    ]
+    .concat();
+    let (bin, _) = common::fake_ffmpeg(&common::temp_dir("deep-scan", "typed"), &log);
+    let report = deep_scan("input.mkv", &Config::new().ffmpeg_bin(&bin)).unwrap();
+
+    let errors: Vec<_> = report
+        .errors
+        .iter()
+        .map(|e| (e.timestamp, e.stream, e.message.as_str()))
+        .collect();
+    let at = Some(Duration::from_millis(1500));
+    assert_eq!(
+        errors,
+        [
+            (None, None, "SEI type 5 size 2040 truncated at 320"),
+            (
+                at,
+                Some(StreamIndex(0)),
+                "error while decoding MB 12 34, bytestream -5"
+            ),
+            (
+                at,
+                Some(StreamIndex(1)),
+                "Error while decoding stream #0:1: Invalid data found when processing input"
+            ),
+        ]
+    );
+    assert_eq!(
+        report.per_stream.into_iter().collect::<Vec<_>>(),
+        [
+            (None, 1),
+            (Some(StreamIndex(0)), 1),
+            (Some(StreamIndex(1)), 1)
+        ]
+    );
+    assert!(report.completed);
+}
+
+#[test]
+fn clean_and_aborted_scans() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("deep-scan", "clean"),
+        &progress(10_000_000, "end"),
+    );
+    let report = deep_scan("input.mkv", &Config::new().ffmpeg_bin(&bin)).unwrap();
+    assert!(report.is_clean());
+    assert_eq!(report.error_count(), 0);
+
+    // ffmpeg gives up after too many errors, but the errors so far are reported.
+    let mut log = progress(0, "continue");
+    for _ in 0..DEEP_SCAN_MAX_ERRORS + 5 {
+        log.push_str("[aist#0:1/aac @ 0x1] [dec:aac @ 0x2] Number of bands exceeds limit\n");
+    }
+    log.push_str("Decode error rate 1 exceeds maximum 0.666667\n");
+    let (bin, _) = common::exiting_ffmpeg(&common::temp_dir("deep-scan", "aborted"), &log, 69);
+    let report = deep_scan("input.mkv", &Config::new().ffmpeg_bin(&bin)).unwrap();
+    assert!(!report.completed);
+    assert_eq!(report.errors.len(), DEEP_SCAN_MAX_ERRORS);
+    assert_eq!(report.error_count(), DEEP_SCAN_MAX_ERRORS + 6);
+    assert_eq!(
+        report.per_stream[&Some(StreamIndex(1))],
+        DEEP_SCAN_MAX_ERRORS + 5
+    );
+
+    // Failing before decoding anything, eg. a missing input.
+    let (bin, _) = common::exiting_ffmpeg(
+        &common::temp_dir("deep-scan", "missing"),
+        "missing.mkv: No such file or directory\n",
+        1,
+    );
+    let err = deep_scan("missing.mkv", &Config::new().ffmpeg_bin(&bin)).unwrap_err();
+    assert!(matches!(err.inner(), FfProbeError::Status(_)));
+    assert_eq!(err.input(), Some(std::path::Path::new("missing.mkv")));
+}
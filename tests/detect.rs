@@ -0,0 +1,172 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::{path::Path, time::Duration};
+
+use ffprobe::{
+    analysis::{
+        detect::{
+            black_intervals, freeze_intervals, scene_changes, silence_intervals, BlackInterval,
+            FreezeInterval, SilenceInterval,
+        },
+        BlackDetectOptions, FreezeDetectOptions, Heuristics, SilenceDetectOptions,
+    },
+    Config,
+};
+
+mod common;
+
+#[test]
+fn black() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("detect", "black"),
+        "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'input.mp4':\n\
+         [blackdetect @ 0x5581c7a0] black_start:0 black_end:2.04 black_duration:2.04\n\
+         [blackdetect @ 0x5581c7a0] black_start:1795.12 black_end:1800 black_duration:4.88\n\
+         [blackdetect @ 0x5581c7a0] black_start:inf black_end:inf black_duration:0\n\
+         [out#0/null @ 0x5581c800] video:0KiB audio:0KiB\n",
+    );
+    let config = Config::new().ffmpeg_bin(bin);
+    let intervals = black_intervals("input.mp4", &config).unwrap();
+    assert_eq!(
+        intervals,
+        [
+            BlackInterval {
+                start: Duration::ZERO,
+                end: Duration::from_millis(2040)
+            },
+            BlackInterval {
+                start: Duration::from_millis(1_795_120),
+                end: Duration::from_secs(1800)
+            },
+        ]
+    );
+    assert_eq!(intervals[1].duration(), Duration::from_millis(4880));
+}
+
+#[test]
+fn silence() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("detect", "silence"),
+        "[silencedetect @ 0x5581c7a0] silence_start: -0.00133333\n\
+         [silencedetect @ 0x5581c7a0] silence_end: 1.50067 | silence_duration: 1.502\n\
+         [silencedetect @ 0x5581c7a0] silence_start: 42.25\n\
+         [silencedetect @ 0x5581c7a0] silence_end: inf | silence_duration: inf\n\
+         size=N/A time=00:01:00.00 bitrate=N/A speed= 512x\n",
+    );
+    let config = Config::new().ffmpeg_bin(bin);
+    let intervals = silence_intervals("input.mp4", &config).unwrap();
+    assert_eq!(
+        intervals,
+        [
+            // Starts before the first sample, clamped to 0.
+            SilenceInterval {
+                start: Duration::ZERO,
+                end: Some(Duration::from_micros(1_500_670))
+            },
+            // Lasts until the end of the input.
+            SilenceInterval {
+                start: Duration::from_millis(42_250),
+                end: None
+            },
+        ]
+    );
+    assert_eq!(intervals[1].duration(), None);
+}
+
+#[test]
+fn freeze() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("detect", "freeze"),
+        "[freezedetect @ 0x5581c7a0] lavfi.freezedetect.freeze_start: 4.2\n\
+         [freezedetect @ 0x5581c7a0] lavfi.freezedetect.freeze_duration: 2.5\n\
+         [freezedetect @ 0x5581c7a0] lavfi.freezedetect.freeze_end: 6.7\n\
+         [freezedetect @ 0x5581c7a0] lavfi.freezedetect.freeze_start: 58\n",
+    );
+    let config = Config::new().ffmpeg_bin(bin);
+    let intervals = freeze_intervals("input.mp4", &config).unwrap();
+    assert_eq!(
+        intervals,
+        [
+            FreezeInterval {
+                start: Duration::from_millis(4200),
+                end: Some(Duration::from_millis(6700))
+            },
+            FreezeInterval {
+                start: Duration::from_secs(58),
+                end: None
+            },
+        ]
+    );
+}
+
+#[test]
+fn scenes() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("detect", "scenes"),
+        "[Parsed_metadata_1 @ 0x5581c7a0] frame:0    pts:300     pts_time:12.5\n\
+         [Parsed_metadata_1 @ 0x5581c7a0] lavfi.scene_score=0.578823\n\
+         [Parsed_metadata_1 @ 0x5581c7a0] frame:1    pts:1201    pts_time:50.0417\n\
+         [Parsed_metadata_1 @ 0x5581c7a0] lavfi.scene_score=1.000000\n\
+         [Parsed_metadata_1 @ 0x5581c7a0] frame:2    pts:N/A     pts_time:N/A\n\
+         [Parsed_metadata_1 @ 0x5581c7a0] lavfi.scene_score=0.412000\n",
+    );
+    let config = Config::new().ffmpeg_bin(bin);
+    let changes = scene_changes("input.mp4", &config).unwrap();
+    assert_eq!(changes.len(), 2, "{changes:?}");
+    assert_eq!(changes[0].time, Duration::from_millis(12_500));
+    assert_eq!(changes[0].score, 0.578823);
+    assert_eq!(changes[1].time, Duration::from_micros(50_041_700));
+    assert_eq!(changes[1].score, 1.);
+}
+
+/// Filter of the recorded ffmpeg arguments.
+fn filter(args: &Path) -> String {
+    let args = std::fs::read_to_string(args).unwrap();
+    let mut args = args.lines();
+    args.find(|arg| arg.starts_with("-filter:"));
+    args.next().unwrap().to_string()
+}
+
+#[test]
+fn default_thresholds() {
+    let (bin, args) = common::fake_ffmpeg(&common::temp_dir("detect", "defaults"), "");
+    let config = Config::new().ffmpeg_bin(bin);
+    black_intervals("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "blackdetect=d=2:pic_th=0.98:pix_th=0.1");
+    silence_intervals("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "silencedetect=n=-60dB:d=2");
+    freeze_intervals("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "freezedetect=n=-60dB:d=2");
+    scene_changes("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "select='gt(scene,0.4)',metadata=print");
+}
+
+#[test]
+fn heuristics_thresholds() {
+    let mut heuristics = Heuristics::default();
+    heuristics.scene_threshold = 0.25;
+    heuristics.black = BlackDetectOptions {
+        min_duration: Duration::from_millis(500),
+        picture_threshold: 0.9,
+        pixel_threshold: 0.05,
+    };
+    heuristics.silence = SilenceDetectOptions {
+        min_duration: Duration::from_millis(1500),
+        noise_db: -50.,
+    };
+    heuristics.freeze = FreezeDetectOptions {
+        min_duration: Duration::from_secs(5),
+        noise_db: -45.5,
+    };
+    let (bin, args) = common::fake_ffmpeg(&common::temp_dir("detect", "heuristics"), "");
+    let config = Config::new().ffmpeg_bin(bin).heuristics(heuristics);
+
+    black_intervals("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "blackdetect=d=0.5:pic_th=0.9:pix_th=0.05");
+    silence_intervals("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "silencedetect=n=-50dB:d=1.5");
+    freeze_intervals("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "freezedetect=n=-45.5dB:d=5");
+    scene_changes("input.mp4", &config).unwrap();
+    assert_eq!(filter(&args), "select='gt(scene,0.25)',metadata=print");
+}
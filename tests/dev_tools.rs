@@ -0,0 +1,42 @@
+#![cfg(all(feature = "dev-tools", feature = "format", feature = "chapters"))]
+
+use std::time::Duration;
+
+use ffprobe::{dev_tools::TestMedia, Config, StreamKinds};
+
+mod common;
+
+#[test]
+fn generate_all_kinds() {
+    let dir = common::temp_dir("dev-tools", "all-kinds");
+    let config = Config::new();
+
+    let path = TestMedia::all_kinds()
+        .generate(&config, dir.join("all.mkv"))
+        .unwrap();
+    let probe = ffprobe::ffprobe(&path).unwrap();
+    let kinds: Vec<_> = probe
+        .streams
+        .iter()
+        .map(|s| match s.stream {
+            StreamKinds::Video(_) => "video",
+            StreamKinds::Audio(_) => "audio",
+            StreamKinds::Subtitle(_) => "subtitle",
+            StreamKinds::Attachment(_) => "attachment",
+            StreamKinds::Data(_) => "data",
+        })
+        .collect();
+    assert_eq!(kinds, ["video", "audio", "subtitle", "attachment"]);
+    assert_eq!(probe.chapters.len(), 2);
+    assert_eq!(probe.chapters[1].tags.title, "Chapter 2");
+
+    let path = TestMedia::new()
+        .video(None)
+        .duration(Duration::from_secs(1))
+        .generate(&config, dir.join("audio.mp4"))
+        .unwrap();
+    let probe = ffprobe::ffprobe(&path).unwrap();
+    assert_eq!(probe.streams.len(), 1);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,168 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::path::PathBuf;
+
+use ffprobe::{error::FfProbeError, Config, Dialect};
+
+mod common;
+
+/// Temp dir with the input and `legacy.json`, the mp4 fixture without chapters.
+fn legacy_dir(name: &str) -> (PathBuf, PathBuf) {
+    let dir = common::temp_dir("dialect", name);
+    let mut json = common::fixture_json("mp4.json");
+    json.as_object_mut().unwrap().remove("chapters");
+    common::write(&dir, "legacy.json", json.to_string());
+    let input = common::input(&dir, "video.mp4");
+    (dir, input)
+}
+
+/// avprobe: no `-print_format` and no `level+` prefix.
+const AVPROBE: &str = r#"case "$*" in
+  *-print_format*) echo "Unrecognized option 'print_format'" >&2; exit 1 ;;
+  *level+*) echo 'Invalid loglevel "level+warning"' >&2; exit 1 ;;
+esac
+cat legacy.json"#;
+
+#[test]
+fn falls_back_to_legacy() {
+    let (dir, input) = legacy_dir("legacy");
+    let (bin, args) = common::appending(&dir, "ffprobe", AVPROBE);
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .capture_warnings(true)
+        .run(&input)
+        .unwrap();
+    assert_eq!(probe.dialect, Dialect::Legacy);
+    assert_eq!(probe.streams.len(), 3);
+    assert!(probe.chapters.is_empty());
+
+    let runs = common::runs(&args);
+    assert_eq!(runs.len(), 2);
+    assert!(runs[0].contains("-print_format\njson\n"));
+    assert_eq!(
+        runs[1],
+        format!(
+            "-v\nwarning\n-of\njson\n-show_format\n-show_streams\n\
+             -protocol_whitelist\nfile,http,tcp,crypto,https,tls\n{}\n",
+            input.display()
+        )
+    );
+}
+
+#[test]
+fn quiet_failure_checks_options() {
+    // In quiet mode the failure logs nothing, the options are checked without the input.
+    let (dir, input) = legacy_dir("quiet");
+    let (bin, args) = common::appending(
+        &dir,
+        "ffprobe",
+        &format!("case \"$*\" in *quiet*-print_format*) exit 1 ;; esac\n{AVPROBE}"),
+    );
+    let probe = Config::new().ffprobe_bin(&bin).run(&input).unwrap();
+    assert_eq!(probe.dialect, Dialect::Legacy);
+
+    let runs = common::runs(&args);
+    assert_eq!(runs.len(), 3);
+    assert!(runs[1].starts_with("-v\nlevel+error\n"));
+    assert!(!runs[1].contains("video.mp4"));
+    assert!(runs[2].starts_with("-v\nquiet\n-of\njson\n"));
+}
+
+#[test]
+fn ffprobe_dialect() {
+    let (dir, input) = legacy_dir("ffprobe");
+    let (bin, args) = common::appending(&dir, "ffprobe", "cat \"$fixture\"");
+    let probe = Config::new().ffprobe_bin(&bin).run(&input).unwrap();
+    assert_eq!(probe.dialect, Dialect::Ffprobe);
+    assert_eq!(common::runs(&args).len(), 1);
+}
+
+#[test]
+fn genuine_failure_is_not_retried() {
+    let (dir, input) = legacy_dir("failure");
+    let (bin, args) = common::appending(
+        &dir,
+        "ffprobe",
+        "echo 'moov atom not found' >&2\n\
+         echo 'Invalid data found when processing input' >&2\nexit 1",
+    );
+    assert!(Config::new()
+        .ffprobe_bin(&bin)
+        .capture_warnings(true)
+        .run(&input)
+        .is_err());
+    assert_eq!(common::runs(&args).len(), 1);
+}
+
+#[test]
+fn legacy_without_whitelist() {
+    // A binary without `-protocol_whitelist` isn't run without it.
+    let script =
+        format!("case \"$*\" in *-protocol_whitelist*) echo \"Unrecognized option 'protocol_whitelist'\" >&2; exit 1 ;; esac\n{AVPROBE}");
+    let (dir, input) = legacy_dir("whitelist");
+    let (bin, args) = common::appending(&dir, "ffprobe", &script);
+    let err = Config::new()
+        .ffprobe_bin(&bin)
+        .capture_warnings(true)
+        .run(&input)
+        .unwrap_err();
+    assert!(
+        matches!(err.inner(), FfProbeError::ProtocolNotAllowed { protocol } if protocol == "file"),
+        "{err:?}"
+    );
+    let runs = common::runs(&args);
+    assert_eq!(runs.len(), 2);
+    assert!(runs.iter().all(|run| run.contains("-protocol_whitelist\n")));
+
+    // At `-v quiet`, from the check of the options.
+    let (dir, input) = legacy_dir("whitelist-quiet");
+    let (bin, _) = common::appending(&dir, "ffprobe", &script);
+    let err = Config::new().ffprobe_bin(&bin).run(&input).unwrap_err();
+    assert!(
+        matches!(err.inner(), FfProbeError::ProtocolNotAllowed { .. }),
+        "{err:?}"
+    );
+
+    // Without a `-protocol_whitelist` to pass.
+    let (dir, input) = legacy_dir("whitelist-any");
+    let (bin, _) = common::appending(&dir, "ffprobe", &script);
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .allow_any_protocol()
+        .run(&input)
+        .unwrap();
+    assert_eq!(probe.dialect, Dialect::Legacy);
+}
+
+#[test]
+fn dialect_is_kept_per_binary() {
+    let (dir, input) = legacy_dir("kept");
+    let (bin, args) = common::appending(&dir, "ffprobe", AVPROBE);
+    let config = Config::new().ffprobe_bin(&bin).capture_warnings(true);
+    config.clone().run(&input).unwrap();
+    assert_eq!(common::runs(&args).len(), 2);
+    // Straight in the legacy dialect.
+    let probe = config.run(&input).unwrap();
+    assert_eq!(probe.dialect, Dialect::Legacy);
+    let runs = common::runs(&args);
+    assert_eq!(runs.len(), 3);
+    assert!(runs[2].contains("-of\njson\n"));
+}
+
+#[test]
+fn quiet_failures_check_options_once() {
+    // eg. a batch of corrupt files.
+    let (dir, input) = legacy_dir("corrupt");
+    let (bin, args) = common::appending(&dir, "ffprobe", "exit 1");
+    let config = Config::new().ffprobe_bin(&bin);
+    assert!(config.clone().run(&input).is_err());
+    assert_eq!(common::runs(&args).len(), 2);
+    assert!(config.run(&input).is_err());
+    assert_eq!(common::runs(&args).len(), 3);
+}
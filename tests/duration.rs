@@ -0,0 +1,123 @@
+#![cfg(all(feature = "streams", feature = "format"))]
+
+use std::time::Duration;
+
+use common::fixture_json as fixture;
+use ffprobe::FfProbe;
+use serde_json::Value;
+
+mod common;
+
+/// The mp4 fixture of a live recording: no format or stream durations, starting at 1.4s.
+fn live_recording() -> Value {
+    let mut json = fixture("mp4.json");
+    json["format"].as_object_mut().unwrap().remove("duration");
+    json["format"]["start_time"] = "1.400000".into();
+    for stream in json["streams"].as_array_mut().unwrap() {
+        stream.as_object_mut().unwrap().remove("duration_ts");
+    }
+    json
+}
+
+#[test]
+fn best_duration_fallbacks() {
+    let probe: FfProbe = serde_json::from_value(fixture("mp4.json")).unwrap();
+    assert_eq!(probe.best_duration(), Some(Duration::from_secs(600)));
+
+    // The longest `DURATION` tag of the Matroska streams.
+    let mut json = fixture("mkv.json");
+    json["format"].as_object_mut().unwrap().remove("duration");
+    let probe: FfProbe = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        probe.best_duration(),
+        Some(Duration::from_millis(6_723_717))
+    );
+
+    let mut probe: FfProbe = serde_json::from_value(live_recording()).unwrap();
+    assert_eq!(probe.best_duration(), None);
+    probe.estimated_duration = Some(Duration::from_secs(90));
+    assert_eq!(probe.best_duration(), Some(Duration::from_secs(90)));
+}
+
+#[cfg(all(unix, feature = "process"))]
+mod estimate {
+    use std::time::Duration;
+
+    use ffprobe::Config;
+    use serde_json::json;
+
+    use crate::common;
+
+    /// ffprobe body printing `probe.json`, and `packets.json` for the tail probe, logging
+    /// the arguments of the tail probe to `tail-args`.
+    const TAIL_FFPROBE: &str = r#"cd "$(dirname "$0")"
+case "$*" in
+  *packet=*) echo "$@" > tail-args; cat packets.json ;;
+  *) cat probe.json ;;
+esac"#;
+
+    #[test]
+    fn estimate_from_last_packet() {
+        let packets = json!({"packets": [
+            {"pts_time": "3721.360000", "dts_time": "3721.280000", "duration_time": "0.040000"},
+            {"pts_time": "3721.400000", "dts_time": "3721.320000", "duration_time": "0.021333"},
+            {"pts_time": "N/A", "dts_time": "3721.360000", "duration_time": "0.040000"},
+            {"pts_time": "3721.300000", "duration_time": "N/A"},
+        ]});
+        let dir = common::temp_dir("duration", "estimate");
+        common::write(&dir, "probe.json", &super::live_recording().to_string());
+        common::write(&dir, "packets.json", &packets.to_string());
+        let config = Config::new().ffprobe_bin(common::script(&dir, "ffprobe", TAIL_FFPROBE));
+        let input = common::input(&dir, "recording.ts");
+        let tail_args = dir.join("tail-args");
+
+        // Only with `estimate_duration`.
+        let probe = config.clone().run(&input).unwrap();
+        assert_eq!(probe.estimated_duration, None);
+        assert!(!tail_args.exists());
+
+        let probe = config.estimate_duration(true).run(&input).unwrap();
+        // 3721.421333s of the last packet, less the start time.
+        let estimated = probe.estimated_duration.unwrap();
+        assert_eq!(estimated, Duration::from_micros(3_720_021_333));
+        assert_eq!(probe.best_duration(), Some(estimated));
+
+        let args = std::fs::read_to_string(tail_args).unwrap();
+        assert!(args.contains("-read_intervals 86400%+#2000"), "{args}");
+        assert!(!args.contains("-show_streams"), "{args}");
+    }
+
+    #[test]
+    fn no_estimate_with_duration() {
+        let probe = super::fixture("mp4.json").to_string();
+        let dir = common::temp_dir("duration", "known");
+        common::write(&dir, "probe.json", &probe);
+        common::write(&dir, "packets.json", "{}");
+        let config = Config::new().ffprobe_bin(common::script(&dir, "ffprobe", TAIL_FFPROBE));
+        let input = common::input(&dir, "recording.ts");
+        let tail_args = dir.join("tail-args");
+        let probe = config.estimate_duration(true).run(&input).unwrap();
+        assert_eq!(probe.estimated_duration, None);
+        assert!(!tail_args.exists());
+
+        // A tail probe without packets.
+        let dir = common::temp_dir("duration", "empty");
+        common::write(&dir, "probe.json", &super::live_recording().to_string());
+        common::write(&dir, "packets.json", "{}");
+        let config = Config::new().ffprobe_bin(common::script(&dir, "ffprobe", TAIL_FFPROBE));
+        let input = common::input(&dir, "recording.ts");
+        let probe = config.estimate_duration(true).run(&input).unwrap();
+        assert_eq!(probe.estimated_duration, None);
+        assert_eq!(probe.best_duration(), None);
+
+        // A failed tail probe, eg. of an unseekable input.
+        let dir = common::temp_dir("duration", "failed");
+        common::write(&dir, "probe.json", &super::live_recording().to_string());
+        common::write(&dir, "packets.json", "not json");
+        let config = Config::new().ffprobe_bin(common::script(&dir, "ffprobe", TAIL_FFPROBE));
+        let input = common::input(&dir, "recording.ts");
+        let probe = config.estimate_duration(true).run(&input).unwrap();
+        assert_eq!(probe.estimated_duration, None);
+        assert!(!probe.streams.is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use ffprobe::{default_edition, matroska_editions};
+
+mod common;
+
+fn element(id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = id
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect();
+    // 8 byte size vint.
+    out.push(0x01);
+    out.extend_from_slice(&(payload.len() as u64).to_be_bytes()[1..]);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn chapter(start_secs: u64, end_secs: u64, title: &str, enabled: bool) -> Vec<u8> {
+    let display = element(
+        0x80,
+        &[element(0x85, title.as_bytes()), element(0x437C, b"eng")].concat(),
+    );
+    element(
+        0xB6,
+        &[
+            element(0x91, &(start_secs * 1_000_000_000).to_be_bytes()),
+            element(0x92, &(end_secs * 1_000_000_000).to_be_bytes()),
+            element(0x4598, &[enabled as u8]),
+            display,
+        ]
+        .concat(),
+    )
+}
+
+fn mkv(chapters_after_cluster: bool) -> Vec<u8> {
+    let theatrical = element(
+        0x45B9,
+        &[
+            element(0x45BC, &[1]),
+            chapter(0, 60, "Opening", true),
+            chapter(60, 120, "Ending", true),
+        ]
+        .concat(),
+    );
+    let extended = element(
+        0x45B9,
+        &[
+            element(0x45BC, &[2]),
+            element(0x45DB, &[1]),
+            element(0x45DD, &[1]),
+            element(0x4520, &element(0x4521, b"Extended")),
+            chapter(0, 60, "Opening", true),
+            chapter(300, 330, "Deleted scene", true),
+            chapter(330, 400, "Outtakes", false),
+            chapter(60, 120, "Ending", true),
+        ]
+        .concat(),
+    );
+    let chapters = element(0x1043_A770, &[theatrical, extended].concat());
+    let cluster = element(0x1F43_B675, &[0; 64]);
+
+    let body = if chapters_after_cluster {
+        // Cluster of unknown size, chapters are only reachable through the SeekHead.
+        let mut cluster = cluster;
+        cluster[4..12].copy_from_slice(&[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        let seek_head = |position: u64| {
+            let seek = element(
+                0x4DBB,
+                &[
+                    element(0x53AB, &[0x10, 0x43, 0xA7, 0x70]),
+                    element(0x53AC, &position.to_be_bytes()),
+                ]
+                .concat(),
+            );
+            element(0x114D_9B74, &seek)
+        };
+        let position = seek_head(0).len() + cluster.len();
+        [seek_head(position as u64), cluster, chapters].concat()
+    } else {
+        [chapters, cluster].concat()
+    };
+    [
+        element(0x1A45_DFA3, &element(0x4282, b"matroska")),
+        element(0x1853_8067, &body),
+    ]
+    .concat()
+}
+
+#[test]
+fn editions() {
+    let dir = common::temp_dir("editions", "chapters");
+
+    for chapters_after_cluster in [false, true] {
+        let path = dir.join(format!("editions-{chapters_after_cluster}.mkv"));
+        std::fs::write(&path, mkv(chapters_after_cluster)).unwrap();
+        let editions = matroska_editions(&path).unwrap();
+        assert_eq!(editions.len(), 2);
+
+        let theatrical = &editions[0];
+        assert!(!theatrical.ordered);
+        assert_eq!(theatrical.chapters.len(), 2);
+        assert_eq!(theatrical.chapters[1].title.as_deref(), Some("Ending"));
+        assert_eq!(theatrical.chapters[1].language.as_deref(), Some("eng"));
+        assert_eq!(theatrical.playback_duration(), None);
+
+        let default = default_edition(&editions).unwrap();
+        assert_eq!(default.uid, Some(2));
+        assert_eq!(default.name.as_deref(), Some("Extended"));
+        assert!(default.ordered);
+        assert_eq!(default.chapters.len(), 4);
+        assert!(!default.chapters[2].enabled);
+        assert_eq!(default.playback_duration(), Some(Duration::from_secs(150)));
+    }
+
+    let path = dir.join("no-chapters.mkv");
+    std::fs::write(
+        &path,
+        [
+            element(0x1A45_DFA3, &element(0x4282, b"matroska")),
+            element(0x1853_8067, &element(0x1F43_B675, &[0; 8])),
+        ]
+        .concat(),
+    )
+    .unwrap();
+    assert!(matroska_editions(&path).unwrap().is_empty());
+    assert!(default_edition(&[]).is_none());
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "process")]
+
+use ffprobe::{error::FfProbeError, Config};
+
+mod common;
+
+#[test]
+fn empty_input() {
+    let dir = common::temp_dir("empty", "input");
+    let empty = common::write(&dir, "empty.mp4", b"");
+
+    // Detected before ffprobe runs.
+    let err = Config::new()
+        .ffprobe_bin(dir.join("missing-ffprobe"))
+        .run(&empty)
+        .unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        FfProbeError::EmptyOrTruncatedInput { size: 0 }
+    ));
+    assert_eq!(
+        err.inner().to_string(),
+        "input is empty or truncated (0 bytes)"
+    );
+    assert_eq!(err.input(), Some(empty.as_path()));
+
+    #[cfg(unix)]
+    {
+        let bin = common::script(&dir, "ffprobe", "exit 1");
+
+        let tiny = dir.join("tiny.mp4");
+        std::fs::write(&tiny, [0u8; 100]).unwrap();
+        let err = Config::new().ffprobe_bin(&bin).run(&tiny).unwrap_err();
+        assert!(matches!(
+            err.inner(),
+            FfProbeError::EmptyOrTruncatedInput { size: 100 }
+        ));
+
+        let corrupt = dir.join("corrupt.mp4");
+        std::fs::write(&corrupt, vec![0u8; 64 * 1024]).unwrap();
+        let err = Config::new().ffprobe_bin(&bin).run(&corrupt).unwrap_err();
+        assert!(matches!(err.inner(), FfProbeError::Status(_)));
+    }
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
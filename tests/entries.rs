@@ -0,0 +1,40 @@
+#![cfg(feature = "process")]
+
+use ffprobe::Entries;
+
+#[test]
+fn parse_narrowed_output() {
+    let entries: Entries = serde_json::from_str(
+        r#"{
+            "programs": [],
+            "streams": [{ "index": 0, "codec_name": "h264" }],
+            "format": { "duration": "600.000000", "bit_rate": "5000000" }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(entries.format_field::<f64>("duration"), Some(600.));
+    assert_eq!(entries.format_field::<u64>("bit_rate"), Some(5_000_000));
+    assert_eq!(entries.format_field::<u64>("size"), None);
+    assert_eq!(entries.stream_field::<u32>(0, "index"), Some(0));
+    assert_eq!(
+        entries.stream_field::<String>(0, "codec_name").as_deref(),
+        Some("h264")
+    );
+    assert!(entries.chapters.is_empty());
+}
+
+#[test]
+fn parse_packets_and_frames() {
+    let entries: Entries = serde_json::from_str(
+        r#"{
+            "packets_and_frames": [
+                { "type": "packet", "pts": 0, "flags": "K__" },
+                { "type": "frame", "pts": 0 },
+                { "type": "packet", "pts": 512, "flags": "___" }
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(entries.packets.len(), 2);
+    assert_eq!(entries.frames.len(), 1);
+}
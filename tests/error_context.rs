@@ -0,0 +1,114 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::{error::Error, ffi::OsString};
+
+use ffprobe::{error::FfProbeError, Config};
+
+mod common;
+
+#[test]
+fn status_error_context() {
+    let dir = common::temp_dir("error", "status");
+    let bin = common::script(&dir, "ffprobe", "echo 'moov atom not found' >&2; exit 1");
+    let input = common::write(&dir, "video.mp4", vec![0u8; 64 * 1024]);
+    let err = Config::new().ffprobe_bin(&bin).run(&input).unwrap_err();
+
+    assert_eq!(err.input(), Some(input.as_path()));
+    let command = err.command().unwrap();
+    assert_eq!(command.first(), Some(&OsString::from(&bin)));
+    assert_eq!(command.last(), Some(&OsString::from(&input)));
+    assert!(command.iter().any(|arg| arg == "-show_streams"));
+
+    assert!(matches!(err.inner(), FfProbeError::Status(_)));
+    // The cause only in `source`, not repeated by reporters printing the chain.
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "failed to probe {} with `{}`",
+            input.display(),
+            command
+                .iter()
+                .map(|arg| arg.to_str().unwrap())
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    );
+    assert!(!err.to_string().contains("ffprobe exited"));
+    let source = err.source().unwrap().downcast_ref::<FfProbeError>();
+    assert!(matches!(source, Some(FfProbeError::Status(_))));
+}
+
+#[test]
+fn legacy_command_in_context() {
+    let dir = common::temp_dir("error", "legacy");
+    let bin = common::script(
+        &dir,
+        "ffprobe",
+        r#"case "$*" in
+*print_format*) echo "Unrecognized option 'print_format'" >&2; exit 1 ;;
+*) exit 1 ;;
+esac"#,
+    );
+    let input = common::write(&dir, "video.mp4", vec![0u8; 64 * 1024]);
+    let err = Config::new().ffprobe_bin(&bin).run(&input).unwrap_err();
+    // The command of the retry in the legacy dialect.
+    let command = err.command().unwrap();
+    assert!(command.iter().any(|arg| arg == "-of"));
+    assert!(!command.iter().any(|arg| arg == "-print_format"));
+}
+
+#[test]
+fn io_error_source() {
+    let input = common::temp_dir("error", "io").join("input.mp4");
+    let err = Config::new()
+        .ffprobe_bin("/nonexistent/ffprobe")
+        .run(&input)
+        .unwrap_err();
+    assert_eq!(err.input(), Some(input.as_path()));
+    let FfProbeError::Io(io) = err.inner() else {
+        panic!("{err:?}");
+    };
+    assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+    let inner = err.source().unwrap();
+    assert!(inner.source().unwrap().is::<std::io::Error>());
+
+    // Errors of parsing alone have no input.
+    let err = ffprobe::FfProbe::from_json("{").unwrap_err();
+    assert_eq!(err.input(), None);
+    assert_eq!(err.command(), None);
+    assert!(err.source().unwrap().is::<serde_json::Error>());
+}
+
+#[test]
+fn context_of_other_probes() {
+    let dir = common::temp_dir("error", "entries");
+    let bin = common::script(&dir, "ffprobe", "exit 1");
+    let input = common::write(&dir, "video.mp4", vec![0u8; 64 * 1024]);
+    let config = Config::new().ffprobe_bin(&bin);
+
+    let spec = ffprobe::EntriesSpec::new().format(["duration"]);
+    let err = ffprobe::ffprobe_entries(config.clone(), spec, &input).unwrap_err();
+    assert_eq!(err.input(), Some(input.as_path()));
+    let command = err.command().unwrap();
+    assert_eq!(command.first(), Some(&OsString::from(&bin)));
+    let position = command.iter().position(|a| a == "-show_entries").unwrap();
+    assert_eq!(command[position + 1], "format=duration");
+    assert!(matches!(err.inner(), FfProbeError::Status(_)));
+
+    let err = ffprobe::ffprobe_raw_sections(config.clone(), &input, &["programs"]).unwrap_err();
+    assert_eq!(err.input(), Some(input.as_path()));
+    let command = err.command().unwrap();
+    assert!(command.iter().any(|arg| arg == "-show_programs"));
+    assert_eq!(command.last(), Some(&OsString::from(&input)));
+
+    // ffmpeg runs, with the ffmpeg command line.
+    let err = ffprobe::analysis::loudness(&input, &config.clone().ffmpeg_bin(&bin)).unwrap_err();
+    assert_eq!(err.input(), Some(input.as_path()));
+    assert!(err.command().unwrap().iter().any(|arg| arg == "-filter:a"));
+
+    // Runs without an input.
+    let err = ffprobe::capabilities::decoders(&config).unwrap_err();
+    assert_eq!(err.input(), None);
+    assert!(err.command().unwrap().iter().any(|arg| arg == "-decoders"));
+    assert!(err.to_string().starts_with("failed to run `"), "{err}");
+}
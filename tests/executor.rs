@@ -0,0 +1,115 @@
+#![cfg(all(unix, feature = "streams", feature = "process"))]
+
+use std::{path::PathBuf, process::Command};
+
+use ffprobe::{executor::Executor, Config};
+
+mod common;
+
+/// ffprobe body logging its working directory and `$PROBE_TOKEN` next to the script.
+const LOG_ENV: &str = r#"pwd > "$(dirname "$0")/pwd"
+printf '%s\n' "$PROBE_TOKEN" > "$(dirname "$0")/env""#;
+
+/// Runs commands through a wrapper script, like a container or job runner would.
+struct Wrapper(PathBuf);
+
+impl Executor for Wrapper {
+    fn command(&self, cmd: &Command) -> Command {
+        let mut wrapped = Command::new(&self.0);
+        wrapped.arg(cmd.get_program()).args(cmd.get_args());
+        wrapped
+    }
+}
+
+#[test]
+fn custom_executor() {
+    let dir = common::temp_dir("executor", "custom");
+    let (ffprobe, args) = common::recording(
+        &dir,
+        "ffprobe",
+        &format!("{LOG_ENV}\ncat '{}'", common::fixture("mp4.json").display()),
+    );
+    let wrapper = common::script(
+        &dir,
+        "wrapper",
+        &format!("echo ran > '{}/wrapped'\nexec \"$@\"", dir.display()),
+    );
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = events.clone();
+
+    let probe = Config::new()
+        .ffprobe_bin(&ffprobe)
+        .executor(Wrapper(wrapper.clone()))
+        .on_spawn(move |event| log.lock().unwrap().push(event.program.clone()))
+        .run("/remote/movie.mp4")
+        .unwrap();
+    assert_eq!(probe.streams.len(), 3);
+    assert!(dir.join("wrapped").exists());
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.ends_with("/remote/movie.mp4\n"));
+    assert_eq!(*events.lock().unwrap(), [wrapper.into_os_string()]);
+}
+
+#[cfg(feature = "ssh")]
+#[test]
+fn ssh() {
+    use ffprobe::executor::Ssh;
+
+    let dir = common::temp_dir("executor", "ssh");
+    let (ffprobe, args) = common::recording(
+        &dir,
+        "ffprobe",
+        &format!("{LOG_ENV}\ncat '{}'", common::fixture("mp4.json").display()),
+    );
+    // Runs the remote command locally.
+    let ssh = common::script(
+        &dir,
+        "ssh",
+        &format!(
+            "printf '%s\\n' \"$@\" > '{}/ssh-args'\nfor arg; do last=$arg; done\nexec sh -c \"$last\"",
+            dir.display()
+        ),
+    );
+    let workdir = dir.join("work dir");
+    std::fs::create_dir_all(&workdir).unwrap();
+
+    let probe = Config::new()
+        .ffprobe_bin(&ffprobe)
+        .env("PROBE_TOKEN", "it's $secret")
+        .current_dir(&workdir)
+        .executor(
+            Ssh::new("media@storage-1")
+                .ssh_bin(&ssh)
+                .option("-p", "2222"),
+        )
+        .run("/srv/media/a movie; rm -rf.mp4")
+        .unwrap();
+    assert_eq!(probe.streams.len(), 3);
+
+    let ssh_args = std::fs::read_to_string(dir.join("ssh-args")).unwrap();
+    assert!(ssh_args.starts_with("-T\n-o\nBatchMode=yes\n-p\n2222\n--\nmedia@storage-1\ncd "));
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.ends_with("\n/srv/media/a movie; rm -rf.mp4\n"));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("env")).unwrap(),
+        "it's $secret\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("pwd")).unwrap().trim_end(),
+        workdir.to_str().unwrap()
+    );
+}
+
+#[cfg(feature = "ssh")]
+#[test]
+fn ssh_remote_command() {
+    use ffprobe::executor::Ssh;
+
+    let mut cmd = Command::new("ffprobe");
+    cmd.args(["-v", "quiet", "it's here.mkv"])
+        .env_remove("LANG");
+    assert_eq!(
+        Ssh::new("host").remote_command(&cmd),
+        r"env -u LANG ffprobe -v quiet 'it'\''s here.mkv'"
+    );
+}
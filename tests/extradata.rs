@@ -0,0 +1,152 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{FfProbe, NalCodec, ParameterSets, SpsInfo};
+use serde_json::{json, Value};
+
+/// avcC of a 1080p H.264 High stream, level 4.
+const AVCC: &[u8] = &[
+    0x01, 0x64, 0x00, 0x28, 0xff, 0xe1, 0x00, 0x1b, 0x67, 0x64, 0x00, 0x28, 0xac, 0xd9, 0x40, 0x78,
+    0x02, 0x27, 0xe5, 0xc0, 0x44, 0x00, 0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x03, 0x00, 0xf0, 0x3c,
+    0x60, 0xc6, 0x58, 0x01, 0x00, 0x06, 0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0,
+];
+
+/// SPS of a 1080p HEVC Main stream, level 4.
+const HEVC_SPS: &[u8] = &[
+    0x42, 0x01, 0x01, 0x01, 0x60, 0x00, 0x00, 0x03, 0x00, 0x90, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03,
+    0x00, 0x78, 0xa0, 0x03, 0xc0, 0x80, 0x10, 0xe5, 0x96, 0x66, 0x69, 0x24, 0xca, 0xe0, 0x10, 0x00,
+    0x00, 0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x01, 0xe0, 0x80,
+];
+
+/// Format `data` like ffprobe's `-show_data`.
+fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::from("\n");
+    for (line, chunk) in data.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk
+            .chunks(2)
+            .map(|g| g.iter().map(|b| format!("{b:02x}")).collect())
+            .collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if (32..127).contains(&b) {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        dump.push_str(&format!(
+            "{:08x}: {:<40} {ascii}\n",
+            line * 16,
+            hex.join(" ")
+        ));
+    }
+    dump
+}
+
+fn fixture() -> Value {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+    serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+}
+
+#[test]
+fn avcc_parameter_sets() {
+    let sets = ParameterSets::parse(NalCodec::H264, AVCC).unwrap();
+    assert_eq!(sets.nal_length_size, Some(4));
+    assert!(sets.vps.is_empty());
+    assert_eq!(sets.sps.len(), 1);
+    assert_eq!(sets.sps[0].len(), 27);
+    assert_eq!(sets.pps, [vec![0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0]]);
+
+    let info = sets.sps_info().unwrap();
+    assert_eq!(info.profile_idc, 100);
+    assert_eq!(info.level_idc, 40);
+    assert_eq!(info.chroma_format_idc, 1);
+    assert_eq!(info.chroma_subsampling(), Some("4:2:0"));
+    assert_eq!((info.bit_depth_luma, info.bit_depth_chroma), (8, 8));
+}
+
+#[test]
+fn annex_b_parameter_sets() {
+    let mut data = vec![0, 0, 0, 1];
+    data.extend_from_slice(&AVCC[8..35]);
+    data.extend_from_slice(&[0, 0, 1]);
+    data.extend_from_slice(&AVCC[38..]);
+    let sets = ParameterSets::parse(NalCodec::H264, &data).unwrap();
+    assert_eq!(sets.nal_length_size, None);
+    assert_eq!(sets.sps, [AVCC[8..35].to_vec()]);
+    assert_eq!(sets.pps, [AVCC[38..].to_vec()]);
+
+    assert_eq!(ParameterSets::parse(NalCodec::H264, &[0x42, 0x00]), None);
+}
+
+#[test]
+fn hvcc_parameter_sets() {
+    let mut hvcc = vec![0x01, 0x01, 0x60, 0, 0, 0, 0x90, 0, 0, 0, 0, 0, 0x78];
+    hvcc.resize(21, 0);
+    // 4 byte NAL unit lengths, 1 array with the SPS.
+    hvcc.extend_from_slice(&[0x0f, 0x01, 0x21, 0x00, 0x01]);
+    hvcc.extend_from_slice(&(HEVC_SPS.len() as u16).to_be_bytes());
+    hvcc.extend_from_slice(HEVC_SPS);
+
+    let sets = ParameterSets::parse(NalCodec::Hevc, &hvcc).unwrap();
+    assert_eq!(sets.nal_length_size, Some(4));
+    assert_eq!(sets.sps, [HEVC_SPS.to_vec()]);
+    assert_eq!(
+        sets.sps_info(),
+        SpsInfo::parse_hevc(HEVC_SPS),
+        "the hvcC SPS is the one parsed on its own"
+    );
+}
+
+#[test]
+fn hevc_sps_info() {
+    let info = SpsInfo::parse_hevc(HEVC_SPS).unwrap();
+    assert_eq!(info.profile_idc, 1);
+    assert_eq!(info.level_idc, 120);
+    assert!(!info.high_tier);
+    assert_eq!(info.chroma_format_idc, 1);
+    assert_eq!((info.bit_depth_luma, info.bit_depth_chroma), (8, 8));
+
+    assert_eq!(SpsInfo::parse_hevc(&HEVC_SPS[..10]), None);
+}
+
+#[test]
+fn stream_extradata() {
+    let mut json = fixture();
+    json["streams"][0]["extradata"] = json!(hex_dump(AVCC));
+    let probe = FfProbe::from_json(&json.to_string()).unwrap();
+    assert_eq!(probe.streams[0].extradata.as_deref(), Some(AVCC));
+    let sets = probe.streams[0].parameter_sets().unwrap();
+    assert_eq!(sets.codec, NalCodec::H264);
+    assert_eq!(sets.sps_info().unwrap().profile_idc, 100);
+    // Not a video stream.
+    assert_eq!(probe.streams[1].extradata, None);
+    assert_eq!(probe.streams[1].parameter_sets(), None);
+
+    // Serialized as the same hex dump.
+    let value = serde_json::to_value(&probe.streams[0]).unwrap();
+    assert_eq!(value["extradata"], json!(hex_dump(AVCC)));
+    assert!(serde_json::to_value(&probe.streams[1])
+        .unwrap()
+        .get("extradata")
+        .is_none());
+
+    json["streams"][0]["extradata"] = json!("\n00000000: zz");
+    assert!(FfProbe::from_json(&json.to_string()).is_err());
+}
+
+#[cfg(feature = "process")]
+#[test]
+fn show_data_arg() {
+    use ffprobe::{Config, ProbeCommand};
+
+    let args = ProbeCommand::new(Config::new())
+        .input("video.mp4")
+        .get_args();
+    assert!(!args.iter().any(|a| a == "-show_data"));
+    let args = ProbeCommand::new(Config::new().show_data(true))
+        .input("video.mp4")
+        .get_args();
+    assert!(args.iter().any(|a| a == "-show_data"));
+}
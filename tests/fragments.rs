@@ -0,0 +1,106 @@
+#![cfg(feature = "streams")]
+
+mod common;
+
+fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn words(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_be_bytes()).collect()
+}
+
+/// Single video track (id 1, timescale 12800) with a trex default duration of 512.
+fn moov() -> Vec<u8> {
+    let tkhd = mp4_box(b"tkhd", &words(&[0, 0, 0, 1, 0, 0]));
+    let mdhd = mp4_box(b"mdhd", &words(&[0, 0, 0, 12800, 0, 0]));
+    let mut hdlr = words(&[0, 0]);
+    hdlr.extend_from_slice(b"vide");
+    hdlr.extend_from_slice(&words(&[0, 0, 0, 0]));
+    let mdia = mp4_box(b"mdia", &[mdhd, mp4_box(b"hdlr", &hdlr)].concat());
+    let trak = mp4_box(b"trak", &[tkhd, mdia].concat());
+    let trex = mp4_box(b"trex", &words(&[0, 1, 1, 512, 0, 0]));
+    mp4_box(b"moov", &[trak, mp4_box(b"mvex", &trex)].concat())
+}
+
+fn moof(sequence: u32, decode_time: u32, trun: Vec<u8>) -> Vec<u8> {
+    let mfhd = mp4_box(b"mfhd", &words(&[0, sequence]));
+    let tfhd = mp4_box(b"tfhd", &words(&[0x02_0000, 1]));
+    let tfdt = mp4_box(b"tfdt", &words(&[0, decode_time]));
+    let traf = mp4_box(b"traf", &[tfhd, tfdt, mp4_box(b"trun", &trun)].concat());
+    mp4_box(b"moof", &[mfhd, traf].concat())
+}
+
+#[test]
+fn enumerate_fragments() {
+    let file = [
+        mp4_box(b"ftyp", b"iso6\0\0\0\0"),
+        moov(),
+        // Explicit sample durations.
+        moof(1, 0, words(&[0x100, 2, 6400, 6400])),
+        mp4_box(b"mdat", &[0; 32]),
+        // trex default durations.
+        moof(2, 12800, words(&[0, 25])),
+        mp4_box(b"mdat", &[0; 16]),
+    ]
+    .concat();
+
+    let path = common::write(
+        &common::temp_dir("fragments", "enumerate"),
+        "fragmented.mp4",
+        file,
+    );
+
+    let fragments = ffprobe::fragments(&path).unwrap();
+    assert_eq!(fragments.len(), 2);
+
+    let first = &fragments[0];
+    assert_eq!(first.sequence_number, 1);
+    assert_eq!(first.mdat_size, Some(40));
+    assert_eq!(first.tracks[0].duration_ticks, 12800);
+    assert_eq!(first.duration(), Some(std::time::Duration::from_secs(1)));
+
+    let second = &fragments[1];
+    assert_eq!(second.offset, first.offset + first.moof_size + 40);
+    assert_eq!(second.tracks[0].sample_count, 25);
+    assert_eq!(second.tracks[0].duration_ticks, 25 * 512);
+    assert_eq!(
+        second.tracks[0].start(),
+        Some(std::time::Duration::from_secs(1))
+    );
+}
+
+#[test]
+fn oversized_box() {
+    // A box with a 64-bit size that overflows its end offset.
+    let mut huge = words(&[1]);
+    huge.extend_from_slice(b"free");
+    huge.extend_from_slice(&(u64::MAX - 4).to_be_bytes());
+    let file = [mp4_box(b"ftyp", b"iso6\0\0\0\0"), huge].concat();
+
+    let path = common::write(
+        &common::temp_dir("fragments", "oversized"),
+        "oversized.mp4",
+        file,
+    );
+
+    let err = ffprobe::fragments(&path).unwrap_err();
+    assert!(err.to_string().contains("exceeds the file"), "{err}");
+}
+
+#[test]
+fn huge_decode_time() {
+    // A crafted `tfdt` beyond `Duration::MAX` at a timescale of 1.
+    let track = ffprobe::TrackFragment {
+        track_id: 1,
+        timescale: Some(1),
+        base_decode_time: Some(u64::MAX),
+        sample_count: 1,
+        duration_ticks: u64::MAX,
+    };
+    assert_eq!(track.start(), None);
+    assert_eq!(track.duration(), None);
+}
@@ -0,0 +1,57 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::time::Duration;
+
+use ffprobe::{analysis::gop_structure, Config};
+use serde_json::json;
+
+mod common;
+
+#[test]
+fn open_and_closed_gops() {
+    let dir = common::temp_dir("gop", "open-closed");
+    // Decode order: a closed GOP `I P B B`, then an open one whose leading B-frames are
+    // displayed before its keyframe, and a discarded packet.
+    let packets: Vec<_> = [
+        ("0.000000", "K__"),
+        ("0.120000", "___"),
+        ("0.040000", "___"),
+        ("0.080000", "___"),
+        ("0.240000", "K__"),
+        ("0.160000", "___"),
+        ("0.200000", "___"),
+        ("0.360000", "___"),
+        ("0.280000", "___"),
+        ("0.320000", "___"),
+        ("0.300000", "__D"),
+    ]
+    .into_iter()
+    .map(|(pts_time, flags)| json!({ "pts_time": pts_time, "flags": flags }))
+    .collect();
+    let (bin, args) = common::fake_ffprobe(&dir, json!({ "packets": packets }).to_string());
+    let input = common::input(&dir, "video.mp4");
+
+    let gop = gop_structure(&input, 0, &Config::new().ffprobe_bin(&bin)).unwrap();
+    assert_eq!(gop.keyframes, [Duration::ZERO, Duration::from_millis(240)]);
+    assert_eq!(gop.frames, 10);
+    assert_eq!((gop.closed_gops, gop.open_gops), (1, 1));
+    assert!(!gop.is_closed());
+    assert_eq!(gop.max_gop_length, Some(6));
+    assert_eq!(gop.average_gop_length, Some(5.));
+    assert_eq!((gop.b_frames, gop.max_consecutive_b_frames), (6, 2));
+    assert_eq!(gop.b_frame_ratio(), Some(0.6));
+    assert_eq!(
+        gop.max_keyframe_interval(),
+        Some(Duration::from_millis(240))
+    );
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-select_streams\n0\n-show_entries\npacket=pts_time,flags\n"));
+    assert!(!args.contains("-show_streams"));
+}
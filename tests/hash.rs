@@ -0,0 +1,65 @@
+#![cfg(all(unix, feature = "process"))]
+
+use ffprobe::{Config, HashAlgo, StreamIndex};
+
+mod common;
+
+#[test]
+fn file_and_stream_hashes() {
+    let dir = common::temp_dir("hash", "sha256");
+    let (ffprobe, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let (ffmpeg, args) = common::printing(
+        &dir,
+        "ffmpeg",
+        "0,v,SHA256=62E8AB\n1,a,SHA256=0b7fc2\n2,s,SHA256=e3b0c4\nSHA256=9f86d0\n",
+    );
+    let config = Config::new().ffprobe_bin(&ffprobe).ffmpeg_bin(&ffmpeg);
+    let input = common::input(&dir, "input.mp4");
+    let probe = config.hash(HashAlgo::Sha256).run(&input).unwrap();
+    let hashes = probe.hashes.unwrap();
+    assert_eq!(hashes.algo, HashAlgo::Sha256);
+    assert_eq!(hashes.file, "9f86d0");
+    assert_eq!(hashes.streams.len(), 3);
+    assert_eq!(hashes.stream(0), Some("62e8ab"));
+    assert_eq!(hashes.stream(2), Some("e3b0c4"));
+    assert_eq!(hashes.stream(3), None);
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-f\ndata\n-protocol_whitelist\nfile,http,tcp,crypto,https,tls\n-i\n"));
+    assert!(args.contains("-map\n0\n-c\ncopy\n-f\nstreamhash\n-hash\nSHA256\npipe:1\n"));
+    assert!(args.contains("-map\n1:0\n-c\ncopy\n-f\nhash\n-hash\nSHA256\npipe:1\n"));
+}
+
+#[test]
+fn file_hash_printed_first() {
+    let dir = common::temp_dir("hash", "md5");
+    let (ffprobe, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let (ffmpeg, _) = common::printing(&dir, "ffmpeg", "MD5=d41d8c\n0,v,MD5=aa\n");
+    let config = Config::new().ffprobe_bin(&ffprobe).ffmpeg_bin(&ffmpeg);
+    let input = common::input(&dir, "input.mp4");
+    let probe = config.hash(HashAlgo::Md5).run(&input).unwrap();
+    let hashes = probe.hashes.unwrap();
+    assert_eq!(hashes.file, "d41d8c");
+    assert_eq!(hashes.streams, [(StreamIndex(0), "aa".to_string())]);
+}
+
+#[test]
+fn missing_file_hash_is_an_error() {
+    let dir = common::temp_dir("hash", "missing");
+    let (ffprobe, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let (ffmpeg, _) = common::printing(&dir, "ffmpeg", "0,v,MD5=aa\n");
+    let config = Config::new().ffprobe_bin(&ffprobe).ffmpeg_bin(&ffmpeg);
+    let input = common::input(&dir, "input.mp4");
+    assert!(config.hash(HashAlgo::Md5).run(&input).is_err());
+}
+
+#[test]
+fn no_hashes_by_default() {
+    let dir = common::temp_dir("hash", "default");
+    let (ffprobe, _) = common::fixture_ffprobe(&dir, "mp4.json", &[]);
+    let (ffmpeg, args) = common::printing(&dir, "ffmpeg", "MD5=d41d8c\n");
+    let config = Config::new().ffprobe_bin(&ffprobe).ffmpeg_bin(&ffmpeg);
+    let input = common::input(&dir, "input.mp4");
+    assert!(config.run(&input).unwrap().hashes.is_none());
+    assert!(!args.exists());
+}
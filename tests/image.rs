@@ -0,0 +1,105 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use ffprobe::{probe_image_config, probe_image_sequence, Config, Rotation};
+use serde_json::{json, Value};
+
+mod common;
+
+/// The fixture with `video` merged into its video stream as the only stream, and
+/// `frames`.
+fn image_json(video: Value, frames: Value) -> String {
+    let mut value = common::fixture_json("mp4.json");
+    let mut stream = value["streams"][0].take();
+    for (key, v) in video.as_object().unwrap() {
+        stream[key] = v.clone();
+    }
+    value["streams"] = json!([stream]);
+    value["frames"] = frames;
+    value.to_string()
+}
+
+#[test]
+fn jpeg_with_exif_orientation() {
+    let dir = common::temp_dir("image", "jpeg");
+    let (bin, args) = common::fake_ffprobe(
+        &dir,
+        image_json(
+            json!({
+                "codec_name": "mjpeg",
+                "width": 4032,
+                "height": 3024,
+                "pix_fmt": "yuvj420p",
+                "nb_read_packets": "1"
+            }),
+            json!([{
+                "media_type": "video",
+                "side_data_list": [{ "side_data_type": "ICC profile" }],
+                "tags": { "Make": "Phone", "Orientation": "6" }
+            }]),
+        ),
+    );
+    let input = common::input(&dir, "image");
+    let image = probe_image_config(&Config::new().ffprobe_bin(&bin), &input).unwrap();
+    assert_eq!(image.codec_name, "mjpeg");
+    assert_eq!((image.width, image.height), (4032, 3024));
+    assert_eq!(image.pix_fmt.as_deref(), Some("yuvj420p"));
+    assert!(image.has_color_profile);
+    assert_eq!(image.exif_orientation, Some(6));
+    assert_eq!(image.rotation, Rotation::clockwise(90.));
+    assert_eq!(image.display_dimensions(), (3024, 4032));
+    assert_eq!(image.frame_count, Some(1));
+    assert!(!image.is_animated());
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-count_packets\n-show_frames\n"));
+    assert!(args.contains("-select_streams\nv:0\n"));
+}
+
+#[test]
+fn animated_gif() {
+    let dir = common::temp_dir("image", "gif");
+    let (bin, _) = common::fake_ffprobe(
+        &dir,
+        image_json(
+            json!({ "codec_name": "gif", "pix_fmt": "bgra", "nb_read_packets": "24" }),
+            json!([{ "media_type": "video" }]),
+        ),
+    );
+    let input = common::input(&dir, "image");
+    let image = probe_image_config(&Config::new().ffprobe_bin(&bin), &input).unwrap();
+    assert!(image.is_animated());
+    assert_eq!(image.frame_count, Some(24));
+    assert!(!image.has_color_profile);
+    assert_eq!(image.exif_orientation, None);
+    assert_eq!(image.rotation, Rotation::NONE);
+}
+
+#[test]
+fn exif_orientations() {
+    let mirrored = Rotation::from_exif_orientation(2).unwrap();
+    assert!(mirrored.hflip && mirrored.degrees == 0);
+    let transposed = Rotation::from_exif_orientation(5).unwrap();
+    assert!(transposed.hflip && transposed.swaps_dimensions());
+    assert_eq!(
+        Rotation::from_exif_orientation(8),
+        Some(Rotation::clockwise(270.))
+    );
+    assert_eq!(Rotation::from_exif_orientation(9), None);
+}
+
+#[test]
+fn glob_sequence() {
+    let dir = common::temp_dir("image", "sequence");
+    let (bin, args) =
+        common::fake_ffprobe(&dir, image_json(json!({ "codec_name": "png" }), json!([])));
+    let probe = probe_image_sequence(&Config::new().ffprobe_bin(&bin), "frames/*.png").unwrap();
+    assert_eq!(probe.streams.len(), 1);
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.ends_with("-f\nimage2\n-pattern_type\nglob\nframes/*.png\n"));
+}
@@ -0,0 +1,96 @@
+#![cfg(all(unix, feature = "streams", feature = "process"))]
+
+use ffprobe::{probe_init_segment_config, Config, InitSegment, InitSegmentSource};
+
+mod common;
+
+fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn words(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_be_bytes()).collect()
+}
+
+/// Init segment of a single video track (id 1, timescale 12800) with a trex default
+/// duration of 512.
+fn init_segment() -> Vec<u8> {
+    let tkhd = mp4_box(b"tkhd", &words(&[0, 0, 0, 1, 0, 0]));
+    let mdhd = mp4_box(b"mdhd", &words(&[0, 0, 0, 12800, 0, 0]));
+    let mut hdlr = words(&[0, 0]);
+    hdlr.extend_from_slice(b"vide");
+    hdlr.extend_from_slice(&words(&[0, 0, 0, 0]));
+    let mdia = mp4_box(b"mdia", &[mdhd, mp4_box(b"hdlr", &hdlr)].concat());
+    let trak = mp4_box(b"trak", &[tkhd, mdia].concat());
+    let trex = mp4_box(b"trex", &words(&[0, 1, 1, 512, 0, 0]));
+    let moov = mp4_box(b"moov", &[trak, mp4_box(b"mvex", &trex)].concat());
+    [mp4_box(b"ftyp", b"iso6\0\0\0\0"), moov].concat()
+}
+
+/// ffprobe body reading the segment from stdin for `pipe:0`, logging the bytes read
+/// next to the script.
+const SEGMENT_FFPROBE: &str = r#"case "$*" in *pipe:0*) wc -c > "$(dirname "$0")/stdin-bytes" ;; esac
+echo '{"streams":[{"codec_type":"video","codec_name":"h264","id":"0x1"}]}'"#;
+
+fn assert_tracks(segment: &InitSegment) {
+    let [track] = &segment.tracks[..] else {
+        panic!("{segment:?}");
+    };
+    assert_eq!(track.track_id, 1);
+    assert_eq!(track.timescale, 12800);
+    assert_eq!(track.handler, "vide");
+    assert_eq!(track.default_sample_duration, Some(512));
+    let codec = track.codec.as_ref().unwrap();
+    assert_eq!(codec.codec_name.as_deref(), Some("h264"));
+}
+
+#[test]
+fn probe_file() {
+    let dir = common::temp_dir("init-segment", "file");
+    let (bin, args) = common::recording(&dir, "ffprobe", SEGMENT_FFPROBE);
+    let path = common::write(&dir, "init.mp4", init_segment());
+
+    let config = Config::new().ffprobe_bin(&bin).capture_warnings(true);
+    let segment = probe_init_segment_config(config, path.as_path()).unwrap();
+    assert_tracks(&segment);
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-v\nlevel+warning\n"), "{args}");
+    assert!(args.contains("-show_streams\n-f\nmp4\n"), "{args}");
+    assert!(
+        args.contains("-protocol_whitelist\nfile,http,tcp,crypto,https,tls\n"),
+        "{args}"
+    );
+    assert!(args.ends_with("/init.mp4\n"), "{args}");
+    assert!(!dir.join("stdin-bytes").exists());
+}
+
+#[test]
+fn probe_bytes() {
+    let dir = common::temp_dir("init-segment", "bytes");
+    let (bin, args) = common::recording(&dir, "ffprobe", SEGMENT_FFPROBE);
+    let bytes = init_segment();
+
+    let config = Config::new().ffprobe_bin(&bin);
+    let segment = probe_init_segment_config(config, InitSegmentSource::Bytes(&bytes)).unwrap();
+    assert_tracks(&segment);
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-v\nquiet\n"), "{args}");
+    // Without `file`, as for other inputs that aren't local.
+    assert!(
+        args.contains("-protocol_whitelist\nhttp,tcp,crypto,https,tls,pipe,fd\n"),
+        "{args}"
+    );
+    assert!(args.ends_with("\npipe:0\n"), "{args}");
+    let read = std::fs::read_to_string(dir.join("stdin-bytes")).unwrap();
+    assert_eq!(read.trim().parse::<usize>().unwrap(), bytes.len());
+
+    // Through the output limit, too.
+    let config = Config::new().ffprobe_bin(&bin).max_output_bytes(1024);
+    let segment = probe_init_segment_config(config, InitSegmentSource::Bytes(&bytes)).unwrap();
+    assert_tracks(&segment);
+}
@@ -0,0 +1,86 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use std::time::Duration;
+
+use ffprobe::{FfProbe, IntegrityFinding, StreamIndex};
+use serde_json::Value;
+
+fn fixture() -> Value {
+    let json = std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap();
+    serde_json::from_slice(&json).unwrap()
+}
+
+fn validate(value: Value) -> Vec<IntegrityFinding> {
+    let probe: FfProbe = serde_json::from_value(value).unwrap();
+    probe.validate().findings
+}
+
+#[test]
+fn consistent_output() {
+    let probe: FfProbe = serde_json::from_value(fixture()).unwrap();
+    let report = probe.validate();
+    assert!(report.is_ok(), "{:?}", report.findings);
+}
+
+#[test]
+fn stream_count_and_duration() {
+    let mut value = fixture();
+    value["format"]["nb_streams"] = 4.into();
+    // Audio ends at 540 s of 600 s, 10% short.
+    value["streams"][1]["duration_ts"] = (540 * 48000).into();
+    // Video at 590 s is within the tolerance.
+    value["streams"][0]["duration_ts"] = (590 * 12288).into();
+    assert_eq!(
+        validate(value),
+        [
+            IntegrityFinding::StreamCountMismatch {
+                nb_streams: 4,
+                streams: 3
+            },
+            IntegrityFinding::DurationMismatch {
+                index: StreamIndex(1),
+                stream: Duration::from_secs(540),
+                format: Duration::from_secs(600),
+            },
+        ]
+    );
+}
+
+#[test]
+fn zero_duration_and_missing_parameters() {
+    let mut value = fixture();
+    value["streams"][0]["duration_ts"] = 0.into();
+    value["streams"][1]["channels"] = 0.into();
+    value["streams"][1]["sample_rate"] = "0".into();
+    let findings = validate(value);
+    assert_eq!(
+        findings,
+        [
+            IntegrityFinding::DurationMismatch {
+                index: StreamIndex(0),
+                stream: Duration::ZERO,
+                format: Duration::from_secs(600),
+            },
+            IntegrityFinding::ZeroDurationVideo {
+                index: StreamIndex(0)
+            },
+            IntegrityFinding::MissingCodecParameters {
+                index: StreamIndex(1),
+                missing: vec!["sample_rate", "channels"],
+            },
+        ]
+    );
+    assert_eq!(
+        findings[2].to_string(),
+        "stream 1 is missing sample_rate, channels"
+    );
+
+    // Cover art has no duration to check.
+    let mut value = fixture();
+    value["streams"][0]["duration_ts"] = 0.into();
+    value["streams"][0]["disposition"]["attached_pic"] = 1.into();
+    assert_eq!(validate(value), []);
+}
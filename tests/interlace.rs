@@ -0,0 +1,145 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::{path::Path, time::Duration};
+
+use ffprobe::{
+    analysis::{interlace_detect, Heuristics, SamplingStrategy, ScanType},
+    Config,
+};
+
+mod common;
+
+/// Config sampling the first minute.
+fn first_minute(ffmpeg: &Path) -> Config {
+    let mut heuristics = Heuristics::default();
+    heuristics.sampling = SamplingStrategy::Window {
+        start: Duration::ZERO,
+        duration: Duration::from_secs(60),
+    };
+    Config::new().ffmpeg_bin(ffmpeg).heuristics(heuristics)
+}
+
+fn idet_log(repeated: [u64; 3], single: [u64; 4], multi: [u64; 4]) -> String {
+    format!(
+        "[Parsed_idet_0 @ 0x5581] Repeated Fields: Neither: {:5} Top: {:5} Bottom: {:5}\n\
+         [Parsed_idet_0 @ 0x5581] Single frame detection: TFF: {:5} BFF: {:5} Progressive: {:5} Undetermined: {:5}\n\
+         [Parsed_idet_0 @ 0x5581] Multi frame detection: TFF: {:5} BFF: {:5} Progressive: {:5} Undetermined: {:5}\n",
+        repeated[0], repeated[1], repeated[2],
+        single[0], single[1], single[2], single[3],
+        multi[0], multi[1], multi[2], multi[3],
+    )
+}
+
+#[test]
+fn interlaced_tff() {
+    let (bin, args) = common::fake_ffmpeg(
+        &common::temp_dir("interlace", "tff"),
+        &idet_log([998, 1, 1], [688, 0, 285, 27], [996, 0, 4, 0]),
+    );
+    let report = interlace_detect("input.ts", &first_minute(&bin)).unwrap();
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(
+        args.starts_with(
+            "-hide_banner\n-nostats\n-nostdin\n-ss\n0\n-t\n60\n\
+             -protocol_whitelist\nfile,http,tcp,crypto,https,tls\n-i\ninput.ts\n"
+        ),
+        "{args}"
+    );
+    assert!(args.contains("-filter:v\nidet\n"));
+
+    assert_eq!(report.multi_frame.total(), 1000);
+    assert_eq!(report.single_frame.undetermined, 27);
+    assert_eq!(report.tff_percent(), 99.6);
+    assert_eq!(report.bff_percent(), 0.);
+    assert_eq!(report.progressive_percent(), 0.4);
+    assert_eq!(report.telecined_percent(), 0.2);
+    assert_eq!(report.scan_type(), ScanType::TopFieldFirst);
+    assert!(report.is_interlaced());
+}
+
+#[test]
+fn telecined_and_progressive() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("interlace", "telecine"),
+        &idet_log([600, 200, 200], [300, 0, 700, 0], [350, 0, 650, 0]),
+    );
+    let config = first_minute(&bin);
+    let report = interlace_detect("input.ts", &config).unwrap();
+    assert_eq!(report.telecined_percent(), 40.);
+    assert_eq!(report.scan_type(), ScanType::Telecined);
+    assert!(!report.is_interlaced());
+
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("interlace", "progressive"),
+        &idet_log([1000, 0, 0], [0, 0, 990, 10], [0, 0, 1000, 0]),
+    );
+    let config = first_minute(&bin);
+    let report = interlace_detect("input.mp4", &config).unwrap();
+    assert_eq!(report.scan_type(), ScanType::Progressive);
+
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("interlace", "static"),
+        &idet_log([10, 0, 0], [0, 0, 2, 8], [1, 0, 2, 7]),
+    );
+    let config = first_minute(&bin);
+    let report = interlace_detect("input.mp4", &config).unwrap();
+    assert_eq!(report.scan_type(), ScanType::Undetermined);
+}
+
+#[test]
+fn missing_summary() {
+    let (bin, _) = common::fake_ffmpeg(
+        &common::temp_dir("interlace", "missing"),
+        "Output #0, null, to 'pipe:':\n",
+    );
+    let config = first_minute(&bin);
+    let err = interlace_detect("input.mp4", &config).unwrap_err();
+    assert!(err.to_string().contains("idet summary"));
+}
+
+#[test]
+fn allowed_protocols() {
+    let (bin, args) = common::fake_ffmpeg(&common::temp_dir("interlace", "protocols"), "");
+    let config = first_minute(&bin);
+    let err = interlace_detect("rtmp://example.com/live", &config).unwrap_err();
+    assert!(
+        matches!(err, ffprobe::error::FfProbeError::ProtocolNotAllowed { .. }),
+        "{err:?}"
+    );
+    assert!(!args.exists());
+
+    // Without `file` for remote inputs.
+    let _ = interlace_detect("https://example.com/a.ts", &config);
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(
+        args.contains("-protocol_whitelist\nhttp,tcp,crypto,https,tls\n-i\nhttps://"),
+        "{args}"
+    );
+}
+
+#[test]
+fn uniform_samples() {
+    let dir = common::temp_dir("interlace", "uniform");
+    let (ffprobe, _) = common::fake_ffprobe(&dir, r#"{"format": {"duration": "30"}}"#);
+    let (ffmpeg, args) = common::fake_ffmpeg(
+        &dir,
+        &idet_log([998, 1, 1], [688, 0, 285, 27], [996, 0, 4, 0]),
+    );
+    let mut heuristics = Heuristics::default();
+    heuristics.sampling = SamplingStrategy::Uniform {
+        count: 2,
+        window: Duration::from_secs(2),
+    };
+    let config = Config::new()
+        .ffprobe_bin(&ffprobe)
+        .ffmpeg_bin(&ffmpeg)
+        .heuristics(heuristics);
+    let report = interlace_detect(common::input(&dir, "input.ts"), &config).unwrap();
+    // Counts of both samples.
+    assert_eq!(report.multi_frame.total(), 2000);
+    assert_eq!(report.repeated_top, 2);
+    assert_eq!(report.scan_type(), ScanType::TopFieldFirst);
+    // The last sample, centered at 20s of the 30s.
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-ss\n19\n-t\n2\n"), "{args}");
+}
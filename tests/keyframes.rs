@@ -0,0 +1,84 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::time::Duration;
+
+use ffprobe::{analysis::keyframe_index, Config};
+use serde_json::{json, Value};
+
+mod common;
+
+/// Packets printed by the fake ffprobe.
+fn packets() -> Value {
+    json!({ "packets": [
+        { "pts_time": "0.000000", "pos": "48", "flags": "K__" },
+        { "pts_time": "0.080000", "pos": "1200", "flags": "___" },
+        { "pts_time": "2.000000", "pos": "90210", "flags": "K__" },
+        { "pts_time": "1.960000", "pos": "90100", "flags": "K_D" },
+        { "dts_time": "4.000000", "pos": "N/A", "flags": "K__" },
+        { "pts_time": "9.000000", "pos": "301000", "flags": "K__" },
+    ]})
+}
+
+#[test]
+fn keyframes_with_offsets() {
+    let dir = common::temp_dir("keyframes", "all");
+    let (bin, args) = common::fake_ffprobe(&dir, packets().to_string());
+    let config = Config::new().ffprobe_bin(&bin);
+    let input = common::input(&dir, "input.mp4");
+    let keyframes = keyframe_index(&input, None, &config).unwrap();
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(args.contains("-select_streams\nv:0\n"));
+    assert!(args.contains("packet=pts_time,dts_time,pos,flags\n"));
+
+    let found: Vec<_> = keyframes.iter().map(|k| (k.timestamp, k.pos)).collect();
+    assert_eq!(
+        found,
+        [
+            (Duration::ZERO, Some(48)),
+            (Duration::from_secs(2), Some(90210)),
+            (Duration::from_secs(4), None),
+            (Duration::from_secs(9), Some(301000)),
+        ]
+    );
+}
+
+#[test]
+fn downsampled() {
+    let dir = common::temp_dir("keyframes", "every");
+    let (bin, _) = common::fake_ffprobe(&dir, packets().to_string());
+    let config = Config::new().ffprobe_bin(&bin);
+    let input = common::input(&dir, "input.mp4");
+    let keyframes = keyframe_index(&input, Some(Duration::from_secs(3)), &config).unwrap();
+    let timestamps: Vec<_> = keyframes.iter().map(|k| k.timestamp).collect();
+    assert_eq!(
+        timestamps,
+        [
+            Duration::ZERO,
+            Duration::from_secs(4),
+            Duration::from_secs(9)
+        ]
+    );
+}
+
+#[test]
+fn no_video_stream() {
+    let dir = common::temp_dir("keyframes", "empty");
+    let (bin, _) = common::fake_ffprobe(&dir, json!({ "packets": [] }).to_string());
+    let config = Config::new().ffprobe_bin(&bin);
+    let input = common::input(&dir, "input.mp4");
+    assert!(keyframe_index(&input, None, &config).is_err());
+}
+
+#[test]
+fn probe_options_dont_apply() {
+    let dir = common::temp_dir("keyframes", "options");
+    let (bin, args) = common::fake_ffprobe(&dir, packets().to_string());
+    let config = Config::new().ffprobe_bin(&bin);
+    let input = common::input(&dir, "input.mp4");
+    let config = config.count_frames(true).count_packets(true);
+    keyframe_index(&input, None, &config).unwrap();
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(!args.contains("-count_frames"), "{args}");
+    assert!(!args.contains("-count_packets"), "{args}");
+    assert_eq!(args.matches("-show_entries").count(), 1, "{args}");
+}
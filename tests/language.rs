@@ -0,0 +1,56 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use ffprobe::{FfProbe, Language, StreamKinds};
+
+fn lang(tag: &str) -> Language {
+    Language::parse(tag).unwrap()
+}
+
+#[test]
+fn normalize_codes() {
+    for tag in ["de", "DE", "deu", "ger", "de-AT", "de_DE.UTF-8"] {
+        assert_eq!(lang(tag).as_str(), "ger", "{tag}");
+    }
+    assert_eq!(lang("deu").iso639_2t(), "deu");
+    assert_eq!(lang("ger").iso639_1(), Some("de"));
+    assert_eq!(lang("ger").name(), Some("German"));
+    assert_eq!(lang("yue").name(), Some("Cantonese"));
+    assert_eq!(lang("yue").iso639_1(), None);
+    // ISO 639-3 codes are kept.
+    assert_eq!(lang("cmn").as_str(), "cmn");
+    for tag in ["und", "zxx", "", "x1", "english", "12"] {
+        assert_eq!(Language::parse(tag), None, "{tag}");
+    }
+}
+
+#[test]
+fn regions() {
+    let brazil = lang("pt-BR");
+    assert_eq!(brazil.region(), Some("BR"));
+    assert_eq!(brazil.to_string(), "pt-BR");
+    assert_eq!(lang("es_419").region(), Some("419"));
+    assert_eq!(lang("zh-Hant-TW").region(), Some("TW"));
+    assert_eq!(lang("zh-Hant").region(), None);
+    assert_eq!("fre".parse::<Language>().unwrap().to_string(), "fr");
+    assert!("und".parse::<Language>().is_err());
+
+    assert!(brazil.matches("pt_BR.UTF-8"));
+    assert!(brazil.matches("por"));
+    assert!(!brazil.matches("pt-PT"));
+    assert!(lang("por").matches("pt-PT"));
+    assert!(!brazil.matches("es"));
+}
+
+#[test]
+fn stream_languages() {
+    let json = std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap();
+    let probe: FfProbe = serde_json::from_slice(&json).unwrap();
+    let StreamKinds::Audio(audio) = &probe.streams[1].stream else {
+        panic!("expected an audio stream");
+    };
+    assert_eq!(audio.language(), Some(lang("en")));
+    assert_eq!(audio.language().unwrap().name(), Some("English"));
+}
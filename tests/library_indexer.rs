@@ -0,0 +1,75 @@
+#![cfg(all(
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+//! Runs the library indexer example, so it keeps compiling and working against the API.
+
+#[allow(dead_code)]
+#[path = "../examples/library_indexer.rs"]
+mod library_indexer;
+
+use ffprobe::Config;
+
+mod common;
+
+#[cfg(unix)]
+#[test]
+fn index_library() {
+    let dir = common::temp_dir("indexer", "library");
+    let library = dir.join("library");
+    std::fs::create_dir_all(library.join("Shows")).unwrap();
+    for file in ["movie.mp4", "Shows/episode.mkv", "broken.mkv", "cover.jpg"] {
+        std::fs::write(library.join(file), b"\x1a\x45\xdf\xa3").unwrap();
+    }
+
+    let bin = common::failing_ffprobe(&dir, "*broken*");
+    let config = Config::new().ffprobe_bin(&bin);
+    let index_path = dir.join("library.json");
+
+    let report = library_indexer::index(config.clone(), &library, &index_path).unwrap();
+    assert_eq!((report.probed, report.cached, report.failed), (2, 0, 1));
+    let index = library_indexer::Index::load(&index_path).unwrap();
+    let movie = &index.entries[&library.join("movie.mp4")];
+    assert_eq!(movie.video, ["h264 1920x1080"]);
+    assert_eq!(movie.chapters, 2);
+    assert!(index.failed.contains_key(&library.join("broken.mkv")));
+
+    // Unchanged files come from the index, changed ones are probed again.
+    std::fs::write(library.join("movie.mp4"), b"\x00\x00\x00\x20ftypisom").unwrap();
+    std::fs::remove_file(library.join("Shows/episode.mkv")).unwrap();
+    let report = library_indexer::index(config, &library, &index_path).unwrap();
+    assert_eq!(
+        (report.probed, report.cached, report.failed, report.removed),
+        (1, 0, 1, 1)
+    );
+    let index = library_indexer::Index::load(&index_path).unwrap();
+    assert_eq!(index.entries.len(), 1);
+}
+
+#[cfg(feature = "dev-tools")]
+#[test]
+fn index_generated_media() {
+    use ffprobe::dev_tools::TestMedia;
+
+    let dir = common::temp_dir("indexer", "media");
+    let library = dir.join("library");
+    std::fs::create_dir_all(&library).unwrap();
+    let config = Config::new();
+    TestMedia::all_kinds()
+        .generate(&config, library.join("all.mkv"))
+        .unwrap();
+    TestMedia::new()
+        .audio(None)
+        .generate(&config, library.join("silent.mp4"))
+        .unwrap();
+
+    let index_path = dir.join("library.json");
+    let report = library_indexer::index(config.clone(), &library, &index_path).unwrap();
+    assert_eq!((report.probed, report.failed), (2, 0));
+    assert_eq!(report.issues.get("no audio stream"), Some(&1));
+
+    let report = library_indexer::index(config, &library, &index_path).unwrap();
+    assert_eq!((report.probed, report.cached), (0, 2));
+}
@@ -0,0 +1,90 @@
+#![cfg(all(unix, feature = "process"))]
+
+use ffprobe::{
+    analysis::{loudness, LoudnessReport, LoudnessTarget},
+    Config,
+};
+
+mod common;
+
+/// The end of the ebur128 log of a 60s programme, with the last measurements before the
+/// summary.
+const EBUR128_LOG: &str = "\
+[Parsed_ebur128_0 @ 0x5581c7a0] t: 59.9       TARGET:-23 LUFS    M: -22.1 S: -23.4     I: -23.1 LUFS       LRA:   5.6 LU  FTPK: -3.2 -3.5 dBFS  TPK: -1.2 -1.4 dBFS
+[Parsed_ebur128_0 @ 0x5581c7a0] t: 60         TARGET:-23 LUFS    M: -22.3 S: -23.4     I: -23.1 LUFS       LRA:   5.6 LU  FTPK: -3.8 -3.6 dBFS  TPK: -1.2 -1.4 dBFS
+[out#0/null @ 0x5581c800] video:0KiB audio:11250KiB subtitle:0KiB other streams:0KiB global headers:0KiB muxing overhead: unknown
+size=N/A time=00:01:00.00 bitrate=N/A speed= 212x
+[Parsed_ebur128_0 @ 0x5581c7a0] Summary:
+
+  Integrated loudness:
+    I:         -23.1 LUFS
+    Threshold: -33.4 LUFS
+
+  Loudness range:
+    LRA:         5.6 LU
+    Threshold:   -43.6 LUFS
+    LRA low:     -26.9 LUFS
+    LRA high:    -21.3 LUFS
+
+  True peak:
+    Peak:       -1.2 dBFS
+";
+
+#[test]
+fn ebur128_summary() {
+    let (bin, args) = common::fake_ffmpeg(&common::temp_dir("loudness", "summary"), EBUR128_LOG);
+    let config = Config::new().ffmpeg_bin(bin);
+    let report = loudness("programme.mxf", &config).unwrap();
+    assert_eq!(
+        report,
+        LoudnessReport {
+            integrated: -23.1,
+            integrated_threshold: -33.4,
+            loudness_range: 5.6,
+            loudness_range_low: -26.9,
+            loudness_range_high: -21.3,
+            true_peak: -1.2,
+        }
+    );
+    assert!(report.complies_with(&LoudnessTarget::EBU_R128));
+    // -1.2 dBTP is above the -2 dBTP of ATSC A/85.
+    assert!(!report.complies_with(&LoudnessTarget::ATSC_A85));
+
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(
+        args.contains("-map\n0:a:0\n-filter:a\nebur128=peak=true\n"),
+        "{args}"
+    );
+}
+
+#[test]
+fn silent_input() {
+    // The true peak of digital silence is `-inf`.
+    let log = EBUR128_LOG
+        .replace("I:         -23.1 LUFS", "I:         -70.0 LUFS")
+        .replace("Peak:       -1.2 dBFS", "Peak:       -inf dBFS");
+    let (bin, _) = common::fake_ffmpeg(&common::temp_dir("loudness", "silent"), &log);
+    let config = Config::new().ffmpeg_bin(bin);
+    let report = loudness("silence.wav", &config).unwrap();
+    assert_eq!(report.integrated, -70.);
+    assert_eq!(report.true_peak, f64::NEG_INFINITY);
+    assert!(!report.complies_with(&LoudnessTarget::EBU_R128));
+}
+
+#[test]
+fn missing_summary() {
+    // eg. ffmpeg was killed before closing the filter.
+    let log = &EBUR128_LOG[..EBUR128_LOG
+        .find("[Parsed_ebur128_0 @ 0x5581c7a0] Summary:")
+        .unwrap()];
+    let (bin, _) = common::fake_ffmpeg(&common::temp_dir("loudness", "missing"), log);
+    let config = Config::new().ffmpeg_bin(bin);
+    let err = loudness("programme.mxf", &config).unwrap_err();
+    assert!(err.to_string().contains("ebur128 summary"), "{err}");
+
+    // A summary without the true peak, ie. without `peak=true`.
+    let log = EBUR128_LOG.replace("    Peak:       -1.2 dBFS\n", "");
+    let (bin, _) = common::fake_ffmpeg(&common::temp_dir("loudness", "no-peak"), &log);
+    let config = Config::new().ffmpeg_bin(bin);
+    assert!(loudness("programme.mxf", &config).is_err());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{
+    mapping::{MappingPreferences, SubtitleMode},
+    AudioStream, AudioTags, FfProbe, SampleRate, Stream, StreamIndex, SubtititleTags,
+    SubtitleStream, VideoStream,
+};
+
+fn audio(index: u64, language: &str, channels: i64, default: bool) -> Stream {
+    let mut tags = AudioTags::default();
+    tags.tags.language = Some(language.to_string());
+    let mut audio = AudioStream::new("aac", SampleRate(48_000), channels);
+    audio.tags = Some(tags);
+    let mut stream = Stream::new(index, audio);
+    stream.disposition.default = default as i64;
+    stream
+}
+
+fn subtitle(index: u64, language: &str, title: &str, forced: bool) -> Stream {
+    let mut tags = SubtititleTags::default();
+    tags.tags.language = Some(language.to_string());
+    tags.tags.title = Some(title.to_string());
+    let mut subtitle = SubtitleStream::new("subrip");
+    subtitle.tags = Some(tags);
+    let mut stream = Stream::new(index, subtitle);
+    stream.disposition.forced = forced as i64;
+    stream
+}
+
+/// Japanese film with an English dub, a commentary and English subtitles.
+fn film() -> FfProbe {
+    let mut cover = Stream::new(0, VideoStream::new("mjpeg", 3000, 3000));
+    cover.disposition.attached_pic = 1;
+    let mut commentary = audio(4, "eng", 2, false);
+    commentary.disposition.comment = 1;
+    FfProbe::new()
+        .with_stream(cover)
+        .with_stream(Stream::new(1, VideoStream::new("h264", 1920, 1080)))
+        .with_stream(audio(2, "jpn", 6, true))
+        .with_stream(audio(3, "eng", 2, false))
+        .with_stream(commentary)
+        .with_stream(subtitle(5, "eng", "English (SDH)", false))
+        .with_stream(subtitle(6, "eng", "English", false))
+        .with_stream(subtitle(7, "eng", "Signs", true))
+        .with_stream(subtitle(8, "jpn", "Forced", true))
+}
+
+#[test]
+fn preferred_audio_and_subtitles() {
+    let probe = film();
+    let japanese = MappingPreferences::new()
+        .audio_language("ja")
+        .subtitle_language("en")
+        .subtitle_mode(SubtitleMode::Smart);
+    let mapping = probe.suggest_mapping(&japanese);
+    assert_eq!(mapping.video, Some(StreamIndex(1)));
+    assert_eq!(mapping.audio, Some(StreamIndex(2)));
+    assert_eq!(mapping.subtitle, Some(StreamIndex(6)));
+    assert_eq!(
+        mapping.map_args(0),
+        ["-map", "0:1", "-map", "0:2", "-map", "0:6"]
+    );
+
+    let sdh = probe.suggest_mapping(&japanese.clone().prefer_sdh(true));
+    assert_eq!(sdh.subtitle, Some(StreamIndex(5)));
+
+    // English audio is understood, only the forced signs track is needed.
+    let english = MappingPreferences::new()
+        .audio_language("en_US.UTF-8")
+        .subtitle_language("en")
+        .subtitle_mode(SubtitleMode::Smart);
+    let mapping = probe.suggest_mapping(&english);
+    assert_eq!(mapping.audio, Some(StreamIndex(3)));
+    assert_eq!(mapping.subtitle, Some(StreamIndex(7)));
+    assert_eq!(
+        probe
+            .suggest_mapping(&english.clone().subtitle_mode(SubtitleMode::Always))
+            .subtitle,
+        Some(StreamIndex(6))
+    );
+    assert_eq!(
+        probe
+            .suggest_mapping(&english.subtitle_mode(SubtitleMode::None))
+            .subtitle,
+        None
+    );
+}
+
+#[test]
+fn fallbacks() {
+    let probe = film();
+    // No preferred language: the default audio track, with its forced subtitles.
+    let mapping = probe.suggest_mapping(&MappingPreferences::new().audio_language("fr"));
+    assert_eq!(mapping.audio, Some(StreamIndex(2)));
+    assert_eq!(mapping.subtitle, Some(StreamIndex(8)));
+
+    let mapping = FfProbe::new()
+        .with_stream(audio(0, "eng", 2, false))
+        .with_stream(audio(1, "ger", 6, false))
+        .suggest_mapping(&MappingPreferences::new());
+    assert_eq!(mapping.video, None);
+    assert_eq!(mapping.audio, Some(StreamIndex(1)));
+    assert_eq!(mapping.map_args(1), ["-map", "1:1"]);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "streams")]
+
+use std::time::Duration;
+
+use ffprobe::{
+    AudioStream, AudioTags, Bitrate, FileSize, MkvStats, SampleRate, Stream, StreamKinds,
+    StreamTags,
+};
+
+#[test]
+fn stream_tags_mkv_stats() {
+    let tags: StreamTags = serde_json::from_value(serde_json::json!({
+        "BPS": "384000",
+        "DURATION": "25:00:01.500000000",
+        "NUMBER_OF_FRAMES": "2812",
+        "NUMBER_OF_BYTES": "4320000",
+        "_STATISTICS_TAGS": "BPS DURATION NUMBER_OF_FRAMES NUMBER_OF_BYTES",
+        "_STATISTICS_WRITING_APP": "mkvmerge v81.0 ('Milord Mine') 64-bit",
+        "_STATISTICS_WRITING_DATE_UTC": "2023-11-04 10:21:07",
+    }))
+    .unwrap();
+    let stats = tags.mkv_stats().unwrap();
+    assert_eq!(stats.frames, Some(2812));
+    assert_eq!(stats.bytes, Some(FileSize(4_320_000)));
+    assert_eq!(stats.duration, Some(Duration::from_millis(90_001_500)));
+    assert_eq!(stats.bitrate, Some(Bitrate(384_000)));
+    assert_eq!(stats.tags.len(), 4);
+    assert_eq!(
+        stats.writing_date_utc.map(|d| d.to_string()),
+        Some("2023-11-04 10:21:07".to_string())
+    );
+
+    let empty: StreamTags = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(empty.mkv_stats(), None);
+}
+
+#[test]
+fn stream_mkv_stats_language_variants() {
+    let mut tags = AudioTags::default();
+    for (key, value) in [
+        ("BPS-eng", "128000"),
+        ("DURATION-eng", "00:00:10.5"),
+        ("NUMBER_OF_BYTES-eng", "168000"),
+    ] {
+        tags.extra.insert(key.to_string(), serde_json::json!(value));
+    }
+    let mut audio = AudioStream::new("aac", SampleRate(48_000), 2);
+    audio.tags = Some(tags);
+    let mut stream = Stream::new(1, audio);
+
+    let stats = stream.mkv_stats().unwrap();
+    assert_eq!(stats.duration, Some(Duration::from_millis(10_500)));
+    assert_eq!(stats.bitrate, Some(Bitrate(128_000)));
+    assert_eq!(stats.frames, None);
+    assert_eq!(stream.bitrate(), Some(Bitrate(128_000)));
+
+    if let StreamKinds::Audio(audio) = &mut stream.stream {
+        audio.tags.as_mut().unwrap().extra.remove("BPS-eng");
+    }
+    assert_eq!(
+        stream.mkv_stats().and_then(|s| s.effective_bitrate()),
+        Some(Bitrate(128_000))
+    );
+    assert_eq!(MkvStats::default().effective_bitrate(), None);
+}
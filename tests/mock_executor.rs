@@ -0,0 +1,83 @@
+#![cfg(all(
+    feature = "test-util",
+    feature = "streams",
+    feature = "format",
+    feature = "chapters"
+))]
+
+use std::{
+    ffi::OsString,
+    sync::{Arc, Mutex},
+};
+
+use ffprobe::{error::FfProbeError, executor::MockExecutor, fixtures, Config, FfProbe};
+
+#[test]
+fn fixtures_parse() {
+    for (name, json) in [
+        ("mp4", fixtures::MP4),
+        ("mkv", fixtures::MKV),
+        ("mp3", fixtures::MP3),
+        ("hls", fixtures::HLS),
+    ] {
+        let probe = FfProbe::from_json(json).unwrap_or_else(|err| panic!("{name}: {err}"));
+        assert!(!probe.streams.is_empty(), "{name}");
+    }
+    let mp3 = FfProbe::from_json(fixtures::MP3).unwrap();
+    assert_eq!(mp3.cover_art().map(|s| s.index), Some(1.into()));
+    let mkv = FfProbe::from_json(fixtures::MKV).unwrap();
+    assert_eq!(mkv.chapters.len(), 2);
+}
+
+#[test]
+fn canned_output() {
+    let mock = MockExecutor::new()
+        .respond("movie.mkv", fixtures::MKV)
+        .respond("-song.mp3", fixtures::MP3);
+    let config = Config::new().executor(mock.clone());
+
+    let probe = config.clone().run("movie.mkv").unwrap();
+    assert_eq!(probe.format.format_name, "matroska,webm");
+    // Matched as passed to ffprobe, with the `./` prefix.
+    let probe = config.run("-song.mp3").unwrap();
+    assert_eq!(probe.streams.len(), 2);
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0][0], OsString::from("ffprobe"));
+    assert_eq!(calls[0].last(), Some(&OsString::from("movie.mkv")));
+    assert!(calls[0].iter().any(|arg| arg == "-show_streams"));
+    assert_eq!(calls[1].last(), Some(&OsString::from("./-song.mp3")));
+}
+
+#[test]
+fn failures_and_fallback() {
+    let mock = MockExecutor::new().fail("broken.mp4", "moov atom not found");
+    let config = Config::new().executor(mock);
+    let err = config.clone().run("broken.mp4").unwrap_err();
+    let FfProbeError::Status(out) = err.inner() else {
+        panic!("{err:?}");
+    };
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(out.stderr, b"moov atom not found");
+    // No response for the input.
+    assert!(matches!(
+        config.run("other.mp4").unwrap_err().inner(),
+        FfProbeError::Status(_)
+    ));
+
+    let config = Config::new().executor(MockExecutor::new().fallback(fixtures::MP4));
+    assert_eq!(config.run("any.mp4").unwrap().streams.len(), 3);
+}
+
+#[test]
+fn spawn_hook() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    Config::new()
+        .executor(MockExecutor::new().fallback(fixtures::MP4))
+        .on_spawn(move |event| recorded.lock().unwrap().push(event.success()))
+        .run("movie.mp4")
+        .unwrap();
+    assert_eq!(*events.lock().unwrap(), [true]);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "format")]
+
+use std::time::Duration;
+
+use ffprobe::{sync_report, FfProbe, Format};
+
+mod common;
+
+fn probe(start_time: Option<f64>, duration: Option<f64>) -> FfProbe {
+    let mut format = Format::new("master.mov", "mov,mp4,m4a,3gp,3g2,mj2");
+    format.start_time = start_time.map(Duration::from_secs_f64);
+    format.duration = duration.map(Duration::from_secs_f64);
+    FfProbe::new().with_format(format)
+}
+
+#[test]
+fn in_sync() {
+    let report = sync_report(
+        &probe(Some(0.), Some(600.)),
+        &probe(Some(0.021), Some(600.01)),
+    );
+    assert!((report.start_offset - 0.021).abs() < 1e-9);
+    assert!((report.duration_difference.unwrap() - 0.01).abs() < 1e-9);
+    assert!(report.is_in_sync(Duration::from_millis(40)));
+    assert!(!report.is_in_sync(Duration::from_millis(10)));
+}
+
+#[test]
+fn late_start() {
+    let report = sync_report(&probe(Some(1.), Some(600.)), &probe(None, Some(601.)));
+    assert_eq!(report.start_offset, -1.);
+    assert_eq!(report.duration_difference, Some(1.));
+    assert!(!report.is_in_sync(Duration::from_millis(500)));
+}
+
+#[test]
+fn unknown_duration() {
+    let report = sync_report(&probe(Some(0.), Some(600.)), &probe(Some(0.), None));
+    assert_eq!(report.duration_difference, None);
+    assert!(report.is_in_sync(Duration::ZERO));
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn probes_every_input() {
+    use ffprobe::{error::FfProbeError, ffprobe_multi, Config};
+
+    let dir = common::temp_dir("multi", "inputs");
+    let calls = dir.join("calls");
+    let bin = common::script(
+        &dir,
+        "ffprobe",
+        &format!(
+            "echo x >> '{}'\ncat '{}'",
+            calls.display(),
+            common::fixture("mp4.json").display()
+        ),
+    );
+    let video = common::input(&dir, "video.mov");
+    let audio = common::input(&dir, "audio.wav");
+    let empty = common::write(&dir, "empty.wav", b"");
+    let config = Config::new().ffprobe_bin(&bin);
+
+    let probes = ffprobe_multi(config.clone(), &[&video, &audio]).unwrap();
+    assert_eq!(probes.len(), 2);
+    assert!(sync_report(&probes[0], &probes[1]).is_in_sync(Duration::ZERO));
+    assert_eq!(std::fs::read_to_string(&calls).unwrap().lines().count(), 2);
+
+    // Nothing is probed if one of the inputs is empty.
+    std::fs::remove_file(&calls).unwrap();
+    let err = ffprobe_multi(config, &[&video, &empty]).unwrap_err();
+    assert!(matches!(
+        err,
+        FfProbeError::EmptyOrTruncatedInput { size: 0 }
+    ));
+    assert!(!calls.exists());
+}
@@ -0,0 +1,98 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use ffprobe::{error::FfProbeError, ffprobe_raw_sections, Config};
+
+mod common;
+
+fn fixture() -> String {
+    format!("cat '{}'", common::fixture("mp4.json").display())
+}
+
+#[test]
+fn output_too_large() {
+    let dir = common::temp_dir("limit", "large");
+    let bin = common::script(&dir, "ffprobe", "head -c 10000000 /dev/zero");
+    let config = Config::new().ffprobe_bin(bin);
+    let input = common::input(&dir, "video.mp4");
+    let err = config.max_output_bytes(1000).run(&input).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        FfProbeError::OutputTooLarge { limit: 1000 }
+    ));
+    assert_eq!(
+        err.inner().to_string(),
+        "ffprobe output exceeds the limit of 1000 bytes"
+    );
+}
+
+#[test]
+fn output_too_large_with_progress() {
+    let dir = common::temp_dir("limit", "progress");
+    let bin = common::script(&dir, "ffprobe", "head -c 10000000 /dev/zero");
+    let config = Config::new().ffprobe_bin(bin);
+    let input = common::input(&dir, "video.mp4");
+    let err = config
+        .max_output_bytes(1000)
+        .progress(|_| {})
+        .run(&input)
+        .unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        FfProbeError::OutputTooLarge { limit: 1000 }
+    ));
+}
+
+#[test]
+fn stderr_too_large() {
+    // Logs until killed.
+    let dir = common::temp_dir("limit", "stderr");
+    let bin = common::script(
+        &dir,
+        "ffprobe",
+        &format!("yes 'non monotonic DTS' >&2\n{}", fixture()),
+    );
+    let config = Config::new().ffprobe_bin(bin).max_output_bytes(100_000);
+    let input = common::input(&dir, "video.mp4");
+    for config in [config.clone(), config.progress(|_| {})] {
+        let err = config.run(&input).unwrap_err();
+        assert!(matches!(
+            err.inner(),
+            FfProbeError::OutputTooLarge { limit: 100_000 }
+        ));
+    }
+}
+
+#[test]
+fn output_within_limit() {
+    let dir = common::temp_dir("limit", "within");
+    let bin = common::script(&dir, "ffprobe", &fixture());
+    let config = Config::new().ffprobe_bin(bin);
+    let input = common::input(&dir, "video.mp4");
+    let size = std::fs::metadata(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap()
+    .len() as usize;
+    assert!(config.clone().max_output_bytes(size).run(&input).is_ok());
+    let err = config.max_output_bytes(size - 1).run(&input).unwrap_err();
+    assert!(matches!(err.inner(), FfProbeError::OutputTooLarge { .. }));
+}
+
+#[test]
+fn raw_sections_lower_limit() {
+    let dir = common::temp_dir("limit", "raw");
+    let bin = common::script(&dir, "ffprobe", &fixture());
+    let config = Config::new().ffprobe_bin(bin);
+    let input = common::input(&dir, "video.mp4");
+    let err = ffprobe_raw_sections(config.max_output_bytes(100), input, &["packets"]).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        FfProbeError::OutputTooLarge { limit: 100 }
+    ));
+}
@@ -0,0 +1,370 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use ffprobe::{FfProbe, StreamIndex};
+
+fn fixture(name: &str) -> FfProbe {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    let data = std::fs::read(&path).unwrap();
+    serde_json::from_slice(&data).unwrap()
+}
+
+#[test]
+fn parse_mp4_fixture() {
+    let probe = fixture("mp4.json");
+    assert_eq!(probe.streams.len(), 3);
+    assert_eq!(probe.chapters.len(), 2);
+    assert_eq!(probe.format.nb_streams, 3);
+    assert_eq!(probe.streams[0].nb_read_packets, Some(14400));
+    assert_eq!(probe.streams[1].nb_read_packets, None);
+    assert_eq!(probe.format.size, ffprobe::FileSize(366_300_000));
+    assert_eq!(probe.format.bit_rate, Some(ffprobe::Bitrate(4_884_000)));
+}
+
+#[test]
+fn from_json() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+    let json = std::fs::read_to_string(path).unwrap();
+    let probe = FfProbe::from_json(&json).unwrap();
+    assert_eq!(probe.streams.len(), 3);
+    assert_eq!(probe.chapters.len(), 2);
+    // Unlike serde, keeps the raw output.
+    assert_eq!(
+        probe.query("format.format_name"),
+        Some(serde_json::json!("mov,mp4,m4a,3gp,3g2,mj2"))
+    );
+    assert_eq!(FfProbe::from_slice(json.as_bytes()).unwrap(), probe);
+    assert!(matches!(
+        FfProbe::from_slice(b"{\"streams\": 1}"),
+        Err(ffprobe::error::FfProbeError::Deserialize(_))
+    ));
+}
+
+#[cfg(feature = "mediainfo")]
+#[test]
+fn mediainfo_report() {
+    use ffprobe::TrackKind;
+
+    let info = fixture("mp4.json").to_mediainfo();
+    let general = info.tracks(TrackKind::General).next().unwrap();
+    assert_eq!(general.get("Format"), Some("MPEG-4"));
+    assert_eq!(general.get("Duration"), Some("600.000"));
+
+    let video = info.tracks(TrackKind::Video).next().unwrap();
+    assert_eq!(video.get("Format"), Some("AVC"));
+    assert_eq!(video.get("Format_Level"), Some("4.1"));
+    assert_eq!(video.get("CodecID"), Some("avc1"));
+    assert_eq!(video.get("ID"), Some("1"));
+
+    let audio = info.tracks(TrackKind::Audio).next().unwrap();
+    assert_eq!(audio.get("SamplingRate"), Some("48000"));
+    assert_eq!(audio.get("Channels"), Some("6"));
+
+    let json: serde_json::Value = serde_json::from_str(&info.to_json()).unwrap();
+    assert_eq!(json["media"]["track"][2]["@type"], "Audio");
+    assert!(info.to_xml().contains("<track type=\"Text\">"));
+}
+
+#[test]
+fn frame_rate() {
+    let probe = fixture("mp4.json");
+    let fps = probe.streams[0].frame_rate().unwrap();
+    assert_eq!(fps.effective_fps(), Some(24.0));
+    assert!(!fps.is_variable());
+    assert!(!fps.is_interlaced());
+    assert!(probe.streams[1].frame_rate().is_none());
+}
+
+#[test]
+fn sample_format() {
+    use ffprobe::{SampleFormat, StreamKinds};
+
+    let probe = fixture("mp4.json");
+    let StreamKinds::Audio(audio) = &probe.streams[1].stream else {
+        panic!("expected an audio stream");
+    };
+    let format = audio.sample_format().unwrap();
+    assert_eq!(format, SampleFormat::Fltp);
+    assert!(format.is_planar());
+    assert_eq!(format.bytes_per_sample(), 4);
+    assert_eq!(format.packed(), SampleFormat::Flt);
+    assert_eq!(format.bits_per_sample(), 32);
+    assert!(format.is_float());
+    assert!(!SampleFormat::S32p.is_float());
+    assert_eq!(audio.bit_depth(), None);
+
+    let mut audio: ffprobe::AudioStream =
+        serde_json::from_value(serde_json::to_value(audio).unwrap()).unwrap();
+    assert_eq!(audio.sample_fmt, Some(SampleFormat::Fltp));
+    audio.bits_per_sample = 24;
+    assert_eq!(audio.bit_depth(), Some(24));
+    audio.bits_per_raw_sample = Some(20);
+    assert_eq!(audio.bit_depth(), Some(20));
+
+    let mut json = serde_json::to_value(&audio).unwrap();
+    json["sample_fmt"] = "unknown".into();
+    let audio: ffprobe::AudioStream = serde_json::from_value(json).unwrap();
+    assert_eq!(audio.sample_format(), None);
+}
+
+#[test]
+fn interlaced_frame_rate() {
+    use ffprobe::{FrameRate, Ratio};
+
+    let field_rate = FrameRate {
+        r_frame_rate: Ratio::new(50, 1),
+        avg_frame_rate: Ratio::new(25, 1),
+        field_order: Some("tt".to_string()),
+        measured_frame_rate: None,
+    };
+    let effective = field_rate.effective_frame_rate().unwrap();
+    assert_eq!(effective.frames_per_second, 25.);
+    assert_eq!(effective.fields_per_second, Some(50.));
+    assert_eq!(effective.to_string(), "50i");
+
+    let ntsc = FrameRate {
+        r_frame_rate: Ratio::new(30000, 1001),
+        avg_frame_rate: Ratio::new(30000, 1001),
+        field_order: Some("progressive".to_string()),
+        measured_frame_rate: None,
+    };
+    assert_eq!(ntsc.effective_frame_rate().unwrap().to_string(), "29.97p");
+}
+
+#[test]
+fn concat_compatibility() {
+    use ffprobe::{compat, SampleRate, StreamKinds};
+
+    let a = fixture("mp4.json");
+    let mut b = a.clone();
+    assert_eq!(compat::can_concat(&a, &b), Ok(()));
+
+    if let StreamKinds::Audio(audio) = &mut b.streams[1].stream {
+        audio.sample_rate = SampleRate(44100);
+    }
+    b.streams.pop();
+    let errors = compat::can_concat(&a, &b).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[1].to_string(),
+        "stream 1: sample rate differs: 48 kHz vs 44.1 kHz"
+    );
+}
+
+#[test]
+fn direct_play() {
+    use ffprobe::compat::{DeviceProfile, PlaybackIssue};
+
+    let mut probe = fixture("mp4.json");
+    assert!(probe
+        .is_direct_playable(&DeviceProfile::ios_hls())
+        .is_direct_playable());
+
+    // 5.1 AAC needs downmixing for a stereo device.
+    let report = probe.is_direct_playable(&DeviceProfile::chromecast());
+    assert!(report.container.is_none());
+    assert_eq!(report.transcode_streams().collect::<Vec<_>>(), [1]);
+    assert_eq!(
+        report.streams[0].1,
+        [PlaybackIssue::AudioChannels {
+            channels: 6,
+            max: 2
+        }]
+    );
+
+    probe.format.format_name = "matroska,webm".to_string();
+    let report = probe.is_direct_playable(&DeviceProfile::ios_hls());
+    assert!(report.needs_remux_only());
+    assert_eq!(
+        report.container.unwrap().to_string(),
+        "unsupported container: matroska,webm"
+    );
+
+    let report = probe.is_direct_playable(&DeviceProfile::new("Radio").audio_codec("aac"));
+    assert_eq!(report.transcode_streams().collect::<Vec<_>>(), [0]);
+    assert_eq!(
+        report.streams[0].1[0].to_string(),
+        "unsupported codec: h264"
+    );
+}
+
+#[test]
+fn cover_art() {
+    let mut probe = fixture("mp4.json");
+    assert!(probe.cover_art().is_none());
+
+    let mut cover = probe.streams[0].clone();
+    cover.index = StreamIndex(3);
+    cover.disposition.attached_pic = 1;
+    probe.streams.push(cover);
+    assert_eq!(probe.cover_art().map(|s| s.index), Some(StreamIndex(3)));
+}
+
+#[test]
+fn container_delay() {
+    let mut probe = fixture("mp4.json");
+    // AAC encoder delay skipped by an edit list
+    probe.streams[1].start_pts = -1024;
+    let delays = probe.container_delays();
+    assert_eq!(delays[0], (StreamIndex(0), 0.));
+    assert!((delays[1].1 + 1024. / 48000.).abs() < 1e-9);
+
+    probe.format.format_name = "mpegts".to_string();
+    probe.streams[0].start_pts = 12288 * 2;
+    probe.streams[1].start_pts = 48000 * 2 + 4800;
+    probe.streams.pop();
+    assert_eq!(probe.timeline_origin(), 2.);
+    assert!((probe.container_delays()[1].1 - 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn subtitle_metadata() {
+    use ffprobe::{Language, StreamKinds, SubtitleKind};
+
+    let mut probe = fixture("mp4.json");
+    let stream = &mut probe.streams[2];
+    let StreamKinds::Subtitle(sub) = &mut stream.stream else {
+        panic!("expected a subtitle stream");
+    };
+    assert_eq!(sub.kind(), SubtitleKind::TextBased);
+    assert_eq!(sub.language().as_ref().map(Language::as_str), Some("ger"));
+    assert!(sub.is_forced(&stream.disposition));
+    assert!(!sub.is_sdh(&stream.disposition));
+
+    let tags = &mut sub.tags.as_mut().unwrap().tags;
+    tags.language = Some("de-AT".to_string());
+    tags.title = Some("German (SDH)".to_string());
+    sub.codec_name = "hdmv_pgs_subtitle".to_string();
+    assert_eq!(sub.language().as_ref().map(Language::as_str), Some("ger"));
+    assert!(sub.is_sdh(&stream.disposition));
+    assert_eq!(sub.kind(), SubtitleKind::Bitmap);
+}
+
+#[test]
+fn bitrates() {
+    use ffprobe::{Bitrate, StreamKinds};
+
+    let mut probe = fixture("mp4.json");
+    assert_eq!(probe.streams[0].bitrate(), Some(Bitrate(4_500_000)));
+    assert_eq!(probe.format.bitrate(), Some(Bitrate(4_884_000)));
+
+    probe.format.bit_rate = None;
+    // 366.3 MB over 600 s
+    assert_eq!(probe.format.bitrate(), Some(Bitrate(4_884_000)));
+
+    if let StreamKinds::Audio(audio) = &mut probe.streams[1].stream {
+        audio.bit_rate = None;
+        let tags = audio.tags.as_mut().unwrap();
+        tags.extra
+            .insert("BPS-eng".to_string(), serde_json::json!("384000"));
+    }
+    assert_eq!(probe.streams[1].bitrate(), Some(Bitrate(384_000)));
+
+    // no format fallback with multiple media streams
+    if let StreamKinds::Video(video) = &mut probe.streams[0].stream {
+        video.bit_rate = None;
+    }
+    assert_eq!(probe.stream_bitrate(0), None);
+    probe.streams.remove(1);
+    assert_eq!(probe.stream_bitrate(0), Some(Bitrate(4_884_000)));
+}
+
+#[test]
+fn all_tags() {
+    let probe = fixture("mp4.json");
+    let format_tags = probe.format.all_tags();
+    assert_eq!(format_tags.get("MAJOR_BRAND"), Some("isom"));
+    assert_eq!(format_tags.get("Title"), Some("Sample"));
+    assert_eq!(
+        format_tags.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+        vec![
+            "major_brand",
+            "minor_version",
+            "compatible_brands",
+            "title",
+            "encoder"
+        ]
+    );
+
+    let video_tags = probe.streams[0].all_tags();
+    assert_eq!(video_tags.get("HANDLER_NAME"), Some("VideoHandler"));
+    assert_eq!(
+        video_tags.get("creation_time"),
+        Some("2024-03-01T12:30:00.000000Z")
+    );
+    assert_eq!(video_tags.len(), 4);
+    assert_eq!(probe.chapters[0].all_tags().get("TITLE"), Some("Opening"));
+
+    let mut probe = probe;
+    probe.format.tags = None;
+    assert!(probe.format.all_tags().is_empty());
+}
+
+#[test]
+fn frame_rate_best_guess() {
+    use ffprobe::{FrameRate, Ratio};
+
+    let mut probe = fixture("mp4.json");
+    let rate = probe.streams[0].frame_rate().unwrap();
+    assert_eq!(rate.measured(), None);
+    assert_eq!(rate.best_guess(), Some(24.));
+
+    // 14399 decoded frames over 600 s
+    probe.streams[0].nb_read_frames = Some(14399);
+    let rate = probe.streams[0].frame_rate().unwrap();
+    assert_eq!(rate.measured_frame_rate, Some(Ratio::new(14399, 600)));
+    assert_eq!(rate.best_guess(), rate.measured());
+    assert!(probe.streams[1].frame_rate().is_none());
+
+    let timestamp_resolution = FrameRate {
+        r_frame_rate: Ratio::new(90000, 1),
+        avg_frame_rate: Ratio::new(0, 0),
+        field_order: None,
+        measured_frame_rate: None,
+    };
+    assert_eq!(timestamp_resolution.declared(), Some(90000.));
+    assert_eq!(timestamp_resolution.best_guess(), None);
+
+    // A bogus average rate, eg. of a stream with a broken duration.
+    let bogus_average = FrameRate {
+        r_frame_rate: Ratio::new(25, 1),
+        avg_frame_rate: Ratio::new(250000, 1),
+        field_order: None,
+        measured_frame_rate: None,
+    };
+    assert_eq!(bogus_average.best_guess(), Some(25.));
+}
+
+#[test]
+fn numbers_and_numeric_strings() {
+    use serde_json::{json, Value};
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+    let mut raw: Value = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+    let video = &mut raw["streams"][0];
+    video["width"] = json!("1920");
+    video["duration_ts"] = json!("7372800");
+    video["nb_frames"] = json!(14400);
+    video["bits_per_raw_sample"] = json!("N/A");
+    video["disposition"]["default"] = json!("1");
+    raw["format"]["nb_streams"] = json!("3");
+    raw["format"]["duration"] = json!(600.0);
+    raw["chapters"][0]["start"] = json!("0");
+
+    let probe: FfProbe = serde_json::from_value(raw).unwrap();
+    let ffprobe::StreamKinds::Video(video) = &probe.streams[0].stream else {
+        panic!("not a video stream");
+    };
+    assert_eq!(video.width, 1920);
+    assert_eq!(video.duration_ts, Some(7372800));
+    assert_eq!(video.bits_per_raw_sample, None);
+    assert_eq!(probe.streams[0].nb_frames, Some(14400));
+    assert_eq!(probe.streams[0].disposition.default, 1);
+    assert_eq!(probe.format.nb_streams, 3);
+    assert_eq!(
+        probe.format.duration,
+        Some(std::time::Duration::from_secs(600))
+    );
+    assert_eq!(probe.chapters[0].start, 0);
+}
@@ -0,0 +1,82 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::path::PathBuf;
+
+use ffprobe::{Config, Dialect, ProbePool};
+
+mod common;
+
+/// ffprobe body printing the fixture, without an input it fails like ffprobe.
+const POOL_FFPROBE: &str = r#"case "$*" in
+  *.mp4*) ;;
+  *) echo 'You have to specify one input file.' >&2; exit 1 ;;
+esac
+cat "$fixture""#;
+
+#[test]
+fn probes_submitted_files() {
+    let dir = common::temp_dir("pool", "submit");
+    let (bin, args) = common::appending(&dir, "ffprobe", POOL_FFPROBE);
+    let pool = ProbePool::new(Config::new().ffprobe_bin(&bin), 3).unwrap();
+    assert_eq!(pool.workers(), 3);
+
+    let files: Vec<PathBuf> = (0..20)
+        .map(|i| common::input(&dir, &format!("video{i}.mp4")))
+        .collect();
+    let handles: Vec<_> = files.iter().map(|file| pool.submit(file)).collect();
+    for handle in handles {
+        let probe = handle.wait().unwrap();
+        assert_eq!(probe.streams.len(), 3);
+        assert_eq!(probe.dialect, Dialect::Ffprobe);
+    }
+
+    // The dialect is checked once, without an input.
+    let runs = common::runs(&args);
+    assert_eq!(runs.len(), 21);
+    assert!(!runs[0].contains(".mp4"));
+    assert_eq!(runs.iter().filter(|run| !run.contains(".mp4")).count(), 1);
+}
+
+#[test]
+fn handle_is_a_future() {
+    let dir = common::temp_dir("pool", "future");
+    let (bin, _) = common::appending(&dir, "ffprobe", POOL_FFPROBE);
+    let pool = ProbePool::new(Config::new().ffprobe_bin(&bin), 1).unwrap();
+    let file = common::input(&dir, "video.mp4");
+    let probe = common::block_on(pool.submit(&file)).unwrap();
+    assert_eq!(probe.format.nb_streams, 3);
+
+    // Dropping the pool finishes the queued files.
+    let handle = pool.submit(&file);
+    drop(pool);
+    assert!(common::block_on(handle).is_ok());
+}
+
+#[test]
+fn resolves_the_binary_in_path() {
+    let dir = common::temp_dir("pool", "path");
+    let (_, args) = common::appending(&dir, "ffprobe", POOL_FFPROBE);
+    let pool = ProbePool::new(
+        Config::new()
+            .dialect(Dialect::Ffprobe)
+            .env("PATH", format!("{}:/usr/bin:/bin", dir.display()))
+            .clear_env(true),
+        1,
+    )
+    .unwrap();
+    let file = common::input(&dir, "video.mp4");
+    pool.submit(&file).wait().unwrap();
+    // A configured dialect isn't checked.
+    assert_eq!(common::runs(&args).len(), 1);
+}
+
+#[test]
+fn missing_binary() {
+    assert!(ProbePool::new(Config::new().ffprobe_bin("/nonexistent/ffprobe"), 2).is_err());
+}
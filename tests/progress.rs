@@ -0,0 +1,82 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ffprobe::{analysis::deep_scan, Config, ProbeProgress};
+
+mod common;
+
+fn collect() -> (Config, Arc<Mutex<Vec<ProbeProgress>>>) {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink = reports.clone();
+    let config = Config::new().progress(move |p| sink.lock().unwrap().push(*p));
+    (config, reports)
+}
+
+#[test]
+fn ffprobe_packet_timestamps() {
+    let fixture = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap();
+    let dir = common::temp_dir("progress", "ffprobe");
+    let bin = common::pausing(
+        &dir,
+        "ffprobe",
+        "{\"packets\": [\n{ \"pts_time\": \"1.500000\" },\n",
+        &format!(
+            "{{ \"pts_time\": \"4.250000\" }}\n],\n{}",
+            &fixture.trim_start()[1..]
+        ),
+        false,
+    );
+    let input = common::input(&dir, "input.mp4");
+    let (config, reports) = collect();
+    let probe = config.ffprobe_bin(&bin).run(&input).unwrap();
+    assert_eq!(probe.streams.len(), 3);
+
+    let reports = reports.lock().unwrap();
+    let (last, running) = reports.split_last().unwrap();
+    assert!(last.done);
+    assert_eq!(last.out_time, Some(Duration::from_millis(4250)));
+    assert!(running.iter().all(|p| !p.done));
+    assert!(running
+        .iter()
+        .any(|p| p.out_time == Some(Duration::from_millis(1500))));
+    assert!(last.elapsed >= Duration::from_millis(800));
+    #[cfg(target_os = "linux")]
+    assert!(last.bytes_read.is_some());
+}
+
+#[test]
+fn ffmpeg_progress_reports() {
+    let dir = common::temp_dir("progress", "ffmpeg");
+    let bin = common::pausing(
+        &dir,
+        "ffmpeg",
+        "out_time_us=1000000\nprogress=continue\n",
+        "out_time_us=2000000\nprogress=end\n",
+        true,
+    );
+    let input = common::input(&dir, "input.mp4");
+    let (config, reports) = collect();
+    let report = deep_scan(&input, &config.ffmpeg_bin(&bin)).unwrap();
+    assert!(report.is_clean());
+
+    let reports = reports.lock().unwrap();
+    let last = reports.last().unwrap();
+    assert!(last.done);
+    assert_eq!(last.out_time, Some(Duration::from_secs(2)));
+    assert!(reports
+        .iter()
+        .any(|p| !p.done && p.out_time == Some(Duration::from_secs(1))));
+}
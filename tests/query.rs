@@ -0,0 +1,42 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use ffprobe::FfProbe;
+use serde_json::json;
+
+mod common;
+
+#[test]
+fn query_deserialized() {
+    let probe: FfProbe = serde_json::from_value(common::fixture_json("mp4.json")).unwrap();
+    assert_eq!(probe.query("streams[0].width"), Some(json!(1920)));
+    assert_eq!(probe.query("streams.1.codec_name"), Some(json!("aac")));
+    assert_eq!(probe.query("format.tags.TITLE"), Some(json!("Sample")));
+    assert_eq!(probe.query("chapters[1].tags.title"), Some(json!("Finale")));
+    assert_eq!(probe.query("streams[9].width"), None);
+    assert_eq!(probe.query("streams[0.width"), None);
+    assert_eq!(probe.query("format.missing"), None);
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn query_raw_output() {
+    let dir = common::temp_dir("query", "raw");
+    let (bin, _) = common::fake_ffprobe(&dir, std::fs::read(common::fixture("mp4.json")).unwrap());
+
+    let probe = ffprobe::Config::new()
+        .ffprobe_bin(&bin)
+        .run("video.mp4")
+        .unwrap();
+    // Raw output keeps fields the typed model skips.
+    assert_eq!(
+        probe.query("chapters[0].end_time"),
+        Some(json!("300.000000"))
+    );
+    assert_eq!(
+        probe.query("streams[0].codec_tag_string"),
+        Some(json!("avc1"))
+    );
+    assert_eq!(probe.query("format.tags.major_brand"), Some(json!("isom")));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,30 @@
+use ffprobe::Ratio;
+
+#[test]
+fn ratio_math() {
+    let ratio: Ratio = "1920:1080".parse().unwrap();
+    assert_eq!(ratio.reduce(), Ratio::new(16, 9));
+    assert_eq!(Ratio::new(0, 0).as_f64(), None);
+    assert_eq!(Ratio::new(1, 4).as_f64(), Some(0.25));
+
+    let scope: Ratio = "1920/804".parse().unwrap();
+    assert_eq!(
+        scope.approximate(Ratio::COMMON_ASPECT_RATIOS),
+        Some(&Ratio::new(239, 100))
+    );
+
+    assert_eq!(Ratio::new(64, 45).scale(720), Some(1024));
+    assert_eq!(Ratio::new(2, 3) * Ratio::new(3, 4), Ratio::new(1, 2));
+
+    // Products past `u64`, eg. of time bases.
+    let fps = Ratio::new(1001, 30000);
+    assert_eq!(
+        Ratio::new(1, 90000) * fps.clone(),
+        Ratio::new(1001, 2_700_000_000)
+    );
+    let chained = (0..4).fold(Ratio::new(1, 90000), |ratio, _| ratio * fps.clone());
+    let expected = (1001f64 / 30000.).powi(4) / 90000.;
+    assert!((chained.as_f64().unwrap() / expected - 1.).abs() < 1e-6);
+    let large = Ratio::new(u64::MAX, u64::MAX - 2) * Ratio::new(u64::MAX - 4, u64::MAX - 6);
+    assert!((large.as_f64().unwrap() - 1.).abs() < 1e-15);
+}
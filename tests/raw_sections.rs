@@ -0,0 +1,29 @@
+#![cfg(all(unix, feature = "process"))]
+
+use ffprobe::{ffprobe_raw_sections, Config};
+
+mod common;
+
+#[test]
+fn raw_sections() {
+    let bin = common::script(
+        &common::temp_dir("raw-sections", "raw"),
+        "ffprobe",
+        r#"case "$*" in *"-show_programs video.ts") echo '{"programs":[{"program_id":1}]}';; *) exit 1;; esac"#,
+    );
+    let sections =
+        ffprobe_raw_sections(Config::new().ffprobe_bin(&bin), "video.ts", &["programs"]).unwrap();
+    assert_eq!(sections["programs"][0]["program_id"], 1);
+}
+
+#[test]
+fn raw_sections_output_limit() {
+    let bin = common::script(
+        &common::temp_dir("raw-sections", "limit"),
+        "ffprobe",
+        "head -c 80000000 /dev/zero",
+    );
+    let err =
+        ffprobe_raw_sections(Config::new().ffprobe_bin(&bin), "video.ts", &["frames"]).unwrap_err();
+    assert!(err.inner().to_string().contains("exceeds the limit"));
+}
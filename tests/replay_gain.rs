@@ -0,0 +1,86 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use ffprobe::{FfProbe, ReplayGain, StreamKinds, Tags};
+use serde_json::{json, Value};
+
+/// The fixture with the given side data and tags on its audio stream and format tags.
+fn probe(side_data: Value, tags: Value, format_tags: Value) -> FfProbe {
+    let json = std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap();
+    let mut value: Value = serde_json::from_slice(&json).unwrap();
+    value["streams"][1]["side_data_list"] = side_data;
+    value["streams"][1]["tags"] = tags;
+    value["format"]["tags"] = format_tags;
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn from_tags() {
+    let tags: Tags = [
+        ("replaygain_track_gain", "-6.54 dB"),
+        ("REPLAYGAIN_TRACK_PEAK", "0.988553"),
+        ("REPLAYGAIN_ALBUM_GAIN", "+1.20dB"),
+        ("REPLAYGAIN_ALBUM_PEAK", "garbage"),
+    ]
+    .into_iter()
+    .collect();
+    let gain = ReplayGain::from_tags(&tags).unwrap();
+    assert_eq!(gain.track_gain, Some(-6.54));
+    assert_eq!(gain.track_peak, Some(0.988553));
+    assert_eq!(gain.album_gain, Some(1.2));
+    assert_eq!(gain.album_peak, None);
+
+    assert_eq!(ReplayGain::from_tags(&Tags::new()), None);
+
+    // Opus: -5 dB relative to -23 LUFS => 0 dB relative to -18 LUFS
+    let opus: Tags = [("R128_TRACK_GAIN", "-1280")].into_iter().collect();
+    assert_eq!(ReplayGain::from_tags(&opus).unwrap().track_gain, Some(0.));
+}
+
+#[test]
+fn stream_side_data_and_tags() {
+    let probe = probe(
+        json!([{
+            "side_data_type": "Replay Gain",
+            "track_gain": -654000,
+            "track_peak": 98855,
+            "album_gain": -2147483648i64,
+            "album_peak": 0
+        }]),
+        json!({ "REPLAYGAIN_ALBUM_GAIN": "-7.00 dB" }),
+        json!({}),
+    );
+    let stream = &probe.streams[1];
+    let gain = stream.replay_gain().unwrap();
+    assert_eq!(gain.track_gain, Some(-6.54));
+    assert_eq!(gain.track_peak, Some(0.98855));
+    assert_eq!(gain.album_gain, Some(-7.));
+    assert_eq!(gain.album_peak, None);
+
+    let StreamKinds::Audio(audio) = &stream.stream else {
+        panic!("expected audio");
+    };
+    let tags_only = audio.replay_gain().unwrap();
+    assert_eq!(
+        (tags_only.track_gain, tags_only.album_gain),
+        (None, Some(-7.))
+    );
+
+    assert_eq!(probe.streams[0].replay_gain(), None);
+}
+
+#[test]
+fn format_tags_fallback() {
+    let probe = probe(
+        json!([]),
+        json!({}),
+        json!({ "REPLAYGAIN_TRACK_GAIN": "-3.10 dB" }),
+    );
+    assert_eq!(probe.streams[1].replay_gain(), None);
+    assert_eq!(probe.replay_gain().unwrap().track_gain, Some(-3.1));
+
+    let none = self::probe(json!([]), json!({}), json!({}));
+    assert_eq!(none.replay_gain(), None);
+}
@@ -0,0 +1,123 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::{path::PathBuf, time::Duration};
+
+use ffprobe::{error::FfProbeError, Config, RetryPolicy};
+
+mod common;
+
+fn attempts(counter: &PathBuf) -> usize {
+    std::fs::read_to_string(counter)
+        .unwrap_or_default()
+        .lines()
+        .count()
+}
+
+fn policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(max_attempts).backoff(Duration::from_millis(1))
+}
+
+#[test]
+fn retries_transient_failures() {
+    let (bin, counter) = common::flaky_ffprobe(
+        &common::temp_dir("retry", "transient"),
+        2,
+        "Server returned 503 Service Unavailable",
+    );
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .retry(policy(3))
+        .run("https://example.com/live.m3u8")
+        .unwrap();
+    assert!(!probe.streams.is_empty());
+    assert_eq!(attempts(&counter), 3);
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let (bin, counter) = common::flaky_ffprobe(
+        &common::temp_dir("retry", "exhausted"),
+        5,
+        "Connection reset by peer",
+    );
+    let err = Config::new()
+        .ffprobe_bin(&bin)
+        .retry(policy(2))
+        .allowed_protocols(["rtsp"])
+        .run("rtsp://camera/stream")
+        .unwrap_err();
+    assert!(matches!(err.inner(), FfProbeError::Status(_)));
+    assert_eq!(attempts(&counter), 2);
+}
+
+#[test]
+fn permanent_failures_are_not_retried() {
+    let (bin, counter) = common::flaky_ffprobe(
+        &common::temp_dir("retry", "permanent"),
+        1,
+        "Server returned 404 Not Found",
+    );
+    assert!(Config::new()
+        .ffprobe_bin(&bin)
+        .retry(policy(3))
+        .run("https://example.com/missing.m3u8")
+        .is_err());
+    assert_eq!(attempts(&counter), 1);
+
+    // Without a log, only network inputs are retried.
+    let (bin, counter) = common::flaky_ffprobe(&common::temp_dir("retry", "local"), 1, "");
+    assert!(Config::new()
+        .ffprobe_bin(&bin)
+        .retry(policy(3))
+        .run("/media/does-not-exist.mkv")
+        .is_err());
+    assert_eq!(attempts(&counter), 1);
+}
+
+#[test]
+fn custom_classification() {
+    let (bin, counter) = common::flaky_ffprobe(&common::temp_dir("retry", "custom"), 1, "");
+    Config::new()
+        .ffprobe_bin(&bin)
+        .retry(policy(3).retry_if(|err| matches!(err, FfProbeError::Status(_))))
+        .run("/media/does-not-exist.mkv")
+        .unwrap();
+    assert_eq!(attempts(&counter), 2);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_retries_with_backoff() {
+    use ffprobe::{ffprobe_async_with, runtime::Tokio};
+
+    let (bin, counter) = common::flaky_ffprobe(
+        &common::temp_dir("retry", "tokio"),
+        2,
+        "Server returned 503 Service Unavailable",
+    );
+    let config = Config::new()
+        .ffprobe_bin(&bin)
+        .retry(RetryPolicy::new(3).backoff(Duration::from_millis(20)));
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let started = std::time::Instant::now();
+    let probe = runtime
+        .block_on(ffprobe_async_with::<Tokio>(
+            config,
+            "https://example.com/live.m3u8",
+        ))
+        .unwrap();
+    assert!(!probe.streams.is_empty());
+    assert_eq!(attempts(&counter), 3);
+    // 20ms before the first retry and 40ms before the second one, waited with
+    // `Tokio::sleep`.
+    assert!(started.elapsed() >= Duration::from_millis(60));
+}
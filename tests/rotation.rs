@@ -0,0 +1,85 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use ffprobe::{FfProbe, Rotation, Stream};
+use serde_json::{json, Value};
+
+/// Video stream of the fixture with the given side data and tags.
+fn video(side_data: Value, tags: Value) -> Stream {
+    let json = std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap();
+    let mut value: Value = serde_json::from_slice(&json).unwrap();
+    value["streams"][0]["side_data_list"] = side_data;
+    value["streams"][0]["tags"] = tags;
+    let probe: FfProbe = serde_json::from_value(value).unwrap();
+    probe.streams[0].clone()
+}
+
+fn matrix(rows: [[i64; 3]; 3]) -> String {
+    rows.iter()
+        .enumerate()
+        .map(|(i, r)| format!("\n{i:08x}: {:>12}{:>12}{:>12}", r[0], r[1], r[2]))
+        .collect()
+}
+
+#[test]
+fn display_matrix_rotation() {
+    let portrait = video(
+        json!([{
+            "side_data_type": "Display Matrix",
+            "displaymatrix": matrix([[0, 65536, 0], [-65536, 0, 0], [0, 0, 1 << 30]]),
+            "rotation": -90
+        }]),
+        json!({}),
+    );
+    let rotation = portrait.rotation();
+    assert_eq!(rotation.degrees, 90);
+    assert!(!rotation.hflip && !rotation.vflip);
+    assert_eq!(
+        portrait.display_dimensions_after_rotation(),
+        Some((1080, 1920))
+    );
+
+    let mirrored = video(
+        json!([{
+            "side_data_type": "Display Matrix",
+            "displaymatrix": matrix([[-65536, 0, 0], [0, 65536, 0], [0, 0, 1 << 30]]),
+            "rotation": 0
+        }]),
+        json!({}),
+    );
+    assert!(mirrored.rotation().hflip);
+    assert_eq!(mirrored.rotation().to_string(), "0° hflip");
+    assert_eq!(
+        mirrored.display_dimensions_after_rotation(),
+        Some((1920, 1080))
+    );
+
+    let upside_down_mirror =
+        Rotation::from_display_matrix(&[65536, 0, 0, 0, -65536, 0, 0, 0, 1 << 30]);
+    assert_eq!(upside_down_mirror.degrees, 0);
+    assert!(upside_down_mirror.vflip && !upside_down_mirror.hflip);
+
+    // Without the matrix, ffprobe's counterclockwise rotation is used.
+    let rotation_only = video(
+        json!([{ "side_data_type": "Display Matrix", "rotation": 90 }]),
+        json!({}),
+    );
+    assert_eq!(rotation_only.rotation().degrees, 270);
+}
+
+#[test]
+fn legacy_rotate_tag() {
+    let tagged = video(json!([]), json!({ "rotate": "180" }));
+    assert_eq!(tagged.rotation(), Rotation::clockwise(180.));
+    assert_eq!(
+        tagged.display_dimensions_after_rotation(),
+        Some((1920, 1080))
+    );
+
+    let plain = video(json!([]), json!({}));
+    assert_eq!(plain.rotation(), Rotation::NONE);
+    assert!(Rotation::clockwise(-90.).swaps_dimensions());
+    assert_eq!(Rotation::clockwise(-90.).degrees, 270);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "process")]
+
+use std::time::Duration;
+
+use ffprobe::analysis::{SampleWindow, SamplingStrategy};
+
+#[test]
+fn uniform_windows() {
+    let strategy = SamplingStrategy::Uniform {
+        count: 3,
+        window: Duration::from_secs(2),
+    };
+    let windows = strategy.windows(Some(Duration::from_secs(100)));
+    let starts: Vec<u64> = windows.iter().map(|w| w.start.as_secs()).collect();
+    assert_eq!(starts, [24, 49, 74]);
+    assert_eq!(windows, strategy.windows(Some(Duration::from_secs(100))));
+    assert_eq!(
+        strategy.decoded_duration(Some(Duration::from_secs(100))),
+        Some(Duration::from_secs(6))
+    );
+    assert_eq!(windows[0].ffmpeg_input_args(), ["-ss", "24", "-t", "2"]);
+
+    // unknown duration falls back to the start
+    assert_eq!(
+        strategy.windows(None),
+        [SampleWindow {
+            start: Duration::ZERO,
+            duration: Some(Duration::from_secs(2)),
+        }]
+    );
+}
+
+#[test]
+fn keyframe_and_window() {
+    let keyframes = SamplingStrategy::EveryNthKeyframe { n: 10 };
+    assert_eq!(keyframes.decoded_duration(None), None);
+    let (input, select) = keyframes.keyframe_args().unwrap();
+    assert_eq!(input, ["-skip_frame", "nokey"]);
+    assert_eq!(select, "select='not(mod(n,10))'");
+
+    let window = SamplingStrategy::Window {
+        start: Duration::from_secs(60),
+        duration: Duration::from_secs(10),
+    };
+    assert!(window.keyframe_args().is_none());
+    assert_eq!(
+        window.windows(None)[0].ffmpeg_input_args(),
+        ["-ss", "60", "-t", "10"]
+    );
+
+    let parsed: SamplingStrategy =
+        serde_json::from_str(r#"{ "type": "window", "start": 60, "duration": 10 }"#).unwrap();
+    assert_eq!(parsed, window);
+}
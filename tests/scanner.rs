@@ -0,0 +1,90 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use ffprobe::{Config, Scanner};
+
+mod common;
+
+#[test]
+fn scan_directory() {
+    let dir = common::temp_dir("scanner", "directory");
+    let library = dir.join("library");
+    std::fs::create_dir_all(library.join("Season 1")).unwrap();
+    std::fs::create_dir_all(library.join(".trash")).unwrap();
+    for file in [
+        "a.mkv",
+        "b.MP4",
+        "notes.txt",
+        "broken.mkv",
+        ".hidden.mkv",
+        "Season 1/e01.mkv",
+        ".trash/old.mkv",
+    ] {
+        std::fs::write(library.join(file), b"\x1a\x45\xdf\xa3").unwrap();
+    }
+
+    let bin = common::failing_ffprobe(&dir, "*broken*");
+    let config = Config::new().ffprobe_bin(&bin);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let progress = Arc::clone(&seen);
+    let scan = Scanner::new(config.clone())
+        .extensions(["mkv", ".mp4"])
+        .parallelism(2)
+        .progress(move |p| progress.lock().unwrap().push((p.done, p.total)))
+        .scan(&library);
+    assert_eq!(scan.size_hint(), (3, Some(3)));
+    let mut results: Vec<_> = scan
+        .map(|(path, probe)| (path.strip_prefix(&library).unwrap().to_owned(), probe))
+        .collect();
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    let names: Vec<_> = results.iter().map(|(p, _)| p.to_str().unwrap()).collect();
+    assert_eq!(names, ["a.mkv", "b.MP4", "broken.mkv"]);
+    assert!(results[0].1.as_ref().unwrap().streams.len() > 1);
+    assert!(results[2].1.is_err());
+    assert_eq!(*seen.lock().unwrap(), [(1, 3), (2, 3), (3, 3)]);
+
+    let mut recursive: Vec<_> = Scanner::new(config.clone())
+        .extensions(["mkv"])
+        .recursive(true)
+        .scan(&library)
+        .map(|(path, _)| path.strip_prefix(&library).unwrap().to_owned())
+        .collect();
+    recursive.sort();
+    assert_eq!(
+        recursive,
+        [
+            Path::new("Season 1/e01.mkv"),
+            Path::new("a.mkv"),
+            Path::new("broken.mkv")
+        ]
+    );
+
+    let filtered = Scanner::new(config.clone())
+        .extensions(["mkv"])
+        .filter(|path| !path.ends_with("a.mkv"))
+        .scan(&library);
+    assert_eq!(filtered.count(), 1);
+
+    let hidden = Scanner::new(config.clone())
+        .recursive(true)
+        .hidden(true)
+        .scan(&library);
+    assert_eq!(hidden.count(), 7);
+
+    let mut missing = Scanner::new(config).scan(dir.join("missing"));
+    let (path, err) = missing.next().unwrap();
+    assert_eq!(path, dir.join("missing"));
+    assert!(err.is_err());
+    assert!(missing.next().is_none());
+}
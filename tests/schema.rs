@@ -0,0 +1,33 @@
+#![cfg(all(
+    feature = "schemars",
+    feature = "streams",
+    feature = "format",
+    feature = "chapters"
+))]
+
+use ffprobe::FfProbe;
+use serde_json::json;
+
+#[test]
+fn ffprobe_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(FfProbe)).unwrap();
+    let properties = &schema["properties"];
+    for section in ["streams", "chapters", "format"] {
+        assert!(properties.get(section).is_some(), "{section}");
+    }
+    // Fields that are never serialized aren't in the schema.
+    assert!(properties.get("stderr_warnings").is_none());
+    assert!(properties.get("hashes").is_none());
+
+    let defs = &schema["$defs"];
+    assert_eq!(defs["Ratio"]["type"], json!("string"));
+    let stream = &defs["Stream"];
+    assert!(stream["properties"].get("index").is_some());
+    assert!(stream["properties"].get("extradata").is_some());
+    // Referenced by the stream kinds flattened into the stream.
+    for kind in ["AudioStream", "VideoStream", "SubtitleStream", "DataStream"] {
+        assert!(defs.get(kind).is_some(), "{kind}");
+    }
+    assert!(defs["VideoStream"]["properties"].get("width").is_some());
+    assert!(defs["SideData"]["anyOf"].as_array().unwrap().len() > 1);
+}
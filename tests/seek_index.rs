@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+mod common;
+
+fn element(id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = id
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect();
+    // 8 byte size vint.
+    out.push(0x01);
+    out.extend_from_slice(&(payload.len() as u64).to_be_bytes()[1..]);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn cue_point(time: u8) -> Vec<u8> {
+    element(0xBB, &element(0xB3, &[time]))
+}
+
+fn webm(cues_first: bool) -> Vec<u8> {
+    let info = element(
+        0x1549_A966,
+        &[
+            element(0x2A_D7B1, &[0x0F, 0x42, 0x40]),
+            element(0x4489, &10_000f64.to_be_bytes()),
+        ]
+        .concat(),
+    );
+    let cues = element(0x1C53_BB6B, &[cue_point(0), cue_point(100)].concat());
+    let cluster = element(0x1F43_B675, &[0; 64]);
+
+    let mut body = vec![info];
+    if cues_first {
+        body.extend([cues, cluster]);
+    } else {
+        body.extend([cluster, cues]);
+    }
+    [
+        element(0x1A45_DFA3, &element(0x4282, b"webm")),
+        element(0x1853_8067, &body.concat()),
+    ]
+    .concat()
+}
+
+#[test]
+fn seek_index() {
+    let dir = common::temp_dir("seek-index", "cues");
+
+    let path = dir.join("cues_first.webm");
+    std::fs::write(&path, webm(true)).unwrap();
+    let info = ffprobe::seek_index_info(&path).unwrap();
+    assert!(info.has_cues);
+    assert!(info.cues_before_clusters);
+    assert!(info.is_web_seekable());
+    assert_eq!(info.cue_points, 2);
+    assert_eq!(info.duration, Some(Duration::from_secs(10)));
+    assert_eq!(info.max_cue_gap, Some(Duration::from_millis(9900)));
+    assert_eq!(info.cues_per_minute(), Some(12.0));
+
+    let path = dir.join("cues_last.webm");
+    std::fs::write(&path, webm(false)).unwrap();
+    let info = ffprobe::seek_index_info(&path).unwrap();
+    assert!(info.has_cues);
+    assert!(!info.cues_before_clusters);
+    assert!(!info.is_web_seekable());
+}
+
+#[test]
+fn huge_element() {
+    let dir = common::temp_dir("seek-index", "huge");
+
+    // An Info element claiming 256 TiB, not allocated before reading.
+    let mut info = vec![0x15, 0x49, 0xA9, 0x66, 0x01, 0x00, 0x01];
+    info.extend([0; 5]);
+    let segment = [&[0x18, 0x53, 0x80, 0x67, 0xFF][..], &info, &[0; 16]].concat();
+    let path = dir.join("huge.webm");
+    std::fs::write(
+        &path,
+        [element(0x1A45_DFA3, &element(0x4282, b"webm")), segment].concat(),
+    )
+    .unwrap();
+    let err = ffprobe::seek_index_info(&path).unwrap_err();
+    assert!(err.to_string().contains("larger than the file"), "{err}");
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{side_data::SphericalMapping, SideData};
+use serde_json::{json, Value};
+
+fn parse(value: Value) -> SideData {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn hdr_metadata() {
+    let SideData::MasteringDisplay(mastering) = parse(json!({
+        "side_data_type": "Mastering display metadata",
+        "red_x": "34000/50000",
+        "red_y": "16000/50000",
+        "green_x": "13250/50000",
+        "green_y": "34500/50000",
+        "blue_x": "7500/50000",
+        "blue_y": "3000/50000",
+        "white_point_x": "15635/50000",
+        "white_point_y": "16450/50000",
+        "min_luminance": "50/10000",
+        "max_luminance": "10000000/10000"
+    })) else {
+        panic!("expected mastering display metadata");
+    };
+    assert_eq!(mastering.max_luminance_nits(), Some(1000.));
+    assert_eq!(mastering.min_luminance_nits(), Some(0.005));
+    assert_eq!(mastering.red_x.unwrap().as_f64(), Some(0.68));
+
+    let light = parse(json!({
+        "side_data_type": "Content light level metadata",
+        "max_content": 1000,
+        "max_average": 400
+    }));
+    let SideData::ContentLightLevel(light) = &light else {
+        panic!("expected content light level");
+    };
+    assert_eq!(
+        (light.max_content, light.max_average),
+        (Some(1000), Some(400))
+    );
+}
+
+#[test]
+fn typed_variants() {
+    let cpb = parse(json!({
+        "side_data_type": "CPB properties",
+        "max_bitrate": 0,
+        "min_bitrate": 0,
+        "avg_bitrate": 0,
+        "buffer_size": 0,
+        "vbv_delay": 18446744073709551615u64
+    }));
+    assert!(matches!(cpb, SideData::CpbProperties(ref c) if c.vbv_delay == Some(u64::MAX)));
+    assert_eq!(cpb.side_data_type(), "CPB properties");
+
+    let dovi = parse(json!({
+        "side_data_type": "DOVI configuration record",
+        "dv_version_major": 1,
+        "dv_version_minor": 0,
+        "dv_profile": 8,
+        "dv_level": 6,
+        "rpu_present_flag": 1,
+        "el_present_flag": 0,
+        "bl_present_flag": 1,
+        "dv_bl_signal_compatibility_id": 1
+    }));
+    assert!(matches!(dovi, SideData::DolbyVision(ref d) if d.dv_profile == Some(8)));
+
+    let stereo =
+        parse(json!({ "side_data_type": "Stereo 3D", "type": "side by side", "inverted": 0 }));
+    assert!(
+        matches!(stereo, SideData::Stereo3D(ref s) if s.kind.as_deref() == Some("side by side"))
+    );
+
+    let spherical = parse(json!({
+        "side_data_type": "Spherical Mapping",
+        "projection": "equirectangular",
+        "yaw": 0,
+        "pitch": 0,
+        "roll": 0
+    }));
+    let SideData::SphericalMapping(SphericalMapping { projection, .. }) = spherical else {
+        panic!("expected spherical mapping");
+    };
+    assert_eq!(projection.as_deref(), Some("equirectangular"));
+
+    let service = parse(json!({ "side_data_type": "Audio Service Type", "service_type": 0 }));
+    assert!(matches!(service, SideData::AudioServiceType(ref s) if s.service_type == Some(0)));
+}
+
+#[test]
+fn unknown_fallback() {
+    let entry = json!({ "side_data_type": "MPEGTS Stream ID", "id": 224 });
+    let side_data = parse(entry.clone());
+    assert_eq!(
+        side_data,
+        SideData::Unknown {
+            side_data_type: "MPEGTS Stream ID".to_string(),
+            raw: entry.clone(),
+        }
+    );
+    assert_eq!(serde_json::to_value(&side_data).unwrap(), entry);
+
+    // A known type with an unexpected layout keeps its data rather than failing the probe.
+    let odd = json!({ "side_data_type": "Content light level metadata", "max_content": "bright" });
+    assert!(matches!(parse(odd), SideData::Unknown { .. }));
+}
+
+#[test]
+fn round_trip() {
+    let entry = json!({
+        "side_data_type": "Display Matrix",
+        "displaymatrix": "\n00000000:            0       65536           0\n00000001:       -65536           0           0\n00000002:            0           0  1073741824\n",
+        "rotation": -90
+    });
+    let side_data = parse(entry.clone());
+    let SideData::DisplayMatrix(matrix) = &side_data else {
+        panic!("expected display matrix");
+    };
+    assert_eq!(
+        matrix.values(),
+        Some([0, 65536, 0, -65536, 0, 0, 0, 0, 1073741824])
+    );
+    assert_eq!(serde_json::to_value(&side_data).unwrap(), entry);
+}
@@ -0,0 +1,74 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use ffprobe::{sidecar::find_subtitles_config, Config, Language};
+
+mod common;
+
+#[test]
+fn find_sidecar_subtitles() {
+    let dir = common::temp_dir("sidecar", "subtitles");
+    std::fs::create_dir_all(dir.join("Subs/Movie")).unwrap();
+    for name in [
+        "Movie.mkv",
+        "Movie.srt",
+        "Movie.en.forced.srt",
+        "Movie.pt-BR.sdh.srt",
+        "Movie.en.hi.srt",
+        "Movie.hi.srt",
+        "Movie.eng.Commentary.ass",
+        "Movie.de.vtt",
+        "Movie.idx",
+        "Movie.sub",
+        "Movie.nfo",
+        "Other.srt",
+        "Subs/Movie/2_English.srt",
+    ] {
+        std::fs::write(dir.join(name), "not empty").unwrap();
+    }
+
+    // Fake ffprobe failing for the WebVTT file and printing the fixture for the others.
+    let bin = common::failing_ffprobe(&dir, "*.vtt");
+
+    let subtitles =
+        find_subtitles_config(&Config::new().ffprobe_bin(&bin), dir.join("Movie.mkv")).unwrap();
+    let lang = |tag: &str| Language::parse(tag);
+    let found: Vec<_> = subtitles
+        .iter()
+        .map(|s| {
+            (
+                s.path.strip_prefix(&dir).unwrap().to_str().unwrap(),
+                s.language.clone(),
+                (s.forced, s.sdh),
+                s.label.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("Movie.de.vtt", lang("de"), (false, false), None),
+            ("Movie.en.forced.srt", lang("en"), (true, false), None),
+            ("Movie.en.hi.srt", lang("en"), (false, true), None),
+            (
+                "Movie.eng.Commentary.ass",
+                lang("en"),
+                (false, false),
+                Some("Commentary")
+            ),
+            ("Movie.hi.srt", lang("hi"), (false, false), None),
+            ("Movie.idx", None, (false, false), None),
+            ("Movie.pt-BR.sdh.srt", lang("pt-BR"), (false, true), None),
+            ("Movie.srt", None, (false, false), None),
+            ("Subs/Movie/2_English.srt", lang("en"), (false, false), None),
+        ]
+    );
+    assert!(subtitles[0].stream.is_none());
+    let stream = subtitles[1].stream.as_ref().unwrap();
+    assert_eq!(stream.index, 2);
+}
@@ -0,0 +1,125 @@
+#![cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+
+use ffprobe::{
+    AmbisonicChannelOrder, AmbisonicNormalization, FfProbe, ProjectionKind, StreamKinds,
+};
+use serde_json::{json, Value};
+
+/// The fixture with `video` and `audio` merged into its video and audio streams.
+fn probe(video: Value, audio: Value) -> FfProbe {
+    let json = std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json"),
+    )
+    .unwrap();
+    let mut value: Value = serde_json::from_slice(&json).unwrap();
+    for (index, fields) in [(0, video), (1, audio)] {
+        for (key, field) in fields.as_object().unwrap() {
+            value["streams"][index][key] = field.clone();
+        }
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn flat() {
+    let probe = probe(json!({}), json!({}));
+    assert_eq!(probe.streams[0].projection(), None);
+    assert_eq!(probe.streams[1].projection(), None);
+    let StreamKinds::Audio(audio) = &probe.streams[1].stream else {
+        panic!("not audio");
+    };
+    assert_eq!(audio.ambisonics(), None);
+}
+
+#[test]
+fn spherical_side_data() {
+    let probe = probe(
+        json!({
+            "side_data_list": [{
+                "side_data_type": "Spherical Mapping",
+                "projection": "tiled equirectangular",
+                "bound_left": 1073741824,
+                "bound_top": 0,
+                "bound_right": 1073741824,
+                "bound_bottom": 0,
+                "yaw": 90,
+                "pitch": -10,
+                "roll": 0
+            }],
+            "tags": { "ProjectionType": "cubemap" }
+        }),
+        json!({}),
+    );
+    let projection = probe.streams[0].projection().unwrap();
+    assert_eq!(projection.kind, ProjectionKind::TiledEquirectangular);
+    assert_eq!(projection.kind.to_string(), "tiled equirectangular");
+    assert_eq!((projection.yaw, projection.pitch), (90., -10.));
+    assert_eq!(projection.bounds, Some([0.25, 0., 0.25, 0.]));
+    assert_eq!(projection.padding, None);
+}
+
+#[test]
+fn spherical_tags() {
+    let probe = probe(
+        json!({ "tags": { "Spherical": "true", "InitialViewHeadingDegrees": "180" } }),
+        json!({}),
+    );
+    let StreamKinds::Video(video) = &probe.streams[0].stream else {
+        panic!("not video");
+    };
+    let projection = video.projection().unwrap();
+    assert_eq!(projection.kind, ProjectionKind::Equirectangular);
+    assert_eq!(projection.yaw, 180.);
+    assert_eq!(probe.streams[0].projection(), Some(projection));
+    assert_eq!(
+        ProjectionKind::parse("Fancy Dome"),
+        ProjectionKind::Other("Fancy Dome".into())
+    );
+}
+
+#[test]
+fn ambisonic_layout() {
+    let probe = probe(
+        json!({}),
+        json!({ "channels": 6, "channel_layout": "ambisonic 1+stereo" }),
+    );
+    let StreamKinds::Audio(audio) = &probe.streams[1].stream else {
+        panic!("not audio");
+    };
+    let ambisonics = audio.ambisonics().unwrap();
+    assert_eq!(ambisonics.order, 1);
+    assert_eq!(ambisonics.ambisonic_channels(), 4);
+    assert_eq!(ambisonics.non_diegetic_channels, 2);
+    assert_eq!(ambisonics.channel_order, AmbisonicChannelOrder::Acn);
+    assert_eq!(ambisonics.normalization, AmbisonicNormalization::Sn3d);
+}
+
+#[test]
+fn ambisonic_tags() {
+    let tagged = probe(
+        json!({}),
+        json!({
+            "channels": 4,
+            "channel_layout": "4.0",
+            "tags": {
+                "ambisonic_order": "1",
+                "ambisonic_channel_ordering": "FuMa",
+                "ambisonic_normalization": "FuMa"
+            }
+        }),
+    );
+    let StreamKinds::Audio(audio) = &tagged.streams[1].stream else {
+        panic!("not audio");
+    };
+    let ambisonics = audio.ambisonics().unwrap();
+    assert_eq!(ambisonics.channel_order, AmbisonicChannelOrder::FuMa);
+    assert_eq!(ambisonics.normalization, AmbisonicNormalization::FuMa);
+    assert_eq!(ambisonics.non_diegetic_channels, 0);
+
+    // Too few channels for the order.
+    let few = probe(json!({}), json!({ "tags": { "ambisonic_order": "3" } }));
+    let StreamKinds::Audio(audio) = &few.streams[1].stream else {
+        panic!("not audio");
+    };
+    assert_eq!(audio.ambisonics(), None);
+}
@@ -0,0 +1,43 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::sync::{Arc, Mutex};
+
+use ffprobe::{Config, SpawnEvent};
+
+mod common;
+
+#[test]
+fn spawn_hook_reports_runs() {
+    let dir = common::temp_dir("spawn", "hook");
+    let input = common::write(&dir, "my video.mkv", b"\x1a\x45\xdf\xa3");
+    let bin = common::script(&dir, "ffprobe", "echo 'moov atom not found' >&2\nexit 3");
+
+    let events: Arc<Mutex<Vec<SpawnEvent>>> = Arc::default();
+    let seen = Arc::clone(&events);
+    let config = Config::new()
+        .ffprobe_bin(&bin)
+        .on_spawn(move |event| seen.lock().unwrap().push(event.clone()));
+
+    assert!(config.clone().run(&input).is_err());
+    assert!(config
+        .clone()
+        .ffprobe_bin(dir.join("missing"))
+        .run(&input)
+        .is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    let failed = &events[0];
+    assert_eq!(failed.program, bin.as_os_str());
+    assert_eq!(failed.args.last(), Some(&input.as_os_str().to_owned()));
+    assert_eq!(failed.exit_code(), Some(3));
+    assert!(!failed.success());
+    assert_eq!(failed.stderr, "moov atom not found");
+    assert!(failed
+        .command_line()
+        .ends_with(&format!("'{}'", input.display())));
+
+    let missing = &events[1];
+    assert_eq!(missing.status, None);
+    assert!(missing.error.is_some());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "streams")]
+
+use ffprobe::{FfProbe, StreamIndex, StreamType};
+use serde_json::{json, Value};
+
+fn fixture() -> Value {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+    serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+}
+
+/// The fixture with a second audio stream, printed before the others.
+fn two_audio_streams() -> FfProbe {
+    let mut json = fixture();
+    let mut audio = json["streams"][1].clone();
+    audio["index"] = json!("3");
+    json["streams"].as_array_mut().unwrap().insert(0, audio);
+    FfProbe::from_json(&json.to_string()).unwrap()
+}
+
+#[test]
+fn index_serde() {
+    let probe = two_audio_streams();
+    assert_eq!(probe.streams[0].index, StreamIndex(3));
+    assert_eq!(probe.streams[1].index, 0);
+    let value = serde_json::to_value(&probe.streams[0]).unwrap();
+    assert_eq!(value["index"], json!(3));
+
+    let index: StreamIndex = serde_json::from_value(json!("2")).unwrap();
+    assert_eq!(index, StreamIndex::from(2));
+    assert_eq!(index.to_string(), "2");
+    assert_eq!(" 2".parse::<StreamIndex>().unwrap(), index);
+    assert_eq!(u64::from(index), 2);
+    assert!(serde_json::from_value::<StreamIndex>(json!("a")).is_err());
+}
+
+#[test]
+fn lookup_by_index() {
+    let probe = two_audio_streams();
+    assert_eq!(probe.by_index(3).unwrap().stream_type(), StreamType::Audio);
+    assert_eq!(probe.by_index(StreamIndex(0)).unwrap().index, 0);
+    assert!(probe.by_index(7).is_none());
+}
+
+#[test]
+fn lookup_by_type_ordinal() {
+    let probe = two_audio_streams();
+    // In index order, not the order of the streams in the output.
+    assert_eq!(
+        probe.by_type_ordinal(StreamType::Audio, 0).unwrap().index,
+        1
+    );
+    assert_eq!(
+        probe.by_type_ordinal(StreamType::Audio, 1).unwrap().index,
+        3
+    );
+    assert!(probe.by_type_ordinal(StreamType::Audio, 2).is_none());
+    assert!(probe.by_type_ordinal(StreamType::Data, 0).is_none());
+
+    assert_eq!(probe.type_ordinal(3), Some((StreamType::Audio, 1)));
+    assert_eq!(probe.type_ordinal(2), Some((StreamType::Subtitle, 0)));
+    assert_eq!(probe.type_ordinal(7), None);
+    assert_eq!(StreamType::Subtitle.specifier(), 's');
+}
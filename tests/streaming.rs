@@ -0,0 +1,63 @@
+#![cfg(all(unix, feature = "streams", feature = "format", feature = "process"))]
+
+use ffprobe::{
+    streaming::{probe_manifest_all_variants, probe_manifest_config, ManifestKind},
+    Bitrate, Config,
+};
+
+mod common;
+
+#[test]
+fn hls_variants() {
+    let (bin, args) =
+        common::fixture_ffprobe(&common::temp_dir("streaming", "hls"), "hls.json", &[]);
+    let config = Config::new().ffprobe_bin(bin);
+    let manifest = probe_manifest_config(config, "https://example.com/master.m3u8").unwrap();
+    assert!(std::fs::read_to_string(&args)
+        .unwrap()
+        .contains("-show_programs"));
+    assert_eq!(manifest.kind, ManifestKind::Hls);
+
+    let bandwidths: Vec<_> = manifest.variants.iter().map(|v| v.bandwidth).collect();
+    assert_eq!(
+        bandwidths,
+        [
+            Some(Bitrate(5_000_000)),
+            Some(Bitrate(2_500_000)),
+            Some(Bitrate(800_000))
+        ]
+    );
+    let top = &manifest.variants[0];
+    assert_eq!(top.program_id, Some(2));
+    assert_eq!(top.streams, [0, 1]);
+    assert_eq!(top.resolution, Some((1920, 1080)));
+    assert_eq!(top.codecs, ["h264", "aac"]);
+    assert_eq!(manifest.variants[1].resolution, Some((1280, 720)));
+    // The lowest variant wasn't reached within the probing limits.
+    assert!(!manifest.variants[2].probed);
+    assert_eq!(manifest.probed_variant(), Some(top));
+    assert_eq!(manifest.streams(&manifest.variants[1]).count(), 2);
+    assert!(manifest.probe.query("programs[0].program_id").is_some());
+}
+
+#[test]
+fn dash_representations() {
+    let (bin, args) =
+        common::fixture_ffprobe(&common::temp_dir("streaming", "dash"), "dash.json", &[]);
+    let config = Config::new().ffprobe_bin(bin);
+    let manifest = probe_manifest_all_variants(config, "https://example.com/manifest.mpd").unwrap();
+    assert!(std::fs::read_to_string(&args)
+        .unwrap()
+        .contains("-probesize"));
+    assert_eq!(manifest.kind, ManifestKind::Dash);
+    assert_eq!(manifest.variants.len(), 3);
+    assert_eq!(manifest.variants[2].streams, [4, 5]);
+    assert_eq!(manifest.variants[2].program_id, None);
+}
+
+#[test]
+fn rejects_other_inputs() {
+    let (bin, _) = common::fixture_ffprobe(&common::temp_dir("streaming", "mp4"), "mp4.json", &[]);
+    let config = Config::new().ffprobe_bin(bin);
+    assert!(probe_manifest_config(config, "video.mp4").is_err());
+}
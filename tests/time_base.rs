@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use ffprobe::{Ratio, TimeBase};
+
+#[test]
+fn conversions() {
+    let mpeg = TimeBase::new(1, 90000).unwrap();
+    assert_eq!(mpeg.to_duration(90_000), Duration::from_secs(1));
+    assert_eq!(mpeg.to_nanos(1), 11_111);
+    assert_eq!(mpeg.to_nanos(-90_000), -1_000_000_000);
+    assert_eq!(mpeg.to_duration(-90_000), Duration::ZERO);
+    assert_eq!(mpeg.from_duration(Duration::from_secs(10)), 900_000);
+    // 1 ms is 90 ticks, 5.5 µs rounds up to half a tick.
+    assert_eq!(mpeg.from_duration(Duration::from_nanos(1_005_556)), 91);
+
+    let ntsc = TimeBase::new(1001, 30000).unwrap();
+    assert_eq!(ntsc.to_duration(30), Duration::from_nanos(1_001_000_000));
+    assert_eq!(ntsc.from_duration(Duration::from_nanos(1_001_000_000)), 30);
+}
+
+#[test]
+fn rescale() {
+    let mpeg = TimeBase::new(1, 90000).unwrap();
+    let millis = TimeBase::new(1, 1000).unwrap();
+    assert_eq!(mpeg.rescale(90_000, millis), 1000);
+    assert_eq!(millis.rescale(1000, mpeg), 90_000);
+    assert_eq!(mpeg.rescale(45, millis), 1);
+    assert_eq!(mpeg.rescale(-45, millis), -1);
+    assert_eq!(mpeg.rescale(44, millis), 0);
+    assert_eq!(millis.rescale(i64::MAX, mpeg), i64::MAX);
+}
+
+#[test]
+fn parse_and_convert() {
+    let time_base: TimeBase = "1/48000".parse().unwrap();
+    assert_eq!(time_base.numerator(), 1);
+    assert_eq!(time_base.denominator(), 48000);
+    assert_eq!(time_base.to_string(), "1/48000");
+    assert_eq!(Ratio::from(time_base), Ratio::new(1, 48000));
+    assert!("0/0".parse::<TimeBase>().is_err());
+    assert!("1/x".parse::<TimeBase>().is_err());
+    assert_eq!(TimeBase::new(1, 0), None);
+    assert_eq!(TimeBase::from_ratio(&Ratio::new(0, 1000)), None);
+}
+
+#[cfg(feature = "streams")]
+#[test]
+fn stream_time_base() {
+    let stream = ffprobe::Stream::new(0, ffprobe::VideoStream::default());
+    assert_eq!(stream.timebase(), TimeBase::new(1, 1000));
+}
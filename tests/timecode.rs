@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use ffprobe::{Ratio, Timecode};
+
+fn ntsc() -> Ratio {
+    Ratio::new(30000, 1001)
+}
+
+#[test]
+fn parse_and_display() {
+    let tc = Timecode::parse("01:02:03:04", Ratio::new(25, 1)).unwrap();
+    assert_eq!((tc.hours, tc.minutes, tc.seconds, tc.frames), (1, 2, 3, 4));
+    assert!(!tc.drop_frame);
+    assert_eq!(tc.to_string(), "01:02:03:04");
+    assert_eq!(tc.frame_number(), (3600 + 120 + 3) * 25 + 4);
+
+    for drop in ["00:01:00;02", "00;01;00;02", "00:01:00.02"] {
+        let tc = Timecode::parse(drop, ntsc()).unwrap();
+        assert!(tc.drop_frame);
+        assert_eq!(tc.to_string(), "00:01:00;02");
+        assert_eq!(tc.frame_number(), 1800);
+    }
+
+    // Out of range, dropped frame numbers and drop frame at 25 fps.
+    for invalid in [
+        "00:00:00:25",
+        "24:00:00:00",
+        "00:60:00:00",
+        "00:00:00",
+        "1:2:3:4:5",
+    ] {
+        assert!(
+            Timecode::parse(invalid, Ratio::new(25, 1)).is_err(),
+            "{invalid}"
+        );
+    }
+    assert!(Timecode::parse("00:01:00;00", ntsc()).is_err());
+    assert!(Timecode::parse("00:10:00;00", ntsc()).is_ok());
+    assert!(Timecode::parse("00:00:01;00", Ratio::new(25, 1)).is_err());
+    assert!(Timecode::parse("00:00:00:00", Ratio::new(0, 0)).is_err());
+}
+
+#[test]
+fn drop_frame_arithmetic() {
+    let tc = Timecode::parse("00:00:59;29", ntsc()).unwrap();
+    assert_eq!(tc.add_frames(1).to_string(), "00:01:00;02");
+    assert_eq!(tc.add_frames(1).add_frames(-1), tc);
+    let tc = Timecode::parse("00:09:59;29", ntsc()).unwrap();
+    assert_eq!(tc.add_frames(1).to_string(), "00:10:00;00");
+
+    // 59.94 fps drops four frame numbers.
+    let tc = Timecode::parse("00:00:59;59", Ratio::new(60000, 1001)).unwrap();
+    assert_eq!(tc.add_frames(1).to_string(), "00:01:00;04");
+
+    // Round trip through every frame of a ten minute block.
+    for frame in 0..17_982 {
+        let tc = Timecode::from_frame_number(frame, ntsc(), true);
+        assert_eq!(tc.frame_number(), frame, "{tc}");
+        assert_eq!(Timecode::parse(&tc.to_string(), ntsc()).unwrap(), tc);
+    }
+
+    // An hour of drop frame timecode is an hour of wall clock time, give or take.
+    let hour = Timecode::parse("01:00:00;00", ntsc()).unwrap();
+    assert_eq!(hour.frame_number(), 107_892);
+    assert_eq!(hour.to_duration(), Duration::from_nanos(3_599_996_400_000));
+}
+
+#[test]
+fn wraps_at_midnight() {
+    let tc = Timecode::parse("23:59:59:24", Ratio::new(25, 1)).unwrap();
+    assert_eq!(tc.add_frames(1).to_string(), "00:00:00:00");
+    let tc = Timecode::parse("00:00:00;00", ntsc()).unwrap();
+    assert_eq!(tc.add_frames(-1).to_string(), "23:59:59;29");
+    assert_eq!(
+        Timecode::parse("00:00:01:00", Ratio::new(24, 1))
+            .unwrap()
+            .to_duration(),
+        Duration::from_secs(1)
+    );
+}
+
+#[cfg(all(feature = "streams", feature = "format", feature = "chapters"))]
+mod start_timecode {
+    use ffprobe::{FfProbe, Ratio};
+    use serde_json::{json, Value};
+
+    fn fixture() -> Value {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mp4.json");
+        serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    fn probe(json: Value) -> FfProbe {
+        FfProbe::from_json(&json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn none() {
+        assert_eq!(probe(fixture()).start_timecode(), None);
+    }
+
+    #[test]
+    fn format_tag() {
+        let mut json = fixture();
+        json["format"]["tags"]["timecode"] = json!("10:00:00:12");
+        let tc = probe(json).start_timecode().unwrap();
+        assert_eq!(tc.to_string(), "10:00:00:12");
+        assert_eq!(tc.rate, Ratio::new(24, 1));
+    }
+
+    #[test]
+    fn tmcd_stream() {
+        let mut json = fixture();
+        json["streams"][0]["avg_frame_rate"] = json!("30000/1001");
+        let mut tmcd = json["streams"][2].clone();
+        tmcd["index"] = json!(3);
+        tmcd["codec_type"] = json!("data");
+        tmcd["codec_tag_string"] = json!("tmcd");
+        tmcd["duration_ts"] = json!(1);
+        tmcd["tags"] = json!({ "timecode": "01:00:00;00" });
+        json["streams"].as_array_mut().unwrap().push(tmcd);
+        let tc = probe(json).start_timecode().unwrap();
+        assert!(tc.drop_frame);
+        assert_eq!(tc.frame_number(), 107_892);
+        assert_eq!(tc.rate, Ratio::new(30000, 1001));
+    }
+
+    #[test]
+    fn gop_side_data() {
+        let mut json = fixture();
+        json["streams"][0]["side_data_list"] = json!([
+            { "side_data_type": "GOP timecode", "timecode": "00:59:58:00" }
+        ]);
+        assert_eq!(
+            probe(json.clone()).start_timecode().unwrap().to_string(),
+            "00:59:58:00"
+        );
+        json["streams"][0]["side_data_list"] = json!([
+            {
+                "side_data_type": "SMPTE 12-1 timecode",
+                "timecodes": [{ "value": "00:59:58:01" }]
+            }
+        ]);
+        assert_eq!(
+            probe(json).start_timecode().unwrap().to_string(),
+            "00:59:58:01"
+        );
+    }
+}
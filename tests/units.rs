@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use ffprobe::{Bitrate, FileSize, SampleRate};
+
+#[test]
+fn unit_display() {
+    assert_eq!(Bitrate(800).to_string(), "800 b/s");
+    assert_eq!(Bitrate(128_000).to_string(), "128 kb/s");
+    assert_eq!(Bitrate(4_500_000).to_string(), "4.50 Mb/s");
+    assert_eq!(FileSize(512).to_string(), "512 B");
+    assert_eq!(FileSize(1536).to_string(), "1.50 KiB");
+    assert_eq!(FileSize(3 << 29).to_string(), "1.50 GiB");
+    assert_eq!(SampleRate(44100).to_string(), "44.1 kHz");
+    assert_eq!(SampleRate(48000).to_string(), "48 kHz");
+}
+
+#[test]
+fn unit_math() {
+    let rate = Bitrate(4_000_000);
+    let size = rate.size_for(Duration::from_secs(10));
+    assert_eq!(size, FileSize(5_000_000));
+    assert_eq!(
+        Bitrate::from_size(size, Duration::from_secs(10)),
+        Some(rate)
+    );
+    assert_eq!(Bitrate::from_size(size, Duration::ZERO), None);
+    assert_eq!(
+        [Bitrate(1), Bitrate(2)].into_iter().sum::<Bitrate>(),
+        Bitrate(3)
+    );
+    assert_eq!(FileSize(1) - FileSize(2), FileSize(0));
+    assert_eq!(
+        SampleRate(48000).duration_of(96000),
+        Some(Duration::from_secs(2))
+    );
+    assert_eq!(SampleRate(1).duration_of(u64::MAX), None);
+    assert_eq!(SampleRate(0).duration_of(1), None);
+}
+
+#[test]
+fn unit_serde() {
+    let rate: Bitrate = serde_json::from_str(r#""384000""#).unwrap();
+    assert_eq!(rate, Bitrate(384_000));
+    let rate: Bitrate = serde_json::from_str("384000").unwrap();
+    assert_eq!(serde_json::to_string(&rate).unwrap(), "384000");
+    assert!(serde_json::from_str::<SampleRate>("-1").is_err());
+}
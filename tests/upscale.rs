@@ -0,0 +1,22 @@
+#![cfg(feature = "process")]
+
+use ffprobe::analysis::{ResolutionClass, UpscaleReport};
+
+#[test]
+fn resolution_classes() {
+    assert_eq!(ResolutionClass::from_height(1080), ResolutionClass::Hd1080);
+    assert_eq!(ResolutionClass::from_height(1088), ResolutionClass::Hd1080);
+    assert_eq!(ResolutionClass::from_height(800), ResolutionClass::Hd720);
+    assert_eq!(ResolutionClass::from_height(2160), ResolutionClass::Uhd2160);
+    assert_eq!(ResolutionClass::from_height(360), ResolutionClass::Sd);
+    assert_eq!(ResolutionClass::Hd1080.to_string(), "1080p");
+    assert_eq!(ResolutionClass::Uhd2160.to_string(), "4K");
+
+    let report = UpscaleReport {
+        height: 2160,
+        source: ResolutionClass::Hd1080,
+        confidence: 0.9,
+        samples: Vec::new(),
+    };
+    assert!(report.is_upscaled());
+}
@@ -1,3 +1,5 @@
+#![cfg(all(feature = "streams", feature = "process"))]
+
 use std::path::Path;
 
 use ffprobe::{Config, StreamKinds};
@@ -59,7 +61,7 @@ fn check_count_frames(path: &Path) {
 
 #[test]
 fn download_and_probe() {
-    let item_urls = vec![
+    let item_urls = [
         // Images.
         "http://commondatastorage.googleapis.com/gtv-videos-bucket/sample/images/BigBuckBunny.jpg",
         // Videos.
@@ -0,0 +1,116 @@
+#![cfg(all(
+    unix,
+    feature = "streams",
+    feature = "format",
+    feature = "chapters",
+    feature = "process"
+))]
+
+use ffprobe::{Config, LogLevel, LogMessage};
+
+mod common;
+
+/// Two warnings of the fake ffprobe.
+const WARNINGS: &[&str] = &[
+    "[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d1c8] stream 0, timescale not set",
+    "",
+    "Application provided invalid, non monotonically increasing dts to muxer",
+];
+
+#[test]
+fn captures_warnings_on_success() {
+    let dir = common::temp_dir("warnings", "capture");
+    let (bin, args) = common::fixture_ffprobe(&dir, "mp4.json", WARNINGS);
+    let input = common::input(&dir, "video.mp4");
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .capture_warnings(true)
+        .run(&input)
+        .unwrap();
+    assert_eq!(
+        probe.stderr_warnings,
+        [
+            "[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d1c8] stream 0, timescale not set",
+            "Application provided invalid, non monotonically increasing dts to muxer",
+        ]
+    );
+    assert!(std::fs::read_to_string(args)
+        .unwrap()
+        .starts_with("-v\nlevel+warning\n"));
+}
+
+#[test]
+fn quiet_by_default() {
+    let dir = common::temp_dir("warnings", "quiet");
+    let (bin, args) = common::fixture_ffprobe(&dir, "mp4.json", WARNINGS);
+    let input = common::input(&dir, "video.mp4");
+    let probe = Config::new().ffprobe_bin(&bin).run(&input).unwrap();
+    assert!(probe.stderr_warnings.is_empty());
+    assert!(std::fs::read_to_string(args)
+        .unwrap()
+        .starts_with("-v\nquiet\n"));
+}
+
+#[test]
+fn loglevel() {
+    let dir = common::temp_dir("warnings", "loglevel");
+    let (bin, args) = common::fixture_ffprobe(
+        &dir,
+        "mp4.json",
+        &[
+            "[mov,mp4,m4a,3gp,3g2,mj2 @ 0x55d1c8] [info] Auto-inserting h264_mp4toannexb",
+            "[aist#0:1/aac @ 0x55d1] [aac @ 0x55d2] [error] channel element 0.0 is not allocated",
+            "[verbose] Reading option '-v' ...",
+        ],
+    );
+    let input = common::input(&dir, "video.mp4");
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .loglevel(LogLevel::Verbose)
+        .run(&input)
+        .unwrap();
+    assert!(std::fs::read_to_string(args)
+        .unwrap()
+        .starts_with("-v\nlevel+verbose\n"));
+    assert_eq!(
+        probe.stderr_warnings[1],
+        "[aist#0:1/aac @ 0x55d1] [aac @ 0x55d2] channel element 0.0 is not allocated"
+    );
+    let levels: Vec<_> = probe.log.iter().map(|m| m.level).collect();
+    assert_eq!(
+        levels,
+        [
+            Some(LogLevel::Info),
+            Some(LogLevel::Error),
+            Some(LogLevel::Verbose)
+        ]
+    );
+    assert_eq!(probe.log[1].context.as_deref(), Some("aac"));
+    assert_eq!(probe.log[1].message, "channel element 0.0 is not allocated");
+    assert_eq!(probe.log[2].context, None);
+    let errors = probe
+        .log
+        .iter()
+        .filter(|m| m.level <= Some(LogLevel::Warning));
+    assert_eq!(errors.count(), 1);
+
+    // Quiet captures nothing, even with warnings requested.
+    let probe = Config::new()
+        .ffprobe_bin(&bin)
+        .capture_warnings(true)
+        .loglevel(LogLevel::Quiet)
+        .run(&input)
+        .unwrap();
+    assert!(probe.log.is_empty());
+}
+
+#[test]
+fn parse_log_message() {
+    let message = LogMessage::parse("moov atom not found");
+    assert_eq!(message.level, None);
+    assert_eq!(message.context, None);
+    assert_eq!(message.message, "moov atom not found");
+    assert_eq!(LogLevel::parse("warning"), Some(LogLevel::Warning));
+    assert_eq!(LogLevel::Trace.to_string(), "trace");
+    assert!(LogLevel::Error < LogLevel::Warning);
+}